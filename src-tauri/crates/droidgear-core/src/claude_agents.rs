@@ -0,0 +1,884 @@
+//! Claude Code subagents and slash commands (core).
+//!
+//! Subagents live in `~/.claude/agents/*.md` and slash commands in
+//! `~/.claude/commands/*.md`; a project can add its own under
+//! `<project>/.claude/agents/` and `<project>/.claude/commands/`. Commands in
+//! subdirectories are namespaced: `commands/git/commit.md` is `git:commit`.
+//! Both are
+//! Markdown with a YAML front-matter block. Agents carry `name`,
+//! `description`, `tools` and `model`; commands carry `description`,
+//! `allowed-tools`, `argument-hint` and `model`. Unknown front-matter keys are
+//! kept when a file is saved.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+
+use crate::claude_permissions::{self, ClaudePermissionSeverity, CLAUDE_PERMISSION_TOOLS};
+use crate::{paths, specs, storage};
+
+/// Model aliases accepted in front matter besides full `claude-*` ids.
+pub const CLAUDE_MODEL_ALIASES: [&str; 4] = ["sonnet", "opus", "haiku", "inherit"];
+
+/// Built-in tools an agent can use that take no permission rules.
+const AGENT_ONLY_TOOLS: [&str; 3] = ["BashOutput", "ExitPlanMode", "TodoWrite"];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ClaudeAssetKind {
+    /// A subagent (`agents/*.md`).
+    Agent,
+    /// A slash command (`commands/*.md`).
+    Command,
+}
+
+impl ClaudeAssetKind {
+    fn dir_name(self) -> &'static str {
+        match self {
+            Self::Agent => "agents",
+            Self::Command => "commands",
+        }
+    }
+
+    /// Front-matter key holding the tools list.
+    fn tools_key(self) -> &'static str {
+        match self {
+            Self::Agent => "tools",
+            Self::Command => "allowed-tools",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Agent => "agent",
+            Self::Command => "command",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ClaudeAssetScope {
+    /// `~/.claude/`.
+    User,
+    /// `<project>/.claude/`.
+    Project,
+}
+
+impl ClaudeAssetScope {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Project => "project",
+        }
+    }
+}
+
+/// A front-matter problem found while reading or before saving.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeAssetIssue {
+    /// Front-matter key the issue is about.
+    pub field: String,
+    pub severity: ClaudePermissionSeverity,
+    pub message: String,
+}
+
+/// A subagent or slash command file.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeAsset {
+    pub kind: ClaudeAssetKind,
+    pub scope: ClaudeAssetScope,
+    /// File name without `.md`: the agent name or the `/command` name, with
+    /// `:` between the subdirectories of a namespaced command.
+    pub name: String,
+    /// Full path to the file; filled in when read.
+    #[serde(default)]
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// `tools` of an agent or `allowed-tools` of a command. Empty means the
+    /// key is omitted (agents then inherit every tool).
+    #[serde(default)]
+    pub tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// `argument-hint` of a slash command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument_hint: Option<String>,
+    /// Markdown after the front matter (the system prompt or command prompt).
+    #[serde(default)]
+    pub body: String,
+    /// Validation issues; filled in when read, ignored on save.
+    #[serde(default)]
+    pub issues: Vec<ClaudeAssetIssue>,
+}
+
+// ============================================================================
+// Path resolution
+// ============================================================================
+
+fn system_home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+fn assets_dir_for_home(
+    home_dir: &Path,
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    project_dir: Option<&Path>,
+) -> Result<PathBuf, String> {
+    let base = match scope {
+        ClaudeAssetScope::User => {
+            let config_paths = paths::load_config_paths_for_home(home_dir);
+            paths::get_claude_home_for_home(home_dir, &config_paths)?
+        }
+        ClaudeAssetScope::Project => project_dir
+            .ok_or("A project directory is required for project scope")?
+            .join(".claude"),
+    };
+    Ok(base.join(kind.dir_name()))
+}
+
+fn validate_asset_name(kind: ClaudeAssetKind, name: &str) -> Result<(), String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    if trimmed != name {
+        return Err("Name cannot start or end with whitespace".to_string());
+    }
+    if name.contains(['/', '\\']) || name.split(':').any(|s| s.is_empty() || s.starts_with('.')) {
+        return Err(
+            "Name cannot contain path separators, empty namespaces or parts starting with '.'"
+                .to_string(),
+        );
+    }
+    if kind == ClaudeAssetKind::Agent
+        && !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        return Err("Agent names may only use lowercase letters, digits and '-'".to_string());
+    }
+    Ok(())
+}
+
+pub fn asset_path_for_home(
+    home_dir: &Path,
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    project_dir: Option<&Path>,
+    name: &str,
+) -> Result<PathBuf, String> {
+    validate_asset_name(kind, name)?;
+    let dir = assets_dir_for_home(home_dir, kind, scope, project_dir)?;
+    let (file, namespaces) = name
+        .rsplit_once(':')
+        .map_or((name, ""), |(namespaces, file)| (file, namespaces));
+    Ok(namespaces
+        .split(':')
+        .filter(|ns| !ns.is_empty())
+        .fold(dir, |dir, ns| dir.join(ns))
+        .join(format!("{file}.md")))
+}
+
+// ============================================================================
+// Front matter
+// ============================================================================
+
+fn yaml_string(map: &serde_yaml::Mapping, key: &str) -> Option<String> {
+    match map.get(key)? {
+        serde_yaml::Value::String(s) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Splits a comma-separated tools list, keeping commas inside `(...)`.
+fn split_tools(value: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut depth = 0usize;
+    let mut current = String::new();
+    for c in value.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                out.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    out.push(current);
+    out.into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Tools may be a comma-separated string or a YAML list.
+fn yaml_tools(map: &serde_yaml::Mapping, key: &str) -> Vec<String> {
+    match map.get(key) {
+        Some(serde_yaml::Value::String(s)) => split_tools(s),
+        Some(serde_yaml::Value::Sequence(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_front_matter(content: &str) -> Result<(serde_yaml::Mapping, String), String> {
+    let Some((yaml, body)) = specs::split_front_matter(content) else {
+        return Ok((serde_yaml::Mapping::new(), content.to_string()));
+    };
+    let map = match serde_yaml::from_str::<serde_yaml::Value>(yaml)
+        .map_err(|e| format!("Invalid front matter: {e}"))?
+    {
+        serde_yaml::Value::Mapping(map) => map,
+        serde_yaml::Value::Null => serde_yaml::Mapping::new(),
+        _ => return Err("Front matter must be a YAML mapping".to_string()),
+    };
+    Ok((map, body.to_string()))
+}
+
+fn issue(field: &str, severity: ClaudePermissionSeverity, message: String) -> ClaudeAssetIssue {
+    ClaudeAssetIssue {
+        field: field.to_string(),
+        severity,
+        message,
+    }
+}
+
+/// Checks the front matter of an asset: required agent fields, the tools
+/// list and the model alias.
+pub fn validate_asset(asset: &ClaudeAsset) -> Vec<ClaudeAssetIssue> {
+    use ClaudePermissionSeverity::{Error, Warning};
+
+    let mut issues = Vec::new();
+    if let Err(e) = validate_asset_name(asset.kind, &asset.name) {
+        issues.push(issue("name", Error, e));
+    }
+    if asset.kind == ClaudeAssetKind::Agent && asset.description.is_none() {
+        issues.push(issue(
+            "description",
+            Error,
+            "Agents need a description so Claude knows when to use them".to_string(),
+        ));
+    }
+
+    let tools_key = asset.kind.tools_key();
+    for tool in &asset.tools {
+        let name = match asset.kind {
+            ClaudeAssetKind::Agent if tool.contains('(') => {
+                issues.push(issue(
+                    tools_key,
+                    Error,
+                    format!("Agent tools take bare tool names, not rules: '{tool}'"),
+                ));
+                continue;
+            }
+            ClaudeAssetKind::Agent => tool.clone(),
+            ClaudeAssetKind::Command => match claude_permissions::parse_permission_rule(tool) {
+                Ok(rule) => rule.tool,
+                Err(e) => {
+                    issues.push(issue(tools_key, Error, format!("'{tool}': {e}")));
+                    continue;
+                }
+            },
+        };
+        let known = claude_permissions::is_mcp_tool(&name)
+            || CLAUDE_PERMISSION_TOOLS.contains(&name.as_str())
+            || AGENT_ONLY_TOOLS.contains(&name.as_str());
+        if !known {
+            issues.push(issue(tools_key, Warning, format!("Unknown tool '{name}'")));
+        }
+    }
+    let mut seen = Vec::new();
+    for tool in &asset.tools {
+        if seen.contains(&tool) {
+            issues.push(issue(
+                tools_key,
+                Warning,
+                format!("Duplicate tool '{tool}'"),
+            ));
+        }
+        seen.push(tool);
+    }
+
+    if let Some(model) = &asset.model {
+        if !CLAUDE_MODEL_ALIASES.contains(&model.as_str()) && !model.starts_with("claude-") {
+            issues.push(issue(
+                "model",
+                Error,
+                format!(
+                    "Unknown model '{model}'; use {} or a full claude-* model id",
+                    CLAUDE_MODEL_ALIASES.join(", ")
+                ),
+            ));
+        }
+    }
+    issues
+}
+
+fn asset_from_content(
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    name: &str,
+    path: &Path,
+    content: &str,
+) -> ClaudeAsset {
+    let mut asset = ClaudeAsset {
+        kind,
+        scope,
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        description: None,
+        tools: Vec::new(),
+        model: None,
+        argument_hint: None,
+        body: content.to_string(),
+        issues: Vec::new(),
+    };
+    let (map, body) = match parse_front_matter(content) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            asset
+                .issues
+                .push(issue("", ClaudePermissionSeverity::Error, e));
+            return asset;
+        }
+    };
+    asset.body = body;
+    asset.description = yaml_string(&map, "description");
+    asset.tools = yaml_tools(&map, kind.tools_key());
+    asset.model = yaml_string(&map, "model");
+    if kind == ClaudeAssetKind::Command {
+        asset.argument_hint = yaml_string(&map, "argument-hint");
+    }
+    asset.issues = validate_asset(&asset);
+    if kind == ClaudeAssetKind::Agent {
+        match yaml_string(&map, "name") {
+            Some(fm_name) if fm_name != name => asset.issues.push(issue(
+                "name",
+                ClaudePermissionSeverity::Warning,
+                format!("Front matter name '{fm_name}' differs from the file name"),
+            )),
+            Some(_) => {}
+            None => asset.issues.push(issue(
+                "name",
+                ClaudePermissionSeverity::Error,
+                "Missing agent name".to_string(),
+            )),
+        }
+    }
+    asset
+}
+
+/// Renders `asset` as Markdown, updating the known keys of `existing` front
+/// matter and keeping the rest.
+fn render_asset(asset: &ClaudeAsset, mut map: serde_yaml::Mapping) -> Result<String, String> {
+    let mut set = |key: &str, value: Option<String>| match value {
+        Some(value) => {
+            map.insert(key.into(), value.into());
+        }
+        None => {
+            map.remove(key);
+        }
+    };
+    if asset.kind == ClaudeAssetKind::Agent {
+        set("name", Some(asset.name.clone()));
+    }
+    set("description", asset.description.clone());
+    set(
+        asset.kind.tools_key(),
+        Some(asset.tools.join(", ")).filter(|t| !t.is_empty()),
+    );
+    set("model", asset.model.clone());
+    if asset.kind == ClaudeAssetKind::Command {
+        set("argument-hint", asset.argument_hint.clone());
+    }
+
+    let mut out = String::new();
+    if !map.is_empty() {
+        let yaml = serde_yaml::to_string(&map)
+            .map_err(|e| format!("Failed to serialize front matter: {e}"))?;
+        out.push_str("---\n");
+        out.push_str(&yaml);
+        out.push_str("---\n");
+    }
+    out.push_str(&asset.body);
+    Ok(out)
+}
+
+// ============================================================================
+// CRUD
+// ============================================================================
+
+fn read_dir_assets(
+    dir: &Path,
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+) -> Result<Vec<ClaudeAsset>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+    let mut assets = Vec::new();
+    collect_dir_assets(entries, "", kind, scope, &mut assets);
+    assets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(assets)
+}
+
+/// Adds the `.md` files of a directory to `assets`, descending into the
+/// subdirectories of commands. Files that cannot be read are listed with an
+/// error issue instead of failing the whole list.
+fn collect_dir_assets(
+    entries: std::fs::ReadDir,
+    namespace: &str,
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    assets: &mut Vec<ClaudeAsset>,
+) {
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // `file_type` does not follow symlinks, so a linked directory cannot
+        // loop the walk.
+        if kind == ClaudeAssetKind::Command && entry.file_type().is_ok_and(|t| t.is_dir()) {
+            let Some(dir_name) = path.file_name().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if dir_name.starts_with('.') {
+                continue;
+            }
+            match std::fs::read_dir(&path) {
+                Ok(entries) => collect_dir_assets(
+                    entries,
+                    &format!("{namespace}{dir_name}:"),
+                    kind,
+                    scope,
+                    assets,
+                ),
+                Err(e) => log::warn!("Failed to read {}: {e}", path.display()),
+            }
+            continue;
+        }
+        if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let name = format!("{namespace}{stem}");
+        match std::fs::read_to_string(&path) {
+            Ok(content) => assets.push(asset_from_content(kind, scope, &name, &path, &content)),
+            Err(e) => {
+                let mut asset = asset_from_content(kind, scope, &name, &path, "");
+                asset.issues = vec![issue(
+                    "",
+                    ClaudePermissionSeverity::Error,
+                    format!("Failed to read {}: {e}", path.display()),
+                )];
+                assets.push(asset);
+            }
+        }
+    }
+}
+
+/// Lists user assets of `kind`, followed by the project's when
+/// `project_dir` is given.
+pub fn list_assets(
+    kind: ClaudeAssetKind,
+    project_dir: Option<&Path>,
+) -> Result<Vec<ClaudeAsset>, String> {
+    list_assets_for_home(&system_home_dir()?, kind, project_dir)
+}
+
+pub fn list_assets_for_home(
+    home_dir: &Path,
+    kind: ClaudeAssetKind,
+    project_dir: Option<&Path>,
+) -> Result<Vec<ClaudeAsset>, String> {
+    let mut assets = read_dir_assets(
+        &assets_dir_for_home(home_dir, kind, ClaudeAssetScope::User, None)?,
+        kind,
+        ClaudeAssetScope::User,
+    )?;
+    if project_dir.is_some() {
+        assets.extend(read_dir_assets(
+            &assets_dir_for_home(home_dir, kind, ClaudeAssetScope::Project, project_dir)?,
+            kind,
+            ClaudeAssetScope::Project,
+        )?);
+    }
+    Ok(assets)
+}
+
+pub fn read_asset(
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    project_dir: Option<&Path>,
+    name: &str,
+) -> Result<ClaudeAsset, String> {
+    read_asset_for_home(&system_home_dir()?, kind, scope, project_dir, name)
+}
+
+pub fn read_asset_for_home(
+    home_dir: &Path,
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    project_dir: Option<&Path>,
+    name: &str,
+) -> Result<ClaudeAsset, String> {
+    let path = asset_path_for_home(home_dir, kind, scope, project_dir, name)?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Ok(asset_from_content(kind, scope, name, &path, &content))
+}
+
+/// Writes an asset, refusing front matter with errors. Unknown keys of an
+/// existing file are kept.
+pub fn save_asset(project_dir: Option<&Path>, asset: &ClaudeAsset) -> Result<(), String> {
+    save_asset_for_home(&system_home_dir()?, project_dir, asset)
+}
+
+pub fn save_asset_for_home(
+    home_dir: &Path,
+    project_dir: Option<&Path>,
+    asset: &ClaudeAsset,
+) -> Result<(), String> {
+    if let Some(error) = validate_asset(asset)
+        .into_iter()
+        .find(|i| i.severity == ClaudePermissionSeverity::Error)
+    {
+        return Err(format!("{}: {}", error.field, error.message));
+    }
+    let path = asset_path_for_home(home_dir, asset.kind, asset.scope, project_dir, &asset.name)?;
+    let existing = if path.exists() {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        parse_front_matter(&content)?.0
+    } else {
+        serde_yaml::Mapping::new()
+    };
+    let content = render_asset(asset, existing)?;
+    if let Some(parent) = path.parent() {
+        if !parent.exists() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+    }
+    storage::atomic_write(&path, content.as_bytes())
+}
+
+/// Creates a new asset with a placeholder description and prompt. Fails
+/// when the file already exists.
+pub fn create_asset(
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    project_dir: Option<&Path>,
+    name: &str,
+) -> Result<ClaudeAsset, String> {
+    create_asset_for_home(&system_home_dir()?, kind, scope, project_dir, name)
+}
+
+pub fn create_asset_for_home(
+    home_dir: &Path,
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    project_dir: Option<&Path>,
+    name: &str,
+) -> Result<ClaudeAsset, String> {
+    let path = asset_path_for_home(home_dir, kind, scope, project_dir, name)?;
+    if path.exists() {
+        return Err(format!("{} '{name}' already exists", kind.label()));
+    }
+    let (description, body) = match kind {
+        ClaudeAssetKind::Agent => (
+            format!("Use this agent when ... ({name})"),
+            format!("\nYou are the {name} agent.\n"),
+        ),
+        ClaudeAssetKind::Command => (format!("/{name}"), "\n$ARGUMENTS\n".to_string()),
+    };
+    let asset = ClaudeAsset {
+        kind,
+        scope,
+        name: name.to_string(),
+        path: String::new(),
+        description: Some(description),
+        tools: Vec::new(),
+        model: None,
+        argument_hint: None,
+        body,
+        issues: Vec::new(),
+    };
+    save_asset_for_home(home_dir, project_dir, &asset)?;
+    read_asset_for_home(home_dir, kind, scope, project_dir, name)
+}
+
+pub fn delete_asset(
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    project_dir: Option<&Path>,
+    name: &str,
+) -> Result<(), String> {
+    delete_asset_for_home(&system_home_dir()?, kind, scope, project_dir, name)
+}
+
+pub fn delete_asset_for_home(
+    home_dir: &Path,
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    project_dir: Option<&Path>,
+    name: &str,
+) -> Result<(), String> {
+    let path = asset_path_for_home(home_dir, kind, scope, project_dir, name)?;
+    if !path.exists() {
+        return Err(format!("{} '{name}' not found", kind.label()));
+    }
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to delete {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_file(path: &Path, contents: &str) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn lists_user_and_project_assets() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path().join("home");
+        let project = temp.path().join("repo");
+        write_file(
+            &home.join(".claude/agents/reviewer.md"),
+            "---\nname: reviewer\ndescription: Reviews diffs\ntools: Read, Grep, Glob\nmodel: sonnet\n---\nYou review code.\n",
+        );
+        write_file(
+            &project.join(".claude/agents/tester.md"),
+            "---\nname: tester\ndescription: Runs tests\n---\nRun tests.\n",
+        );
+        write_file(&home.join(".claude/agents/notes.txt"), "ignored");
+
+        let agents = list_assets_for_home(&home, ClaudeAssetKind::Agent, Some(&project)).unwrap();
+        assert_eq!(agents.len(), 2);
+        let reviewer = &agents[0];
+        assert_eq!(reviewer.scope, ClaudeAssetScope::User);
+        assert_eq!(reviewer.tools, vec!["Read", "Grep", "Glob"]);
+        assert_eq!(reviewer.model.as_deref(), Some("sonnet"));
+        assert_eq!(reviewer.body, "You review code.\n");
+        assert!(reviewer.issues.is_empty(), "{:?}", reviewer.issues);
+        assert_eq!(agents[1].scope, ClaudeAssetScope::Project);
+
+        let user_only = list_assets_for_home(&home, ClaudeAssetKind::Agent, None).unwrap();
+        assert_eq!(user_only.len(), 1);
+    }
+
+    #[test]
+    fn validates_tools_and_model() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        write_file(
+            &home.join(".claude/commands/commit.md"),
+            "---\nallowed-tools: Bash(git add:*), Bash(git commit:*), Frobnicate\nmodel: gpt-5\nargument-hint: '[message]'\n---\nCommit with $ARGUMENTS\n",
+        );
+        write_file(
+            &home.join(".claude/agents/bad.md"),
+            "---\nname: other\ntools: Bash(ls), Read\n---\n",
+        );
+
+        let command = read_asset_for_home(
+            home,
+            ClaudeAssetKind::Command,
+            ClaudeAssetScope::User,
+            None,
+            "commit",
+        )
+        .unwrap();
+        assert_eq!(command.argument_hint.as_deref(), Some("[message]"));
+        assert_eq!(command.tools.len(), 3);
+        let messages: Vec<_> = command.issues.iter().map(|i| i.message.as_str()).collect();
+        assert!(messages
+            .iter()
+            .any(|m| m.contains("Unknown tool 'Frobnicate'")));
+        assert!(messages.iter().any(|m| m.contains("Unknown model 'gpt-5'")));
+
+        let agent = read_asset_for_home(
+            home,
+            ClaudeAssetKind::Agent,
+            ClaudeAssetScope::User,
+            None,
+            "bad",
+        )
+        .unwrap();
+        let fields: Vec<_> = agent
+            .issues
+            .iter()
+            .map(|i| (i.field.as_str(), i.severity))
+            .collect();
+        assert!(fields.contains(&("description", ClaudePermissionSeverity::Error)));
+        assert!(fields.contains(&("tools", ClaudePermissionSeverity::Error)));
+        assert!(fields.contains(&("name", ClaudePermissionSeverity::Warning)));
+    }
+
+    #[test]
+    fn save_keeps_unknown_keys_and_rejects_errors() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let path = home.join(".claude/agents/helper.md");
+        write_file(
+            &path,
+            "---\nname: helper\ndescription: Helps\ncolor: blue\n---\nBody\n",
+        );
+
+        let mut agent = read_asset_for_home(
+            home,
+            ClaudeAssetKind::Agent,
+            ClaudeAssetScope::User,
+            None,
+            "helper",
+        )
+        .unwrap();
+        agent.tools = vec!["Read".to_string(), "mcp__github__search".to_string()];
+        agent.model = Some("opus".to_string());
+        save_asset_for_home(home, None, &agent).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("color: blue"));
+        assert!(content.contains("tools: Read, mcp__github__search"));
+        assert!(content.ends_with("---\nBody\n"));
+
+        agent.model = Some("gpt-5".to_string());
+        let err = save_asset_for_home(home, None, &agent).unwrap_err();
+        assert!(err.starts_with("model:"), "{err}");
+    }
+
+    #[test]
+    fn create_and_delete_project_command() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path().join("home");
+        let project = temp.path().join("repo");
+
+        let created = create_asset_for_home(
+            &home,
+            ClaudeAssetKind::Command,
+            ClaudeAssetScope::Project,
+            Some(&project),
+            "deploy",
+        )
+        .unwrap();
+        assert!(created.issues.is_empty(), "{:?}", created.issues);
+        assert!(project.join(".claude/commands/deploy.md").exists());
+        assert!(create_asset_for_home(
+            &home,
+            ClaudeAssetKind::Command,
+            ClaudeAssetScope::Project,
+            Some(&project),
+            "deploy",
+        )
+        .is_err());
+        assert!(create_asset_for_home(
+            &home,
+            ClaudeAssetKind::Command,
+            ClaudeAssetScope::Project,
+            None,
+            "other",
+        )
+        .is_err());
+
+        delete_asset_for_home(
+            &home,
+            ClaudeAssetKind::Command,
+            ClaudeAssetScope::Project,
+            Some(&project),
+            "deploy",
+        )
+        .unwrap();
+        assert!(!project.join(".claude/commands/deploy.md").exists());
+        assert!(validate_asset_name(ClaudeAssetKind::Agent, "Bad Name").is_err());
+        assert!(validate_asset_name(ClaudeAssetKind::Command, "../x").is_err());
+        assert!(validate_asset_name(ClaudeAssetKind::Command, "git:").is_err());
+        assert!(validate_asset_name(ClaudeAssetKind::Command, "git:.hidden").is_err());
+    }
+
+    #[test]
+    fn lists_namespaced_commands_and_unreadable_files() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let commands = home.join(".claude/commands");
+        write_file(
+            &commands.join("deploy.md"),
+            "---
+description: Ship
+---
+Go
+",
+        );
+        write_file(
+            &commands.join("git/commit.md"),
+            "---
+description: Commit
+---
+Commit
+",
+        );
+        write_file(
+            &commands.join("git/pr/open.md"),
+            "Open a PR
+",
+        );
+        write_file(
+            &commands.join(".hidden/skip.md"),
+            "skipped
+",
+        );
+        std::fs::write(commands.join("broken.md"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let listed = list_assets_for_home(home, ClaudeAssetKind::Command, None).unwrap();
+        let names: Vec<_> = listed.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["broken", "deploy", "git:commit", "git:pr:open"]);
+        assert_eq!(listed[0].issues.len(), 1);
+        assert_eq!(
+            listed[0].issues[0].severity,
+            ClaudePermissionSeverity::Error
+        );
+        assert!(listed[0].issues[0].message.contains("Failed to read"));
+        assert!(listed[2].issues.is_empty(), "{:?}", listed[2].issues);
+
+        let commit = read_asset_for_home(
+            home,
+            ClaudeAssetKind::Command,
+            ClaudeAssetScope::User,
+            None,
+            "git:commit",
+        )
+        .unwrap();
+        assert_eq!(commit.description.as_deref(), Some("Commit"));
+        create_asset_for_home(
+            home,
+            ClaudeAssetKind::Command,
+            ClaudeAssetScope::User,
+            None,
+            "ops:restart",
+        )
+        .unwrap();
+        assert!(commands.join("ops/restart.md").exists());
+    }
+}
@@ -0,0 +1,314 @@
+//! Claude Code session transcripts (core).
+//!
+//! Reads `~/.claude/projects/<project>/<session-id>.jsonl` and maps it onto the
+//! shared [`SessionSummary`] / [`SessionDetail`] types.
+
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::sessions::{
+    self, ContentBlock, SessionDetail, SessionMessage, SessionSource, SessionSummary, TokenUsage,
+};
+
+pub(crate) fn projects_dir_for_home(home_dir: &Path) -> Result<PathBuf, String> {
+    let config_paths = paths::load_config_paths_for_home(home_dir);
+    let claude_dir = paths::get_claude_home_for_home(home_dir, &config_paths)?;
+    Ok(claude_dir.join("projects"))
+}
+
+fn read_lines(path: &Path) -> Result<impl Iterator<Item = Value>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open session file: {e}"))?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<Value>(&line).ok()))
+}
+
+fn message_blocks(message: &Value) -> Vec<ContentBlock> {
+    match &message["content"] {
+        Value::String(text) => vec![ContentBlock::Text { text: text.clone() }],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(sessions::parse_content_block)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Aggregated header data for one transcript. Claude Code writes one line per
+/// content block, repeating the API message id and usage on each, so usage is
+/// counted once per message id.
+struct TranscriptInfo {
+    id: String,
+    title: Option<String>,
+    first_prompt: Option<String>,
+    cwd: String,
+    model: String,
+    token_usage: TokenUsage,
+}
+
+impl TranscriptInfo {
+    fn new(path: &Path) -> Self {
+        TranscriptInfo {
+            id: path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("")
+                .to_string(),
+            title: None,
+            first_prompt: None,
+            cwd: String::new(),
+            model: "unknown".to_string(),
+            token_usage: TokenUsage::default(),
+        }
+    }
+
+    /// Picks up the title, working directory, first prompt and latest model.
+    fn observe(&mut self, json: &Value) {
+        match json["type"].as_str().unwrap_or("") {
+            "summary" => {
+                if let Some(summary) = json["summary"].as_str() {
+                    self.title = Some(summary.to_string());
+                }
+            }
+            "user" => {
+                if self.cwd.is_empty() {
+                    self.cwd = json["cwd"].as_str().unwrap_or("").to_string();
+                }
+                if self.first_prompt.is_none() && !json["isMeta"].as_bool().unwrap_or(false) {
+                    self.first_prompt =
+                        message_blocks(&json["message"])
+                            .into_iter()
+                            .find_map(|b| match b {
+                                ContentBlock::Text { text } => Some(text),
+                                _ => None,
+                            });
+                }
+            }
+            "assistant" => {
+                if let Some(model) = json["message"]["model"].as_str() {
+                    if !model.starts_with('<') {
+                        self.model = model.to_string();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn add_message_usage(json: &Value, counted: &mut HashSet<String>, total: &mut TokenUsage) {
+    if json["type"].as_str() != Some("assistant") {
+        return;
+    }
+    let message = &json["message"];
+    let message_id = message["id"].as_str().unwrap_or("").to_string();
+    if message_id.is_empty() || counted.insert(message_id) {
+        if let Some(usage) = sessions::token_usage_from_json(&message["usage"]) {
+            total.add(&usage);
+        }
+    }
+}
+
+fn scan_transcript(path: &Path) -> Result<TranscriptInfo, String> {
+    let mut info = TranscriptInfo::new(path);
+    let mut counted: HashSet<String> = HashSet::new();
+    for json in read_lines(path)? {
+        info.observe(&json);
+        add_message_usage(&json, &mut counted, &mut info.token_usage);
+    }
+    Ok(info)
+}
+
+/// Header data from the head and tail of a transcript only; token usage is
+/// left empty because it is spread over every assistant line.
+fn summarize_transcript(path: &Path) -> Result<TranscriptInfo, String> {
+    let ends = sessions::read_jsonl_ends(
+        path,
+        sessions::SUMMARY_HEAD_LINES,
+        sessions::SUMMARY_TAIL_BYTES,
+    )?;
+    let mut info = TranscriptInfo::new(path);
+    for json in ends.head.iter().chain(&ends.tail) {
+        info.observe(json);
+    }
+    Ok(info)
+}
+
+/// Sums the usage of every API message in a transcript.
+pub(crate) fn transcript_token_usage(path: &Path) -> Result<TokenUsage, String> {
+    let mut total = TokenUsage::default();
+    let mut counted: HashSet<String> = HashSet::new();
+    for json in read_lines(path)? {
+        add_message_usage(&json, &mut counted, &mut total);
+    }
+    Ok(total)
+}
+
+pub(crate) fn list_sessions_for_home(home_dir: &Path) -> Result<Vec<SessionSummary>, String> {
+    let projects_dir = projects_dir_for_home(home_dir)?;
+    if !projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sessions = Vec::new();
+    let project_dirs = fs::read_dir(&projects_dir)
+        .map_err(|e| format!("Failed to read Claude projects directory: {e}"))?;
+
+    for project_dir in project_dirs.flatten().map(|e| e.path()) {
+        if !project_dir.is_dir() {
+            continue;
+        }
+        let project = project_dir
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        let Ok(entries) = fs::read_dir(&project_dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let Ok(info) = summarize_transcript(&path) else {
+                continue;
+            };
+            let title = info.title.unwrap_or_else(|| {
+                sessions::title_from_prompt(info.first_prompt.as_deref().unwrap_or(""))
+            });
+
+            sessions.push(SessionSummary {
+                id: info.id,
+                title,
+                project: project.clone(),
+                model: info.model,
+                modified_at: sessions::modified_at_ms(&path),
+                token_usage: info.token_usage,
+                path: path.with_extension("").to_string_lossy().to_string(),
+                source: SessionSource::ClaudeCode,
+            });
+        }
+    }
+
+    Ok(sessions)
+}
+
+pub(crate) fn get_session_detail(session_path: &str) -> Result<SessionDetail, String> {
+    let jsonl_path = PathBuf::from(format!("{session_path}.jsonl"));
+    if !jsonl_path.exists() {
+        return Err("Session file not found".to_string());
+    }
+
+    let info = scan_transcript(&jsonl_path)?;
+    let project = jsonl_path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_string();
+
+    let mut messages: Vec<SessionMessage> = Vec::new();
+    for json in read_lines(&jsonl_path)? {
+        let line_type = json["type"].as_str().unwrap_or("");
+        if line_type != "user" && line_type != "assistant" {
+            continue;
+        }
+        let message = &json["message"];
+        let content = message_blocks(message);
+        if content.is_empty() {
+            continue;
+        }
+
+        // Consecutive lines of one streamed assistant message share its API id.
+        let api_id = message["id"].as_str().unwrap_or("");
+        if let Some(last) = messages.last_mut() {
+            if !api_id.is_empty() && last.id == api_id {
+                last.content.extend(content);
+                continue;
+            }
+        }
+
+        let id = if api_id.is_empty() {
+            json["uuid"].as_str().unwrap_or("").to_string()
+        } else {
+            api_id.to_string()
+        };
+        messages.push(SessionMessage {
+            id,
+            role: message["role"].as_str().unwrap_or(line_type).to_string(),
+            content,
+            timestamp: json["timestamp"].as_str().unwrap_or("").to_string(),
+            token_usage: sessions::token_usage_from_json(&message["usage"]),
+        });
+    }
+
+    let title = info
+        .title
+        .unwrap_or_else(|| sessions::title_from_prompt(info.first_prompt.as_deref().unwrap_or("")));
+
+    Ok(SessionDetail {
+        id: info.id,
+        title,
+        project,
+        model: info.model,
+        cwd: info.cwd,
+        modified_at: sessions::modified_at_ms(&jsonl_path),
+        token_usage: info.token_usage,
+        messages,
+        source: SessionSource::ClaudeCode,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_session_detail, list_sessions_for_home, transcript_token_usage};
+    use crate::sessions::{ContentBlock, SessionSource};
+    use tempfile::TempDir;
+
+    const TRANSCRIPT: &str = r#"{"type":"user","uuid":"u1","cwd":"/work/app","timestamp":"2026-01-01T00:00:00Z","message":{"role":"user","content":"Fix the login bug\nplease"}}
+{"type":"assistant","uuid":"a1","timestamp":"2026-01-01T00:00:01Z","message":{"id":"msg_1","role":"assistant","model":"claude-sonnet-4-5","usage":{"input_tokens":100,"output_tokens":20,"cache_read_input_tokens":50},"content":[{"type":"text","text":"Reading"}]}}
+{"type":"assistant","uuid":"a2","timestamp":"2026-01-01T00:00:02Z","message":{"id":"msg_1","role":"assistant","model":"claude-sonnet-4-5","usage":{"input_tokens":100,"output_tokens":20,"cache_read_input_tokens":50},"content":[{"type":"tool_use","id":"tu1","name":"Read","input":{"file_path":"login.rs"}}]}}
+{"type":"user","uuid":"u2","timestamp":"2026-01-01T00:00:03Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"tu1","content":"fn login() {}"}]}}
+"#;
+
+    #[test]
+    fn lists_and_reads_claude_transcripts() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join(".claude/projects/-work-app");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("abc.jsonl"), TRANSCRIPT).unwrap();
+
+        let list = list_sessions_for_home(temp.path()).unwrap();
+        assert_eq!(list.len(), 1);
+        let summary = &list[0];
+        assert_eq!(summary.id, "abc");
+        assert_eq!(summary.title, "Fix the login bug");
+        assert_eq!(summary.project, "-work-app");
+        assert_eq!(summary.model, "claude-sonnet-4-5");
+        assert_eq!(summary.source, SessionSource::ClaudeCode);
+        assert_eq!(summary.token_usage.input_tokens, 0.0);
+
+        let usage = transcript_token_usage(&dir.join("abc.jsonl")).unwrap();
+        assert_eq!(usage.input_tokens, 100.0);
+        assert_eq!(usage.cache_read_tokens, 50.0);
+
+        let detail = get_session_detail(&summary.path).unwrap();
+        assert_eq!(detail.cwd, "/work/app");
+        assert_eq!(detail.messages.len(), 3);
+        assert_eq!(detail.messages[1].content.len(), 2);
+        assert!(matches!(
+            &detail.messages[1].content[1],
+            ContentBlock::ToolUse { name, .. } if name == "Read"
+        ));
+        assert!(matches!(
+            &detail.messages[2].content[0],
+            ContentBlock::ToolResult { tool_use_id, .. } if tool_use_id == "tu1"
+        ));
+    }
+}
@@ -10,12 +10,18 @@
 //! Legacy DroidGear-managed Claude profiles stored under
 //! `~/.droidgear/claude/profiles/` are migrated to custom settings files on
 //! first list call.
+//!
+//! Repo-level layers (`<project>/.claude/settings.json` and
+//! `settings.local.json`) can be read, edited and merged with the user
+//! settings the way Claude Code does, with per-key provenance.
 
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use specta::Type;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+use crate::claude_settings_sections::{self, ClaudeMergeOptions, ClaudeSettingsSections};
 use crate::{claude, paths, storage};
 
 const CLAUDE_SETTINGS_DIR: &str = "claude-settings";
@@ -42,6 +48,60 @@ pub struct ClaudeSettingsFileInfo {
     pub exists: bool,
 }
 
+/// A Claude Code settings layer. Layers are merged in declaration order,
+/// later ones taking precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ClaudeSettingsScope {
+    /// `~/.claude/settings.json`.
+    User,
+    /// `<project>/.claude/settings.json`, shared through the repo.
+    Project,
+    /// `<project>/.claude/settings.local.json`, not checked in.
+    Local,
+}
+
+impl ClaudeSettingsScope {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Project => "project",
+            Self::Local => "local",
+        }
+    }
+}
+
+/// A settings layer discovered for a project.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeSettingsLayerInfo {
+    pub scope: ClaudeSettingsScope,
+    pub path: String,
+    pub exists: bool,
+}
+
+/// Where a key of the effective settings comes from.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeSettingsKeySource {
+    /// Dotted path of the key, e.g. `env.ANTHROPIC_MODEL`.
+    pub key: String,
+    /// Effective value of the key.
+    pub value: Value,
+    /// Layers that set the key, lowest precedence first. The last one wins
+    /// for scalars; arrays are concatenated.
+    pub scopes: Vec<ClaudeSettingsScope>,
+}
+
+/// User, project and local settings merged the way Claude Code does.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeEffectiveSettings {
+    pub layers: Vec<ClaudeSettingsLayerInfo>,
+    pub settings: Value,
+    pub sources: Vec<ClaudeSettingsKeySource>,
+}
+
 // ============================================================================
 // Path resolution
 // ============================================================================
@@ -50,7 +110,7 @@ fn system_home_dir() -> Result<PathBuf, String> {
     dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
 }
 
-fn claude_settings_dir_for_home(home_dir: &Path) -> PathBuf {
+pub(crate) fn claude_settings_dir_for_home(home_dir: &Path) -> PathBuf {
     paths::droidgear_dir_from_home(home_dir).join(CLAUDE_SETTINGS_DIR)
 }
 
@@ -536,11 +596,15 @@ pub fn read_settings_file_for_home(home_dir: &Path, name: &str) -> Result<Value,
             Err(err)
         }
     })?;
+    read_settings_json(&path)
+}
+
+fn read_settings_json(path: &Path) -> Result<Value, String> {
     if !path.exists() {
         return Ok(serde_json::json!({}));
     }
     let content =
-        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read settings file: {e}"))?;
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read settings file: {e}"))?;
     if content.trim().is_empty() {
         return Ok(serde_json::json!({}));
     }
@@ -615,6 +679,43 @@ pub fn save_settings_file(name: &str, value: Value) -> Result<(), String> {
     save_settings_file_for_home(&system_home_dir()?, name, value)
 }
 
+// ============================================================================
+// Typed sections
+// ============================================================================
+
+/// Reads the typed sections (`hooks`, `statusLine`, ...) of a settings file.
+pub fn read_settings_sections_for_home(
+    home_dir: &Path,
+    name: &str,
+) -> Result<ClaudeSettingsSections, String> {
+    let value = read_settings_file_for_home(home_dir, name)?;
+    claude_settings_sections::sections_from_settings(&value)
+}
+
+pub fn read_settings_sections(name: &str) -> Result<ClaudeSettingsSections, String> {
+    read_settings_sections_for_home(&system_home_dir()?, name)
+}
+
+/// Validates the sections and writes them into the settings file, keeping
+/// every other key as is.
+pub fn save_settings_sections_for_home(
+    home_dir: &Path,
+    name: &str,
+    sections: &ClaudeSettingsSections,
+) -> Result<(), String> {
+    claude_settings_sections::validate_sections(sections)?;
+    let mut value = read_settings_file_for_home(home_dir, name)?;
+    let doc = value
+        .as_object_mut()
+        .ok_or("Settings file root must be a JSON object")?;
+    claude_settings_sections::apply_sections_to_settings(doc, sections)?;
+    save_settings_file_for_home(home_dir, name, value)
+}
+
+pub fn save_settings_sections(name: &str, sections: &ClaudeSettingsSections) -> Result<(), String> {
+    save_settings_sections_for_home(&system_home_dir()?, name, sections)
+}
+
 // ============================================================================
 // Duplicate
 // ============================================================================
@@ -672,12 +773,41 @@ pub fn duplicate_settings_file_for_home(
 /// because merge is a literal merge — the source file was already normalized by save,
 /// and a stale top-level `model` in the live global file is the user's own raw state
 /// that merge must not silently rewrite.
-pub fn merge_settings_file_to_global(name: &str) -> Result<(), String> {
+pub fn merge_settings_file_to_global(name: &str) -> Result<Vec<String>, String> {
     merge_settings_file_to_global_for_home(&system_home_dir()?, name)
 }
 
-pub fn merge_settings_file_to_global_for_home(home_dir: &Path, name: &str) -> Result<(), String> {
+pub fn merge_settings_file_to_global_for_home(
+    home_dir: &Path,
+    name: &str,
+) -> Result<Vec<String>, String> {
+    merge_settings_file_to_global_with_options_for_home(
+        home_dir,
+        name,
+        &ClaudeMergeOptions::default(),
+    )
+}
+
+/// Like `merge_settings_file_to_global`, with a merge strategy per top-level
+/// key (see `ClaudeMergeStrategy`). The source's typed sections are
+/// validated first so a malformed hook never reaches the global file; hook
+/// events and types DroidGear does not know are merged as is and returned as
+/// warnings.
+pub fn merge_settings_file_to_global_with_options(
+    name: &str,
+    options: &ClaudeMergeOptions,
+) -> Result<Vec<String>, String> {
+    merge_settings_file_to_global_with_options_for_home(&system_home_dir()?, name, options)
+}
+
+pub fn merge_settings_file_to_global_with_options_for_home(
+    home_dir: &Path,
+    name: &str,
+    options: &ClaudeMergeOptions,
+) -> Result<Vec<String>, String> {
     let source = read_settings_file_for_home(home_dir, name)?;
+    let sections = claude_settings_sections::sections_from_settings(&source)?;
+    let warnings = claude_settings_sections::section_warnings(&sections);
     let source_obj = source
         .as_object()
         .ok_or("Settings file root must be a JSON object")?;
@@ -690,22 +820,12 @@ pub fn merge_settings_file_to_global_for_home(home_dir: &Path, name: &str) -> Re
     };
 
     for (key, value) in source_obj {
-        let should_shallow_merge = key == "env" || key == "permissions";
-        if should_shallow_merge {
-            if let (Value::Object(source_sub), Some(Value::Object(live_sub))) =
-                (value, merged.get(key))
-            {
-                let mut merged_sub = live_sub.clone();
-                for (sk, sv) in source_sub {
-                    merged_sub.insert(sk.clone(), sv.clone());
-                }
-                merged.insert(key.clone(), Value::Object(merged_sub));
-            } else {
-                merged.insert(key.clone(), value.clone());
-            }
-        } else {
-            merged.insert(key.clone(), value.clone());
-        }
+        let next = claude_settings_sections::merge_value(
+            options.strategy_for(key),
+            merged.get(key),
+            value,
+        );
+        merged.insert(key.clone(), next);
     }
 
     let result = Value::Object(merged);
@@ -718,7 +838,8 @@ pub fn merge_settings_file_to_global_for_home(home_dir: &Path, name: &str) -> Re
                 .map_err(|e| format!("Failed to create claude config dir: {e}"))?;
         }
     }
-    storage::atomic_write(&global_path, &bytes)
+    storage::atomic_write(&global_path, &bytes)?;
+    Ok(warnings)
 }
 
 // ============================================================================
@@ -737,6 +858,194 @@ pub fn load_settings_file_from_live_for_home(home_dir: &Path, name: &str) -> Res
     save_settings_file_for_home(home_dir, name, live)
 }
 
+// ============================================================================
+// Project layers
+// ============================================================================
+
+/// Path of a repo-level settings layer. `User` is not a project layer.
+pub fn project_settings_layer_path(
+    project_dir: &Path,
+    scope: ClaudeSettingsScope,
+) -> Result<PathBuf, String> {
+    let dir = project_dir.join(".claude");
+    match scope {
+        ClaudeSettingsScope::User => Err("User settings are not a project layer".to_string()),
+        ClaudeSettingsScope::Project => Ok(dir.join("settings.json")),
+        ClaudeSettingsScope::Local => Ok(dir.join("settings.local.json")),
+    }
+}
+
+/// Lists the user, project and local layers for `project_dir`, lowest
+/// precedence first, whether or not they exist.
+pub fn list_settings_layers(project_dir: &Path) -> Result<Vec<ClaudeSettingsLayerInfo>, String> {
+    list_settings_layers_for_home(&system_home_dir()?, project_dir)
+}
+
+pub fn list_settings_layers_for_home(
+    home_dir: &Path,
+    project_dir: &Path,
+) -> Result<Vec<ClaudeSettingsLayerInfo>, String> {
+    let paths = [
+        (
+            ClaudeSettingsScope::User,
+            global_settings_path_for_home(home_dir)?,
+        ),
+        (
+            ClaudeSettingsScope::Project,
+            project_settings_layer_path(project_dir, ClaudeSettingsScope::Project)?,
+        ),
+        (
+            ClaudeSettingsScope::Local,
+            project_settings_layer_path(project_dir, ClaudeSettingsScope::Local)?,
+        ),
+    ];
+    Ok(paths
+        .into_iter()
+        .map(|(scope, path)| ClaudeSettingsLayerInfo {
+            scope,
+            exists: path.exists(),
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect())
+}
+
+/// Reads a project layer; `{}` when it does not exist yet.
+pub fn read_project_settings_layer(
+    project_dir: &Path,
+    scope: ClaudeSettingsScope,
+) -> Result<Value, String> {
+    read_settings_json(&project_settings_layer_path(project_dir, scope)?)
+}
+
+/// Writes a project layer, creating `<project>/.claude/` when needed. The
+/// document is normalized like a custom settings file.
+pub fn save_project_settings_layer(
+    project_dir: &Path,
+    scope: ClaudeSettingsScope,
+    mut value: Value,
+) -> Result<(), String> {
+    if !matches!(value, Value::Object(_)) {
+        return Err("Settings file root must be a JSON object".to_string());
+    }
+    clean_settings_document(&mut value);
+
+    let path = project_settings_layer_path(project_dir, scope)?;
+    if let Some(parent) = path.parent() {
+        if !parent.exists() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create project .claude dir: {e}"))?;
+        }
+    }
+    let bytes = serde_json::to_vec_pretty(&value)
+        .map_err(|e| format!("Failed to serialize settings file: {e}"))?;
+    storage::atomic_write(&path, &bytes)
+}
+
+pub fn read_project_settings_sections(
+    project_dir: &Path,
+    scope: ClaudeSettingsScope,
+) -> Result<ClaudeSettingsSections, String> {
+    let value = read_project_settings_layer(project_dir, scope)?;
+    claude_settings_sections::sections_from_settings(&value)
+}
+
+pub fn save_project_settings_sections(
+    project_dir: &Path,
+    scope: ClaudeSettingsScope,
+    sections: &ClaudeSettingsSections,
+) -> Result<(), String> {
+    claude_settings_sections::validate_sections(sections)?;
+    let mut value = read_project_settings_layer(project_dir, scope)?;
+    let doc = value
+        .as_object_mut()
+        .ok_or("Settings file root must be a JSON object")?;
+    claude_settings_sections::apply_sections_to_settings(doc, sections)?;
+    save_project_settings_layer(project_dir, scope, value)
+}
+
+/// Merges a layer into `target`: objects are merged key by key, arrays are
+/// concatenated without duplicates and anything else is replaced. Records
+/// which scope set each leaf key.
+fn merge_layer(
+    target: &mut Map<String, Value>,
+    layer: &Map<String, Value>,
+    scope: ClaudeSettingsScope,
+    prefix: &[String],
+    sources: &mut BTreeMap<Vec<String>, Vec<ClaudeSettingsScope>>,
+) {
+    for (key, value) in layer {
+        let mut path = prefix.to_vec();
+        path.push(key.clone());
+        match (target.get_mut(key), value) {
+            (Some(Value::Object(existing)), Value::Object(incoming)) => {
+                merge_layer(existing, incoming, scope, &path, sources);
+            }
+            (None, Value::Object(incoming)) => {
+                let mut fresh = Map::new();
+                merge_layer(&mut fresh, incoming, scope, &path, sources);
+                target.insert(key.clone(), Value::Object(fresh));
+            }
+            (Some(Value::Array(existing)), Value::Array(incoming)) => {
+                for item in incoming {
+                    if !existing.contains(item) {
+                        existing.push(item.clone());
+                    }
+                }
+                sources.entry(path).or_default().push(scope);
+            }
+            (_, value) => {
+                sources.retain(|k, _| !(k.len() > path.len() && k.starts_with(&path)));
+                target.insert(key.clone(), value.clone());
+                sources.entry(path).or_default().push(scope);
+            }
+        }
+    }
+}
+
+/// Merges the user, project and local layers of `project_dir`.
+pub fn effective_settings(project_dir: &Path) -> Result<ClaudeEffectiveSettings, String> {
+    effective_settings_for_home(&system_home_dir()?, project_dir)
+}
+
+pub fn effective_settings_for_home(
+    home_dir: &Path,
+    project_dir: &Path,
+) -> Result<ClaudeEffectiveSettings, String> {
+    let layers = list_settings_layers_for_home(home_dir, project_dir)?;
+    let mut merged = Map::new();
+    let mut scopes = BTreeMap::new();
+    for layer in &layers {
+        let value = read_settings_json(Path::new(&layer.path))
+            .map_err(|e| format!("{} ({}): {e}", layer.path, layer.scope.label()))?;
+        let Value::Object(map) = value else {
+            return Err(format!(
+                "{}: settings file root must be a JSON object",
+                layer.path
+            ));
+        };
+        merge_layer(&mut merged, &map, layer.scope, &[], &mut scopes);
+    }
+
+    let settings = Value::Object(merged);
+    let sources = scopes
+        .into_iter()
+        .map(|(path, scopes)| ClaudeSettingsKeySource {
+            key: path.join("."),
+            value: path
+                .iter()
+                .try_fold(&settings, |value, segment| value.get(segment))
+                .cloned()
+                .unwrap_or(Value::Null),
+            scopes,
+        })
+        .collect();
+    Ok(ClaudeEffectiveSettings {
+        layers,
+        settings,
+        sources,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -931,4 +1240,202 @@ mod tests {
         let second = migrate_legacy_profiles_for_home(home_dir).unwrap();
         assert_eq!(second, 0);
     }
+
+    #[test]
+    fn sections_round_trip_without_touching_other_keys() {
+        let temp = TempDir::new().unwrap();
+        let home_dir = temp.path();
+        write_file(
+            &home_dir.join(".droidgear/claude-settings/team.json"),
+            r#"{"env":{"A":"1"},"outputStyle":"Explanatory"}"#,
+        );
+
+        let mut sections = read_settings_sections_for_home(home_dir, "team").unwrap();
+        assert_eq!(sections.output_style.as_deref(), Some("Explanatory"));
+        sections.api_key_helper = Some("~/bin/key.sh".to_string());
+        sections.output_style = None;
+        save_settings_sections_for_home(home_dir, "team", &sections).unwrap();
+
+        let value = read_settings_file_for_home(home_dir, "team").unwrap();
+        assert_eq!(value["apiKeyHelper"], "~/bin/key.sh");
+        assert!(value.get("outputStyle").is_none());
+        assert_eq!(value["env"]["A"], "1");
+
+        sections.api_key_helper = Some(" ".to_string());
+        let err = save_settings_sections_for_home(home_dir, "team", &sections).unwrap_err();
+        assert!(err.contains("apiKeyHelper"));
+    }
+
+    #[test]
+    fn merge_strategies_control_each_section() {
+        let temp = TempDir::new().unwrap();
+        let home_dir = temp.path();
+        write_file(
+            &home_dir.join(".claude/settings.json"),
+            r#"{
+              "env":{"LIVE":"1"},
+              "permissions":{"allow":["Bash(ls)"]},
+              "hooks":{"Stop":[{"hooks":[{"type":"command","command":"live"}]}]},
+              "outputStyle":"Live"
+            }"#,
+        );
+        write_file(
+            &home_dir.join(".droidgear/claude-settings/team.json"),
+            r#"{
+              "env":{"TEAM":"1"},
+              "permissions":{"allow":["Bash(git status)"]},
+              "hooks":{"Stop":[{"hooks":[{"type":"command","command":"team"}]}]},
+              "outputStyle":"Team"
+            }"#,
+        );
+
+        merge_settings_file_to_global_for_home(home_dir, "team").unwrap();
+        let merged = read_settings_file_for_home(home_dir, "Global").unwrap();
+        assert_eq!(merged["env"]["LIVE"], "1");
+        assert_eq!(merged["permissions"]["allow"].as_array().unwrap().len(), 1);
+        assert_eq!(merged["hooks"]["Stop"].as_array().unwrap().len(), 1);
+
+        write_file(
+            &home_dir.join(".claude/settings.json"),
+            r#"{
+              "permissions":{"allow":["Bash(ls)"]},
+              "hooks":{"Stop":[{"hooks":[{"type":"command","command":"live"}]}]},
+              "outputStyle":"Live"
+            }"#,
+        );
+        let mut options = ClaudeMergeOptions::appending();
+        options.strategies.insert(
+            "outputStyle".to_string(),
+            claude_settings_sections::ClaudeMergeStrategy::Keep,
+        );
+        merge_settings_file_to_global_with_options_for_home(home_dir, "team", &options).unwrap();
+        let merged = read_settings_file_for_home(home_dir, "Global").unwrap();
+        assert_eq!(
+            merged["permissions"]["allow"],
+            serde_json::json!(["Bash(ls)", "Bash(git status)"])
+        );
+        let stop = merged["hooks"]["Stop"].as_array().unwrap();
+        assert_eq!(stop.len(), 2);
+        assert_eq!(stop[1]["hooks"][0]["command"], "team");
+        assert_eq!(merged["outputStyle"], "Live");
+    }
+
+    #[test]
+    fn merge_rejects_invalid_hooks_and_warns_on_unknown_ones() {
+        let temp = TempDir::new().unwrap();
+        let home_dir = temp.path();
+        write_file(&home_dir.join(".claude/settings.json"), "{}");
+        write_file(
+            &home_dir.join(".droidgear/claude-settings/bad.json"),
+            r#"{"hooks":{"Stop":[{"hooks":[{"type":"command","command":" "}]}]}}"#,
+        );
+
+        let err = merge_settings_file_to_global_for_home(home_dir, "bad").unwrap_err();
+        assert!(err.contains("empty command"));
+        assert_eq!(
+            read_settings_file_for_home(home_dir, "Global").unwrap(),
+            serde_json::json!({})
+        );
+
+        // Events and hook types DroidGear does not know are merged with a warning.
+        write_file(
+            &home_dir.join(".droidgear/claude-settings/newer.json"),
+            r#"{"hooks":{"NewEvent":[{"hooks":[{"type":"command","command":"x"}]}]}}"#,
+        );
+        let warnings = merge_settings_file_to_global_for_home(home_dir, "newer").unwrap();
+        assert_eq!(warnings, ["Unknown hook event 'NewEvent'"]);
+        let merged = read_settings_file_for_home(home_dir, "Global").unwrap();
+        assert_eq!(merged["hooks"]["NewEvent"][0]["hooks"][0]["command"], "x");
+    }
+
+    #[test]
+    fn effective_settings_merge_layers_with_provenance() {
+        let temp = TempDir::new().unwrap();
+        let home_dir = temp.path().join("home");
+        let project = temp.path().join("repo");
+        write_file(
+            &home_dir.join(".claude/settings.json"),
+            r#"{"model":"opus","env":{"A":"1","B":"1"},"permissions":{"allow":["Read"]}}"#,
+        );
+        write_file(
+            &project.join(".claude/settings.json"),
+            r#"{"env":{"B":"2"},"permissions":{"allow":["Bash(npm test)","Read"]}}"#,
+        );
+        write_file(
+            &project.join(".claude/settings.local.json"),
+            r#"{"model":"sonnet"}"#,
+        );
+
+        let effective = effective_settings_for_home(&home_dir, &project).unwrap();
+        assert!(effective.layers.iter().all(|l| l.exists));
+        assert_eq!(effective.settings["model"], "sonnet");
+        assert_eq!(effective.settings["env"]["A"], "1");
+        assert_eq!(effective.settings["env"]["B"], "2");
+        assert_eq!(
+            effective.settings["permissions"]["allow"],
+            serde_json::json!(["Read", "Bash(npm test)"])
+        );
+
+        let scopes = |key: &str| {
+            effective
+                .sources
+                .iter()
+                .find(|s| s.key == key)
+                .map(|s| s.scopes.clone())
+                .unwrap()
+        };
+        use ClaudeSettingsScope::*;
+        assert_eq!(scopes("model"), vec![User, Local]);
+        assert_eq!(scopes("env.A"), vec![User]);
+        assert_eq!(scopes("env.B"), vec![User, Project]);
+        assert_eq!(scopes("permissions.allow"), vec![User, Project]);
+    }
+
+    #[test]
+    fn effective_settings_drop_nested_sources_when_replaced() {
+        let temp = TempDir::new().unwrap();
+        let home_dir = temp.path().join("home");
+        let project = temp.path().join("repo");
+        write_file(
+            &home_dir.join(".claude/settings.json"),
+            r#"{"statusLine":{"type":"command","command":"x"}}"#,
+        );
+        write_file(
+            &project.join(".claude/settings.json"),
+            r#"{"statusLine":null}"#,
+        );
+
+        let effective = effective_settings_for_home(&home_dir, &project).unwrap();
+        let keys: Vec<_> = effective.sources.iter().map(|s| s.key.as_str()).collect();
+        assert_eq!(keys, vec!["statusLine"]);
+        assert!(!effective.layers[2].exists);
+    }
+
+    #[test]
+    fn project_layers_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path();
+
+        assert_eq!(
+            read_project_settings_layer(project, ClaudeSettingsScope::Local).unwrap(),
+            serde_json::json!({})
+        );
+        save_project_settings_layer(
+            project,
+            ClaudeSettingsScope::Local,
+            serde_json::json!({"model":"opus","env":{"ANTHROPIC_MODEL":"sonnet"}}),
+        )
+        .unwrap();
+        let saved = read_project_settings_layer(project, ClaudeSettingsScope::Local).unwrap();
+        assert!(saved.get("model").is_none());
+        assert!(project.join(".claude/settings.local.json").exists());
+
+        assert!(project_settings_layer_path(project, ClaudeSettingsScope::User).is_err());
+        assert!(save_project_settings_layer(
+            project,
+            ClaudeSettingsScope::Project,
+            serde_json::json!([])
+        )
+        .is_err());
+    }
 }
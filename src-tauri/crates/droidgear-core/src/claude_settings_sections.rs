@@ -0,0 +1,476 @@
+//! Typed Claude Code settings sections (core).
+//!
+//! `claude_settings_files` stores settings documents as raw JSON. The
+//! sections that carry most team customization — `hooks`, `statusLine`,
+//! `apiKeyHelper`, `outputStyle` and `enabledPlugins` — get typed models
+//! here so they can be validated and edited structurally.
+//!
+//! This module also defines the per-key merge strategies used when a
+//! settings file is merged into the global `settings.json`.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use specta::Type;
+use std::collections::BTreeMap;
+
+/// Hook events known to DroidGear. Other events are kept and reported by
+/// [`section_warnings`], since newer Claude Code versions add more.
+pub const CLAUDE_HOOK_EVENTS: [&str; 9] = [
+    "PreToolUse",
+    "PostToolUse",
+    "Notification",
+    "UserPromptSubmit",
+    "Stop",
+    "SubagentStop",
+    "PreCompact",
+    "SessionStart",
+    "SessionEnd",
+];
+
+/// Events whose matcher is a regex over tool names.
+const TOOL_MATCHER_EVENTS: [&str; 2] = ["PreToolUse", "PostToolUse"];
+
+/// Top-level settings keys covered by [`ClaudeSettingsSections`].
+pub const CLAUDE_SECTION_KEYS: [&str; 5] = [
+    "hooks",
+    "statusLine",
+    "apiKeyHelper",
+    "outputStyle",
+    "enabledPlugins",
+];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A single hook: a shell command, or a prompt evaluated by the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeHook {
+    /// `command` or `prompt`; other types are kept as is.
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    /// Timeout in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u32>,
+    /// Fields DroidGear does not model, written back unchanged.
+    #[serde(flatten)]
+    #[specta(skip)]
+    pub extra: Map<String, Value>,
+}
+
+/// Hooks that run when `matcher` matches (all of them when it is empty).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeHookMatcher {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matcher: Option<String>,
+    pub hooks: Vec<ClaudeHook>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeStatusLine {
+    /// Always `command`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub padding: Option<u32>,
+    /// Fields DroidGear does not model, written back unchanged.
+    #[serde(flatten)]
+    #[specta(skip)]
+    pub extra: Map<String, Value>,
+}
+
+/// The typed sections of a Claude settings document. `None` means the key
+/// is absent from the document.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeSettingsSections {
+    /// Hook matchers keyed by event name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<BTreeMap<String, Vec<ClaudeHookMatcher>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_line: Option<ClaudeStatusLine>,
+    /// Script whose output is used as the API key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_helper: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_style: Option<String>,
+    /// `plugin@marketplace` -> enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled_plugins: Option<BTreeMap<String, bool>>,
+}
+
+/// How a top-level key of a settings file is merged into the global file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ClaudeMergeStrategy {
+    /// The source value replaces the live one.
+    Replace,
+    /// Objects are shallow-merged, source keys winning.
+    Merge,
+    /// Arrays are concatenated without duplicates, recursively inside objects
+    /// (e.g. hooks are added next to the live hooks for the same event).
+    Append,
+    /// The live value is kept; the source value is only used when the key is
+    /// missing from the global file.
+    Keep,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeMergeOptions {
+    /// Strategy per top-level key. Unlisted keys use
+    /// [`default_merge_strategy`].
+    #[serde(default)]
+    pub strategies: BTreeMap<String, ClaudeMergeStrategy>,
+}
+
+impl ClaudeMergeOptions {
+    /// Appends hooks and permission rules instead of replacing them.
+    pub fn appending() -> Self {
+        Self {
+            strategies: BTreeMap::from([
+                ("hooks".to_string(), ClaudeMergeStrategy::Append),
+                ("permissions".to_string(), ClaudeMergeStrategy::Append),
+            ]),
+        }
+    }
+
+    pub fn strategy_for(&self, key: &str) -> ClaudeMergeStrategy {
+        self.strategies
+            .get(key)
+            .copied()
+            .unwrap_or_else(|| default_merge_strategy(key))
+    }
+}
+
+/// `env` and `permissions` are shallow-merged; everything else is replaced.
+pub fn default_merge_strategy(key: &str) -> ClaudeMergeStrategy {
+    match key {
+        "env" | "permissions" => ClaudeMergeStrategy::Merge,
+        _ => ClaudeMergeStrategy::Replace,
+    }
+}
+
+// ============================================================================
+// Reading / writing sections
+// ============================================================================
+
+fn parse_section<T: serde::de::DeserializeOwned>(
+    doc: &Map<String, Value>,
+    key: &str,
+) -> Result<Option<T>, String> {
+    match doc.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| format!("Invalid `{key}`: {e}")),
+    }
+}
+
+/// Extracts and validates the typed sections of a settings document.
+pub fn sections_from_settings(value: &Value) -> Result<ClaudeSettingsSections, String> {
+    let doc = value
+        .as_object()
+        .ok_or("Settings file root must be a JSON object")?;
+    let sections = ClaudeSettingsSections {
+        hooks: parse_section(doc, "hooks")?,
+        status_line: parse_section(doc, "statusLine")?,
+        api_key_helper: parse_section(doc, "apiKeyHelper")?,
+        output_style: parse_section(doc, "outputStyle")?,
+        enabled_plugins: parse_section(doc, "enabledPlugins")?,
+    };
+    validate_sections(&sections)?;
+    Ok(sections)
+}
+
+/// Writes the sections into a settings document, removing keys whose
+/// section is `None`. Other keys are left untouched.
+pub fn apply_sections_to_settings(
+    doc: &mut Map<String, Value>,
+    sections: &ClaudeSettingsSections,
+) -> Result<(), String> {
+    let value = serde_json::to_value(sections)
+        .map_err(|e| format!("Failed to serialize settings sections: {e}"))?;
+    let Value::Object(mut rendered) = value else {
+        return Ok(());
+    };
+    for key in CLAUDE_SECTION_KEYS {
+        match rendered.remove(key) {
+            Some(value) => doc.insert(key.to_string(), value),
+            None => doc.remove(key),
+        };
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+fn non_empty(value: Option<&str>) -> bool {
+    value.is_some_and(|s| !s.trim().is_empty())
+}
+
+fn validate_hook(event: &str, hook: &ClaudeHook) -> Result<(), String> {
+    match hook.kind.as_str() {
+        "command" if !non_empty(hook.command.as_deref()) => {
+            Err(format!("A command hook for {event} has an empty command"))
+        }
+        "prompt" if !non_empty(hook.prompt.as_deref()) => {
+            Err(format!("A prompt hook for {event} has an empty prompt"))
+        }
+        _ => match hook.timeout {
+            Some(0) => Err(format!("Hook timeout for {event} must be positive")),
+            _ => Ok(()),
+        },
+    }
+}
+
+fn validate_hooks(hooks: &BTreeMap<String, Vec<ClaudeHookMatcher>>) -> Result<(), String> {
+    for (event, matchers) in hooks {
+        for group in matchers {
+            let matcher = group.matcher.as_deref().unwrap_or("");
+            if group.hooks.is_empty() {
+                return Err(format!("Matcher '{matcher}' for {event} has no hooks"));
+            }
+            if TOOL_MATCHER_EVENTS.contains(&event.as_str())
+                && !matcher.is_empty()
+                && matcher != "*"
+            {
+                Regex::new(matcher)
+                    .map_err(|e| format!("Invalid matcher '{matcher}' for {event}: {e}"))?;
+            }
+            for hook in &group.hooks {
+                validate_hook(event, hook)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Hook events and types DroidGear does not know. They are kept as is, so
+/// these are reported to the user rather than rejected.
+pub fn section_warnings(sections: &ClaudeSettingsSections) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for (event, matchers) in sections.hooks.iter().flatten() {
+        if !CLAUDE_HOOK_EVENTS.contains(&event.as_str()) {
+            warnings.push(format!("Unknown hook event '{event}'"));
+        }
+        for hook in matchers.iter().flat_map(|group| &group.hooks) {
+            if !matches!(hook.kind.as_str(), "command" | "prompt") {
+                warnings.push(format!("Unknown hook type '{}' for {event}", hook.kind));
+            }
+        }
+    }
+    warnings
+}
+
+pub fn validate_sections(sections: &ClaudeSettingsSections) -> Result<(), String> {
+    if let Some(hooks) = &sections.hooks {
+        validate_hooks(hooks)?;
+    }
+    if let Some(status_line) = &sections.status_line {
+        if status_line.kind != "command" {
+            return Err(format!(
+                "Unsupported statusLine type '{}'",
+                status_line.kind
+            ));
+        }
+        if status_line.command.trim().is_empty() {
+            return Err("statusLine command cannot be empty".to_string());
+        }
+    }
+    if sections.api_key_helper.is_some() && !non_empty(sections.api_key_helper.as_deref()) {
+        return Err("apiKeyHelper cannot be empty".to_string());
+    }
+    if sections.output_style.is_some() && !non_empty(sections.output_style.as_deref()) {
+        return Err("outputStyle cannot be empty".to_string());
+    }
+    for plugin in sections.enabled_plugins.iter().flat_map(|p| p.keys()) {
+        let valid = plugin
+            .split_once('@')
+            .is_some_and(|(name, market)| !name.is_empty() && !market.is_empty());
+        if !valid {
+            return Err(format!(
+                "Plugin '{plugin}' must be written as plugin@marketplace"
+            ));
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Merging
+// ============================================================================
+
+fn append_values(live: &Value, source: &Value) -> Value {
+    match (live, source) {
+        (Value::Object(live_obj), Value::Object(source_obj)) => {
+            let mut merged = live_obj.clone();
+            for (key, value) in source_obj {
+                let next = match live_obj.get(key) {
+                    Some(existing) => append_values(existing, value),
+                    None => value.clone(),
+                };
+                merged.insert(key.clone(), next);
+            }
+            Value::Object(merged)
+        }
+        (Value::Array(live_items), Value::Array(source_items)) => {
+            let mut merged = live_items.clone();
+            for item in source_items {
+                if !merged.contains(item) {
+                    merged.push(item.clone());
+                }
+            }
+            Value::Array(merged)
+        }
+        _ => source.clone(),
+    }
+}
+
+/// Merges one top-level value according to `strategy`.
+pub fn merge_value(strategy: ClaudeMergeStrategy, live: Option<&Value>, source: &Value) -> Value {
+    let Some(live) = live else {
+        return source.clone();
+    };
+    match strategy {
+        ClaudeMergeStrategy::Replace => source.clone(),
+        ClaudeMergeStrategy::Keep => live.clone(),
+        ClaudeMergeStrategy::Append => append_values(live, source),
+        ClaudeMergeStrategy::Merge => match (live, source) {
+            (Value::Object(live_obj), Value::Object(source_obj)) => {
+                let mut merged = live_obj.clone();
+                for (key, value) in source_obj {
+                    merged.insert(key.clone(), value.clone());
+                }
+                Value::Object(merged)
+            }
+            _ => source.clone(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sections_parse_and_validate_hooks() {
+        let doc = json!({
+            "hooks": {
+                "PreToolUse": [{
+                    "matcher": "Edit|Write",
+                    "hooks": [{"type": "command", "command": "fmt.sh", "timeout": 30}]
+                }],
+                "Stop": [{"hooks": [{"type": "prompt", "prompt": "Summarize"}]}]
+            },
+            "statusLine": {"type": "command", "command": "~/.claude/status.sh"},
+            "enabledPlugins": {"formatter@team": true}
+        });
+        let sections = sections_from_settings(&doc).unwrap();
+        let hooks = sections.hooks.unwrap();
+        assert_eq!(
+            hooks["PreToolUse"][0].matcher.as_deref(),
+            Some("Edit|Write")
+        );
+        assert_eq!(hooks["PreToolUse"][0].hooks[0].timeout, Some(30));
+        assert_eq!(sections.status_line.unwrap().command, "~/.claude/status.sh");
+    }
+
+    #[test]
+    fn validation_rejects_malformed_sections() {
+        let cases = [
+            (
+                json!({"hooks": {"PreToolUse": [{"matcher": "(", "hooks": [{"type": "command", "command": "x"}]}]}}),
+                "Invalid matcher",
+            ),
+            (
+                json!({"hooks": {"Stop": [{"hooks": [{"type": "command", "command": " "}]}]}}),
+                "empty command",
+            ),
+            (json!({"hooks": {"Stop": [{"hooks": []}]}}), "has no hooks"),
+            (
+                json!({"statusLine": {"type": "static", "command": "x"}}),
+                "statusLine type",
+            ),
+            (json!({"apiKeyHelper": ""}), "apiKeyHelper"),
+            (
+                json!({"enabledPlugins": {"formatter": true}}),
+                "plugin@marketplace",
+            ),
+            (json!({"outputStyle": 3}), "Invalid `outputStyle`"),
+        ];
+        for (doc, expected) in cases {
+            let err = sections_from_settings(&doc).unwrap_err();
+            assert!(err.contains(expected), "{err} should contain {expected}");
+        }
+    }
+
+    #[test]
+    fn unknown_hook_events_and_types_are_warnings() {
+        let doc = json!({
+            "hooks": {
+                "PermissionRequest": [{"hooks": [{"type": "command", "command": "audit"}]}],
+                "Stop": [{"hooks": [{"type": "agent", "agent": "reviewer"}]}]
+            },
+            "statusLine": {"type": "command", "command": "status.sh", "refresh": 5}
+        });
+        let sections = sections_from_settings(&doc).unwrap();
+        assert_eq!(
+            section_warnings(&sections),
+            [
+                "Unknown hook event 'PermissionRequest'",
+                "Unknown hook type 'agent' for Stop",
+            ]
+        );
+
+        // Unmodelled fields survive a round trip.
+        let mut out = Map::new();
+        apply_sections_to_settings(&mut out, &sections).unwrap();
+        assert_eq!(out["hooks"]["Stop"][0]["hooks"][0]["agent"], "reviewer");
+        assert_eq!(out["statusLine"]["refresh"], 5);
+    }
+
+    #[test]
+    fn apply_sections_sets_and_removes_keys() {
+        let mut doc = json!({"env": {"A": "1"}, "outputStyle": "Explanatory"})
+            .as_object()
+            .unwrap()
+            .clone();
+        let sections = ClaudeSettingsSections {
+            api_key_helper: Some("~/bin/key.sh".to_string()),
+            ..Default::default()
+        };
+        apply_sections_to_settings(&mut doc, &sections).unwrap();
+        assert_eq!(doc["apiKeyHelper"], "~/bin/key.sh");
+        assert!(doc.get("outputStyle").is_none());
+        assert_eq!(doc["env"]["A"], "1");
+    }
+
+    #[test]
+    fn append_strategy_adds_hooks_without_duplicates() {
+        let shared = json!({"matcher": "Bash", "hooks": [{"type": "command", "command": "audit"}]});
+        let live = json!({"PreToolUse": [shared.clone()]});
+        let source = json!({
+            "PreToolUse": [shared, {"matcher": "Edit", "hooks": [{"type": "command", "command": "fmt"}]}],
+            "Stop": [{"hooks": [{"type": "command", "command": "notify"}]}]
+        });
+        let merged = merge_value(ClaudeMergeStrategy::Append, Some(&live), &source);
+        assert_eq!(merged["PreToolUse"].as_array().unwrap().len(), 2);
+        assert_eq!(merged["Stop"][0]["hooks"][0]["command"], "notify");
+
+        let kept = merge_value(ClaudeMergeStrategy::Keep, Some(&live), &source);
+        assert_eq!(kept, live);
+    }
+}
@@ -11,11 +11,13 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use specta::Type;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
-use crate::{json, paths, storage};
+use crate::mcp::{McpServer, McpServerType};
+use crate::mcp_sync::{self, McpSyncTarget};
+use crate::{codex_model_catalog, json, paths, storage};
 
 // ============================================================================
 // Types
@@ -24,23 +26,6 @@ use crate::{json, paths, storage};
 pub(crate) const OPENAI_API_KEY_FIELD: &str = "OPENAI_API_KEY";
 pub(crate) const EXPERIMENTAL_BEARER_TOKEN_FIELD: &str = "experimental_bearer_token";
 
-/// DeepSeek V4 models that require the Codex model catalog file
-/// (`model_catalog_json` in config.toml). Content mirrors the official
-/// DeepSeek setup script (codex-deepseek-setup.sh).
-const DEEPSEEK_V4_MODELS: [&str; 2] = ["deepseek-v4-flash", "deepseek-v4-pro"];
-
-/// MiMo models that require the Codex model catalog file
-/// (`model_catalog_json` in config.toml).
-const MIMO_MODELS: [&str; 2] = ["mimo-v2.5", "mimo-v2.5-pro"];
-
-/// Model catalog content for the DeepSeek V4 models, extracted verbatim
-/// from the official setup script.
-const DEEPSEEK_MODELS_JSON: &str = include_str!("../res/codex-models.json");
-
-/// Model catalog content for the MiMo models, extracted verbatim from the
-/// official MiMo Codex docs.
-const MIMO_MODELS_JSON: &str = include_str!("../res/codex-mimo-models.json");
-
 /// Codex Provider 配置（对应 config.toml 中的 [model_providers.<id>]）
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
@@ -93,6 +78,127 @@ pub struct CodexProfile {
     /// Saved Codex auth profile name to restore on apply (openai mode only).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub auth_profile_name: Option<String>,
+    /// Sandbox, approval and execution settings written to config.toml.
+    #[serde(default, skip_serializing_if = "CodexExecutionSettings::is_empty")]
+    pub execution: CodexExecutionSettings,
+    /// MCP servers written to `[mcp_servers.<name>]` while the profile is active.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp_servers: Vec<McpServer>,
+    /// `[features]` flags set while the profile is active.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub features: BTreeMap<String, bool>,
+    /// Top-level `web_search` mode, one of [`CODEX_WEB_SEARCH_MODES`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_search: Option<String>,
+}
+
+pub const CODEX_SANDBOX_MODES: [&str; 3] = ["read-only", "workspace-write", "danger-full-access"];
+pub const CODEX_APPROVAL_POLICIES: [&str; 4] = ["untrusted", "on-failure", "on-request", "never"];
+pub const CODEX_MODEL_VERBOSITIES: [&str; 3] = ["low", "medium", "high"];
+pub const CODEX_REASONING_SUMMARIES: [&str; 4] = ["auto", "concise", "detailed", "none"];
+pub const CODEX_SHELL_ENV_INHERIT: [&str; 3] = ["all", "core", "none"];
+pub const CODEX_WEB_SEARCH_MODES: [&str; 3] = ["disabled", "cached", "live"];
+
+/// Top-level config.toml keys owned by [`CodexExecutionSettings`].
+const EXECUTION_KEYS: [&str; 8] = [
+    "sandbox_mode",
+    "approval_policy",
+    "sandbox_workspace_write",
+    "shell_environment_policy",
+    "notify",
+    "model_verbosity",
+    "model_reasoning_summary",
+    "hide_agent_reasoning",
+];
+
+/// Sandbox, approval and execution settings of a profile.
+///
+/// Applying a profile first removes the keys any saved profile sets, so
+/// switching away from a profile never leaves its settings behind. A profile
+/// with no settings leaves the other keys of config.toml alone; once any is
+/// set, applying the profile also removes the ones it leaves unset.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CodexExecutionSettings {
+    /// One of [`CODEX_SANDBOX_MODES`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox_mode: Option<String>,
+    /// One of [`CODEX_APPROVAL_POLICIES`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_policy: Option<String>,
+    /// `[sandbox_workspace_write]`, used with `sandbox_mode = "workspace-write"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox_workspace_write: Option<CodexSandboxWorkspaceWrite>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_environment_policy: Option<CodexShellEnvironmentPolicy>,
+    /// Command (program and arguments) run on agent notifications
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify: Option<Vec<String>>,
+    /// One of [`CODEX_MODEL_VERBOSITIES`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_verbosity: Option<String>,
+    /// One of [`CODEX_REASONING_SUMMARIES`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_reasoning_summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hide_agent_reasoning: Option<bool>,
+}
+
+/// `[sandbox_workspace_write]` in config.toml
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CodexSandboxWorkspaceWrite {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub writable_roots: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_access: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_tmpdir_env_var: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_slash_tmp: Option<bool>,
+}
+
+/// `[shell_environment_policy]` in config.toml
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CodexShellEnvironmentPolicy {
+    /// One of [`CODEX_SHELL_ENV_INHERIT`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_default_excludes: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include_only: Vec<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub set: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental_use_profile: Option<bool>,
+}
+
+impl CodexExecutionSettings {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The config.toml keys these settings write.
+    fn keys(&self) -> Vec<&'static str> {
+        [
+            self.sandbox_mode.is_some(),
+            self.approval_policy.is_some(),
+            self.sandbox_workspace_write.is_some(),
+            self.shell_environment_policy.is_some(),
+            self.notify.is_some(),
+            self.model_verbosity.is_some(),
+            self.model_reasoning_summary.is_some(),
+            self.hide_agent_reasoning.is_some(),
+        ]
+        .into_iter()
+        .zip(EXECUTION_KEYS)
+        .filter_map(|(set, key)| set.then_some(key))
+        .collect()
+    }
 }
 
 /// Codex Live 配置状态
@@ -117,6 +223,8 @@ pub struct CodexCurrentConfig {
     pub model_reasoning_effort: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "CodexExecutionSettings::is_empty")]
+    pub execution: CodexExecutionSettings,
 }
 
 // ============================================================================
@@ -162,7 +270,7 @@ fn codex_auth_path_for_home(home_dir: &Path) -> Result<PathBuf, String> {
     Ok(codex_config_dir_for_home(home_dir)?.join("auth.json"))
 }
 
-fn codex_config_path_for_home(home_dir: &Path) -> Result<PathBuf, String> {
+pub(crate) fn codex_config_path_for_home(home_dir: &Path) -> Result<PathBuf, String> {
     Ok(codex_config_dir_for_home(home_dir)?.join("config.toml"))
 }
 
@@ -194,53 +302,13 @@ fn now_rfc3339() -> String {
 /// (`model_catalog_json` in config.toml). Each family keeps its own catalog
 /// under `~/.codex/model-catalogs/` so Codex only lists models that the
 /// configured endpoint actually serves.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum ModelCatalog {
-    DeepSeek,
-    Mimo,
-}
-
-impl ModelCatalog {
-    /// Catalog file name under `~/.codex/model-catalogs/`.
-    fn file_name(self) -> &'static str {
-        match self {
-            ModelCatalog::DeepSeek => "deepseek.json",
-            ModelCatalog::Mimo => "mimo.json",
-        }
-    }
-
-    /// Catalog content for this family.
-    fn content(self) -> &'static str {
-        match self {
-            ModelCatalog::DeepSeek => DEEPSEEK_MODELS_JSON,
-            ModelCatalog::Mimo => MIMO_MODELS_JSON,
-        }
-    }
-}
-
-/// Whether `model` belongs to a family that needs the Codex model catalog,
-/// and which one.
-pub(crate) fn catalog_for_model(model: &str) -> Option<ModelCatalog> {
-    let model = model.trim();
-    if DEEPSEEK_V4_MODELS.contains(&model) {
-        Some(ModelCatalog::DeepSeek)
-    } else if MIMO_MODELS.contains(&model) {
-        Some(ModelCatalog::Mimo)
-    } else {
-        None
-    }
-}
-
 /// Value for `model_catalog_json` in config.toml: `~/.codex/model-catalogs/<family>.json`
 /// when the codex home is the default `~/.codex`, otherwise the absolute
 /// path so a custom codex home does not dangle.
-fn model_catalog_json_value_for_home(
-    home_dir: &Path,
-    catalog: ModelCatalog,
-) -> Result<String, String> {
+fn model_catalog_json_value_for_home(home_dir: &Path, family: &str) -> Result<String, String> {
     let config_paths = paths::load_config_paths_for_home(home_dir);
     let codex_home = paths::get_codex_home_for_home(home_dir, &config_paths)?;
-    let rel = format!("model-catalogs/{}", catalog.file_name());
+    let rel = format!("model-catalogs/{family}.json");
     if codex_home == home_dir.join(".codex") {
         Ok(format!("~/.codex/{rel}"))
     } else {
@@ -262,9 +330,12 @@ pub fn sync_models_json_for_home(home_dir: &Path, model: &str) -> Result<(), Str
             .map_err(|e| format!("Failed to remove legacy codex models.json: {e}"))?;
     }
 
-    if let Some(catalog) = catalog_for_model(model) {
-        let catalog_path = codex_dir.join("model-catalogs").join(catalog.file_name());
-        storage::atomic_write(&catalog_path, catalog.content().as_bytes())
+    if let Some(family) = codex_model_catalog::catalog_family_for_model_for_home(home_dir, model)? {
+        let content = codex_model_catalog::render_codex_model_catalog_for_home(home_dir, &family)?;
+        let catalog_path = codex_dir
+            .join("model-catalogs")
+            .join(format!("{family}.json"));
+        storage::atomic_write(&catalog_path, content.as_bytes())
             .map_err(|e| format!("Failed to write codex model catalog: {e}"))?;
     }
     Ok(())
@@ -461,14 +532,16 @@ pub(crate) fn apply_profile_to_config_map(
         );
     }
 
-    // Model families that ship a catalog (DeepSeek V4, MiMo) point
+    // Models with a catalog (see codex_model_catalog) point
     // model_catalog_json at their per-family catalog under model-catalogs/;
     // other models must not reference it, or Codex looks for a missing file.
-    match catalog_for_model(&resolved_model) {
-        Some(catalog) => {
+    let catalog_family =
+        codex_model_catalog::catalog_family_for_model_for_home(home_dir, &resolved_model)?;
+    match catalog_family.as_deref() {
+        Some(family) => {
             config.insert(
                 "model_catalog_json".to_string(),
-                toml::Value::String(model_catalog_json_value_for_home(home_dir, catalog)?),
+                toml::Value::String(model_catalog_json_value_for_home(home_dir, family)?),
             );
         }
         None => {
@@ -476,18 +549,333 @@ pub(crate) fn apply_profile_to_config_map(
         }
     }
 
+    let owned: BTreeSet<&str> = if profile.execution.is_empty() {
+        list_codex_profiles_for_home(home_dir)?
+            .iter()
+            .flat_map(|other| other.execution.keys())
+            .collect()
+    } else {
+        EXECUTION_KEYS.into()
+    };
+    for key in owned {
+        config.remove(key);
+    }
+    config.extend(execution_settings_to_toml(&profile.execution)?);
+
+    apply_toolset_to_config_map(config, profile, home_dir, catalog_family.as_deref())
+}
+
+/// Writes the profile's MCP servers, feature flags and web search mode.
+///
+/// Servers and features declared by other saved profiles are removed unless
+/// this profile declares them too; entries no profile declares are left alone.
+fn apply_toolset_to_config_map(
+    config: &mut toml::map::Map<String, toml::Value>,
+    profile: &CodexProfile,
+    home_dir: &Path,
+    catalog_family: Option<&str>,
+) -> Result<(), String> {
+    let mut toolset = profile_toolset_to_toml(home_dir, profile, catalog_family)?;
+    let mut owned_servers: BTreeSet<String> = BTreeSet::new();
+    let mut owned_features: BTreeSet<String> = BTreeSet::new();
+    for other in list_codex_profiles_for_home(home_dir)?
+        .iter()
+        .chain(std::iter::once(profile))
+    {
+        owned_servers.extend(other.mcp_servers.iter().map(|s| s.name.clone()));
+        owned_features.extend(other.features.keys().cloned());
+    }
+
+    for (key, owned) in [("mcp_servers", owned_servers), ("features", owned_features)] {
+        let mut table = match config.remove(key) {
+            Some(toml::Value::Table(table)) => table,
+            _ => toml::map::Map::new(),
+        };
+        table.retain(|name, _| !owned.contains(name));
+        if let Some(toml::Value::Table(entries)) = toolset.remove(key) {
+            table.extend(entries);
+        }
+        if !table.is_empty() {
+            config.insert(key.to_string(), toml::Value::Table(table));
+        }
+    }
+
+    config.remove("web_search");
+    if let Some(mode) = toolset.remove("web_search") {
+        config.insert("web_search".to_string(), mode);
+        // The deprecated `tools.web_search` flag would contradict the mode.
+        if let Some(toml::Value::Table(tools)) = config.get_mut("tools") {
+            tools.remove("web_search");
+            if tools.is_empty() {
+                config.remove("tools");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `[mcp_servers]`, `[features]` and `web_search` entries a profile brings
+/// along, as written to config.toml or passed as `-c` overrides.
+pub(crate) fn profile_toolset_to_toml(
+    home_dir: &Path,
+    profile: &CodexProfile,
+    catalog_family: Option<&str>,
+) -> Result<toml::map::Map<String, toml::Value>, String> {
+    let mut table = toml::map::Map::new();
+
+    if !profile.mcp_servers.is_empty() {
+        let mut names = BTreeSet::new();
+        for server in &profile.mcp_servers {
+            if server.name.trim().is_empty() {
+                return Err("MCP server name cannot be empty".to_string());
+            }
+            if !names.insert(server.name.as_str()) {
+                return Err(format!("Duplicate MCP server '{}'", server.name));
+            }
+            if server.config.server_type == McpServerType::Sse {
+                return Err(format!(
+                    "Codex does not support SSE MCP server '{}'",
+                    server.name
+                ));
+            }
+        }
+        let mut servers = toml::map::Map::new();
+        for (name, value) in mcp_sync::render_servers(
+            home_dir,
+            McpSyncTarget::Codex,
+            &profile.mcp_servers,
+            mcp_sync::HeaderMode::Deferred,
+        )? {
+            let value = toml::Value::try_from(value)
+                .map_err(|e| format!("Failed to convert MCP server '{name}': {e}"))?;
+            servers.insert(name, value);
+        }
+        table.insert("mcp_servers".to_string(), toml::Value::Table(servers));
+    }
+
+    if !profile.features.is_empty() {
+        let mut features = toml::map::Map::new();
+        for (name, enabled) in &profile.features {
+            if name.trim().is_empty() {
+                return Err("Feature name cannot be empty".to_string());
+            }
+            features.insert(name.clone(), toml::Value::Boolean(*enabled));
+        }
+        table.insert("features".to_string(), toml::Value::Table(features));
+    }
+
     // MiMo 当前不支持 web search，写入 config.toml 关闭。
     // 等 MiMo 网关支持后删除这段兼容。
-    if matches!(catalog_for_model(&resolved_model), Some(ModelCatalog::Mimo)) {
-        config.insert(
+    let web_search = if catalog_family == Some("mimo") {
+        Some("disabled")
+    } else {
+        profile.web_search.as_deref()
+    };
+    if let Some(mode) = web_search {
+        check_choice("web_search", mode, &CODEX_WEB_SEARCH_MODES)?;
+        table.insert(
             "web_search".to_string(),
-            toml::Value::String("disabled".to_string()),
+            toml::Value::String(mode.to_string()),
         );
+    }
+    Ok(table)
+}
+
+fn check_choice(field: &str, value: &str, allowed: &[&str]) -> Result<(), String> {
+    if allowed.contains(&value) {
+        Ok(())
     } else {
-        config.remove("web_search");
+        Err(format!(
+            "Invalid {field} '{value}': expected one of {}",
+            allowed.join(", ")
+        ))
     }
+}
 
-    Ok(())
+fn toml_string_array(values: &[String]) -> toml::Value {
+    toml::Value::Array(values.iter().cloned().map(toml::Value::String).collect())
+}
+
+/// Converts execution settings to the config.toml keys they own, rejecting
+/// values Codex would not accept.
+pub(crate) fn execution_settings_to_toml(
+    settings: &CodexExecutionSettings,
+) -> Result<toml::map::Map<String, toml::Value>, String> {
+    let mut table = toml::map::Map::new();
+    let mut put_choice =
+        |key: &str, value: &Option<String>, allowed: &[&str]| -> Result<(), String> {
+            if let Some(value) = value {
+                check_choice(key, value, allowed)?;
+                table.insert(key.to_string(), toml::Value::String(value.clone()));
+            }
+            Ok(())
+        };
+    put_choice("sandbox_mode", &settings.sandbox_mode, &CODEX_SANDBOX_MODES)?;
+    put_choice(
+        "approval_policy",
+        &settings.approval_policy,
+        &CODEX_APPROVAL_POLICIES,
+    )?;
+    put_choice(
+        "model_verbosity",
+        &settings.model_verbosity,
+        &CODEX_MODEL_VERBOSITIES,
+    )?;
+    put_choice(
+        "model_reasoning_summary",
+        &settings.model_reasoning_summary,
+        &CODEX_REASONING_SUMMARIES,
+    )?;
+
+    if let Some(write) = &settings.sandbox_workspace_write {
+        let mut sub = toml::map::Map::new();
+        if !write.writable_roots.is_empty() {
+            sub.insert(
+                "writable_roots".to_string(),
+                toml_string_array(&write.writable_roots),
+            );
+        }
+        for (key, value) in [
+            ("network_access", write.network_access),
+            ("exclude_tmpdir_env_var", write.exclude_tmpdir_env_var),
+            ("exclude_slash_tmp", write.exclude_slash_tmp),
+        ] {
+            if let Some(value) = value {
+                sub.insert(key.to_string(), toml::Value::Boolean(value));
+            }
+        }
+        table.insert(
+            "sandbox_workspace_write".to_string(),
+            toml::Value::Table(sub),
+        );
+    }
+
+    if let Some(policy) = &settings.shell_environment_policy {
+        let mut sub = toml::map::Map::new();
+        if let Some(inherit) = &policy.inherit {
+            check_choice(
+                "shell_environment_policy.inherit",
+                inherit,
+                &CODEX_SHELL_ENV_INHERIT,
+            )?;
+            sub.insert("inherit".to_string(), toml::Value::String(inherit.clone()));
+        }
+        for (key, value) in [
+            ("ignore_default_excludes", policy.ignore_default_excludes),
+            ("experimental_use_profile", policy.experimental_use_profile),
+        ] {
+            if let Some(value) = value {
+                sub.insert(key.to_string(), toml::Value::Boolean(value));
+            }
+        }
+        for (key, values) in [
+            ("exclude", &policy.exclude),
+            ("include_only", &policy.include_only),
+        ] {
+            if !values.is_empty() {
+                sub.insert(key.to_string(), toml_string_array(values));
+            }
+        }
+        if !policy.set.is_empty() {
+            sub.insert(
+                "set".to_string(),
+                toml::Value::Table(
+                    policy
+                        .set
+                        .iter()
+                        .map(|(k, v)| (k.clone(), toml::Value::String(v.clone())))
+                        .collect(),
+                ),
+            );
+        }
+        table.insert(
+            "shell_environment_policy".to_string(),
+            toml::Value::Table(sub),
+        );
+    }
+
+    if let Some(notify) = &settings.notify {
+        if notify.is_empty() {
+            return Err("notify must name a program to run".to_string());
+        }
+        table.insert("notify".to_string(), toml_string_array(notify));
+    }
+    if let Some(hide) = settings.hide_agent_reasoning {
+        table.insert(
+            "hide_agent_reasoning".to_string(),
+            toml::Value::Boolean(hide),
+        );
+    }
+
+    Ok(table)
+}
+
+fn toml_strings(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads the execution settings back from a parsed config.toml. Values are
+/// taken as written, without checking them against the known choices.
+pub(crate) fn toml_to_execution_settings(
+    config: &toml::map::Map<String, toml::Value>,
+) -> CodexExecutionSettings {
+    let string = |table: &toml::map::Map<String, toml::Value>, key: &str| {
+        table.get(key).and_then(|v| v.as_str()).map(str::to_string)
+    };
+    let boolean = |table: &toml::map::Map<String, toml::Value>, key: &str| {
+        table.get(key).and_then(|v| v.as_bool())
+    };
+
+    let sandbox_workspace_write = config
+        .get("sandbox_workspace_write")
+        .and_then(|v| v.as_table())
+        .map(|t| CodexSandboxWorkspaceWrite {
+            writable_roots: toml_strings(t.get("writable_roots")),
+            network_access: boolean(t, "network_access"),
+            exclude_tmpdir_env_var: boolean(t, "exclude_tmpdir_env_var"),
+            exclude_slash_tmp: boolean(t, "exclude_slash_tmp"),
+        });
+    let shell_environment_policy = config
+        .get("shell_environment_policy")
+        .and_then(|v| v.as_table())
+        .map(|t| CodexShellEnvironmentPolicy {
+            inherit: string(t, "inherit"),
+            ignore_default_excludes: boolean(t, "ignore_default_excludes"),
+            exclude: toml_strings(t.get("exclude")),
+            include_only: toml_strings(t.get("include_only")),
+            set: t
+                .get("set")
+                .and_then(|v| v.as_table())
+                .map(|set| {
+                    set.iter()
+                        .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            experimental_use_profile: boolean(t, "experimental_use_profile"),
+        });
+
+    CodexExecutionSettings {
+        sandbox_mode: string(config, "sandbox_mode"),
+        approval_policy: string(config, "approval_policy"),
+        sandbox_workspace_write,
+        shell_environment_policy,
+        notify: config
+            .get("notify")
+            .filter(|v| v.is_array())
+            .map(|v| toml_strings(Some(v))),
+        model_verbosity: string(config, "model_verbosity"),
+        model_reasoning_summary: string(config, "model_reasoning_summary"),
+        hide_agent_reasoning: boolean(config, "hide_agent_reasoning"),
+    }
 }
 
 pub(crate) fn apply_api_key_to_auth_map(
@@ -509,7 +897,7 @@ pub(crate) fn apply_api_key_to_auth_map(
 }
 
 /// Parse CodexProviderConfig from toml::Value
-fn toml_to_provider_config(value: &toml::Value) -> Result<CodexProviderConfig, String> {
+pub(crate) fn toml_to_provider_config(value: &toml::Value) -> Result<CodexProviderConfig, String> {
     let table = value.as_table().ok_or("Provider config must be a table")?;
 
     let name = table
@@ -797,6 +1185,10 @@ pub fn create_default_codex_profile_for_home(home_dir: &Path) -> Result<CodexPro
         model_reasoning_effort: Some("high".to_string()),
         api_key: Some(String::new()),
         auth_profile_name: None,
+        execution: Default::default(),
+        mcp_servers: Vec::new(),
+        features: Default::default(),
+        web_search: None,
     };
 
     write_profile_file(home_dir, &profile)?;
@@ -976,50 +1368,66 @@ pub fn read_codex_current_config_for_home(home_dir: &Path) -> Result<CodexCurren
     let config_path = codex_config_path_for_home(home_dir)?;
     let auth_path = codex_auth_path_for_home(home_dir)?;
 
-    let (providers, model_provider, model, model_reasoning_effort) = if config_path.exists() {
-        let s = std::fs::read_to_string(&config_path)
-            .map_err(|e| format!("Failed to read config.toml: {e}"))?;
-        if s.trim().is_empty() {
-            (HashMap::new(), "openai".to_string(), String::new(), None)
-        } else {
-            let config: toml::map::Map<String, toml::Value> =
-                toml::from_str(&s).map_err(|e| format!("Failed to parse config.toml: {e}"))?;
-
-            let providers = config
-                .get("model_providers")
-                .and_then(|v| v.as_table())
-                .map(|table| {
-                    table
-                        .iter()
-                        .filter_map(|(k, v)| {
-                            toml_to_provider_config(v).ok().map(|c| (k.clone(), c))
-                        })
-                        .collect::<HashMap<_, _>>()
-                })
-                .unwrap_or_default();
-
-            let model_provider = config
-                .get("model_provider")
-                .and_then(|v| v.as_str())
-                .unwrap_or("openai")
-                .to_string();
-
-            let model = config
-                .get("model")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let model_reasoning_effort = config
-                .get("model_reasoning_effort")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-
-            (providers, model_provider, model, model_reasoning_effort)
-        }
-    } else {
-        (HashMap::new(), "openai".to_string(), String::new(), None)
+    let empty = || {
+        (
+            HashMap::new(),
+            "openai".to_string(),
+            String::new(),
+            None,
+            CodexExecutionSettings::default(),
+        )
     };
+    let (providers, model_provider, model, model_reasoning_effort, execution) =
+        if config_path.exists() {
+            let s = std::fs::read_to_string(&config_path)
+                .map_err(|e| format!("Failed to read config.toml: {e}"))?;
+            if s.trim().is_empty() {
+                empty()
+            } else {
+                let config: toml::map::Map<String, toml::Value> =
+                    toml::from_str(&s).map_err(|e| format!("Failed to parse config.toml: {e}"))?;
+
+                let providers = config
+                    .get("model_providers")
+                    .and_then(|v| v.as_table())
+                    .map(|table| {
+                        table
+                            .iter()
+                            .filter_map(|(k, v)| {
+                                toml_to_provider_config(v).ok().map(|c| (k.clone(), c))
+                            })
+                            .collect::<HashMap<_, _>>()
+                    })
+                    .unwrap_or_default();
+
+                let model_provider = config
+                    .get("model_provider")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("openai")
+                    .to_string();
+
+                let model = config
+                    .get("model")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                let model_reasoning_effort = config
+                    .get("model_reasoning_effort")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+
+                (
+                    providers,
+                    model_provider,
+                    model,
+                    model_reasoning_effort,
+                    toml_to_execution_settings(&config),
+                )
+            }
+        } else {
+            empty()
+        };
 
     let mut providers = providers;
     if let Some(provider) = providers.get_mut(&model_provider) {
@@ -1059,6 +1467,7 @@ pub fn read_codex_current_config_for_home(home_dir: &Path) -> Result<CodexCurren
         model,
         model_reasoning_effort,
         api_key,
+        execution,
     })
 }
 
@@ -1121,10 +1530,12 @@ pub fn read_codex_current_config() -> Result<CodexCurrentConfig, String> {
 #[cfg(test)]
 mod tests {
     use super::{
-        apply_codex_profile_for_home, apply_profile_to_config_map, catalog_for_model,
-        provider_config_to_toml, resolve_active_provider, resolve_codex_profile_selector_for_home,
-        save_codex_profile_for_home, save_codex_profile_for_home_and_apply_if_active,
-        sync_models_json_for_home, CodexProfile, CodexProviderConfig, ModelCatalog,
+        apply_codex_profile_for_home, apply_profile_to_config_map, provider_config_to_toml,
+        read_codex_current_config_for_home, resolve_active_provider,
+        resolve_codex_profile_selector_for_home, save_codex_profile_for_home,
+        save_codex_profile_for_home_and_apply_if_active, sync_models_json_for_home,
+        CodexExecutionSettings, CodexProfile, CodexProviderConfig, CodexSandboxWorkspaceWrite,
+        CodexShellEnvironmentPolicy, McpServer, McpServerType,
     };
     use std::collections::HashMap;
     use tempfile::TempDir;
@@ -1142,6 +1553,10 @@ mod tests {
             model_reasoning_effort: None,
             api_key: None,
             auth_profile_name: None,
+            execution: Default::default(),
+            mcp_servers: Vec::new(),
+            features: Default::default(),
+            web_search: None,
         }
     }
 
@@ -1204,6 +1619,10 @@ mod tests {
             model_reasoning_effort: None,
             api_key: None,
             auth_profile_name: None,
+            execution: Default::default(),
+            mcp_servers: Vec::new(),
+            features: Default::default(),
+            web_search: None,
         };
 
         let (id, config) = resolve_active_provider(&profile);
@@ -1242,6 +1661,10 @@ mod tests {
             model_reasoning_effort: Some("high".to_string()),
             api_key: None,
             auth_profile_name: None,
+            execution: Default::default(),
+            mcp_servers: Vec::new(),
+            features: Default::default(),
+            web_search: None,
         };
 
         let temp = TempDir::new().unwrap();
@@ -1369,25 +1792,22 @@ mod tests {
     }
 
     #[test]
-    fn catalog_for_model_matches_deepseek_and_mimo_families() {
-        assert_eq!(
-            catalog_for_model("deepseek-v4-flash"),
-            Some(ModelCatalog::DeepSeek)
-        );
-        assert_eq!(
-            catalog_for_model("deepseek-v4-pro"),
-            Some(ModelCatalog::DeepSeek)
-        );
-        assert_eq!(
-            catalog_for_model("  deepseek-v4-flash  "),
-            Some(ModelCatalog::DeepSeek)
-        );
-        assert_eq!(catalog_for_model("mimo-v2.5-pro"), Some(ModelCatalog::Mimo));
-        assert_eq!(catalog_for_model("mimo-v2.5"), Some(ModelCatalog::Mimo));
-        assert_eq!(catalog_for_model("  mimo-v2.5  "), Some(ModelCatalog::Mimo));
-        assert_eq!(catalog_for_model("gpt-5"), None);
-        assert_eq!(catalog_for_model("deepseek-chat"), None);
-        assert_eq!(catalog_for_model(""), None);
+    fn catalog_family_matches_registry_and_curated_models() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let family = |model: &str| {
+            crate::codex_model_catalog::catalog_family_for_model_for_home(home, model).unwrap()
+        };
+        assert_eq!(family("deepseek-v4-flash").as_deref(), Some("deepseek"));
+        assert_eq!(family("  deepseek-v4-pro  ").as_deref(), Some("deepseek"));
+        assert_eq!(family("deepseek-chat").as_deref(), Some("deepseek"));
+        assert_eq!(family("mimo-v2.5-pro").as_deref(), Some("mimo"));
+        assert_eq!(family("  mimo-v2.5  ").as_deref(), Some("mimo"));
+        assert_eq!(family("glm-5").as_deref(), Some("glm"));
+        assert_eq!(family("gpt-5"), None);
+        assert_eq!(family("o3"), None);
+        assert_eq!(family("my-local-model"), None);
+        assert_eq!(family(""), None);
     }
 
     #[test]
@@ -1420,6 +1840,13 @@ mod tests {
         assert!(mimo_content.contains("mimo-v2.5"));
         assert!(deepseek_path.exists(), "other family catalogs are kept");
 
+        // Registry models get a generated catalog for their family.
+        sync_models_json_for_home(home, "glm-5").unwrap();
+        let glm_content =
+            std::fs::read_to_string(home.join(".codex").join("model-catalogs").join("glm.json"))
+                .unwrap();
+        assert!(glm_content.contains("\"slug\": \"glm-5\""));
+
         // Non-catalog models do not touch family catalogs.
         sync_models_json_for_home(home, "gpt-5").unwrap();
         assert!(deepseek_path.exists());
@@ -1478,6 +1905,10 @@ mod tests {
             model_reasoning_effort: None,
             api_key: None,
             auth_profile_name: None,
+            execution: Default::default(),
+            mcp_servers: Vec::new(),
+            features: Default::default(),
+            web_search: None,
         }
     }
 
@@ -1518,6 +1949,48 @@ mod tests {
             "models without a catalog must not reference one"
         );
     }
+
+    #[test]
+    fn apply_profile_to_config_map_catalogs_registry_aliases() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        let mut config = toml::map::Map::new();
+        apply_profile_to_config_map(&mut config, &sample_profile_with_model("deepseek-v3"), home)
+            .unwrap();
+        assert_eq!(
+            config.get("model_catalog_json").and_then(|v| v.as_str()),
+            Some("~/.codex/model-catalogs/deepseek.json")
+        );
+        sync_models_json_for_home(home, "deepseek-v3").unwrap();
+        let catalog = std::fs::read_to_string(
+            home.join(".codex")
+                .join("model-catalogs")
+                .join("deepseek.json"),
+        )
+        .unwrap();
+        assert!(catalog.contains("\"slug\": \"deepseek-v3\""));
+    }
+
+    #[test]
+    fn apply_profile_to_config_map_fails_on_broken_catalog_override() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let dir = crate::codex_model_catalog::user_catalogs_dir_for_home(home);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("deepseek.json"), "{not json").unwrap();
+
+        let mut config = toml::map::Map::new();
+        let err = apply_profile_to_config_map(
+            &mut config,
+            &sample_profile_with_model("deepseek-v4-flash"),
+            home,
+        )
+        .unwrap_err();
+        assert!(err.contains("deepseek.json"), "{err}");
+        assert!(sync_models_json_for_home(home, "deepseek-v4-flash").is_err());
+    }
+
     #[test]
     fn apply_profile_to_config_map_disables_web_search_for_mimo() {
         let temp = TempDir::new().unwrap();
@@ -1556,6 +2029,139 @@ mod tests {
         );
     }
 
+    #[test]
+    fn apply_profile_to_config_map_sets_profile_toolset() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        let mut profile = sample_profile_with_model("gpt-5");
+        profile.features = [("unified_exec".to_string(), false)].into();
+        profile.web_search = Some("cached".to_string());
+        profile.mcp_servers = vec![McpServer {
+            name: "remote".to_string(),
+            config: crate::mcp::McpServerConfig {
+                server_type: McpServerType::Http,
+                url: Some("https://mcp.example.com".to_string()),
+                headers: Some([("X-Key".to_string(), "k".to_string())].into()),
+                tool_timeout_sec: Some(30),
+                ..Default::default()
+            },
+        }];
+
+        let mut config: toml::map::Map<String, toml::Value> =
+            toml::from_str("[tools]\nweb_search = true\n\n[features]\nplan_tool = true\n").unwrap();
+        apply_profile_to_config_map(&mut config, &profile, home).unwrap();
+
+        let remote = &config["mcp_servers"]["remote"];
+        assert_eq!(remote["url"].as_str(), Some("https://mcp.example.com"));
+        assert_eq!(remote["http_headers"]["X-Key"].as_str(), Some("k"));
+        assert_eq!(remote["tool_timeout_sec"].as_integer(), Some(30));
+        assert_eq!(config["features"]["unified_exec"].as_bool(), Some(false));
+        assert_eq!(config["features"]["plan_tool"].as_bool(), Some(true));
+        assert_eq!(config["web_search"].as_str(), Some("cached"));
+        assert!(!config.contains_key("tools"), "legacy flag is dropped");
+
+        // Switching to a profile without a toolset removes what the first
+        // (saved) profile brought and keeps the rest.
+        save_codex_profile_for_home(home, profile).unwrap();
+        let mut plain = sample_profile_with_model("gpt-5");
+        plain.id = "plain".to_string();
+        apply_profile_to_config_map(&mut config, &plain, home).unwrap();
+        assert!(!config.contains_key("mcp_servers"));
+        assert!(!config["features"]
+            .as_table()
+            .unwrap()
+            .contains_key("unified_exec"));
+        assert!(!config.contains_key("web_search"));
+
+        let mut mimo = sample_profile_with_model("mimo-v2.5");
+        mimo.web_search = Some("live".to_string());
+        apply_profile_to_config_map(&mut config, &mimo, home).unwrap();
+        assert_eq!(config["web_search"].as_str(), Some("disabled"));
+
+        plain.web_search = Some("sometimes".to_string());
+        assert!(apply_profile_to_config_map(&mut config, &plain, home).is_err());
+    }
+
+    #[test]
+    fn execution_settings_apply_and_read_back() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let config_path = home.join(".codex").join("config.toml");
+        std::fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &config_path,
+            "approval_policy = \"untrusted\"\nnotify = [\"say\"]\n",
+        )
+        .unwrap();
+
+        // Profiles without execution settings leave the live keys alone.
+        let plain = sample_profile_with_model("gpt-5");
+        save_codex_profile_for_home(home, plain.clone()).unwrap();
+        apply_codex_profile_for_home(home, &plain.id).unwrap();
+        let current = read_codex_current_config_for_home(home).unwrap();
+        assert_eq!(
+            current.execution.approval_policy.as_deref(),
+            Some("untrusted")
+        );
+        assert_eq!(current.execution.notify, Some(vec!["say".to_string()]));
+
+        let mut locked = sample_profile_with_model("gpt-5");
+        locked.id = "review".to_string();
+        locked.execution = CodexExecutionSettings {
+            sandbox_mode: Some("workspace-write".to_string()),
+            approval_policy: Some("on-request".to_string()),
+            sandbox_workspace_write: Some(CodexSandboxWorkspaceWrite {
+                writable_roots: vec!["/srv/cache".to_string()],
+                network_access: Some(false),
+                exclude_slash_tmp: Some(true),
+                ..Default::default()
+            }),
+            shell_environment_policy: Some(CodexShellEnvironmentPolicy {
+                inherit: Some("none".to_string()),
+                include_only: vec!["PATH".to_string()],
+                set: [("LANG".to_string(), "C".to_string())].into(),
+                ..Default::default()
+            }),
+            model_verbosity: Some("low".to_string()),
+            model_reasoning_summary: Some("concise".to_string()),
+            hide_agent_reasoning: Some(true),
+            ..Default::default()
+        };
+        save_codex_profile_for_home(home, locked.clone()).unwrap();
+        apply_codex_profile_for_home(home, &locked.id).unwrap();
+
+        let current = read_codex_current_config_for_home(home).unwrap();
+        assert_eq!(current.execution, locked.execution);
+        let raw = std::fs::read_to_string(&config_path).unwrap();
+        assert!(raw.contains("[sandbox_workspace_write]"));
+        assert!(!raw.contains("notify"), "unset settings are removed");
+
+        // Switching back to a profile without execution settings removes what
+        // the full-auto profile wrote, but keeps keys no profile sets.
+        let mut auto = sample_profile_with_model("gpt-5");
+        auto.id = "auto".to_string();
+        auto.execution = CodexExecutionSettings {
+            sandbox_mode: Some("danger-full-access".to_string()),
+            approval_policy: Some("never".to_string()),
+            ..Default::default()
+        };
+        save_codex_profile_for_home(home, auto.clone()).unwrap();
+        apply_codex_profile_for_home(home, &auto.id).unwrap();
+        let raw = std::fs::read_to_string(&config_path).unwrap();
+        std::fs::write(&config_path, format!("notify = [\"say\"]\n{raw}")).unwrap();
+        apply_codex_profile_for_home(home, &plain.id).unwrap();
+        let current = read_codex_current_config_for_home(home).unwrap();
+        assert_eq!(current.execution.sandbox_mode, None);
+        assert_eq!(current.execution.approval_policy, None);
+        assert_eq!(current.execution.notify, Some(vec!["say".to_string()]));
+
+        locked.execution.sandbox_mode = Some("yolo".to_string());
+        save_codex_profile_for_home(home, locked.clone()).unwrap();
+        let err = apply_codex_profile_for_home(home, &locked.id).unwrap_err();
+        assert!(err.contains("sandbox_mode"), "{err}");
+    }
+
     #[test]
     fn save_active_profile_applies_immediately() {
         let temp = TempDir::new().unwrap();
@@ -7,8 +7,15 @@
 //! Official auth is detected by the presence of an `auth_mode` field
 //! in `auth.json` (e.g., `"chatgpt"`). BYOK auth only contains
 //! `OPENAI_API_KEY`.
-
-use chrono::Utc;
+//!
+//! The order of saved profiles doubles as the rotation order: when the
+//! active ChatGPT account hits its usage limit, [`rotate_profile_for_home`]
+//! marks it as limited and switches to the next official profile that is
+//! not limited. Before any saved snapshot is copied over the live
+//! `auth.json`, tokens Codex refreshed since the snapshot was taken are
+//! written back into the active profile so they are never lost.
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::path::{Path, PathBuf};
@@ -37,6 +44,17 @@ pub struct CodexAuthProfile {
     /// Live config.toml `model_reasoning_effort` snapshotted when this auth was saved.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub model_reasoning_effort: Option<String>,
+    /// RFC 3339 time until which the account is considered rate limited.
+    /// Rotation skips the profile until then.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limited_until: Option<String>,
+}
+
+impl CodexAuthProfile {
+    /// Whether the profile is still inside its rate-limit cooldown.
+    pub fn is_limited(&self) -> bool {
+        is_profile_limited(self, Utc::now())
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -44,6 +62,7 @@ pub struct CodexAuthProfile {
 struct ProfilesManifest {
     #[serde(default)]
     active: Option<String>,
+    /// Saved profiles, in rotation order.
     #[serde(default)]
     profiles: Vec<CodexAuthProfile>,
 }
@@ -64,6 +83,35 @@ pub struct CodexAuthConflictInfo {
     pub target_is_official: bool,
 }
 
+/// How the live `auth.json` relates to the active profile's saved snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexAuthFreshness {
+    pub active: Option<String>,
+    /// Whether the live login is the same ChatGPT account as the snapshot.
+    pub same_account: bool,
+    pub saved_last_refresh: Option<String>,
+    pub live_last_refresh: Option<String>,
+    /// Codex refreshed the live tokens after the snapshot was saved, so
+    /// restoring the snapshot as-is would discard them.
+    pub refreshed_since_save: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexAuthRotation {
+    /// Profile that was active (and is now marked as limited), if any.
+    pub from: Option<String>,
+    pub to: String,
+    /// Official profiles passed over because they are still limited.
+    pub skipped: Vec<String>,
+    pub limited_until: Option<String>,
+}
+
+/// Cooldown applied to a rotated-away account when none is given. Matches
+/// the length of ChatGPT's rolling usage window.
+pub const DEFAULT_LIMIT_COOLDOWN_MINUTES: u32 = 300;
+
 // ============================================================================
 // Path Helpers
 // ============================================================================
@@ -286,6 +334,71 @@ fn auth_json_exists(home_dir: &Path) -> bool {
         .unwrap_or(false)
 }
 
+fn auth_account_id(auth: &serde_json::Map<String, serde_json::Value>) -> Option<&str> {
+    auth.get("tokens")
+        .and_then(|t| t.get("account_id"))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+}
+
+fn auth_last_refresh(auth: &serde_json::Map<String, serde_json::Value>) -> Option<&str> {
+    auth.get("last_refresh")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Whether the live auth is a newer token set for the same account as `saved`.
+/// Without comparable `last_refresh` values, differing tokens count as newer.
+fn is_refreshed_since(
+    live: &serde_json::Map<String, serde_json::Value>,
+    saved: &serde_json::Map<String, serde_json::Value>,
+) -> bool {
+    let same_account = matches!(
+        (auth_account_id(live), auth_account_id(saved)),
+        (Some(a), Some(b)) if a == b
+    );
+    if !same_account {
+        return false;
+    }
+    match (
+        auth_last_refresh(live).and_then(parse_time),
+        auth_last_refresh(saved).and_then(parse_time),
+    ) {
+        (Some(live_at), Some(saved_at)) => live_at > saved_at,
+        _ => live.get("tokens") != saved.get("tokens"),
+    }
+}
+
+fn is_profile_limited(profile: &CodexAuthProfile, now: DateTime<Utc>) -> bool {
+    profile
+        .limited_until
+        .as_deref()
+        .and_then(parse_time)
+        .is_some_and(|until| until > now)
+}
+
+/// Copy the live auth.json into the active profile when Codex refreshed its
+/// tokens after the snapshot was saved. Returns whether the snapshot changed.
+fn sync_live_into_active_profile(home_dir: &Path) -> Result<bool, String> {
+    let freshness = check_auth_freshness_for_home(home_dir)?;
+    let Some(active) = freshness.active.filter(|_| freshness.refreshed_since_save) else {
+        return Ok(false);
+    };
+    let live_auth_path = codex_auth_path_for_home(home_dir)?;
+    std::fs::copy(
+        &live_auth_path,
+        auth_json_in_profile_dir_for_home(home_dir, &active),
+    )
+    .map_err(|e| format!("Failed to update auth profile '{active}' with refreshed tokens: {e}"))?;
+    Ok(true)
+}
+
 fn droidgear_codex_dir_for_home(home_dir: &Path) -> PathBuf {
     home_dir.join(".droidgear").join("codex")
 }
@@ -377,6 +490,7 @@ pub fn save_current_as_profile_for_home(
             codex_profile_id: active_codex_id,
             model,
             model_reasoning_effort,
+            limited_until: None,
         });
     } else if let Some(p) = manifest.profiles.iter_mut().find(|p| p.name == name) {
         p.label = label.to_string();
@@ -407,6 +521,7 @@ pub fn switch_profile_for_home(home_dir: &Path, name: &str) -> Result<(), String
     }
 
     let live_auth_path = codex_auth_path_for_home(home_dir)?;
+    sync_live_into_active_profile(home_dir)?;
 
     // Backup current auth to the currently active profile (if any)
     if let Some(ref current_active) = manifest.active {
@@ -506,6 +621,7 @@ pub fn restore_auth_file_for_home(home_dir: &Path, name: &str) -> Result<(), Str
     if let Some(codex_dir) = live_auth_path.parent() {
         ensure_dir(codex_dir)?;
     }
+    sync_live_into_active_profile(home_dir)?;
     std::fs::copy(&target_auth_path, &live_auth_path)
         .map_err(|e| format!("Failed to restore auth profile '{name}': {e}"))?;
 
@@ -585,6 +701,138 @@ pub fn detect_auth_conflict_for_home(
     })
 }
 
+/// Compare the live auth.json with the active profile's saved snapshot.
+pub fn check_auth_freshness_for_home(home_dir: &Path) -> Result<CodexAuthFreshness, String> {
+    let active = read_manifest(home_dir).active;
+    let live = if auth_json_exists(home_dir) {
+        read_live_auth(home_dir)?
+    } else {
+        serde_json::Map::new()
+    };
+    let saved = match active.as_deref() {
+        Some(name) => read_profile_auth(home_dir, name)?,
+        None => serde_json::Map::new(),
+    };
+
+    let same_account = matches!(
+        (auth_account_id(&live), auth_account_id(&saved)),
+        (Some(a), Some(b)) if a == b
+    );
+    Ok(CodexAuthFreshness {
+        same_account,
+        saved_last_refresh: auth_last_refresh(&saved).map(str::to_string),
+        live_last_refresh: auth_last_refresh(&live).map(str::to_string),
+        refreshed_since_save: active.is_some() && is_refreshed_since(&live, &saved),
+        active,
+    })
+}
+
+/// Reorder saved profiles. Listed names come first in the given order; any
+/// profile not listed keeps its relative position after them.
+pub fn set_rotation_order_for_home(home_dir: &Path, names: &[String]) -> Result<(), String> {
+    let mut manifest = read_manifest(home_dir);
+    let mut ordered = Vec::with_capacity(manifest.profiles.len());
+    for name in names {
+        if ordered.iter().any(|p: &CodexAuthProfile| &p.name == name) {
+            return Err(format!("Profile '{name}' is listed more than once"));
+        }
+        let index = manifest
+            .profiles
+            .iter()
+            .position(|p| &p.name == name)
+            .ok_or_else(|| format!("Profile '{name}' not found"))?;
+        ordered.push(manifest.profiles.remove(index));
+    }
+    ordered.append(&mut manifest.profiles);
+    manifest.profiles = ordered;
+    write_manifest(home_dir, &manifest)
+}
+
+/// Mark a profile as rate limited until `until` (RFC 3339), or clear the
+/// marker when `until` is `None`.
+pub fn set_profile_limited_until_for_home(
+    home_dir: &Path,
+    name: &str,
+    until: Option<&str>,
+) -> Result<(), String> {
+    if let Some(until) = until {
+        parse_time(until).ok_or_else(|| format!("Invalid time '{until}'"))?;
+    }
+    let mut manifest = read_manifest(home_dir);
+    let profile = manifest
+        .profiles
+        .iter_mut()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Profile '{name}' not found"))?;
+    profile.limited_until = until.map(str::to_string);
+    write_manifest(home_dir, &manifest)
+}
+
+/// Mark the active profile as limited for `cooldown_minutes` (default
+/// [`DEFAULT_LIMIT_COOLDOWN_MINUTES`]) and switch to the next official
+/// profile in rotation order that is not limited.
+pub fn rotate_profile_for_home(
+    home_dir: &Path,
+    cooldown_minutes: Option<u32>,
+) -> Result<CodexAuthRotation, String> {
+    let mut manifest = read_manifest(home_dir);
+    let now = Utc::now();
+    let from = manifest.active.clone();
+    let start = from
+        .as_deref()
+        .and_then(|name| manifest.profiles.iter().position(|p| p.name == name));
+
+    let mut limited_until = None;
+    if let Some(index) = start {
+        let minutes = cooldown_minutes.unwrap_or(DEFAULT_LIMIT_COOLDOWN_MINUTES);
+        let until = (now + Duration::minutes(i64::from(minutes)))
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+        manifest.profiles[index].limited_until = Some(until.clone());
+        limited_until = Some(until);
+    }
+
+    let count = manifest.profiles.len();
+    let offset = start.map(|i| i + 1).unwrap_or(0);
+    let mut skipped = Vec::new();
+    let mut target = None;
+    for step in 0..count {
+        let index = (offset + step) % count;
+        if Some(index) == start {
+            continue;
+        }
+        let profile = &manifest.profiles[index];
+        if !profile.is_official {
+            continue;
+        }
+        if is_profile_limited(profile, now) {
+            skipped.push(profile.name.clone());
+            continue;
+        }
+        target = Some(index);
+        break;
+    }
+
+    // Expired markers are dropped once the profile is picked again.
+    if let Some(index) = target {
+        manifest.profiles[index].limited_until = None;
+    }
+    write_manifest(home_dir, &manifest)?;
+
+    let index = target.ok_or_else(|| {
+        "No healthy Codex auth profile to rotate to; every other official login is limited"
+            .to_string()
+    })?;
+    let to = manifest.profiles[index].name.clone();
+    switch_profile_for_home(home_dir, &to)?;
+
+    Ok(CodexAuthRotation {
+        from,
+        to,
+        skipped,
+        limited_until,
+    })
+}
+
 // ============================================================================
 // Public API (system home wrappers)
 // ============================================================================
@@ -656,6 +904,22 @@ pub fn clear_active() -> Result<(), String> {
     clear_active_for_home(&system_home_dir()?)
 }
 
+pub fn check_auth_freshness() -> Result<CodexAuthFreshness, String> {
+    check_auth_freshness_for_home(&system_home_dir()?)
+}
+
+pub fn set_rotation_order(names: &[String]) -> Result<(), String> {
+    set_rotation_order_for_home(&system_home_dir()?, names)
+}
+
+pub fn set_profile_limited_until(name: &str, until: Option<&str>) -> Result<(), String> {
+    set_profile_limited_until_for_home(&system_home_dir()?, name, until)
+}
+
+pub fn rotate_profile(cooldown_minutes: Option<u32>) -> Result<CodexAuthRotation, String> {
+    rotate_profile_for_home(&system_home_dir()?, cooldown_minutes)
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -906,6 +1170,7 @@ model = ""
                 codex_profile_id: None,
                 model: None,
                 model_reasoning_effort: None,
+                limited_until: None,
             }],
         };
         write_manifest(tmp.path(), &manifest).unwrap();
@@ -926,4 +1191,121 @@ model = ""
         let conflict = detect_auth_conflict_for_home(tmp.path(), "acct-1").unwrap();
         assert!(!conflict.has_conflict);
     }
+
+    fn write_live_auth(home: &Path, account: &str, access: &str, last_refresh: &str) {
+        let auth = serde_json::json!({
+            "auth_mode": "chatgpt",
+            "tokens": {
+                "id_token": "id",
+                "access_token": access,
+                "refresh_token": "refresh",
+                "account_id": account
+            },
+            "last_refresh": last_refresh
+        });
+        let auth_path = codex_auth_path_for_home(home).unwrap();
+        std::fs::create_dir_all(auth_path.parent().unwrap()).unwrap();
+        std::fs::write(&auth_path, serde_json::to_string_pretty(&auth).unwrap()).unwrap();
+    }
+
+    fn live_access_token(home: &Path) -> String {
+        read_live_auth(home).unwrap()["tokens"]["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn refreshed_live_tokens_survive_switching_back_to_active_profile() {
+        let tmp = TempDir::new().unwrap();
+        write_live_auth(tmp.path(), "acct-a", "old", "2026-06-29T00:00:00Z");
+        save_current_as_profile_for_home(tmp.path(), "acct-1", "Account 1").unwrap();
+
+        write_live_auth(tmp.path(), "acct-a", "new", "2026-07-01T00:00:00Z");
+        let freshness = check_auth_freshness_for_home(tmp.path()).unwrap();
+        assert!(freshness.same_account);
+        assert!(freshness.refreshed_since_save);
+        assert_eq!(
+            freshness.saved_last_refresh.as_deref(),
+            Some("2026-06-29T00:00:00Z")
+        );
+
+        switch_profile_for_home(tmp.path(), "acct-1").unwrap();
+        assert_eq!(live_access_token(tmp.path()), "new");
+        let saved = read_profile_auth(tmp.path(), "acct-1").unwrap();
+        assert_eq!(saved["tokens"]["access_token"], "new");
+        assert!(
+            !check_auth_freshness_for_home(tmp.path())
+                .unwrap()
+                .refreshed_since_save
+        );
+    }
+
+    #[test]
+    fn freshness_ignores_a_different_live_account() {
+        let tmp = TempDir::new().unwrap();
+        write_live_auth(tmp.path(), "acct-a", "a", "2026-06-29T00:00:00Z");
+        save_current_as_profile_for_home(tmp.path(), "acct-1", "Account 1").unwrap();
+
+        write_live_auth(tmp.path(), "acct-b", "b", "2026-07-01T00:00:00Z");
+        let freshness = check_auth_freshness_for_home(tmp.path()).unwrap();
+        assert!(!freshness.same_account);
+        assert!(!freshness.refreshed_since_save);
+
+        restore_auth_file_for_home(tmp.path(), "acct-1").unwrap();
+        let saved = read_profile_auth(tmp.path(), "acct-1").unwrap();
+        assert_eq!(saved["tokens"]["account_id"], "acct-a");
+        assert_eq!(live_access_token(tmp.path()), "a");
+    }
+
+    #[test]
+    fn rotate_skips_limited_profiles_and_marks_the_current_one() {
+        let tmp = TempDir::new().unwrap();
+        for (name, account) in [("one", "a"), ("two", "b"), ("three", "c")] {
+            write_live_auth(tmp.path(), account, account, "2026-06-29T00:00:00Z");
+            save_current_as_profile_for_home(tmp.path(), name, name).unwrap();
+        }
+        switch_profile_for_home(tmp.path(), "one").unwrap();
+        let later = (Utc::now() + Duration::hours(1)).to_rfc3339();
+        set_profile_limited_until_for_home(tmp.path(), "two", Some(&later)).unwrap();
+
+        let rotation = rotate_profile_for_home(tmp.path(), Some(60)).unwrap();
+        assert_eq!(rotation.from.as_deref(), Some("one"));
+        assert_eq!(rotation.to, "three");
+        assert_eq!(rotation.skipped, vec!["two".to_string()]);
+        assert_eq!(live_access_token(tmp.path()), "c");
+
+        let state = list_profiles_for_home(tmp.path()).unwrap();
+        assert_eq!(state.active.as_deref(), Some("three"));
+        assert!(state.profiles[0].limited_until.is_some());
+
+        let err = rotate_profile_for_home(tmp.path(), None).unwrap_err();
+        assert!(err.contains("No healthy"));
+        assert_eq!(live_access_token(tmp.path()), "c");
+
+        set_profile_limited_until_for_home(tmp.path(), "two", None).unwrap();
+        let rotation = rotate_profile_for_home(tmp.path(), None).unwrap();
+        assert_eq!(rotation.to, "two");
+    }
+
+    #[test]
+    fn set_rotation_order_puts_listed_profiles_first() {
+        let tmp = TempDir::new().unwrap();
+        for name in ["one", "two", "three"] {
+            write_live_auth(tmp.path(), name, name, "2026-06-29T00:00:00Z");
+            save_current_as_profile_for_home(tmp.path(), name, name).unwrap();
+        }
+
+        set_rotation_order_for_home(tmp.path(), &["three".to_string()]).unwrap();
+        let names: Vec<_> = list_profiles_for_home(tmp.path())
+            .unwrap()
+            .profiles
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, ["three", "one", "two"]);
+
+        let err = set_rotation_order_for_home(tmp.path(), &["missing".to_string()]).unwrap_err();
+        assert!(err.contains("not found"));
+    }
 }
@@ -0,0 +1,371 @@
+//! Codex model catalogs (core).
+//!
+//! Codex only knows the context window and reasoning levels of OpenAI
+//! models; other models need an entry in the JSON file named by
+//! `model_catalog_json`. Catalogs are built per model family (`deepseek`,
+//! `glm`, `qwen`, ...) from three sources, later ones winning by `slug`:
+//!
+//! 1. entries generated from the bundled model registry, one per id and
+//!    alias so a profile may name a model either way,
+//! 2. the curated catalogs shipped for DeepSeek V4 and MiMo,
+//! 3. user overrides in `~/.droidgear/codex/catalogs/<family>.json`, whose
+//!    entries are merged key by key over the entry with the same slug (or
+//!    over a generated default when there is none).
+
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::model_registry::{self, RegistryModel};
+
+/// Curated catalog for the DeepSeek V4 models, extracted verbatim from the
+/// official setup script (codex-deepseek-setup.sh).
+const DEEPSEEK_MODELS_JSON: &str = include_str!("../res/codex-models.json");
+
+/// Curated catalog for the MiMo models, extracted verbatim from the official
+/// MiMo Codex docs.
+const MIMO_MODELS_JSON: &str = include_str!("../res/codex-mimo-models.json");
+
+/// Families Codex ships its own metadata for; they never get a catalog.
+const NATIVE_FAMILIES: [&str; 3] = ["gpt", "o", "codex"];
+
+fn curated_catalog(family: &str) -> Option<&'static str> {
+    match family {
+        "deepseek" => Some(DEEPSEEK_MODELS_JSON),
+        "mimo" => Some(MIMO_MODELS_JSON),
+        _ => None,
+    }
+}
+
+/// `~/.droidgear/codex/catalogs/`
+pub fn user_catalogs_dir_for_home(home_dir: &Path) -> PathBuf {
+    home_dir.join(".droidgear").join("codex").join("catalogs")
+}
+
+/// Family of `model`: its leading ASCII letters, lowercased
+/// (`deepseek-v4-pro` -> `deepseek`, `qwen3-coder-plus` -> `qwen`).
+pub fn model_family(model: &str) -> String {
+    model
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+fn catalog_models(content: &str) -> Result<Vec<Value>, String> {
+    let value: Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid model catalog: {e}"))?;
+    match value.get("models") {
+        Some(Value::Array(models)) => Ok(models.clone()),
+        _ => Err("Model catalog must have a \"models\" array".to_string()),
+    }
+}
+
+fn slug(entry: &Value) -> Option<&str> {
+    entry.get("slug").and_then(|v| v.as_str())
+}
+
+/// Entry fields every generated model shares, taken from the first curated
+/// DeepSeek entry so new Codex fields keep their upstream defaults.
+fn base_entry() -> Map<String, Value> {
+    let mut base = catalog_models(DEEPSEEK_MODELS_JSON)
+        .ok()
+        .and_then(|models| models.into_iter().next())
+        .and_then(|entry| entry.as_object().cloned())
+        .unwrap_or_default();
+    base.insert("default_verbosity".to_string(), Value::Null);
+    base.insert("support_verbosity".to_string(), Value::Bool(false));
+    base.insert("supports_search_tool".to_string(), Value::Bool(false));
+    base.insert("web_search_tool_type".to_string(), Value::Null);
+    base
+}
+
+fn effort_description(effort: &str) -> &'static str {
+    match effort {
+        "none" => "Disable reasoning",
+        "minimal" => "Minimal reasoning for the fastest responses",
+        "low" => "Fast responses with lighter reasoning",
+        "medium" => "Balanced speed and reasoning depth",
+        "high" => "Extra reasoning depth for complex problems",
+        "xhigh" => "Very deep reasoning for hard problems",
+        "max" => "Maximum reasoning depth for the hardest problems",
+        _ => "Reasoning effort",
+    }
+}
+
+/// Catalog entry for a registry model, listed under `slug_name` (its id or
+/// one of its aliases).
+fn registry_entry(model: &RegistryModel, slug_name: &str, priority: usize) -> Value {
+    let mut entry = base_entry();
+    let efforts: Vec<&str> = if model.reasoning {
+        model
+            .reasoning_config
+            .as_ref()
+            .map(|config| config.efforts.iter().map(String::as_str).collect())
+            .unwrap_or_else(|| vec!["low", "medium", "high"])
+            .into_iter()
+            .filter(|effort| {
+                // A `null` in the thinking level map marks an unsupported level.
+                !model
+                    .thinking_level_map
+                    .as_ref()
+                    .is_some_and(|map| matches!(map.get(*effort), Some(None)))
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let default_effort = ["high", "medium"]
+        .into_iter()
+        .find(|e| efforts.contains(e))
+        .or(efforts.first().copied());
+    let input: Vec<&str> = model
+        .input
+        .iter()
+        .map(String::as_str)
+        .filter(|m| matches!(*m, "text" | "image"))
+        .collect();
+    let auto_compact = model
+        .max_output_tokens
+        .filter(|max| *max < model.context_window)
+        .map(|max| model.context_window - max);
+
+    for (key, value) in [
+        ("slug", json!(slug_name)),
+        ("display_name", json!(model.name)),
+        (
+            "description",
+            json!(format!("{} (DroidGear registry)", model.name)),
+        ),
+        ("context_window", json!(model.context_window)),
+        ("max_context_window", json!(model.context_window)),
+        ("auto_compact_token_limit", json!(auto_compact)),
+        ("input_modalities", json!(input)),
+        ("supports_image_detail_original", json!(false)),
+        ("default_reasoning_level", json!(default_effort)),
+        (
+            "supported_reasoning_levels",
+            Value::Array(
+                efforts
+                    .iter()
+                    .map(|e| json!({"effort": e, "description": effort_description(e)}))
+                    .collect(),
+            ),
+        ),
+        ("supports_reasoning_summaries", json!(model.reasoning)),
+        ("priority", json!(priority)),
+    ] {
+        entry.insert(key.to_string(), value);
+    }
+    Value::Object(entry)
+}
+
+fn upsert(models: &mut Vec<Value>, entry: Value) {
+    match slug(&entry).and_then(|s| models.iter().position(|m| slug(m) == Some(s))) {
+        Some(index) => models[index] = entry,
+        None => models.push(entry),
+    }
+}
+
+fn read_user_catalog(home_dir: &Path, family: &str) -> Result<Vec<Value>, String> {
+    let path = user_catalogs_dir_for_home(home_dir).join(format!("{family}.json"));
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    catalog_models(&content).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Catalog entries for `family`, or an empty list for families Codex knows
+/// natively.
+pub fn build_codex_model_catalog_for_home(
+    home_dir: &Path,
+    family: &str,
+) -> Result<Vec<Value>, String> {
+    if family.is_empty() || NATIVE_FAMILIES.contains(&family) {
+        return Ok(Vec::new());
+    }
+
+    let mut models: Vec<Value> = model_registry::registry_models()
+        .iter()
+        .flat_map(|m| {
+            std::iter::once(&m.id)
+                .chain(&m.aliases)
+                .filter(|name| model_family(name) == family)
+                .map(move |name| (m, name))
+        })
+        .enumerate()
+        .map(|(i, (m, name))| registry_entry(m, name, i + 1))
+        .collect();
+    if let Some(curated) = curated_catalog(family) {
+        for entry in catalog_models(curated)? {
+            upsert(&mut models, entry);
+        }
+    }
+
+    for user in read_user_catalog(home_dir, family)? {
+        let Some(user_slug) = slug(&user).map(str::to_string) else {
+            return Err(format!(
+                "Entries in the {family} catalog override need a \"slug\""
+            ));
+        };
+        let mut merged = models
+            .iter()
+            .find(|m| slug(m) == Some(user_slug.as_str()))
+            .and_then(|m| m.as_object().cloned())
+            .unwrap_or_else(|| {
+                let mut entry = base_entry();
+                entry.insert("slug".to_string(), json!(user_slug));
+                entry.insert("display_name".to_string(), json!(user_slug));
+                entry
+            });
+        if let Some(fields) = user.as_object() {
+            merged.extend(fields.clone());
+        }
+        upsert(&mut models, Value::Object(merged));
+    }
+    Ok(models)
+}
+
+/// Family whose catalog has an entry for `model`, if any. Codex looks the
+/// model up by slug, so the configured name must match exactly.
+pub fn catalog_family_for_model_for_home(
+    home_dir: &Path,
+    model: &str,
+) -> Result<Option<String>, String> {
+    let model = model.trim();
+    let family = model_family(model);
+    Ok(build_codex_model_catalog_for_home(home_dir, &family)?
+        .iter()
+        .any(|entry| slug(entry) == Some(model))
+        .then_some(family))
+}
+
+/// Serialized `{"models": [...]}` catalog for `family`.
+pub(crate) fn render_codex_model_catalog_for_home(
+    home_dir: &Path,
+    family: &str,
+) -> Result<String, String> {
+    let models = build_codex_model_catalog_for_home(home_dir, family)?;
+    serde_json::to_string_pretty(&json!({ "models": models }))
+        .map_err(|e| format!("Failed to serialize model catalog: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry<'a>(models: &'a [Value], slug_name: &str) -> &'a Value {
+        models
+            .iter()
+            .find(|m| slug(m) == Some(slug_name))
+            .unwrap_or_else(|| panic!("{slug_name} missing"))
+    }
+
+    #[test]
+    fn generates_entries_from_registry_and_keeps_curated_ones() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        let glm = build_codex_model_catalog_for_home(home, "glm").unwrap();
+        let glm5 = entry(&glm, "glm-5");
+        let registry = model_registry::find_registry_model("glm-5").unwrap();
+        assert_eq!(glm5["context_window"], json!(registry.context_window));
+        assert_eq!(glm5["input_modalities"], json!(registry.input));
+        assert_eq!(
+            glm5["supported_reasoning_levels"]
+                .as_array()
+                .unwrap()
+                .is_empty(),
+            !registry.reasoning
+        );
+
+        let deepseek = build_codex_model_catalog_for_home(home, "deepseek").unwrap();
+        assert_eq!(
+            entry(&deepseek, "deepseek-v4-pro")["display_name"],
+            json!("DeepSeek-V4-Pro")
+        );
+        entry(&deepseek, "deepseek-chat");
+
+        assert!(build_codex_model_catalog_for_home(home, "gpt")
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            catalog_family_for_model_for_home(home, "qwen3-coder-plus")
+                .unwrap()
+                .as_deref(),
+            Some("qwen")
+        );
+        assert_eq!(
+            catalog_family_for_model_for_home(home, "gpt-5").unwrap(),
+            None
+        );
+        assert_eq!(
+            catalog_family_for_model_for_home(home, "llama3").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn registry_aliases_get_their_own_entries() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let deepseek = build_codex_model_catalog_for_home(home, "deepseek").unwrap();
+        let alias = entry(&deepseek, "deepseek-v3");
+        assert_eq!(
+            alias["context_window"],
+            entry(&deepseek, "deepseek-chat")["context_window"]
+        );
+        entry(&deepseek, "deepseek-r1");
+        assert_eq!(
+            catalog_family_for_model_for_home(home, "deepseek-v3")
+                .unwrap()
+                .as_deref(),
+            Some("deepseek")
+        );
+    }
+
+    #[test]
+    fn user_overrides_merge_over_generated_entries() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let dir = user_catalogs_dir_for_home(home);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("glm.json"),
+            r#"{"models": [
+                {"slug": "glm-5", "context_window": 64000},
+                {"slug": "glm-local", "context_window": 32000}
+            ]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("llama.json"),
+            r#"{"models": [{"slug": "llama3", "display_name": "Llama 3"}]}"#,
+        )
+        .unwrap();
+
+        let glm = build_codex_model_catalog_for_home(home, "glm").unwrap();
+        let glm5 = entry(&glm, "glm-5");
+        assert_eq!(glm5["context_window"], json!(64000));
+        assert!(glm5["display_name"].as_str().unwrap().contains("GLM"));
+        assert_eq!(entry(&glm, "glm-local")["context_window"], json!(32000));
+
+        assert_eq!(
+            catalog_family_for_model_for_home(home, "llama3")
+                .unwrap()
+                .as_deref(),
+            Some("llama")
+        );
+
+        std::fs::write(
+            dir.join("glm.json"),
+            r#"{"models": [{"context_window": 1}]}"#,
+        )
+        .unwrap();
+        assert!(build_codex_model_catalog_for_home(home, "glm").is_err());
+        assert!(catalog_family_for_model_for_home(home, "glm-5").is_err());
+    }
+}
@@ -0,0 +1,492 @@
+//! Codex native `[profiles.<name>]` interop (core).
+//!
+//! Imports the profile tables of `~/.codex/config.toml` as DroidGear
+//! profiles and exports DroidGear profiles back as native tables, so
+//! `codex --profile <name>` works without DroidGear. Export only adds
+//! `[profiles.<name>]` and `[model_providers.<id>]` entries, replacing an
+//! existing profile table only when asked to; top-level settings are left
+//! as they are.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::codex::{self, CodexProfile, CodexProviderConfig};
+use crate::storage;
+
+/// Execution keys Codex accepts inside a `[profiles.<name>]` table. Other
+/// execution settings are global in Codex and are not exported.
+const PROFILE_EXECUTION_KEYS: [&str; 4] = [
+    "sandbox_mode",
+    "approval_policy",
+    "model_reasoning_summary",
+    "model_verbosity",
+];
+
+type TomlMap = toml::map::Map<String, toml::Value>;
+
+/// A `[profiles.<name>]` table in config.toml
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexNativeProfile {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_reasoning_effort: Option<String>,
+}
+
+fn system_home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+fn read_config(path: &Path) -> Result<TomlMap, String> {
+    if !path.exists() {
+        return Ok(TomlMap::new());
+    }
+    let s =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read config.toml: {e}"))?;
+    if s.trim().is_empty() {
+        return Ok(TomlMap::new());
+    }
+    toml::from_str(&s).map_err(|e| format!("Failed to parse config.toml: {e}"))
+}
+
+/// Native name for a DroidGear profile: its name with anything other than
+/// ASCII letters, digits, `_` and `-` collapsed to `-`.
+pub fn native_profile_name(name: &str) -> String {
+    name.trim()
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn str_value(table: &TomlMap, key: &str) -> Option<String> {
+    table.get(key).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+fn native_tables(config: &TomlMap) -> Vec<(String, TomlMap)> {
+    let mut tables: Vec<(String, TomlMap)> = config
+        .get("profiles")
+        .and_then(|v| v.as_table())
+        .map(|profiles| {
+            profiles
+                .iter()
+                .filter_map(|(name, v)| v.as_table().map(|t| (name.clone(), t.clone())))
+                .collect()
+        })
+        .unwrap_or_default();
+    tables.sort_by(|a, b| a.0.cmp(&b.0));
+    tables
+}
+
+pub fn list_codex_native_profiles_for_home(
+    home_dir: &Path,
+) -> Result<Vec<CodexNativeProfile>, String> {
+    let config = read_config(&codex::codex_config_path_for_home(home_dir)?)?;
+    Ok(native_tables(&config)
+        .into_iter()
+        .map(|(name, table)| CodexNativeProfile {
+            model_provider: str_value(&table, "model_provider"),
+            model: str_value(&table, "model"),
+            model_reasoning_effort: str_value(&table, "model_reasoning_effort"),
+            name,
+        })
+        .collect())
+}
+
+pub fn list_codex_native_profiles() -> Result<Vec<CodexNativeProfile>, String> {
+    list_codex_native_profiles_for_home(&system_home_dir()?)
+}
+
+/// Imports the named `[profiles.*]` tables, or all of them when `names` is
+/// empty. Values a table leaves out fall back to the top-level ones, as in
+/// Codex. A DroidGear profile whose name (or native name) matches is updated
+/// in place; otherwise a new profile is created.
+pub fn import_codex_native_profiles_for_home(
+    home_dir: &Path,
+    names: &[String],
+) -> Result<Vec<CodexProfile>, String> {
+    let config = read_config(&codex::codex_config_path_for_home(home_dir)?)?;
+    let tables = native_tables(&config);
+    if let Some(missing) = names
+        .iter()
+        .find(|name| !tables.iter().any(|(n, _)| n == *name))
+    {
+        return Err(format!(
+            "Codex profile '{missing}' not found in config.toml"
+        ));
+    }
+
+    let existing = codex::list_codex_profiles_for_home(home_dir)?;
+    let mut imported = Vec::new();
+    for (name, table) in tables {
+        if !names.is_empty() && !names.contains(&name) {
+            continue;
+        }
+        let model_provider = str_value(&table, "model_provider")
+            .or_else(|| str_value(&config, "model_provider"))
+            .unwrap_or_else(|| "openai".to_string());
+        let model = str_value(&table, "model")
+            .or_else(|| str_value(&config, "model"))
+            .unwrap_or_default();
+        let model_reasoning_effort = str_value(&table, "model_reasoning_effort")
+            .or_else(|| str_value(&config, "model_reasoning_effort"));
+
+        let mut providers = std::collections::HashMap::new();
+        if model_provider != "openai" {
+            if let Some(value) = config
+                .get("model_providers")
+                .and_then(|v| v.get(&model_provider))
+            {
+                let mut provider = codex::toml_to_provider_config(value)?;
+                provider.model = Some(model.clone());
+                provider.model_reasoning_effort = model_reasoning_effort.clone();
+                providers.insert(model_provider.clone(), provider);
+            }
+        }
+
+        let mut profile = match existing
+            .iter()
+            .find(|p| p.name == name || native_profile_name(&p.name) == name)
+        {
+            Some(p) => p.clone(),
+            None => CodexProfile {
+                id: Uuid::new_v4().to_string(),
+                name: name.clone(),
+                description: Some("Imported from config.toml".to_string()),
+                created_at: String::new(),
+                updated_at: String::new(),
+                providers: Default::default(),
+                model_provider: String::new(),
+                model: String::new(),
+                model_reasoning_effort: None,
+                api_key: None,
+                auth_profile_name: None,
+                execution: Default::default(),
+                mcp_servers: Vec::new(),
+                features: Default::default(),
+                web_search: None,
+            },
+        };
+        profile.api_key = providers
+            .get(&model_provider)
+            .and_then(|p| p.api_key.clone())
+            .or(profile.api_key);
+        profile.providers.extend(providers);
+        profile.model_provider = model_provider;
+        profile.model = model;
+        profile.model_reasoning_effort = model_reasoning_effort;
+        profile.execution = codex::toml_to_execution_settings(&table);
+
+        codex::save_codex_profile_for_home(home_dir, profile.clone())?;
+        imported.push(codex::get_codex_profile_for_home(home_dir, &profile.id)?);
+    }
+    Ok(imported)
+}
+
+pub fn import_codex_native_profiles(names: &[String]) -> Result<Vec<CodexProfile>, String> {
+    import_codex_native_profiles_for_home(&system_home_dir()?, names)
+}
+
+/// Writes the given DroidGear profiles into config.toml as native profiles.
+///
+/// The active provider of each profile is added under `[model_providers]`.
+/// When a different provider already uses that id, the profile's provider is
+/// written as `<id>-<native name>` instead, so existing entries are never
+/// overwritten with other settings.
+///
+/// An existing `[profiles.<name>]` table is only replaced when `overwrite`
+/// is set, and two profiles that map to the same native name are rejected.
+/// Nothing is written when any profile fails.
+pub fn export_codex_profiles_to_native_for_home(
+    home_dir: &Path,
+    ids: &[String],
+    overwrite: bool,
+) -> Result<Vec<CodexNativeProfile>, String> {
+    let config_path = codex::codex_config_path_for_home(home_dir)?;
+    let mut config = read_config(&config_path)?;
+    let existing: Vec<String> = native_tables(&config)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    let mut exported: Vec<CodexNativeProfile> = Vec::new();
+    let mut sources: Vec<String> = Vec::new();
+
+    for id in ids {
+        let profile = codex::get_codex_profile_for_home(home_dir, id)?;
+        let name = native_profile_name(&profile.name);
+        if name.is_empty() {
+            return Err(format!(
+                "Profile '{}' has no usable characters for a Codex profile name",
+                profile.name
+            ));
+        }
+        if let Some(index) = exported.iter().position(|e| e.name == name) {
+            return Err(format!(
+                "Profiles '{}' and '{}' would both be exported as [profiles.{name}]",
+                sources[index], profile.name
+            ));
+        }
+        if !overwrite && existing.contains(&name) {
+            return Err(format!(
+                "[profiles.{name}] already exists in config.toml; export with overwrite to replace it"
+            ));
+        }
+        let (mut provider_id, provider) = codex::resolve_active_provider(&profile);
+        let model = codex::resolved_model(&profile, provider);
+        let effort = codex::resolved_reasoning_effort(&profile, provider);
+
+        if provider_id != "openai" {
+            let mut provider_config = provider.cloned().unwrap_or(CodexProviderConfig {
+                name: None,
+                base_url: None,
+                wire_api: None,
+                requires_openai_auth: None,
+                env_key: None,
+                env_key_instructions: None,
+                http_headers: None,
+                query_params: None,
+                model: None,
+                model_reasoning_effort: None,
+                api_key: None,
+            });
+            provider_config.api_key = codex::resolved_api_key(&profile, provider);
+            let table = codex::provider_config_to_toml(&provider_id, &provider_config)?;
+
+            let providers = config
+                .entry("model_providers")
+                .or_insert_with(|| toml::Value::Table(TomlMap::new()))
+                .as_table_mut()
+                .ok_or("model_providers in config.toml must be a table")?;
+            if providers
+                .get(&provider_id)
+                .is_some_and(|current| current != &table)
+            {
+                provider_id = format!("{provider_id}-{name}");
+            }
+            providers.insert(provider_id.clone(), table);
+        }
+
+        let mut native = TomlMap::new();
+        native.insert(
+            "model_provider".to_string(),
+            toml::Value::String(provider_id.clone()),
+        );
+        native.insert("model".to_string(), toml::Value::String(model.clone()));
+        if let Some(effort) = &effort {
+            native.insert(
+                "model_reasoning_effort".to_string(),
+                toml::Value::String(effort.clone()),
+            );
+        }
+        let execution = codex::execution_settings_to_toml(&profile.execution)?;
+        for key in PROFILE_EXECUTION_KEYS {
+            if let Some(value) = execution.get(key) {
+                native.insert(key.to_string(), value.clone());
+            }
+        }
+
+        config
+            .entry("profiles")
+            .or_insert_with(|| toml::Value::Table(TomlMap::new()))
+            .as_table_mut()
+            .ok_or("profiles in config.toml must be a table")?
+            .insert(name.clone(), toml::Value::Table(native));
+        sources.push(profile.name.clone());
+        exported.push(CodexNativeProfile {
+            name,
+            model_provider: Some(provider_id),
+            model: Some(model),
+            model_reasoning_effort: effort,
+        });
+    }
+
+    let toml_str = toml::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize config.toml: {e}"))?;
+    storage::atomic_write(&config_path, toml_str.as_bytes())?;
+    Ok(exported)
+}
+
+pub fn export_codex_profiles_to_native(
+    ids: &[String],
+    overwrite: bool,
+) -> Result<Vec<CodexNativeProfile>, String> {
+    export_codex_profiles_to_native_for_home(&system_home_dir()?, ids, overwrite)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_config(home: &Path, contents: &str) {
+        let path = home.join(".codex").join("config.toml");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn imports_native_profiles_with_top_level_fallbacks() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        write_config(
+            home,
+            r#"
+model = "gpt-5"
+model_provider = "openai"
+
+[model_providers.local]
+name = "Local"
+base_url = "http://localhost:8080/v1"
+experimental_bearer_token = "sk-local"
+
+[profiles.review]
+approval_policy = "untrusted"
+sandbox_mode = "read-only"
+
+[profiles.auto]
+model_provider = "local"
+model = "qwen"
+approval_policy = "never"
+"#,
+        );
+
+        let listed = list_codex_native_profiles_for_home(home).unwrap();
+        let names: Vec<_> = listed.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["auto", "review"]);
+
+        let imported = import_codex_native_profiles_for_home(home, &[]).unwrap();
+        assert_eq!(imported.len(), 2);
+        let auto = &imported[0];
+        assert_eq!(auto.model_provider, "local");
+        assert_eq!(auto.api_key.as_deref(), Some("sk-local"));
+        assert_eq!(
+            auto.providers["local"].base_url.as_deref(),
+            Some("http://localhost:8080/v1")
+        );
+        let review = &imported[1];
+        assert_eq!(review.model, "gpt-5");
+        assert_eq!(review.model_provider, "openai");
+        assert_eq!(review.execution.sandbox_mode.as_deref(), Some("read-only"));
+
+        // Importing again updates the same profiles instead of adding copies.
+        import_codex_native_profiles_for_home(home, &["review".to_string()]).unwrap();
+        assert_eq!(codex::list_codex_profiles_for_home(home).unwrap().len(), 2);
+        assert!(import_codex_native_profiles_for_home(home, &["nope".to_string()]).is_err());
+    }
+
+    #[test]
+    fn export_adds_profiles_without_touching_top_level_settings() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        write_config(
+            home,
+            r#"
+model = "gpt-5"
+model_provider = "custom"
+sandbox_mode = "workspace-write"
+
+[model_providers.custom]
+name = "Existing"
+base_url = "https://existing.example/v1"
+"#,
+        );
+
+        let mut profile = codex::create_default_codex_profile_for_home(home).unwrap();
+        profile.name = "Full Auto".to_string();
+        if let Some(provider) = profile.providers.get_mut("custom") {
+            provider.base_url = Some("https://mine.example/v1".to_string());
+        }
+        profile.execution.approval_policy = Some("never".to_string());
+        profile.execution.hide_agent_reasoning = Some(true);
+        codex::save_codex_profile_for_home(home, profile.clone()).unwrap();
+
+        let exported =
+            export_codex_profiles_to_native_for_home(home, &[profile.id.clone()], false).unwrap();
+        assert_eq!(exported[0].name, "Full-Auto");
+        assert_eq!(
+            exported[0].model_provider.as_deref(),
+            Some("custom-Full-Auto")
+        );
+
+        let config = read_config(&codex::codex_config_path_for_home(home).unwrap()).unwrap();
+        assert_eq!(str_value(&config, "model").as_deref(), Some("gpt-5"));
+        assert_eq!(
+            str_value(&config, "sandbox_mode").as_deref(),
+            Some("workspace-write")
+        );
+        let providers = config["model_providers"].as_table().unwrap();
+        assert_eq!(
+            providers["custom"]["base_url"].as_str(),
+            Some("https://existing.example/v1")
+        );
+        assert_eq!(
+            providers["custom-Full-Auto"]["base_url"].as_str(),
+            Some("https://mine.example/v1")
+        );
+        let native = config["profiles"]["Full-Auto"].as_table().unwrap();
+        assert_eq!(
+            str_value(native, "approval_policy").as_deref(),
+            Some("never")
+        );
+        assert!(!native.contains_key("hide_agent_reasoning"));
+    }
+
+    #[test]
+    fn export_keeps_hand_written_profiles_unless_overwriting() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        write_config(home, "[profiles.Mine]\nmodel = \"hand-written\"\n");
+        let mut profile = codex::create_default_codex_profile_for_home(home).unwrap();
+        profile.name = "Mine".to_string();
+        codex::save_codex_profile_for_home(home, profile.clone()).unwrap();
+
+        let err = export_codex_profiles_to_native_for_home(home, &[profile.id.clone()], false)
+            .unwrap_err();
+        assert!(err.contains("[profiles.Mine] already exists"), "{err}");
+        let config = read_config(&codex::codex_config_path_for_home(home).unwrap()).unwrap();
+        assert_eq!(
+            config["profiles"]["Mine"]["model"].as_str(),
+            Some("hand-written")
+        );
+
+        export_codex_profiles_to_native_for_home(home, &[profile.id], true).unwrap();
+        let config = read_config(&codex::codex_config_path_for_home(home).unwrap()).unwrap();
+        assert_ne!(
+            config["profiles"]["Mine"]["model"].as_str(),
+            Some("hand-written")
+        );
+    }
+
+    #[test]
+    fn export_rejects_profiles_with_the_same_native_name() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let mut first = codex::create_default_codex_profile_for_home(home).unwrap();
+        first.name = "Full Auto".to_string();
+        codex::save_codex_profile_for_home(home, first.clone()).unwrap();
+        let mut second = first.clone();
+        second.id = "second".to_string();
+        second.name = "Full/Auto".to_string();
+        codex::save_codex_profile_for_home(home, second.clone()).unwrap();
+
+        for overwrite in [false, true] {
+            let err = export_codex_profiles_to_native_for_home(
+                home,
+                &[first.id.clone(), second.id.clone()],
+                overwrite,
+            )
+            .unwrap_err();
+            assert!(
+                err.contains("both be exported as [profiles.Full-Auto]"),
+                "{err}"
+            );
+        }
+        assert!(!codex::codex_config_path_for_home(home).unwrap().exists());
+    }
+}
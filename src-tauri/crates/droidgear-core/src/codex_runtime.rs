@@ -2,14 +2,19 @@
 //!
 //! Builds a runtime `CODEX_HOME` snapshot plus child-process-only env without
 //! mutating the live `config.toml` / `auth.json`.
+//!
+//! Sessions and history are shared with the live `CODEX_HOME` by default. An
+//! isolated run keeps them in the runtime home, where they can later be merged
+//! back or discarded; an ephemeral run drops them when it exits.
 
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
-use crate::{codex, json, paths, storage};
+use crate::{codex, codex_model_catalog, json, paths, storage};
 
 const CODEX_CONFIG_SUPPORT_MIN_VERSION: &str = "0.128.0";
 const CODEX_RUNTIME_DIR: &str = "runtime/codex";
@@ -19,6 +24,92 @@ const AUTH_MODE_FIELD: &str = "auth_mode";
 const LAST_REFRESH_FIELD: &str = "last_refresh";
 const AGENT_IDENTITY_FIELD: &str = "agent_identity";
 const MANAGED_RUNTIME_FILES: [&str; 2] = ["config.toml", "auth.json"];
+/// Live CODEX_HOME entries holding sessions and prompt history.
+const HISTORY_ENTRIES: [&str; 5] = [
+    "sessions",
+    "archived_sessions",
+    "history.jsonl",
+    "session_index.jsonl",
+    "threads.db",
+];
+/// Directories of [`HISTORY_ENTRIES`] holding one rollout file per session.
+const SESSION_DIRS: [&str; 2] = ["sessions", "archived_sessions"];
+/// Append-only JSONL logs of [`HISTORY_ENTRIES`].
+const HISTORY_LOGS: [&str; 2] = ["history.jsonl", "session_index.jsonl"];
+const RUN_MARKER_FILE: &str = ".droidgear-run.json";
+/// `[mcp_servers.<name>]` keys left out of `--config` overrides.
+const MCP_SECRET_KEYS: [&str; 2] = ["env", "http_headers"];
+
+/// Where a temporary run keeps the sessions and history it records
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CodexHistoryMode {
+    /// Shared with the live CODEX_HOME, like a normal Codex run
+    #[default]
+    Shared,
+    /// Kept in the runtime home until merged back or discarded
+    Isolated,
+    /// Discarded when the run exits
+    Ephemeral,
+}
+
+impl CodexHistoryMode {
+    pub const ALL: [CodexHistoryMode; 3] = [
+        CodexHistoryMode::Shared,
+        CodexHistoryMode::Isolated,
+        CodexHistoryMode::Ephemeral,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CodexHistoryMode::Shared => "shared",
+            CodexHistoryMode::Isolated => "isolated",
+            CodexHistoryMode::Ephemeral => "ephemeral",
+        }
+    }
+}
+
+/// Per-run options of a temporary run
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CodexRunOptions {
+    pub history_mode: CodexHistoryMode,
+    /// Merge the run's sessions into the live CODEX_HOME when it exits
+    /// (isolated and ephemeral runs only).
+    pub merge_history_on_exit: bool,
+}
+
+/// An isolated run whose history is still in its runtime home
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexIsolatedRun {
+    pub path: String,
+    pub profile_id: String,
+    pub profile_name: String,
+    pub created_at: String,
+    pub session_files: u32,
+}
+
+/// What merging a run's history into the live CODEX_HOME copied. Codex's
+/// SQLite session indexes are never merged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexHistoryMergeReport {
+    /// Session rollout files copied
+    pub sessions: u32,
+    /// `history.jsonl` entries appended
+    pub history_entries: u32,
+}
+
+/// `.droidgear-run.json` in runtime homes that do not share history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RunMarker {
+    profile_id: String,
+    profile_name: String,
+    history_mode: CodexHistoryMode,
+    created_at: String,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -63,6 +154,7 @@ pub struct CodexTemporaryLaunchPlan {
     pub unset_env: Vec<String>,
     pub warnings: Vec<String>,
     pub runtime_home_path: PathBuf,
+    pub options: CodexRunOptions,
 }
 
 impl From<&CodexTemporaryLaunchPlan> for CodexTemporaryRunPlan {
@@ -158,7 +250,10 @@ fn provider_overrides(provider_id: &str, provider: &codex::CodexProviderConfig)
     overrides
 }
 
-pub fn build_cli_overrides(profile: &codex::CodexProfile) -> Result<Vec<String>, String> {
+pub fn build_cli_overrides_for_home(
+    home_dir: &Path,
+    profile: &codex::CodexProfile,
+) -> Result<Vec<String>, String> {
     let (provider_id, provider) = codex::resolve_active_provider(profile);
     validate_provider_id(&provider_id)?;
 
@@ -181,9 +276,62 @@ pub fn build_cli_overrides(profile: &codex::CodexProfile) -> Result<Vec<String>,
         overrides.extend(provider_overrides(&provider_id, provider));
     }
 
+    let execution = codex::execution_settings_to_toml(&profile.execution)?;
+    push_table_overrides(&mut overrides, "", &execution);
+
+    // Overrides can only add entries; servers and features of other profiles
+    // are dropped from the runtime config snapshot instead.
+    let model = codex::resolved_model(profile, provider);
+    let catalog_family = codex_model_catalog::catalog_family_for_model_for_home(home_dir, &model)?;
+    let mut toolset = codex::profile_toolset_to_toml(home_dir, profile, catalog_family.as_deref())?;
+    // MCP env values and headers often hold secrets, which must not show up
+    // in the process list; the runtime config.toml snapshot carries them.
+    if let Some(toml::Value::Table(servers)) = toolset.get_mut("mcp_servers") {
+        for (_, server) in servers.iter_mut() {
+            if let toml::Value::Table(server) = server {
+                for key in MCP_SECRET_KEYS {
+                    server.remove(key);
+                }
+            }
+        }
+    }
+    push_table_overrides(&mut overrides, "", &toolset);
+
     Ok(overrides)
 }
 
+pub fn build_cli_overrides(profile: &codex::CodexProfile) -> Result<Vec<String>, String> {
+    let home_dir = dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
+    build_cli_overrides_for_home(&home_dir, profile)
+}
+
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+    if bare {
+        key.to_string()
+    } else {
+        quote_toml_string(key)
+    }
+}
+
+/// Flattens `table` into dotted `key=value` overrides; arrays stay inline.
+fn push_table_overrides(
+    overrides: &mut Vec<String>,
+    prefix: &str,
+    table: &toml::map::Map<String, toml::Value>,
+) {
+    for (key, value) in table {
+        let key = format!("{prefix}{}", toml_key(key));
+        match value {
+            toml::Value::Table(sub) => push_table_overrides(overrides, &format!("{key}."), sub),
+            value => overrides.push(format!("{key}={value}")),
+        }
+    }
+}
+
 fn runtime_dir_for_home(home_dir: &Path) -> PathBuf {
     crate::paths::droidgear_dir_from_home(home_dir).join(CODEX_RUNTIME_DIR)
 }
@@ -271,9 +419,17 @@ fn create_shared_entry(target: &Path, link_path: &Path) -> Result<(), String> {
     }
 }
 
+fn is_history_entry(name: &str) -> bool {
+    HISTORY_ENTRIES
+        .iter()
+        .any(|entry| name == *entry || name.starts_with(&format!("{entry}-")))
+        || (name.starts_with("state_") && name.contains(".sqlite"))
+}
+
 fn populate_runtime_shared_entries(
     live_codex_home: &Path,
     runtime_home_path: &Path,
+    share_history: bool,
 ) -> Result<(), String> {
     if !live_codex_home.exists() {
         return Ok(());
@@ -287,7 +443,9 @@ fn populate_runtime_shared_entries(
         let Some(file_name_str) = file_name.to_str() else {
             continue;
         };
-        if MANAGED_RUNTIME_FILES.contains(&file_name_str) {
+        if MANAGED_RUNTIME_FILES.contains(&file_name_str)
+            || (!share_history && is_history_entry(file_name_str))
+        {
             continue;
         }
 
@@ -343,13 +501,26 @@ fn build_unset_env(profile: &codex::CodexProfile) -> Vec<String> {
 fn build_runtime_home_snapshot(
     home_dir: &Path,
     profile: &codex::CodexProfile,
+    options: &CodexRunOptions,
 ) -> Result<PathBuf, String> {
     let config_paths = paths::load_config_paths_for_home(home_dir);
     let live_codex_home = paths::get_codex_home_for_home(home_dir, &config_paths)?;
     let runtime_home_path = next_runtime_home_path(home_dir)?;
     std::fs::create_dir_all(&runtime_home_path)
         .map_err(|e| format!("Failed to create Codex runtime home: {e}"))?;
-    populate_runtime_shared_entries(&live_codex_home, &runtime_home_path)?;
+    let share_history = options.history_mode == CodexHistoryMode::Shared;
+    populate_runtime_shared_entries(&live_codex_home, &runtime_home_path, share_history)?;
+    if !share_history {
+        let marker = RunMarker {
+            profile_id: profile.id.clone(),
+            profile_name: profile.name.clone(),
+            history_mode: options.history_mode,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        let marker = serde_json::to_string_pretty(&marker)
+            .map_err(|e| format!("Failed to serialize Codex run marker: {e}"))?;
+        storage::atomic_write(&runtime_home_path.join(RUN_MARKER_FILE), marker.as_bytes())?;
+    }
 
     let (provider_id, _) = codex::resolve_active_provider(profile);
     validate_provider_id(&provider_id)?;
@@ -389,6 +560,13 @@ pub fn cleanup_stale_runtime_homes_for_home(home_dir: &Path) -> Result<u32, Stri
         if !name.starts_with(TEMP_RUNTIME_PREFIX) {
             continue;
         }
+        // Isolated histories stay until merged back or discarded.
+        if read_run_marker(&path)
+            .is_some_and(|marker| marker.history_mode == CodexHistoryMode::Isolated)
+            && has_history(&path)
+        {
+            continue;
+        }
 
         let Ok(metadata) = entry.metadata() else {
             continue;
@@ -459,11 +637,19 @@ pub fn validate_cli_capability(capability: &CodexCliCapability) -> Result<(), St
 pub fn build_temporary_run_plan_for_home(
     home_dir: &Path,
     profile: &codex::CodexProfile,
+) -> Result<CodexTemporaryLaunchPlan, String> {
+    build_temporary_run_plan_with_options_for_home(home_dir, profile, &CodexRunOptions::default())
+}
+
+pub fn build_temporary_run_plan_with_options_for_home(
+    home_dir: &Path,
+    profile: &codex::CodexProfile,
+    options: &CodexRunOptions,
 ) -> Result<CodexTemporaryLaunchPlan, String> {
     let (provider_id, _) = codex::resolve_active_provider(profile);
     validate_provider_id(&provider_id)?;
 
-    let runtime_home_path = build_runtime_home_snapshot(home_dir, profile)?;
+    let runtime_home_path = build_runtime_home_snapshot(home_dir, profile, options)?;
     let secret_env = Vec::new();
     let warnings = Vec::new();
 
@@ -478,6 +664,7 @@ pub fn build_temporary_run_plan_for_home(
         unset_env: build_unset_env(profile),
         warnings,
         runtime_home_path,
+        options: *options,
     })
 }
 
@@ -488,15 +675,251 @@ pub fn build_temporary_run_plan(
     build_temporary_run_plan_for_home(&home_dir, profile)
 }
 
+fn read_run_marker(runtime_home: &Path) -> Option<RunMarker> {
+    let contents = std::fs::read_to_string(runtime_home.join(RUN_MARKER_FILE)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Files below `dir`, relative to it.
+fn collect_files(dir: &Path, prefix: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let relative = prefix.join(entry.file_name());
+        if path.is_dir() {
+            collect_files(&path, &relative, files)?;
+        } else {
+            files.push(relative);
+        }
+    }
+    Ok(())
+}
+
+fn session_files(runtime_home: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in SESSION_DIRS {
+        let _ = collect_files(&runtime_home.join(dir), Path::new(dir), &mut files);
+    }
+    files.sort();
+    files
+}
+
+fn has_history(runtime_home: &Path) -> bool {
+    !session_files(runtime_home).is_empty()
+        || std::fs::metadata(runtime_home.join("history.jsonl")).is_ok_and(|m| m.len() > 0)
+}
+
+/// Resolves `path` to a temporary-run home under `~/.droidgear/runtime/codex`.
+fn resolve_runtime_home(home_dir: &Path, path: &Path) -> Result<PathBuf, String> {
+    let invalid = || format!("Not a Codex runtime home: {}", path.display());
+    let runtime_dir = runtime_dir_for_home(home_dir)
+        .canonicalize()
+        .map_err(|_| invalid())?;
+    let path = path.canonicalize().map_err(|_| invalid())?;
+    let is_run = path.parent() == Some(runtime_dir.as_path())
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(TEMP_RUNTIME_PREFIX));
+    if is_run {
+        Ok(path)
+    } else {
+        Err(invalid())
+    }
+}
+
+pub fn list_codex_isolated_runs_for_home(home_dir: &Path) -> Result<Vec<CodexIsolatedRun>, String> {
+    let runtime_dir = runtime_dir_for_home(home_dir);
+    if !runtime_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = std::fs::read_dir(&runtime_dir)
+        .map_err(|e| format!("Failed to read Codex runtime directory: {e}"))?;
+
+    let mut runs = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(marker) = read_run_marker(&path) else {
+            continue;
+        };
+        if marker.history_mode != CodexHistoryMode::Isolated || !has_history(&path) {
+            continue;
+        }
+        runs.push(CodexIsolatedRun {
+            path: path.to_string_lossy().to_string(),
+            profile_id: marker.profile_id,
+            profile_name: marker.profile_name,
+            created_at: marker.created_at,
+            session_files: session_files(&path).len() as u32,
+        });
+    }
+    runs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(runs)
+}
+
+pub fn list_codex_isolated_runs() -> Result<Vec<CodexIsolatedRun>, String> {
+    let home_dir = dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
+    list_codex_isolated_runs_for_home(&home_dir)
+}
+
+fn read_lines(path: &Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .map(|s| s.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Copies the sessions of a runtime home into the live CODEX_HOME and appends
+/// its history entries. Files and entries already present are skipped, so
+/// merging twice changes nothing.
+///
+/// Codex's SQLite session indexes (`threads.db`, `state_*.sqlite`) are not
+/// merged. Codex versions that list sessions from them may leave merged
+/// sessions out of the `codex resume` picker; the rollout files are in place,
+/// so they can still be resumed by id.
+pub fn merge_codex_run_history_for_home(
+    home_dir: &Path,
+    runtime_home: &Path,
+) -> Result<CodexHistoryMergeReport, String> {
+    let runtime_home = resolve_runtime_home(home_dir, runtime_home)?;
+    let config_paths = paths::load_config_paths_for_home(home_dir);
+    let live_codex_home = paths::get_codex_home_for_home(home_dir, &config_paths)?;
+    let mut report = CodexHistoryMergeReport::default();
+
+    for relative in session_files(&runtime_home) {
+        let target = live_codex_home.join(&relative);
+        if target.exists() {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        std::fs::copy(runtime_home.join(&relative), &target)
+            .map_err(|e| format!("Failed to copy {}: {e}", relative.display()))?;
+        report.sessions += 1;
+    }
+
+    for log in HISTORY_LOGS {
+        let live_path = live_codex_home.join(log);
+        let live = std::fs::read_to_string(&live_path).unwrap_or_default();
+        let existing: BTreeSet<&str> = live.lines().collect();
+        let new_lines: Vec<String> = read_lines(&runtime_home.join(log))
+            .into_iter()
+            .filter(|line| !line.trim().is_empty() && !existing.contains(line.as_str()))
+            .collect();
+        if new_lines.is_empty() {
+            continue;
+        }
+        // Append instead of rewriting: a running Codex may add entries to the
+        // live log at the same time, and a rewrite would drop them.
+        let mut contents = String::new();
+        if !live.is_empty() && !live.ends_with('\n') {
+            contents.push('\n');
+        }
+        for line in &new_lines {
+            contents.push_str(line);
+            contents.push('\n');
+        }
+        std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&live_path)
+            .and_then(|mut file| file.write_all(contents.as_bytes()))
+            .map_err(|e| format!("Failed to append to {}: {e}", live_path.display()))?;
+        if log == "history.jsonl" {
+            report.history_entries += new_lines.len() as u32;
+        }
+    }
+
+    Ok(report)
+}
+
+pub fn merge_codex_run_history(runtime_home: &Path) -> Result<CodexHistoryMergeReport, String> {
+    let home_dir = dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
+    merge_codex_run_history_for_home(&home_dir, runtime_home)
+}
+
+/// Deletes a temporary-run home together with any history it kept.
+pub fn discard_codex_runtime_home_for_home(
+    home_dir: &Path,
+    runtime_home: &Path,
+) -> Result<(), String> {
+    let runtime_home = resolve_runtime_home(home_dir, runtime_home)?;
+    std::fs::remove_dir_all(&runtime_home)
+        .map_err(|e| format!("Failed to remove Codex runtime home: {e}"))
+}
+
+pub fn discard_codex_runtime_home(runtime_home: &Path) -> Result<(), String> {
+    let home_dir = dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
+    discard_codex_runtime_home_for_home(&home_dir, runtime_home)
+}
+
+/// Options for a run whose exit DroidGear cannot observe, such as one in an
+/// external terminal: [`finish_temporary_run_for_home`] never runs, so an
+/// ephemeral run becomes isolated and merge-on-exit is turned off. The
+/// history then stays in the runtime home until merged back or discarded.
+/// Returns the adjusted options and a warning when anything changed.
+pub fn unattended_run_options(options: CodexRunOptions) -> (CodexRunOptions, Option<String>) {
+    if options.history_mode != CodexHistoryMode::Ephemeral && !options.merge_history_on_exit {
+        return (options, None);
+    }
+    let adjusted = CodexRunOptions {
+        history_mode: match options.history_mode {
+            CodexHistoryMode::Ephemeral => CodexHistoryMode::Isolated,
+            mode => mode,
+        },
+        merge_history_on_exit: false,
+    };
+    let warning = if adjusted.history_mode == CodexHistoryMode::Isolated {
+        "This launch cannot act when Codex exits, so its history is kept isolated; merge or discard it from the isolated runs list."
+    } else {
+        "This launch cannot act when Codex exits, so merge-on-exit is off."
+    };
+    (adjusted, Some(warning.to_string()))
+}
+
+/// Applies the plan's exit options once the run is over: merges its history
+/// when asked to, then drops the runtime home of an ephemeral run.
+pub fn finish_temporary_run_for_home(
+    home_dir: &Path,
+    plan: &CodexTemporaryLaunchPlan,
+) -> Result<Option<CodexHistoryMergeReport>, String> {
+    let options = plan.options;
+    if options.history_mode == CodexHistoryMode::Shared {
+        return Ok(None);
+    }
+    let report = if options.merge_history_on_exit {
+        Some(merge_codex_run_history_for_home(
+            home_dir,
+            &plan.runtime_home_path,
+        )?)
+    } else {
+        None
+    };
+    if options.history_mode == CodexHistoryMode::Ephemeral {
+        discard_codex_runtime_home_for_home(home_dir, &plan.runtime_home_path)?;
+    }
+    Ok(report)
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        build_cli_overrides, build_temporary_run_plan_for_home,
-        cleanup_stale_runtime_homes_for_home, parse_codex_cli_capability, validate_cli_capability,
-        version_is_at_least, CodexInAppRunPlan, CodexTemporaryLaunchPlan,
+        build_cli_overrides, build_cli_overrides_for_home, build_temporary_run_plan_for_home,
+        build_temporary_run_plan_with_options_for_home, cleanup_stale_runtime_homes_for_home,
+        finish_temporary_run_for_home, list_codex_isolated_runs_for_home,
+        merge_codex_run_history_for_home, parse_codex_cli_capability, unattended_run_options,
+        validate_cli_capability, version_is_at_least, CodexHistoryMergeReport, CodexHistoryMode,
+        CodexInAppRunPlan, CodexRunOptions, CodexTemporaryLaunchPlan,
     };
     use crate::codex::{CodexProfile, CodexProviderConfig};
+    use crate::mcp::{McpServer, McpServerConfig, McpServerType};
     use std::collections::HashMap;
+    use std::io::Write;
     use std::path::{Path, PathBuf};
     use tempfile::TempDir;
 
@@ -534,6 +957,10 @@ mod tests {
             model_reasoning_effort: Some("medium".to_string()),
             api_key: Some("sk-profile".to_string()),
             auth_profile_name: None,
+            execution: Default::default(),
+            mcp_servers: Vec::new(),
+            features: Default::default(),
+            web_search: None,
         }
     }
 
@@ -569,6 +996,150 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn cli_overrides_include_execution_settings() {
+        let mut profile = sample_profile();
+        profile.execution = crate::codex::CodexExecutionSettings {
+            sandbox_mode: Some("workspace-write".to_string()),
+            approval_policy: Some("never".to_string()),
+            sandbox_workspace_write: Some(crate::codex::CodexSandboxWorkspaceWrite {
+                writable_roots: vec!["/tmp/out".to_string()],
+                network_access: Some(true),
+                ..Default::default()
+            }),
+            shell_environment_policy: Some(crate::codex::CodexShellEnvironmentPolicy {
+                inherit: Some("core".to_string()),
+                set: [("CI.MODE".to_string(), "1".to_string())].into(),
+                ..Default::default()
+            }),
+            notify: Some(vec!["notify-send".to_string(), "codex".to_string()]),
+            hide_agent_reasoning: Some(true),
+            ..Default::default()
+        };
+
+        let overrides = build_cli_overrides(&profile).unwrap();
+        for expected in [
+            r#"sandbox_mode="workspace-write""#,
+            r#"approval_policy="never""#,
+            r#"sandbox_workspace_write.writable_roots=["/tmp/out"]"#,
+            "sandbox_workspace_write.network_access=true",
+            r#"shell_environment_policy.inherit="core""#,
+            r#"shell_environment_policy.set."CI.MODE"="1""#,
+            r#"notify=["notify-send", "codex"]"#,
+            "hide_agent_reasoning=true",
+        ] {
+            assert!(
+                overrides.contains(&expected.to_string()),
+                "missing {expected} in {overrides:?}"
+            );
+        }
+
+        profile.execution.approval_policy = Some("sometimes".to_string());
+        assert!(build_cli_overrides(&profile).is_err());
+    }
+
+    fn docs_server() -> McpServer {
+        McpServer {
+            name: "docs".to_string(),
+            config: McpServerConfig {
+                server_type: McpServerType::Stdio,
+                command: Some("npx".to_string()),
+                args: Some(vec!["-y".to_string(), "docs-mcp".to_string()]),
+                env: Some([("DOCS_TOKEN".to_string(), "t".to_string())].into()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn cli_overrides_include_profile_toolset() {
+        let temp = TempDir::new().unwrap();
+        let mut profile = sample_profile();
+        let mut remote = docs_server();
+        remote.name = "remote".to_string();
+        remote.config = McpServerConfig {
+            server_type: McpServerType::Http,
+            url: Some("https://mcp.example.com".to_string()),
+            headers: Some([("X-Key".to_string(), "secret-key".to_string())].into()),
+            ..Default::default()
+        };
+        profile.mcp_servers = vec![docs_server(), remote];
+        profile.features = [("web_search_request".to_string(), true)].into();
+        profile.web_search = Some("live".to_string());
+
+        let overrides = build_cli_overrides_for_home(temp.path(), &profile).unwrap();
+        for expected in [
+            r#"mcp_servers.docs.command="npx""#,
+            r#"mcp_servers.docs.args=["-y", "docs-mcp"]"#,
+            r#"mcp_servers.remote.url="https://mcp.example.com""#,
+            "features.web_search_request=true",
+            r#"web_search="live""#,
+        ] {
+            assert!(
+                overrides.contains(&expected.to_string()),
+                "missing {expected} in {overrides:?}"
+            );
+        }
+        assert!(
+            !overrides
+                .iter()
+                .any(|o| o.contains("DOCS_TOKEN") || o.contains("secret-key")),
+            "MCP secrets stay out of argv: {overrides:?}"
+        );
+
+        profile.mcp_servers[0].config.server_type = McpServerType::Sse;
+        assert!(build_cli_overrides_for_home(temp.path(), &profile).is_err());
+    }
+
+    #[test]
+    fn temporary_run_snapshot_swaps_profile_toolsets() {
+        let temp = TempDir::new().unwrap();
+        let live_home = temp.path().join(".codex");
+        let live_config = "[mcp_servers.manual]\ncommand = \"manual-mcp\"\n\n\
+                           [mcp_servers.search]\ncommand = \"search-mcp\"\n\n\
+                           [features]\nplan_tool = true\n";
+        write_file(&live_home.join("config.toml"), live_config);
+
+        // Another profile brought `search` and `plan_tool` into the live config.
+        let mut other = sample_profile();
+        other.id = "other".to_string();
+        other.mcp_servers = vec![McpServer {
+            name: "search".to_string(),
+            config: McpServerConfig {
+                command: Some("search-mcp".to_string()),
+                ..Default::default()
+            },
+        }];
+        other.features = [("plan_tool".to_string(), true)].into();
+        crate::codex::save_codex_profile_for_home(temp.path(), other).unwrap();
+
+        let mut profile = sample_profile();
+        profile.mcp_servers = vec![docs_server()];
+        let plan = build_temporary_run_plan_for_home(temp.path(), &profile).unwrap();
+
+        let runtime_config =
+            std::fs::read_to_string(plan.runtime_home_path.join("config.toml")).unwrap();
+        let config: toml::Value = toml::from_str(&runtime_config).unwrap();
+        let servers = config
+            .get("mcp_servers")
+            .and_then(|v| v.as_table())
+            .unwrap();
+        assert!(servers.contains_key("manual"), "unowned servers are kept");
+        assert_eq!(
+            servers["docs"]["env"]["DOCS_TOKEN"].as_str(),
+            Some("t"),
+            "MCP env values go into the snapshot"
+        );
+        assert!(plan.args.is_empty());
+        assert!(!servers.contains_key("search"));
+        assert!(config.get("features").is_none());
+        assert_eq!(
+            std::fs::read_to_string(live_home.join("config.toml")).unwrap(),
+            live_config,
+            "the live config is untouched"
+        );
+    }
+
     #[test]
     fn temporary_run_plan_uses_runtime_codex_home_and_provider_specific_secret_env() {
         let temp = TempDir::new().unwrap();
@@ -854,6 +1425,10 @@ env_key = "OLD_API_KEY"
             model_reasoning_effort: None,
             api_key: None,
             auth_profile_name: None,
+            execution: Default::default(),
+            mcp_servers: Vec::new(),
+            features: Default::default(),
+            web_search: None,
         };
 
         let error = build_temporary_run_plan_for_home(temp.path(), &profile).unwrap_err();
@@ -910,6 +1485,169 @@ env_key = "OLD_API_KEY"
         assert!(other_dir.exists());
     }
 
+    fn write_live_history(temp: &TempDir) -> PathBuf {
+        let live_home = temp.path().join(".codex");
+        write_file(&live_home.join("history.jsonl"), "{\"text\":\"live\"}\n");
+        write_file(&live_home.join("sessions/2026/05/live.jsonl"), "{}\n");
+        write_file(&live_home.join("state_5.sqlite"), "sqlite-state");
+        write_file(&live_home.join("AGENTS.md"), "# shared\n");
+        live_home
+    }
+
+    #[test]
+    fn isolated_run_keeps_history_until_merged() {
+        let temp = TempDir::new().unwrap();
+        let live_home = write_live_history(&temp);
+        let options = CodexRunOptions {
+            history_mode: CodexHistoryMode::Isolated,
+            merge_history_on_exit: false,
+        };
+        let plan = build_temporary_run_plan_with_options_for_home(
+            temp.path(),
+            &sample_profile(),
+            &options,
+        )
+        .unwrap();
+        let runtime = &plan.runtime_home_path;
+
+        assert!(
+            runtime.join("AGENTS.md").exists(),
+            "non-history entries are shared"
+        );
+        for entry in ["history.jsonl", "sessions", "state_5.sqlite"] {
+            assert!(!runtime.join(entry).exists(), "{entry} must not be shared");
+        }
+        // Runs that recorded nothing are not listed.
+        assert!(list_codex_isolated_runs_for_home(temp.path())
+            .unwrap()
+            .is_empty());
+
+        write_file(&runtime.join("history.jsonl"), "{\"text\":\"isolated\"}\n");
+        write_file(&runtime.join("sessions/2026/05/run.jsonl"), "{}\n");
+        let old = filetime::FileTime::from_unix_time(946684800, 0);
+        filetime::set_file_mtime(runtime, old).unwrap();
+        assert_eq!(
+            cleanup_stale_runtime_homes_for_home(temp.path()).unwrap(),
+            0
+        );
+
+        let runs = list_codex_isolated_runs_for_home(temp.path()).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].profile_id, "demo");
+        assert_eq!(runs[0].session_files, 1);
+
+        let report = merge_codex_run_history_for_home(temp.path(), runtime).unwrap();
+        assert_eq!(
+            report,
+            CodexHistoryMergeReport {
+                sessions: 1,
+                history_entries: 1
+            }
+        );
+        assert!(live_home.join("sessions/2026/05/run.jsonl").exists());
+        assert_eq!(
+            std::fs::read_to_string(live_home.join("history.jsonl")).unwrap(),
+            "{\"text\":\"live\"}\n{\"text\":\"isolated\"}\n"
+        );
+        assert_eq!(
+            merge_codex_run_history_for_home(temp.path(), runtime).unwrap(),
+            CodexHistoryMergeReport::default(),
+            "merging twice changes nothing"
+        );
+        assert!(merge_codex_run_history_for_home(temp.path(), &live_home).is_err());
+    }
+
+    #[test]
+    fn merge_appends_to_live_history_in_place() {
+        let temp = TempDir::new().unwrap();
+        let live_home = write_live_history(&temp);
+        let options = CodexRunOptions {
+            history_mode: CodexHistoryMode::Isolated,
+            merge_history_on_exit: false,
+        };
+        let plan = build_temporary_run_plan_with_options_for_home(
+            temp.path(),
+            &sample_profile(),
+            &options,
+        )
+        .unwrap();
+        let runtime = &plan.runtime_home_path;
+        write_file(&runtime.join("history.jsonl"), "{\"text\":\"isolated\"}\n");
+
+        // A Codex still running against the live home keeps its handle open
+        // and may write a last line without a newline.
+        let live_path = live_home.join("history.jsonl");
+        let mut running = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&live_path)
+            .unwrap();
+        running.write_all(b"{\"text\":\"concurrent\"}").unwrap();
+
+        merge_codex_run_history_for_home(temp.path(), runtime).unwrap();
+        running.write_all(b"{\"text\":\"after\"}\n").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&live_path).unwrap(),
+            "{\"text\":\"live\"}\n{\"text\":\"concurrent\"}\n{\"text\":\"isolated\"}\n{\"text\":\"after\"}\n"
+        );
+    }
+
+    #[test]
+    fn ephemeral_run_is_discarded_on_finish() {
+        let temp = TempDir::new().unwrap();
+        let live_home = write_live_history(&temp);
+        let mut options = CodexRunOptions {
+            history_mode: CodexHistoryMode::Ephemeral,
+            merge_history_on_exit: false,
+        };
+        let plan = build_temporary_run_plan_with_options_for_home(
+            temp.path(),
+            &sample_profile(),
+            &options,
+        )
+        .unwrap();
+        write_file(&plan.runtime_home_path.join("sessions/a.jsonl"), "{}\n");
+        assert_eq!(
+            finish_temporary_run_for_home(temp.path(), &plan).unwrap(),
+            None
+        );
+        assert!(!plan.runtime_home_path.exists());
+        assert!(!live_home.join("sessions/a.jsonl").exists());
+
+        options.merge_history_on_exit = true;
+        let plan = build_temporary_run_plan_with_options_for_home(
+            temp.path(),
+            &sample_profile(),
+            &options,
+        )
+        .unwrap();
+        write_file(&plan.runtime_home_path.join("sessions/b.jsonl"), "{}\n");
+        let report = finish_temporary_run_for_home(temp.path(), &plan).unwrap();
+        assert_eq!(report.map(|r| r.sessions), Some(1));
+        assert!(!plan.runtime_home_path.exists());
+        assert!(live_home.join("sessions/b.jsonl").exists());
+    }
+
+    #[test]
+    fn unattended_runs_keep_ephemeral_history_isolated() {
+        let shared = CodexRunOptions::default();
+        assert_eq!(unattended_run_options(shared), (shared, None));
+
+        let (options, warning) = unattended_run_options(CodexRunOptions {
+            history_mode: CodexHistoryMode::Ephemeral,
+            merge_history_on_exit: true,
+        });
+        assert_eq!(options.history_mode, CodexHistoryMode::Isolated);
+        assert!(!options.merge_history_on_exit);
+        assert!(warning.unwrap().contains("isolated"));
+
+        let (options, warning) = unattended_run_options(CodexRunOptions {
+            history_mode: CodexHistoryMode::Shared,
+            merge_history_on_exit: true,
+        });
+        assert_eq!(options, shared);
+        assert!(warning.is_some());
+    }
+
     #[test]
     fn in_app_run_plan_merges_secret_env_into_env() {
         let plan = CodexTemporaryLaunchPlan {
@@ -920,6 +1658,7 @@ env_key = "OLD_API_KEY"
             unset_env: vec!["OPENAI_API_KEY".to_string()],
             warnings: vec![],
             runtime_home_path: PathBuf::from("/tmp/runtime"),
+            options: Default::default(),
         };
 
         let in_app: CodexInAppRunPlan = (&plan).into();
@@ -0,0 +1,449 @@
+//! Codex session rollouts (core).
+//!
+//! Reads `~/.codex/sessions/YYYY/MM/DD/rollout-*.jsonl` and maps it onto the
+//! shared [`SessionSummary`] / [`SessionDetail`] types.
+
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::sessions::{
+    self, ContentBlock, SessionDetail, SessionMessage, SessionSource, SessionSummary, TokenUsage,
+};
+
+pub(crate) fn sessions_dir_for_home(home_dir: &Path) -> Result<PathBuf, String> {
+    let config_paths = paths::load_config_paths_for_home(home_dir);
+    let codex_dir = paths::get_codex_home_for_home(home_dir, &config_paths)?;
+    Ok(codex_dir.join("sessions"))
+}
+
+/// Collects `rollout-*.jsonl` files below the date-partitioned sessions dir.
+fn collect_rollouts(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.is_dir() {
+            collect_rollouts(&path, out);
+        } else if path.extension().and_then(|s| s.to_str()) == Some("jsonl")
+            && path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .is_some_and(|s| s.starts_with("rollout-"))
+        {
+            out.push(path);
+        }
+    }
+}
+
+/// Rollout lines are `{timestamp, type, payload}` envelopes; very old rollouts
+/// stored the item at the top level instead.
+fn rollout_item(json: Value) -> (String, String, Value) {
+    let timestamp = json["timestamp"].as_str().unwrap_or("").to_string();
+    match json.get("payload") {
+        Some(payload) => {
+            let kind = json["type"].as_str().unwrap_or("").to_string();
+            (kind, timestamp, payload.clone())
+        }
+        None => ("response_item".to_string(), timestamp, json),
+    }
+}
+
+fn rollout_items(path: &Path) -> Result<Vec<(String, String, Value)>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open session file: {e}"))?;
+    let mut items = Vec::new();
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        let Ok(json) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        items.push(rollout_item(json));
+    }
+    Ok(items)
+}
+
+fn message_text(payload: &Value) -> String {
+    payload["content"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|c| c["text"].as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+/// Codex injects `<environment_context>` / `<user_instructions>` as user
+/// messages; they are not part of the conversation.
+fn is_injected_context(text: &str) -> bool {
+    let t = text.trim_start();
+    t.starts_with("<environment_context>")
+        || t.starts_with("<user_instructions>")
+        || t.starts_with("# AGENTS.md")
+}
+
+/// Converts Codex's cumulative `token_count` totals to [`TokenUsage`]. OpenAI
+/// reports cached tokens as part of `input_tokens`, so they are split out.
+fn token_usage_from_codex(total: &Value) -> Option<TokenUsage> {
+    if !total.is_object() {
+        return None;
+    }
+    let f = |k: &str| total[k].as_f64().unwrap_or(0.0);
+    let cached = f("cached_input_tokens");
+    Some(TokenUsage {
+        input_tokens: (f("input_tokens") - cached).max(0.0),
+        output_tokens: f("output_tokens"),
+        cache_creation_tokens: 0.0,
+        cache_read_tokens: cached,
+        thinking_tokens: f("reasoning_output_tokens"),
+    })
+}
+
+fn tool_call_block(payload: &Value) -> ContentBlock {
+    let id = payload["call_id"].as_str().unwrap_or("");
+    let name = payload["name"].as_str().unwrap_or("");
+    let input = match (payload.get("arguments"), payload.get("input")) {
+        (Some(Value::String(args)), _) => {
+            serde_json::from_str(args).unwrap_or_else(|_| Value::String(args.clone()))
+        }
+        (_, Some(input)) => input.clone(),
+        _ => Value::Null,
+    };
+    sessions::file_edit_block(id, name, &input).unwrap_or_else(|| ContentBlock::ToolUse {
+        id: id.to_string(),
+        name: name.to_string(),
+        input,
+    })
+}
+
+fn tool_output_block(payload: &Value) -> ContentBlock {
+    let raw = payload["output"].as_str().unwrap_or("");
+    // Function outputs are usually `{"output": "...", "metadata": {"exit_code": N}}`.
+    let (output, is_error) = match serde_json::from_str::<Value>(raw) {
+        Ok(v) if v.get("output").is_some() => (
+            v["output"].as_str().unwrap_or("").to_string(),
+            v["metadata"]["exit_code"].as_i64().is_some_and(|c| c != 0),
+        ),
+        _ => (raw.to_string(), false),
+    };
+    ContentBlock::ToolResult {
+        tool_use_id: payload["call_id"].as_str().unwrap_or("").to_string(),
+        output,
+        is_error,
+    }
+}
+
+struct Rollout {
+    id: String,
+    cwd: String,
+    model: String,
+    first_prompt: Option<String>,
+    token_usage: TokenUsage,
+    messages: Vec<SessionMessage>,
+}
+
+fn parse_rollout(path: &Path) -> Result<Rollout, String> {
+    let mut rollout = Rollout {
+        id: String::new(),
+        cwd: String::new(),
+        model: "unknown".to_string(),
+        first_prompt: None,
+        token_usage: TokenUsage::default(),
+        messages: Vec::new(),
+    };
+
+    for (kind, timestamp, payload) in rollout_items(path)? {
+        let (role, block) = match kind.as_str() {
+            "session_meta" => {
+                rollout.id = payload["id"].as_str().unwrap_or("").to_string();
+                rollout.cwd = payload["cwd"].as_str().unwrap_or("").to_string();
+                continue;
+            }
+            "turn_context" => {
+                if let Some(model) = payload["model"].as_str() {
+                    rollout.model = model.to_string();
+                }
+                continue;
+            }
+            "event_msg" => {
+                if payload["type"].as_str() == Some("token_count") {
+                    if let Some(usage) =
+                        token_usage_from_codex(&payload["info"]["total_token_usage"])
+                    {
+                        rollout.token_usage = usage;
+                    }
+                }
+                continue;
+            }
+            "response_item" => match payload["type"].as_str().unwrap_or("") {
+                "message" => {
+                    let role = payload["role"].as_str().unwrap_or("");
+                    let text = message_text(&payload);
+                    if (role != "user" && role != "assistant")
+                        || text.is_empty()
+                        || is_injected_context(&text)
+                    {
+                        continue;
+                    }
+                    if role == "user" && rollout.first_prompt.is_none() {
+                        rollout.first_prompt = Some(text.clone());
+                    }
+                    (role.to_string(), ContentBlock::Text { text })
+                }
+                "reasoning" => {
+                    let thinking = payload["summary"]
+                        .as_array()
+                        .map(|items| {
+                            items
+                                .iter()
+                                .filter_map(|s| s["text"].as_str())
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                        })
+                        .unwrap_or_default();
+                    if thinking.is_empty() {
+                        continue;
+                    }
+                    ("assistant".to_string(), ContentBlock::Thinking { thinking })
+                }
+                "function_call" | "custom_tool_call" | "local_shell_call" => {
+                    ("assistant".to_string(), tool_call_block(&payload))
+                }
+                "function_call_output" | "custom_tool_call_output" => {
+                    ("tool".to_string(), tool_output_block(&payload))
+                }
+                _ => continue,
+            },
+            _ => continue,
+        };
+
+        // Fold consecutive items from the same speaker into one message.
+        if let Some(last) = rollout.messages.last_mut() {
+            if last.role == role {
+                last.content.push(block);
+                continue;
+            }
+        }
+        rollout.messages.push(SessionMessage {
+            id: format!("{}", rollout.messages.len()),
+            role,
+            content: vec![block],
+            timestamp,
+            token_usage: None,
+        });
+    }
+
+    if rollout.id.is_empty() {
+        rollout.id = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string();
+    }
+    Ok(rollout)
+}
+
+/// Header data from the head and tail of a rollout only. `token_count`
+/// totals are cumulative, so the last one in the tail is the session total.
+fn summarize_rollout(path: &Path) -> Result<Rollout, String> {
+    let ends = sessions::read_jsonl_ends(
+        path,
+        sessions::SUMMARY_HEAD_LINES,
+        sessions::SUMMARY_TAIL_BYTES,
+    )?;
+    let mut rollout = Rollout {
+        id: String::new(),
+        cwd: String::new(),
+        model: "unknown".to_string(),
+        first_prompt: None,
+        token_usage: TokenUsage::default(),
+        messages: Vec::new(),
+    };
+
+    for json in ends.head.into_iter().chain(ends.tail) {
+        let (kind, _, payload) = rollout_item(json);
+        match kind.as_str() {
+            "session_meta" if rollout.id.is_empty() => {
+                rollout.id = payload["id"].as_str().unwrap_or("").to_string();
+                rollout.cwd = payload["cwd"].as_str().unwrap_or("").to_string();
+            }
+            "turn_context" => {
+                if let Some(model) = payload["model"].as_str() {
+                    rollout.model = model.to_string();
+                }
+            }
+            "event_msg" if payload["type"].as_str() == Some("token_count") => {
+                if let Some(usage) = token_usage_from_codex(&payload["info"]["total_token_usage"]) {
+                    rollout.token_usage = usage;
+                }
+            }
+            "response_item"
+                if rollout.first_prompt.is_none()
+                    && payload["type"].as_str() == Some("message")
+                    && payload["role"].as_str() == Some("user") =>
+            {
+                let text = message_text(&payload);
+                if !text.is_empty() && !is_injected_context(&text) {
+                    rollout.first_prompt = Some(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if rollout.id.is_empty() {
+        rollout.id = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string();
+    }
+    Ok(rollout)
+}
+
+fn project_for(rollout: &Rollout) -> String {
+    if rollout.cwd.is_empty() {
+        "codex".to_string()
+    } else {
+        sessions::project_name_for_cwd(&rollout.cwd)
+    }
+}
+
+pub(crate) fn list_sessions_for_home(home_dir: &Path) -> Result<Vec<SessionSummary>, String> {
+    let sessions_dir = sessions_dir_for_home(home_dir)?;
+    if !sessions_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    collect_rollouts(&sessions_dir, &mut files);
+
+    let mut sessions = Vec::new();
+    for path in files {
+        let Ok(rollout) = summarize_rollout(&path) else {
+            continue;
+        };
+        sessions.push(SessionSummary {
+            title: sessions::title_from_prompt(rollout.first_prompt.as_deref().unwrap_or("")),
+            project: project_for(&rollout),
+            id: rollout.id,
+            model: rollout.model,
+            modified_at: sessions::modified_at_ms(&path),
+            token_usage: rollout.token_usage,
+            path: path.with_extension("").to_string_lossy().to_string(),
+            source: SessionSource::Codex,
+        });
+    }
+
+    Ok(sessions)
+}
+
+pub(crate) fn get_session_detail(session_path: &str) -> Result<SessionDetail, String> {
+    let jsonl_path = PathBuf::from(format!("{session_path}.jsonl"));
+    if !jsonl_path.exists() {
+        return Err("Session file not found".to_string());
+    }
+
+    let rollout = parse_rollout(&jsonl_path)?;
+    Ok(SessionDetail {
+        title: sessions::title_from_prompt(rollout.first_prompt.as_deref().unwrap_or("")),
+        project: project_for(&rollout),
+        id: rollout.id,
+        model: rollout.model,
+        cwd: rollout.cwd,
+        modified_at: sessions::modified_at_ms(&jsonl_path),
+        token_usage: rollout.token_usage,
+        messages: rollout.messages,
+        source: SessionSource::Codex,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_session_detail, list_sessions_for_home};
+    use crate::sessions::{ContentBlock, SessionSource};
+    use tempfile::TempDir;
+
+    const ROLLOUT: &str = r#"{"timestamp":"2026-01-01T00:00:00Z","type":"session_meta","payload":{"id":"sess-1","cwd":"/work/app"}}
+{"timestamp":"2026-01-01T00:00:00Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>cwd</environment_context>"}]}}
+{"timestamp":"2026-01-01T00:00:01Z","type":"turn_context","payload":{"cwd":"/work/app","model":"gpt-5.2"}}
+{"timestamp":"2026-01-01T00:00:01Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"List the files"}]}}
+{"timestamp":"2026-01-01T00:00:02Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"ls\"]}","call_id":"c1"}}
+{"timestamp":"2026-01-01T00:00:03Z","type":"response_item","payload":{"type":"function_call_output","call_id":"c1","output":"{\"output\":\"a.rs\",\"metadata\":{\"exit_code\":0}}"}}
+{"timestamp":"2026-01-01T00:00:04Z","type":"response_item","payload":{"type":"custom_tool_call","name":"apply_patch","call_id":"c2","input":"*** Begin Patch\n*** Update File: a.rs\n+x\n*** End Patch"}}
+{"timestamp":"2026-01-01T00:00:05Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":1000,"cached_input_tokens":400,"output_tokens":50,"reasoning_output_tokens":10}}}}
+"#;
+
+    #[test]
+    fn lists_and_reads_codex_rollouts() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join(".codex/sessions/2026/01/01");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("rollout-2026-01-01T00-00-00-sess-1.jsonl"),
+            ROLLOUT,
+        )
+        .unwrap();
+
+        let list = list_sessions_for_home(temp.path()).unwrap();
+        assert_eq!(list.len(), 1);
+        let summary = &list[0];
+        assert_eq!(summary.id, "sess-1");
+        assert_eq!(summary.title, "List the files");
+        assert_eq!(summary.project, "-work-app");
+        assert_eq!(summary.model, "gpt-5.2");
+        assert_eq!(summary.source, SessionSource::Codex);
+        assert_eq!(summary.token_usage.input_tokens, 600.0);
+        assert_eq!(summary.token_usage.cache_read_tokens, 400.0);
+        assert_eq!(summary.token_usage.thinking_tokens, 10.0);
+
+        let detail = get_session_detail(&summary.path).unwrap();
+        assert_eq!(detail.cwd, "/work/app");
+        let roles: Vec<&str> = detail.messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["user", "assistant", "tool", "assistant"]);
+        assert!(matches!(
+            &detail.messages[1].content[0],
+            ContentBlock::ToolUse { name, input, .. }
+                if name == "shell" && input["command"][0] == "ls"
+        ));
+        assert!(matches!(
+            &detail.messages[2].content[0],
+            ContentBlock::ToolResult { output, is_error: false, .. } if output == "a.rs"
+        ));
+        assert!(matches!(
+            &detail.messages[3].content[0],
+            ContentBlock::FileEdit { path, .. } if path == "a.rs"
+        ));
+    }
+
+    #[test]
+    fn summary_reads_only_rollout_ends() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join(".codex/sessions/2026/01/02");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut body = ROLLOUT.to_string();
+        let filler = format!(
+            "{{\"timestamp\":\"t\",\"type\":\"response_item\",\"payload\":{{\"type\":\"message\",\"role\":\"assistant\",\"content\":[{{\"type\":\"output_text\",\"text\":\"{}\"}}]}}}}\n",
+            "x".repeat(1024)
+        );
+        for _ in 0..200 {
+            body.push_str(&filler);
+        }
+        body.push_str(r#"{"timestamp":"t","type":"turn_context","payload":{"model":"gpt-5.3"}}"#);
+        body.push('\n');
+        body.push_str(r#"{"timestamp":"t","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":5000,"cached_input_tokens":0,"output_tokens":70}}}}"#);
+        body.push('\n');
+        std::fs::write(dir.join("rollout-2026-01-02T00-00-00-sess-1.jsonl"), body).unwrap();
+
+        let list = list_sessions_for_home(temp.path()).unwrap();
+        let summary = &list[0];
+        assert_eq!(summary.id, "sess-1");
+        assert_eq!(summary.title, "List the files");
+        assert_eq!(summary.model, "gpt-5.3");
+        assert_eq!(summary.token_usage.input_tokens, 5000.0);
+        assert_eq!(summary.token_usage.output_tokens, 70.0);
+    }
+}
@@ -0,0 +1,525 @@
+//! File watcher service (core).
+//!
+//! Watches the Droid sessions and specs directories, the Factory, Codex and
+//! Claude settings files and the named Claude settings files, reporting
+//! changes as [`WatchEvent`]s on a channel. Events come from `notify`'s
+//! platform backend; targets whose directory does not exist yet are picked
+//! up once it appears, together with any files already in it.
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::{claude_settings_files, paths, sessions, specs};
+
+/// How often the polling backend rescans, and how often targets whose
+/// directory is missing are retried.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What kind of data a change affects
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchKind {
+    Sessions,
+    Specs,
+    FactorySettings,
+    CodexSettings,
+    ClaudeSettings,
+}
+
+/// A file or directory that was created, modified or removed
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchEvent {
+    pub kind: WatchKind,
+    pub path: PathBuf,
+}
+
+/// A directory to watch, optionally limited to some file names
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchTarget {
+    pub kind: WatchKind,
+    pub dir: PathBuf,
+    /// File names to report; empty reports everything in `dir`
+    pub files: Vec<String>,
+    /// Also watch subdirectories
+    pub recursive: bool,
+}
+
+impl WatchTarget {
+    fn matches(&self, path: &Path) -> bool {
+        if self.files.is_empty() {
+            return true;
+        }
+        path.parent() == Some(self.dir.as_path())
+            && path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| self.files.iter().any(|f| f == n))
+    }
+}
+
+/// Sessions, specs, the Factory, Codex and Claude settings files and the
+/// named Claude settings files, honouring configured paths.
+pub fn default_watch_targets_for_home(home_dir: &Path) -> Result<Vec<WatchTarget>, String> {
+    let config_paths = paths::load_config_paths_for_home(home_dir);
+    let factory_dir = paths::get_factory_home_for_home(home_dir, &config_paths)?;
+    let files = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+    Ok(vec![
+        WatchTarget {
+            kind: WatchKind::Sessions,
+            dir: sessions::sessions_dir_for_home(home_dir)?,
+            files: Vec::new(),
+            recursive: true,
+        },
+        WatchTarget {
+            kind: WatchKind::Specs,
+            dir: specs::specs_dir_for_home(home_dir)?,
+            files: Vec::new(),
+            recursive: false,
+        },
+        WatchTarget {
+            kind: WatchKind::FactorySettings,
+            dir: factory_dir,
+            files: files(&["settings.json", "config.json", "mcp.json"]),
+            recursive: false,
+        },
+        WatchTarget {
+            kind: WatchKind::CodexSettings,
+            dir: paths::get_codex_home_for_home(home_dir, &config_paths)?,
+            files: files(&["config.toml", "auth.json"]),
+            recursive: false,
+        },
+        WatchTarget {
+            kind: WatchKind::ClaudeSettings,
+            dir: paths::get_claude_home_for_home(home_dir, &config_paths)?,
+            files: files(&["settings.json", "settings.local.json"]),
+            recursive: false,
+        },
+        WatchTarget {
+            kind: WatchKind::ClaudeSettings,
+            dir: claude_settings_files::claude_settings_dir_for_home(home_dir),
+            files: Vec::new(),
+            recursive: false,
+        },
+    ])
+}
+
+/// A running watcher. Events queue up until drained; dropping the watcher
+/// stops its thread.
+#[derive(Debug)]
+pub struct FileWatcher {
+    events: Receiver<WatchEvent>,
+    added: Sender<WatchTarget>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FileWatcher {
+    /// Starts the platform backend (inotify, FSEvents, kqueue or
+    /// ReadDirectoryChanges).
+    pub fn start(targets: Vec<WatchTarget>) -> Result<Self, String> {
+        let (raw_tx, raw_rx) = mpsc::channel();
+        let watcher = notify::recommended_watcher(raw_tx)
+            .map_err(|e| format!("Failed to create file watcher: {e}"))?;
+        Ok(Self::spawn(Box::new(watcher), raw_rx, targets))
+    }
+
+    /// Starts the portable backend that compares file metadata every
+    /// `interval`.
+    pub fn start_polling(targets: Vec<WatchTarget>, interval: Duration) -> Result<Self, String> {
+        let (raw_tx, raw_rx) = mpsc::channel();
+        let config = notify::Config::default().with_poll_interval(interval);
+        let watcher = notify::PollWatcher::new(raw_tx, config)
+            .map_err(|e| format!("Failed to create file watcher: {e}"))?;
+        Ok(Self::spawn(Box::new(watcher), raw_rx, targets))
+    }
+
+    fn spawn(
+        watcher: Box<dyn Watcher + Send>,
+        raw: Receiver<notify::Result<notify::Event>>,
+        targets: Vec<WatchTarget>,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let (added_tx, added_rx) = mpsc::channel();
+        let mut state = Watched {
+            watcher,
+            targets: Vec::new(),
+        };
+        // Watch before returning so no later change is missed.
+        for target in targets {
+            state.add(target);
+        }
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let thread = std::thread::spawn(move || run(state, raw, added_rx, tx, thread_stop));
+        Self {
+            events: rx,
+            added: added_tx,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Also watches `target`, e.g. a project's `.claude` directory while one
+    /// of its settings layers is open. Watching the same target twice is a
+    /// no-op.
+    pub fn watch(&self, target: WatchTarget) {
+        let _ = self.added.send(target);
+    }
+
+    /// Returns all queued events without blocking.
+    pub fn drain(&self) -> Vec<WatchEvent> {
+        self.events.try_iter().collect()
+    }
+
+    /// Waits up to `timeout` for the next event.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<WatchEvent> {
+        self.events.recv_timeout(timeout).ok()
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+pub fn start_file_watcher_for_home(home_dir: &Path) -> Result<FileWatcher, String> {
+    FileWatcher::start(default_watch_targets_for_home(home_dir)?)
+}
+
+// ============================================================================
+// Watcher thread
+// ============================================================================
+
+struct TargetState {
+    target: WatchTarget,
+    /// The directory as the backend reports it (FSEvents resolves symlinks
+    /// such as `/var` -> `/private/var`); `None` while it is not watched.
+    root: Option<PathBuf>,
+}
+
+struct Watched {
+    watcher: Box<dyn Watcher + Send>,
+    targets: Vec<TargetState>,
+}
+
+impl Watched {
+    fn add(&mut self, target: WatchTarget) {
+        if self.targets.iter().any(|t| t.target == target) {
+            return;
+        }
+        let mut state = TargetState { target, root: None };
+        self.try_watch(&mut state);
+        self.targets.push(state);
+    }
+
+    fn try_watch(&mut self, state: &mut TargetState) -> bool {
+        let target = &state.target;
+        let Ok(root) = fs::canonicalize(&target.dir) else {
+            return false;
+        };
+        if !root.is_dir() {
+            return false;
+        }
+        // Targets can share a directory; keep the widest mode on it.
+        let recursive = target.recursive
+            || self
+                .targets
+                .iter()
+                .any(|t| t.root.as_ref() == Some(&root) && t.target.recursive);
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        if self.watcher.watch(&target.dir, mode).is_err() {
+            return false;
+        }
+        state.root = Some(root);
+        true
+    }
+
+    /// Retries targets whose directory was missing, returning the ones that
+    /// are now watched.
+    fn retry(&mut self) -> Vec<WatchTarget> {
+        let mut targets = std::mem::take(&mut self.targets);
+        let mut appeared = Vec::new();
+        for state in targets.iter_mut().filter(|t| t.root.is_none()) {
+            if self.try_watch(state) {
+                appeared.push(state.target.clone());
+            }
+        }
+        self.targets = targets;
+        appeared
+    }
+
+    /// Maps a backend event to events for the targets it belongs to.
+    fn events_for(&mut self, event: notify::Event) -> Vec<WatchEvent> {
+        if matches!(event.kind, EventKind::Access(_)) {
+            return Vec::new();
+        }
+        let is_dir_removal = matches!(
+            event.kind,
+            EventKind::Remove(notify::event::RemoveKind::Folder)
+        );
+        let mut out = Vec::new();
+        for raw in &event.paths {
+            for state in &mut self.targets {
+                let Some(root) = &state.root else {
+                    continue;
+                };
+                let target = &state.target;
+                let Some(path) = under_target(target, root, raw) else {
+                    continue;
+                };
+                if path == target.dir {
+                    // The watched directory itself went away.
+                    if !path.is_dir() {
+                        let _ = self.watcher.unwatch(&target.dir);
+                        state.root = None;
+                    }
+                    continue;
+                }
+                if is_dir_removal {
+                    continue;
+                }
+                if path.is_dir() {
+                    // Files created before the backend watched a new
+                    // subdirectory would otherwise go unreported.
+                    if target.recursive && matches!(event.kind, EventKind::Create(_)) {
+                        scan(target, &path, &mut out);
+                    }
+                    continue;
+                }
+                if target.matches(&path) {
+                    out.push(WatchEvent {
+                        kind: target.kind,
+                        path,
+                    });
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Returns `raw` rewritten onto `target.dir` when it lies in the target.
+fn under_target(target: &WatchTarget, root: &Path, raw: &Path) -> Option<PathBuf> {
+    let relative = raw
+        .strip_prefix(root)
+        .or_else(|_| raw.strip_prefix(&target.dir))
+        .ok()?;
+    if !target.recursive && relative.components().count() > 1 {
+        return None;
+    }
+    Some(target.dir.join(relative))
+}
+
+/// Reports every matching file under `dir`.
+fn scan(target: &WatchTarget, dir: &Path, out: &mut Vec<WatchEvent>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            if target.recursive {
+                scan(target, &path, out);
+            }
+        } else if target.matches(&path) {
+            out.push(WatchEvent {
+                kind: target.kind,
+                path,
+            });
+        }
+    }
+}
+
+fn run(
+    mut watched: Watched,
+    raw: Receiver<notify::Result<notify::Event>>,
+    added: Receiver<WatchTarget>,
+    tx: Sender<WatchEvent>,
+    stop: Arc<AtomicBool>,
+) {
+    let mut last_retry = Instant::now();
+
+    while !stop.load(Ordering::Relaxed) {
+        let mut out = Vec::new();
+        for target in added.try_iter() {
+            watched.add(target);
+        }
+        if last_retry.elapsed() >= DEFAULT_POLL_INTERVAL {
+            last_retry = Instant::now();
+            for target in watched.retry() {
+                out.push(WatchEvent {
+                    kind: target.kind,
+                    path: target.dir.clone(),
+                });
+                scan(&target, &target.dir, &mut out);
+            }
+        }
+
+        let mut next = raw.recv_timeout(Duration::from_millis(100)).ok();
+        while let Some(result) = next {
+            if let Ok(event) = result {
+                out.extend(watched.events_for(event));
+            }
+            next = raw.try_recv().ok();
+        }
+
+        for event in out {
+            if tx.send(event).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn targets(root: &Path) -> Vec<WatchTarget> {
+        vec![
+            WatchTarget {
+                kind: WatchKind::Sessions,
+                dir: root.join("sessions"),
+                files: Vec::new(),
+                recursive: true,
+            },
+            WatchTarget {
+                kind: WatchKind::FactorySettings,
+                dir: root.to_path_buf(),
+                files: vec!["settings.json".to_string()],
+                recursive: false,
+            },
+        ]
+    }
+
+    /// Waits for an event of `kind` on `path`, skipping unrelated ones.
+    fn wait_for(watcher: &FileWatcher, kind: WatchKind, path: &Path) -> bool {
+        wait_for_within(watcher, kind, path, Duration::from_secs(5))
+    }
+
+    fn wait_for_within(
+        watcher: &FileWatcher,
+        kind: WatchKind,
+        path: &Path,
+        timeout: Duration,
+    ) -> bool {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            if let Some(event) = watcher.recv_timeout(Duration::from_millis(50)) {
+                if event.kind == kind && event.path == path {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn exercise(watcher: &FileWatcher, root: &Path) {
+        // Ignored: not one of the watched settings files.
+        fs::write(root.join("other.json"), "{}").unwrap();
+        let settings = root.join("settings.json");
+        fs::write(&settings, "{}").unwrap();
+        assert!(wait_for(watcher, WatchKind::FactorySettings, &settings));
+
+        // The sessions dir does not exist at start; files written right
+        // after their directory is created are still seen.
+        let project = root.join("sessions").join("-repo");
+        fs::create_dir_all(&project).unwrap();
+        let session = project.join("s1.jsonl");
+        fs::write(&session, "{}\n").unwrap();
+        assert!(wait_for(watcher, WatchKind::Sessions, &session));
+
+        let nested = root.join("sessions").join("-other");
+        fs::create_dir_all(&nested).unwrap();
+        let second = nested.join("s2.jsonl");
+        fs::write(&second, "{}\n").unwrap();
+        assert!(wait_for(watcher, WatchKind::Sessions, &second));
+
+        assert!(watcher
+            .drain()
+            .iter()
+            .all(|e| e.path != root.join("other.json")));
+    }
+
+    #[test]
+    fn polling_backend_reports_changes() {
+        let temp = TempDir::new().unwrap();
+        let watcher =
+            FileWatcher::start_polling(targets(temp.path()), Duration::from_millis(50)).unwrap();
+        exercise(&watcher, temp.path());
+    }
+
+    #[test]
+    fn native_backend_reports_changes() {
+        let temp = TempDir::new().unwrap();
+        let watcher = FileWatcher::start(targets(temp.path())).unwrap();
+        exercise(&watcher, temp.path());
+    }
+
+    #[test]
+    fn added_targets_are_watched() {
+        let temp = TempDir::new().unwrap();
+        let claude = temp.path().join("project").join(".claude");
+        fs::create_dir_all(&claude).unwrap();
+        let watcher = FileWatcher::start(Vec::new()).unwrap();
+        let target = WatchTarget {
+            kind: WatchKind::ClaudeSettings,
+            dir: claude.clone(),
+            files: vec!["settings.local.json".to_string()],
+            recursive: false,
+        };
+        watcher.watch(target.clone());
+        watcher.watch(target);
+
+        // The target is added on the watcher thread, so keep writing until
+        // a change is reported.
+        let layer = claude.join("settings.local.json");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut seen = false;
+        while !seen && Instant::now() < deadline {
+            fs::write(&layer, "{}").unwrap();
+            seen = wait_for_within(
+                &watcher,
+                WatchKind::ClaudeSettings,
+                &layer,
+                Duration::from_millis(200),
+            );
+        }
+        assert!(seen);
+    }
+
+    #[test]
+    fn default_targets_include_named_claude_settings() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let dir = claude_settings_files::claude_settings_dir_for_home(home);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("work.json"), "{}").unwrap();
+        let named =
+            claude_settings_files::get_settings_path_by_name_for_home(home, "work").unwrap();
+        let targets = default_watch_targets_for_home(home).unwrap();
+        assert!(targets.iter().any(|t| t.kind == WatchKind::ClaudeSettings
+            && named.parent() == Some(t.dir.as_path())
+            && t.matches(&named)));
+    }
+}
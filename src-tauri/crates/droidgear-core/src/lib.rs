@@ -1,26 +1,43 @@
 pub mod channel;
 pub mod channel_export;
 pub mod claude;
+pub mod claude_agents;
+pub mod claude_permissions;
 pub mod claude_runtime;
+pub mod claude_sessions;
 pub mod claude_settings_files;
+pub mod claude_settings_sections;
 pub mod codex;
 pub mod codex_auth_profiles;
+pub mod codex_model_catalog;
+pub mod codex_native_profiles;
 pub mod codex_runtime;
+pub mod codex_sessions;
 pub mod connectivity;
 pub mod droid_runtime;
 pub mod droid_settings_files;
 pub mod factory_auth_profiles;
 pub mod factory_settings;
+pub mod file_watcher;
 pub mod hermes;
 pub mod json;
 pub mod mcp;
+pub mod mcp_presets;
+pub mod mcp_probe;
+pub mod mcp_sync;
+pub mod model_registry;
 pub mod omp;
 pub mod openclaw;
 pub mod opencode;
 pub mod paths;
 pub mod pi;
+pub mod session_analytics;
+pub mod session_export;
+pub mod session_retention;
 pub mod sessions;
+pub mod spec_templates;
 pub mod specs;
+pub mod specs_history;
 pub mod storage;
 pub mod trusted_folders;
 
@@ -1,32 +1,82 @@
 //! MCP (Model Context Protocol) server configuration management (core).
 //!
-//! Handles reading and writing MCP server configurations in `~/.factory/mcp.json`.
+//! DroidGear keeps its own MCP server catalog in `~/.droidgear/mcp-servers.json`
+//! and renders it into each tool's config (see [`crate::mcp_sync`]). Factory's
+//! `~/.factory/mcp.json` is kept in step on every change, and servers added
+//! there by hand show up in the catalog until the next write adopts them.
+//!
+//! Repositories can also carry project-scoped servers in `.factory/mcp.json`
+//! (Droid) and `.mcp.json` (Claude Code); those files are edited in place.
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use specta::Type;
 use std::collections::HashMap;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::mcp_probe;
+use crate::mcp_sync::{self, McpSyncTarget};
+use crate::{paths, storage};
 
-use crate::paths;
+/// How long a `Command` header source may run
+const HEADER_COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
 
 // ============================================================================
 // Types
 // ============================================================================
 
 /// MCP server type
-#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum McpServerType {
+    #[default]
     Stdio,
+    /// Streamable HTTP
     Http,
+    /// Legacy HTTP+SSE transport (GET an event stream, POST to its endpoint)
+    Sse,
+}
+
+impl McpServerType {
+    /// Whether the server is reached over the network (`url`/`headers`).
+    pub fn is_remote(&self) -> bool {
+        matches!(self, McpServerType::Http | McpServerType::Sse)
+    }
+}
+
+/// Where a dynamic header value comes from
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpHeaderSourceKind {
+    /// An environment variable
+    Env,
+    /// A file holding the value (`~` expands to home)
+    File,
+    /// A shell command printing the value, e.g. a token refresh
+    Command,
+}
+
+/// Header value resolved when syncing or probing instead of stored inline
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct McpHeaderSource {
+    pub kind: McpHeaderSourceKind,
+    /// Variable name, file path or shell command
+    pub value: String,
+    /// Prepended to the resolved value, e.g. `Bearer `
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
 }
 
 /// MCP server configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct McpServerConfig {
-    /// Server type (stdio or http)
+    /// Server type (stdio, http or sse)
     #[serde(rename = "type")]
     pub server_type: McpServerType,
     /// Whether the server is disabled
@@ -41,12 +91,22 @@ pub struct McpServerConfig {
     /// Environment variables (stdio only)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
-    /// HTTP URL (http only)
+    /// Server URL (http/sse only)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
-    /// HTTP headers (http only)
+    /// Static HTTP headers (http/sse only)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<HashMap<String, String>>,
+    /// Headers resolved from env vars, files or commands (http/sse only).
+    /// DroidGear-only; an explicit sync writes the resolved values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header_sources: Option<HashMap<String, McpHeaderSource>>,
+    /// Seconds to wait for the server to start and answer `initialize`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startup_timeout_sec: Option<u32>,
+    /// Seconds a single tool call may take
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_timeout_sec: Option<u32>,
 }
 
 /// MCP server entry with name
@@ -59,76 +119,100 @@ pub struct McpServer {
     pub config: McpServerConfig,
 }
 
-// ============================================================================
-// Helpers
-// ============================================================================
-
-fn mcp_config_path_for_home(home_dir: &Path) -> Result<PathBuf, String> {
-    let config_paths = paths::load_config_paths_for_home(home_dir);
-    let factory_dir = paths::get_factory_home_for_home(home_dir, &config_paths)?;
-
-    if !factory_dir.exists() {
-        std::fs::create_dir_all(&factory_dir)
-            .map_err(|e| format!("Failed to create .factory directory: {e}"))?;
-    }
+/// Where an MCP server is configured
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpScope {
+    /// The DroidGear catalog, synced to every tool
+    User,
+    /// A project-level file inside a repository
+    Project,
+}
 
-    Ok(factory_dir.join("mcp.json"))
+/// Project-level MCP file
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpProjectFile {
+    /// `.factory/mcp.json`, read by Droid
+    Factory,
+    /// `.mcp.json`, read by Claude Code
+    Claude,
 }
 
-fn read_mcp_file_for_home(home_dir: &Path) -> Result<Value, String> {
-    let config_path = mcp_config_path_for_home(home_dir)?;
+impl McpProjectFile {
+    /// Lowest to highest precedence.
+    pub const ALL: [McpProjectFile; 2] = [McpProjectFile::Claude, McpProjectFile::Factory];
 
-    if !config_path.exists() {
-        return Ok(serde_json::json!({ "mcpServers": {} }));
+    pub fn relative_path(self) -> &'static str {
+        match self {
+            McpProjectFile::Factory => ".factory/mcp.json",
+            McpProjectFile::Claude => ".mcp.json",
+        }
     }
 
-    let contents = std::fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read MCP config file: {e}"))?;
-
-    if contents.trim().is_empty() {
-        return Ok(serde_json::json!({ "mcpServers": {} }));
+    pub fn path_in(self, project_dir: &Path) -> PathBuf {
+        project_dir.join(self.relative_path())
     }
 
-    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse MCP config JSON: {e}"))
+    fn sync_target(self) -> McpSyncTarget {
+        match self {
+            McpProjectFile::Factory => McpSyncTarget::Factory,
+            McpProjectFile::Claude => McpSyncTarget::Claude,
+        }
+    }
 }
 
-fn write_mcp_file_for_home(home_dir: &Path, config: &Value) -> Result<(), String> {
-    let config_path = mcp_config_path_for_home(home_dir)?;
-
-    let actual_path = if config_path.is_symlink() {
-        std::fs::canonicalize(&config_path)
-            .map_err(|e| format!("Failed to resolve symlink: {e}"))?
-    } else {
-        config_path
-    };
-
-    let temp_path = actual_path.with_extension("tmp");
-    let json_content = serde_json::to_string_pretty(config)
-        .map_err(|e| format!("Failed to serialize MCP config: {e}"))?;
-
-    std::fs::write(&temp_path, json_content)
-        .map_err(|e| format!("Failed to write MCP config file: {e}"))?;
-
-    std::fs::rename(&temp_path, &actual_path).map_err(|e| {
-        let _ = std::fs::remove_file(&temp_path);
-        format!("Failed to finalize MCP config file: {e}")
-    })?;
+/// A project-level MCP file and the servers it defines
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpProjectConfig {
+    pub file: McpProjectFile,
+    pub path: String,
+    pub exists: bool,
+    pub servers: Vec<McpServer>,
+}
 
-    Ok(())
+/// Where an effective server comes from
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerOrigin {
+    pub scope: McpScope,
+    /// Set for project-scoped servers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<McpProjectFile>,
+    pub path: String,
 }
 
-fn system_home_dir() -> Result<PathBuf, String> {
-    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+/// A server in the merged user + project view
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpEffectiveServer {
+    pub name: String,
+    pub config: McpServerConfig,
+    /// The definition that wins
+    pub origin: McpServerOrigin,
+    /// Definitions of the same name it overrides, lowest precedence first
+    pub shadowed: Vec<McpServerOrigin>,
 }
 
 // ============================================================================
-// Public API
+// Helpers
 // ============================================================================
 
-pub fn load_mcp_servers_for_home(home_dir: &Path) -> Result<Vec<McpServer>, String> {
-    let config = read_mcp_file_for_home(home_dir)?;
+/// `~/.droidgear/mcp-servers.json`, the DroidGear-owned MCP catalog
+pub fn mcp_catalog_path_for_home(home_dir: &Path) -> PathBuf {
+    paths::droidgear_dir_from_home(home_dir).join("mcp-servers.json")
+}
 
-    let servers: Vec<McpServer> = config
+/// `~/.factory/mcp.json` (or custom Factory home)
+pub(crate) fn factory_mcp_path_for_home(home_dir: &Path) -> Result<PathBuf, String> {
+    let config_paths = paths::load_config_paths_for_home(home_dir);
+    let factory_dir = paths::get_factory_home_for_home(home_dir, &config_paths)?;
+    Ok(factory_dir.join("mcp.json"))
+}
+
+fn servers_from_value(config: &Value) -> Vec<McpServer> {
+    config
         .get("mcpServers")
         .and_then(|v| v.as_object())
         .map(|obj| {
@@ -142,50 +226,365 @@ pub fn load_mcp_servers_for_home(home_dir: &Path) -> Result<Vec<McpServer>, Stri
                 })
                 .collect()
         })
-        .unwrap_or_default();
+        .unwrap_or_default()
+}
 
-    Ok(servers)
+/// The catalog as stored, plus Factory entries DroidGear does not manage yet.
+pub(crate) struct Catalog {
+    pub servers: Vec<McpServer>,
+    /// Unmanaged Factory entries shown as catalog servers; they are adopted
+    /// (written to the catalog and marked as owned) on the next write.
+    pub imported: Vec<String>,
+    /// Catalog servers whose name an unmanaged Factory entry also uses
+    pub conflicts: Vec<String>,
 }
 
-pub fn load_mcp_servers() -> Result<Vec<McpServer>, String> {
-    load_mcp_servers_for_home(&system_home_dir()?)
+/// Parses the stored catalog strictly: a malformed file or entry is an error
+/// rather than an empty catalog that the next save would sync to Factory.
+fn read_stored_catalog(path: &Path) -> Result<Vec<McpServer>, String> {
+    let config = read_json_object(path)?;
+    let Some(servers) = config.get("mcpServers") else {
+        return Ok(Vec::new());
+    };
+    let servers = servers
+        .as_object()
+        .ok_or_else(|| format!("{}: mcpServers is not an object", path.display()))?;
+    servers
+        .iter()
+        .map(|(name, value)| {
+            let config: McpServerConfig = serde_json::from_value(value.clone())
+                .map_err(|e| format!("{}: invalid server {name}: {e}", path.display()))?;
+            Ok(McpServer {
+                name: name.clone(),
+                config,
+            })
+        })
+        .collect()
 }
 
-pub fn save_mcp_server_for_home(home_dir: &Path, server: McpServer) -> Result<(), String> {
-    let mut config = read_mcp_file_for_home(home_dir)?;
+/// Reads the catalog and picks up servers added to Factory's `mcp.json`
+/// outside DroidGear. Nothing is written.
+pub(crate) fn read_catalog_for_home(home_dir: &Path) -> Result<Catalog, String> {
+    let mut catalog = Catalog {
+        servers: read_stored_catalog(&mcp_catalog_path_for_home(home_dir))?,
+        imported: Vec::new(),
+        conflicts: Vec::new(),
+    };
 
-    let server_value = serde_json::to_value(&server.config)
-        .map_err(|e| format!("Failed to serialize server config: {e}"))?;
+    // A broken Factory file must not hide the catalog; the next sync reports it.
+    let unmanaged = mcp_sync::unmanaged_entries_for_home(home_dir, McpSyncTarget::Factory)
+        .unwrap_or_else(|e| {
+            log::warn!("MCP: not importing Factory servers: {e}");
+            Default::default()
+        });
+    for server in servers_from_value(&serde_json::json!({ "mcpServers": unmanaged })) {
+        if catalog.servers.iter().any(|s| s.name == server.name) {
+            catalog.conflicts.push(server.name);
+        } else {
+            catalog.imported.push(server.name.clone());
+            catalog.servers.push(server);
+        }
+    }
+    Ok(catalog)
+}
 
-    if let Some(obj) = config.as_object_mut() {
-        let mcp_servers = obj
-            .entry("mcpServers")
-            .or_insert_with(|| serde_json::json!({}));
+/// Writes imported Factory servers into the catalog and marks them as owned,
+/// so later syncs treat them like any other catalog server.
+pub(crate) fn adopt_imported_for_home(home_dir: &Path, catalog: &Catalog) -> Result<(), String> {
+    if catalog.imported.is_empty() {
+        return Ok(());
+    }
+    write_catalog_for_home(home_dir, &catalog.servers)?;
+    mcp_sync::adopt_owned_for_home(home_dir, McpSyncTarget::Factory, &catalog.imported)
+}
 
-        if let Some(servers_obj) = mcp_servers.as_object_mut() {
-            servers_obj.insert(server.name.clone(), server_value);
-        }
+fn write_catalog_for_home(home_dir: &Path, servers: &[McpServer]) -> Result<(), String> {
+    let mut map = serde_json::Map::new();
+    for server in servers {
+        let value = serde_json::to_value(&server.config)
+            .map_err(|e| format!("Failed to serialize server config: {e}"))?;
+        map.insert(server.name.clone(), value);
     }
+    let config = serde_json::json!({ "mcpServers": map });
+    let s = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize MCP catalog: {e}"))?;
+    storage::atomic_write(&mcp_catalog_path_for_home(home_dir), s.as_bytes())
+}
 
-    write_mcp_file_for_home(home_dir, &config)
+/// Persists the catalog and keeps Factory's `mcp.json` in step with it.
+/// Header sources are not resolved here, so a failing or slow source never
+/// blocks an unrelated edit; an explicit sync refreshes those entries.
+fn commit_catalog_for_home(home_dir: &Path, catalog: &Catalog) -> Result<(), String> {
+    // Catalog first: a name owned but missing from the catalog would be
+    // removed from Factory by the sync.
+    write_catalog_for_home(home_dir, &catalog.servers)?;
+    if !catalog.imported.is_empty() {
+        mcp_sync::adopt_owned_for_home(home_dir, McpSyncTarget::Factory, &catalog.imported)?;
+    }
+    mcp_sync::sync_targets_for_home(
+        home_dir,
+        &[McpSyncTarget::Factory],
+        mcp_sync::HeaderMode::Deferred,
+    )?;
+    Ok(())
 }
 
-pub fn save_mcp_server(server: McpServer) -> Result<(), String> {
-    save_mcp_server_for_home(&system_home_dir()?, server)
+fn expand_home(path: &str, home_dir: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home_dir.join(rest),
+        None if path == "~" => home_dir.to_path_buf(),
+        None => PathBuf::from(path),
+    }
 }
 
-pub fn delete_mcp_server_for_home(home_dir: &Path, name: &str) -> Result<(), String> {
-    let mut config = read_mcp_file_for_home(home_dir)?;
+/// Reads `pipe` to the end on a thread and hands the bytes to `rx`.
+fn drain_pipe(pipe: Option<impl Read + Send + 'static>) -> mpsc::Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel();
+    if let Some(mut pipe) = pipe {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = pipe.read_to_end(&mut buf);
+            let _ = tx.send(buf);
+        });
+    }
+    rx
+}
 
-    if let Some(obj) = config.as_object_mut() {
-        if let Some(mcp_servers) = obj.get_mut("mcpServers") {
-            if let Some(servers_obj) = mcp_servers.as_object_mut() {
-                servers_obj.remove(name);
+fn run_header_command(command: &str, timeout: Duration) -> Result<String, String> {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    let mut child = mcp_probe::isolate_process_group(&mut cmd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run `{command}`: {e}"))?;
+    let stdout = drain_pipe(child.stdout.take());
+    let stderr = drain_pipe(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(20));
             }
+            Ok(None) => {
+                mcp_probe::stop_child(&mut child);
+                return Err(format!(
+                    "`{command}` did not finish within {}s",
+                    timeout.as_secs_f32()
+                ));
+            }
+            Err(e) => {
+                mcp_probe::stop_child(&mut child);
+                return Err(format!("Failed to wait for `{command}`: {e}"));
+            }
+        }
+    };
+
+    // Processes that left the group may keep the pipes open; don't wait on them.
+    let drain = |rx: mpsc::Receiver<Vec<u8>>| {
+        rx.recv_timeout(Duration::from_secs(1))
+            .map(|buf| String::from_utf8_lossy(&buf).to_string())
+            .unwrap_or_default()
+    };
+    let (stdout, stderr) = (drain(stdout), drain(stderr));
+    if !status.success() {
+        return Err(format!(
+            "`{command}` exited with {status}: {}",
+            stderr.trim()
+        ));
+    }
+    Ok(stdout)
+}
+
+fn resolve_header_source(source: &McpHeaderSource, home_dir: &Path) -> Result<String, String> {
+    let raw = match source.kind {
+        McpHeaderSourceKind::Env => std::env::var(&source.value)
+            .map_err(|_| format!("Environment variable {} is not set", source.value))?,
+        McpHeaderSourceKind::File => {
+            let path = expand_home(&source.value, home_dir);
+            std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {e}", path.display()))?
         }
+        McpHeaderSourceKind::Command => run_header_command(&source.value, HEADER_COMMAND_TIMEOUT)?,
+    };
+    let value = raw.trim();
+    if value.is_empty() {
+        return Err(format!("{} resolved to an empty value", source.value));
+    }
+    Ok(format!("{}{value}", source.prefix.as_deref().unwrap_or("")))
+}
+
+/// Static headers merged with resolved `header_sources` (sources win).
+pub fn resolve_mcp_headers_for_home(
+    home_dir: &Path,
+    config: &McpServerConfig,
+) -> Result<HashMap<String, String>, String> {
+    let mut headers = config.headers.clone().unwrap_or_default();
+    for (name, source) in config.header_sources.iter().flatten() {
+        let value = resolve_header_source(source, home_dir)
+            .map_err(|e| format!("Failed to resolve header {name}: {e}"))?;
+        headers.insert(name.clone(), value);
+    }
+    Ok(headers)
+}
+
+pub fn resolve_mcp_headers(config: &McpServerConfig) -> Result<HashMap<String, String>, String> {
+    resolve_mcp_headers_for_home(&system_home_dir()?, config)
+}
+
+/// Project files may omit `type` (Claude Code infers it from the fields).
+fn project_servers_from_value(config: &Value) -> Vec<McpServer> {
+    let Some(obj) = config.get("mcpServers").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+    obj.iter()
+        .filter_map(|(name, value)| {
+            let mut value = value.clone();
+            let entry = value.as_object_mut()?;
+            if !entry.contains_key("type") {
+                let kind = if entry.contains_key("url") {
+                    "http"
+                } else {
+                    "stdio"
+                };
+                entry.insert("type".into(), kind.into());
+            }
+            let config: McpServerConfig = serde_json::from_value(value).ok()?;
+            Some(McpServer {
+                name: name.clone(),
+                config,
+            })
+        })
+        .collect()
+}
+
+/// Reads an MCP JSON file strictly so a malformed file is never overwritten.
+fn read_json_object(path: &Path) -> Result<Value, String> {
+    if !path.exists() {
+        return Ok(serde_json::json!({}));
+    }
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    if contents.trim().is_empty() {
+        return Ok(serde_json::json!({}));
+    }
+    let value: Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+    if !value.is_object() {
+        return Err(format!("{} is not a JSON object", path.display()));
+    }
+    Ok(value)
+}
+
+/// Renders `server` for a project file. Secrets resolved from header sources
+/// would end up committed, so only static headers are allowed.
+fn render_project_server(file: McpProjectFile, server: &McpServer) -> Result<Value, String> {
+    if server.config.header_sources.is_some() {
+        return Err(format!(
+            "{} uses dynamic header sources, which {} cannot hold",
+            server.name,
+            file.relative_path()
+        ));
+    }
+    let headers = server.config.headers.clone().unwrap_or_default();
+    mcp_sync::render_server(file.sync_target(), server, &headers).ok_or_else(|| {
+        let reason = if server.config.disabled {
+            "disabled servers"
+        } else {
+            "this transport"
+        };
+        format!(
+            "{} cannot hold {} ({})",
+            file.relative_path(),
+            reason,
+            server.name
+        )
+    })
+}
+
+/// Applies `edit` to the `mcpServers` map of a project file, keeping every
+/// other key as-is.
+fn edit_project_file(
+    project_dir: &Path,
+    file: McpProjectFile,
+    edit: impl FnOnce(&mut serde_json::Map<String, Value>) -> Result<(), String>,
+) -> Result<(), String> {
+    let path = file.path_in(project_dir);
+    let mut config = read_json_object(&path)?;
+    let Some(root) = config.as_object_mut() else {
+        return Err(format!("{} is not a JSON object", path.display()));
+    };
+    let servers = root
+        .entry("mcpServers")
+        .or_insert_with(|| serde_json::json!({}));
+    let servers = servers
+        .as_object_mut()
+        .ok_or_else(|| format!("{}: mcpServers is not an object", path.display()))?;
+    edit(servers)?;
+    let s = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize {}: {e}", path.display()))?;
+    storage::atomic_write(&path, s.as_bytes())
+}
+
+fn project_origin(project_dir: &Path, file: McpProjectFile) -> McpServerOrigin {
+    McpServerOrigin {
+        scope: McpScope::Project,
+        file: Some(file),
+        path: file.path_in(project_dir).to_string_lossy().to_string(),
     }
+}
+
+fn system_home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+pub fn load_mcp_servers_for_home(home_dir: &Path) -> Result<Vec<McpServer>, String> {
+    Ok(read_catalog_for_home(home_dir)?.servers)
+}
+
+pub fn load_mcp_servers() -> Result<Vec<McpServer>, String> {
+    load_mcp_servers_for_home(&system_home_dir()?)
+}
+
+pub fn save_mcp_server_for_home(home_dir: &Path, server: McpServer) -> Result<(), String> {
+    let mut catalog = read_catalog_for_home(home_dir)?;
+    if catalog.conflicts.contains(&server.name) {
+        let path = factory_mcp_path_for_home(home_dir)?;
+        return Err(format!(
+            "{} already has a server named {} that DroidGear does not manage",
+            path.display(),
+            server.name
+        ));
+    }
+    match catalog.servers.iter_mut().find(|s| s.name == server.name) {
+        Some(existing) => *existing = server,
+        None => catalog.servers.push(server),
+    }
+    commit_catalog_for_home(home_dir, &catalog)
+}
+
+pub fn save_mcp_server(server: McpServer) -> Result<(), String> {
+    save_mcp_server_for_home(&system_home_dir()?, server)
+}
 
-    write_mcp_file_for_home(home_dir, &config)
+pub fn delete_mcp_server_for_home(home_dir: &Path, name: &str) -> Result<(), String> {
+    let mut catalog = read_catalog_for_home(home_dir)?;
+    catalog.servers.retain(|s| s.name != name);
+    commit_catalog_for_home(home_dir, &catalog)
 }
 
 pub fn delete_mcp_server(name: &str) -> Result<(), String> {
@@ -197,25 +596,200 @@ pub fn toggle_mcp_server_for_home(
     name: &str,
     disabled: bool,
 ) -> Result<(), String> {
-    let mut config = read_mcp_file_for_home(home_dir)?;
-
-    if let Some(obj) = config.as_object_mut() {
-        if let Some(mcp_servers) = obj.get_mut("mcpServers") {
-            if let Some(servers_obj) = mcp_servers.as_object_mut() {
-                if let Some(server) = servers_obj.get_mut(name) {
-                    if let Some(server_obj) = server.as_object_mut() {
-                        server_obj.insert("disabled".to_string(), serde_json::json!(disabled));
-                    }
-                } else {
-                    return Err(format!("Server not found: {name}"));
+    let mut catalog = read_catalog_for_home(home_dir)?;
+    let server = catalog
+        .servers
+        .iter_mut()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("Server not found: {name}"))?;
+    server.config.disabled = disabled;
+    commit_catalog_for_home(home_dir, &catalog)
+}
+
+pub fn toggle_mcp_server(name: &str, disabled: bool) -> Result<(), String> {
+    toggle_mcp_server_for_home(&system_home_dir()?, name, disabled)
+}
+
+// ============================================================================
+// Project scope
+// ============================================================================
+
+/// Lists the project-level MCP files of `project_dir`, including missing ones
+/// so callers can offer to create them.
+pub fn load_project_mcp_configs(project_dir: &Path) -> Result<Vec<McpProjectConfig>, String> {
+    McpProjectFile::ALL
+        .iter()
+        .map(|&file| {
+            let path = file.path_in(project_dir);
+            Ok(McpProjectConfig {
+                file,
+                exists: path.exists(),
+                servers: project_servers_from_value(&read_json_object(&path)?),
+                path: path.to_string_lossy().to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn save_project_mcp_server(
+    project_dir: &Path,
+    file: McpProjectFile,
+    server: McpServer,
+) -> Result<(), String> {
+    let value = render_project_server(file, &server)?;
+    edit_project_file(project_dir, file, |servers| {
+        servers.insert(server.name.clone(), value);
+        Ok(())
+    })
+}
+
+pub fn delete_project_mcp_server(
+    project_dir: &Path,
+    file: McpProjectFile,
+    name: &str,
+) -> Result<(), String> {
+    edit_project_file(project_dir, file, |servers| {
+        servers
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| format!("Server not found: {name}"))
+    })
+}
+
+/// User servers merged with the project's. Project files win over the
+/// catalog and `.factory/mcp.json` wins over `.mcp.json`.
+pub fn effective_mcp_servers_for_home(
+    home_dir: &Path,
+    project_dir: &Path,
+) -> Result<Vec<McpEffectiveServer>, String> {
+    let user_origin = McpServerOrigin {
+        scope: McpScope::User,
+        file: None,
+        path: mcp_catalog_path_for_home(home_dir)
+            .to_string_lossy()
+            .to_string(),
+    };
+    let mut merged: Vec<McpEffectiveServer> = read_catalog_for_home(home_dir)?
+        .servers
+        .into_iter()
+        .map(|s| McpEffectiveServer {
+            name: s.name,
+            config: s.config,
+            origin: user_origin.clone(),
+            shadowed: Vec::new(),
+        })
+        .collect();
+
+    for project in load_project_mcp_configs(project_dir)? {
+        let origin = project_origin(project_dir, project.file);
+        for server in project.servers {
+            match merged.iter_mut().find(|e| e.name == server.name) {
+                Some(existing) => {
+                    let previous = std::mem::replace(&mut existing.origin, origin.clone());
+                    existing.shadowed.push(previous);
+                    existing.config = server.config;
                 }
+                None => merged.push(McpEffectiveServer {
+                    name: server.name,
+                    config: server.config,
+                    origin: origin.clone(),
+                    shadowed: Vec::new(),
+                }),
             }
         }
     }
+    Ok(merged)
+}
 
-    write_mcp_file_for_home(home_dir, &config)
+pub fn effective_mcp_servers(project_dir: &Path) -> Result<Vec<McpEffectiveServer>, String> {
+    effective_mcp_servers_for_home(&system_home_dir()?, project_dir)
 }
 
-pub fn toggle_mcp_server(name: &str, disabled: bool) -> Result<(), String> {
-    toggle_mcp_server_for_home(&system_home_dir()?, name, disabled)
+/// Moves a project server into the user catalog.
+pub fn promote_mcp_server_for_home(
+    home_dir: &Path,
+    project_dir: &Path,
+    file: McpProjectFile,
+    name: &str,
+) -> Result<(), String> {
+    let path = file.path_in(project_dir);
+    let server = project_servers_from_value(&read_json_object(&path)?)
+        .into_iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("Server not found in {}: {name}", path.display()))?;
+    let mut catalog = read_catalog_for_home(home_dir)?;
+    if catalog.servers.iter().any(|s| s.name == name) {
+        return Err(format!("A user-level server named {name} already exists"));
+    }
+    catalog.servers.push(server);
+    commit_catalog_for_home(home_dir, &catalog)?;
+    delete_project_mcp_server(project_dir, file, name)
+}
+
+pub fn promote_mcp_server(
+    project_dir: &Path,
+    file: McpProjectFile,
+    name: &str,
+) -> Result<(), String> {
+    promote_mcp_server_for_home(&system_home_dir()?, project_dir, file, name)
+}
+
+/// Moves a user server into a project file.
+pub fn demote_mcp_server_for_home(
+    home_dir: &Path,
+    project_dir: &Path,
+    file: McpProjectFile,
+    name: &str,
+) -> Result<(), String> {
+    let mut catalog = read_catalog_for_home(home_dir)?;
+    let index = catalog
+        .servers
+        .iter()
+        .position(|s| s.name == name)
+        .ok_or_else(|| format!("Server not found: {name}"))?;
+    let value = render_project_server(file, &catalog.servers[index])?;
+    edit_project_file(project_dir, file, |project| {
+        if project.contains_key(name) {
+            return Err(format!(
+                "{} already defines a server named {name}",
+                file.relative_path()
+            ));
+        }
+        project.insert(name.to_string(), value);
+        Ok(())
+    })?;
+    catalog.servers.remove(index);
+    commit_catalog_for_home(home_dir, &catalog)
+}
+
+pub fn demote_mcp_server(
+    project_dir: &Path,
+    file: McpProjectFile,
+    name: &str,
+) -> Result<(), String> {
+    demote_mcp_server_for_home(&system_home_dir()?, project_dir, file, name)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::run_header_command;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn header_commands_are_bounded_by_a_timeout() {
+        assert_eq!(
+            run_header_command("printf token", Duration::from_secs(5)).unwrap(),
+            "token"
+        );
+        assert!(
+            run_header_command("echo nope >&2; exit 3", Duration::from_secs(5))
+                .unwrap_err()
+                .contains("nope")
+        );
+
+        let started = Instant::now();
+        let err = run_header_command("sleep 30; true", Duration::from_millis(200)).unwrap_err();
+        assert!(err.contains("did not finish"), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
 }
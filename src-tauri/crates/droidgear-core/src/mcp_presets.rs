@@ -0,0 +1,454 @@
+//! MCP server presets (core).
+//!
+//! Presets ship bundled with DroidGear and can be added as JSON files under
+//! `~/.droidgear/mcp-presets/`. A preset declares the inputs it needs (API
+//! keys, paths, ...) and one or more variants whose config may reference them
+//! as `${inputId}`. Installing a preset validates the inputs, substitutes them
+//! and saves the result to the MCP catalog.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::mcp::{self, McpServer, McpServerConfig};
+use crate::paths;
+
+const BUNDLED_PRESETS_JSON: &str = include_str!("../res/mcp-presets.json");
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// What a preset input holds, which decides how it is validated
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpPresetInputKind {
+    #[default]
+    Text,
+    /// Masked when prompted
+    Secret,
+    /// Must exist; `~` expands to home
+    Path,
+    /// Must be an http(s) URL
+    Url,
+}
+
+/// A value the user supplies when installing a preset
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpPresetInput {
+    /// Referenced from variant configs as `${id}`
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub kind: McpPresetInputKind,
+    #[serde(default = "default_required")]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+    /// Used when the value is left blank
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Regex the value must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Where to obtain the value, e.g. an API key dashboard
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help_url: Option<String>,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// One way of running a preset (e.g. local stdio vs hosted HTTP)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpPresetVariant {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub config: McpServerConfig,
+}
+
+/// Where a preset was loaded from
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpPresetSource {
+    #[default]
+    Bundled,
+    User,
+}
+
+/// An installable MCP server template
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpPreset {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub inputs: Vec<McpPresetInput>,
+    pub variants: Vec<McpPresetVariant>,
+    #[serde(default, skip_deserializing)]
+    pub source: McpPresetSource,
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// `~/.droidgear/mcp-presets/`
+pub fn mcp_presets_dir_for_home(home_dir: &Path) -> PathBuf {
+    paths::droidgear_dir_from_home(home_dir).join("mcp-presets")
+}
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\$\{([A-Za-z0-9_-]+)\}").expect("valid pattern")
+}
+
+fn visit_strings(value: &mut Value, f: &mut impl FnMut(&mut String)) {
+    match value {
+        Value::String(s) => f(s),
+        Value::Array(items) => items.iter_mut().for_each(|v| visit_strings(v, f)),
+        Value::Object(map) => map.values_mut().for_each(|v| visit_strings(v, f)),
+        _ => {}
+    }
+}
+
+/// Checks that a preset is installable: it has variants, its input ids are
+/// unique and every placeholder refers to a declared input.
+fn validate_preset(preset: &McpPreset) -> Result<(), String> {
+    if preset.id.trim().is_empty() {
+        return Err("Preset id is required".to_string());
+    }
+    if preset.variants.is_empty() {
+        return Err(format!("Preset {} has no variants", preset.id));
+    }
+    let mut ids = HashSet::new();
+    for input in &preset.inputs {
+        if !ids.insert(input.id.as_str()) {
+            return Err(format!(
+                "Preset {} declares input {} twice",
+                preset.id, input.id
+            ));
+        }
+        if let Some(pattern) = &input.pattern {
+            Regex::new(pattern)
+                .map_err(|e| format!("Preset {} input {}: {e}", preset.id, input.id))?;
+        }
+    }
+    let re = placeholder_regex();
+    for variant in &preset.variants {
+        let mut config = serde_json::to_value(&variant.config)
+            .map_err(|e| format!("Failed to serialize preset config: {e}"))?;
+        let mut unknown = None;
+        visit_strings(&mut config, &mut |s| {
+            for cap in re.captures_iter(s) {
+                if !ids.contains(&cap[1]) && unknown.is_none() {
+                    unknown = Some(cap[1].to_string());
+                }
+            }
+        });
+        if let Some(name) = unknown {
+            return Err(format!(
+                "Preset {} variant {} uses undeclared input ${{{name}}}",
+                preset.id, variant.id
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn read_user_presets(home_dir: &Path) -> Result<Vec<McpPreset>, String> {
+    let dir = mcp_presets_dir_for_home(home_dir);
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut presets = Vec::new();
+    for path in paths {
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let mut preset: McpPreset = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+        validate_preset(&preset).map_err(|e| format!("{}: {e}", path.display()))?;
+        preset.source = McpPresetSource::User;
+        presets.push(preset);
+    }
+    Ok(presets)
+}
+
+/// Validates one input value, returning what gets substituted: the default
+/// for a blank value, and the expanded path for `path` inputs.
+pub fn validate_mcp_preset_input(
+    home_dir: &Path,
+    input: &McpPresetInput,
+    value: &str,
+) -> Result<String, String> {
+    let mut value = value.trim().to_string();
+    if value.is_empty() {
+        value = input.default.clone().unwrap_or_default();
+    }
+    if value.is_empty() {
+        if input.required {
+            return Err(format!("{} is required", input.label));
+        }
+        return Ok(value);
+    }
+
+    match input.kind {
+        McpPresetInputKind::Text | McpPresetInputKind::Secret => {}
+        McpPresetInputKind::Path => {
+            let path = match value.strip_prefix('~') {
+                Some("") => home_dir.to_path_buf(),
+                Some(rest) => home_dir.join(rest.trim_start_matches(['/', '\\'])),
+                None => PathBuf::from(&value),
+            };
+            if !path.exists() {
+                return Err(format!(
+                    "{}: {} does not exist",
+                    input.label,
+                    path.display()
+                ));
+            }
+            value = path.to_string_lossy().to_string();
+        }
+        McpPresetInputKind::Url => {
+            if !(value.starts_with("http://") || value.starts_with("https://")) {
+                return Err(format!("{} must be an http(s) URL", input.label));
+            }
+        }
+    }
+
+    if let Some(pattern) = &input.pattern {
+        let re = Regex::new(pattern).map_err(|e| format!("{}: {e}", input.label))?;
+        if !re.is_match(&value) {
+            return Err(format!("{} does not match {pattern}", input.label));
+        }
+    }
+    Ok(value)
+}
+
+/// Renders a preset variant into a server config. Args, env vars and headers
+/// left empty by optional inputs are dropped.
+pub fn render_mcp_preset(
+    home_dir: &Path,
+    preset: &McpPreset,
+    variant_id: &str,
+    values: &HashMap<String, String>,
+) -> Result<McpServerConfig, String> {
+    let variant = preset
+        .variants
+        .iter()
+        .find(|v| v.id == variant_id)
+        .ok_or_else(|| format!("Preset {} has no variant {variant_id}", preset.id))?;
+
+    let mut resolved = HashMap::new();
+    for input in &preset.inputs {
+        let raw = values.get(&input.id).map(String::as_str).unwrap_or("");
+        resolved.insert(
+            input.id.clone(),
+            validate_mcp_preset_input(home_dir, input, raw)?,
+        );
+    }
+
+    let re = placeholder_regex();
+    let mut config = serde_json::to_value(&variant.config)
+        .map_err(|e| format!("Failed to serialize preset config: {e}"))?;
+    visit_strings(&mut config, &mut |s| {
+        *s = re
+            .replace_all(s, |cap: &regex::Captures| {
+                resolved.get(&cap[1]).cloned().unwrap_or_default()
+            })
+            .to_string();
+    });
+
+    if let Some(args) = config.get_mut("args").and_then(Value::as_array_mut) {
+        args.retain(|a| a.as_str() != Some(""));
+    }
+    for key in ["env", "headers"] {
+        if let Some(map) = config.get_mut(key).and_then(Value::as_object_mut) {
+            map.retain(|_, v| v.as_str() != Some(""));
+            if map.is_empty() {
+                config.as_object_mut().map(|c| c.remove(key));
+            }
+        }
+    }
+
+    serde_json::from_value(config).map_err(|e| format!("Invalid preset config: {e}"))
+}
+
+fn system_home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Bundled presets followed by user presets; a user preset replaces a
+/// bundled one with the same id.
+pub fn load_mcp_presets_for_home(home_dir: &Path) -> Result<Vec<McpPreset>, String> {
+    let mut presets: Vec<McpPreset> = serde_json::from_str(BUNDLED_PRESETS_JSON)
+        .map_err(|e| format!("Failed to parse bundled MCP presets: {e}"))?;
+    for preset in read_user_presets(home_dir)? {
+        match presets.iter_mut().find(|p| p.id == preset.id) {
+            Some(existing) => *existing = preset,
+            None => presets.push(preset),
+        }
+    }
+    Ok(presets)
+}
+
+pub fn load_mcp_presets() -> Result<Vec<McpPreset>, String> {
+    load_mcp_presets_for_home(&system_home_dir()?)
+}
+
+/// Renders a preset and adds it to the MCP catalog as `server_name`.
+pub fn install_mcp_preset_for_home(
+    home_dir: &Path,
+    preset_id: &str,
+    variant_id: &str,
+    server_name: &str,
+    values: &HashMap<String, String>,
+) -> Result<McpServer, String> {
+    let name = server_name.trim();
+    if name.is_empty() {
+        return Err("Server name is required".to_string());
+    }
+    let preset = load_mcp_presets_for_home(home_dir)?
+        .into_iter()
+        .find(|p| p.id == preset_id)
+        .ok_or_else(|| format!("Preset not found: {preset_id}"))?;
+    if mcp::load_mcp_servers_for_home(home_dir)?
+        .iter()
+        .any(|s| s.name == name)
+    {
+        return Err(format!("MCP server {name} already exists"));
+    }
+
+    let server = McpServer {
+        name: name.to_string(),
+        config: render_mcp_preset(home_dir, &preset, variant_id, values)?,
+    };
+    mcp::save_mcp_server_for_home(home_dir, server.clone())?;
+    Ok(server)
+}
+
+pub fn install_mcp_preset(
+    preset_id: &str,
+    variant_id: &str,
+    server_name: &str,
+    values: &HashMap<String, String>,
+) -> Result<McpServer, String> {
+    install_mcp_preset_for_home(
+        &system_home_dir()?,
+        preset_id,
+        variant_id,
+        server_name,
+        values,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::McpServerType;
+    use tempfile::TempDir;
+
+    #[test]
+    fn bundled_presets_are_valid() {
+        let temp = TempDir::new().unwrap();
+        let presets = load_mcp_presets_for_home(temp.path()).unwrap();
+        assert!(presets.iter().any(|p| p.id == "exa"));
+        for preset in &presets {
+            validate_preset(preset).unwrap();
+            assert_eq!(preset.source, McpPresetSource::Bundled);
+        }
+    }
+
+    #[test]
+    fn install_validates_inputs_and_substitutes_placeholders() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+
+        let values = HashMap::from([("apiKey".to_string(), "has space".to_string())]);
+        let err = install_mcp_preset_for_home(home, "exa", "stdio", "exa", &values).unwrap_err();
+        assert!(err.contains("does not match"), "{err}");
+        let err =
+            install_mcp_preset_for_home(home, "exa", "stdio", "exa", &HashMap::new()).unwrap_err();
+        assert_eq!(err, "Exa API key is required");
+
+        let values = HashMap::from([("apiKey".to_string(), "ctx7-abc".to_string())]);
+        let server =
+            install_mcp_preset_for_home(home, "context7", "http", "docs", &values).unwrap();
+        assert_eq!(server.config.server_type, McpServerType::Http);
+        assert_eq!(
+            server.config.headers.unwrap()["CONTEXT7_API_KEY"],
+            "ctx7-abc"
+        );
+        let saved = mcp::load_mcp_servers_for_home(home).unwrap();
+        assert_eq!(saved[0].name, "docs");
+
+        let err =
+            install_mcp_preset_for_home(home, "context7", "http", "docs", &values).unwrap_err();
+        assert!(err.contains("already exists"));
+
+        // Path inputs default, expand `~` and must exist.
+        let server =
+            install_mcp_preset_for_home(home, "filesystem", "stdio", "fs", &HashMap::new())
+                .unwrap();
+        assert_eq!(
+            server.config.args.unwrap().last().unwrap(),
+            &home.to_string_lossy().to_string()
+        );
+        let values = HashMap::from([("root".to_string(), "~/missing".to_string())]);
+        assert!(
+            install_mcp_preset_for_home(home, "filesystem", "stdio", "fs2", &values)
+                .unwrap_err()
+                .contains("does not exist")
+        );
+    }
+
+    #[test]
+    fn user_presets_override_bundled_and_reject_unknown_placeholders() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let dir = mcp_presets_dir_for_home(home);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("exa.json"),
+            r#"{"id":"exa","name":"Exa (team)","inputs":[{"id":"token","label":"Token","required":false}],
+               "variants":[{"id":"stdio","config":{"type":"stdio","command":"exa","args":["--token","${token}"],"env":{"T":"${token}"}}}]}"#,
+        )
+        .unwrap();
+
+        let presets = load_mcp_presets_for_home(home).unwrap();
+        let exa = presets.iter().find(|p| p.id == "exa").unwrap();
+        assert_eq!(exa.name, "Exa (team)");
+        assert_eq!(exa.source, McpPresetSource::User);
+
+        // Optional inputs left blank drop their args and env entries.
+        let config = render_mcp_preset(home, exa, "stdio", &HashMap::new()).unwrap();
+        assert_eq!(config.args.unwrap(), vec!["--token"]);
+        assert!(config.env.is_none());
+
+        std::fs::write(
+            dir.join("bad.json"),
+            r#"{"id":"bad","name":"Bad","variants":[{"id":"x","config":{"type":"stdio","command":"${nope}"}}]}"#,
+        )
+        .unwrap();
+        let err = load_mcp_presets_for_home(home).unwrap_err();
+        assert!(err.contains("undeclared input ${nope}"), "{err}");
+    }
+}
@@ -0,0 +1,956 @@
+//! MCP server health check (core).
+//!
+//! Launches a stdio server (or POSTs to an HTTP one), performs the JSON-RPC
+//! `initialize` handshake and `tools/list`, and reports what the server says
+//! about itself. Failures keep the tail of the server's stderr.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use crate::mcp::{self, McpServerConfig, McpServerType};
+
+const MCP_PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+const PROTOCOL_VERSION: &str = "2025-06-18";
+const MAX_TOOL_PAGES: usize = 50;
+const STDERR_TAIL_BYTES: usize = 4096;
+const STDERR_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Outcome of probing one MCP server
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpProbeResult {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    pub tool_count: u32,
+    pub tool_names: Vec<String>,
+    /// Time from launch (or first request) to the `initialize` response
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Tail of the server's stderr (stdio only), kept on failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Handshake {
+    server_name: Option<String>,
+    server_version: Option<String>,
+    protocol_version: Option<String>,
+    latency_ms: u64,
+    tool_names: Vec<String>,
+}
+
+impl Handshake {
+    fn apply_initialize(&mut self, result: &Value, started_at: Instant) {
+        self.latency_ms = started_at.elapsed().as_millis() as u64;
+        let info = result.get("serverInfo");
+        let text = |v: Option<&Value>| v.and_then(|v| v.as_str()).map(String::from);
+        self.server_name = text(info.and_then(|i| i.get("name")));
+        self.server_version = text(info.and_then(|i| i.get("version")));
+        self.protocol_version = text(result.get("protocolVersion"));
+    }
+
+    /// Records one `tools/list` page and returns the next cursor.
+    fn apply_tools_page(&mut self, result: &Value) -> Option<String> {
+        if let Some(tools) = result.get("tools").and_then(|v| v.as_array()) {
+            self.tool_names.extend(
+                tools
+                    .iter()
+                    .filter_map(|t| t.get("name").and_then(|n| n.as_str()))
+                    .map(String::from),
+            );
+        }
+        result
+            .get("nextCursor")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+    }
+
+    fn into_result(self, error: Option<String>, stderr: Option<String>) -> McpProbeResult {
+        McpProbeResult {
+            success: error.is_none(),
+            server_name: self.server_name,
+            server_version: self.server_version,
+            protocol_version: self.protocol_version,
+            tool_count: self.tool_names.len() as u32,
+            tool_names: self.tool_names,
+            latency_ms: self.latency_ms,
+            error,
+            stderr,
+        }
+    }
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn initialize_request() -> Value {
+    request(
+        1,
+        "initialize",
+        json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "droidgear", "version": env!("CARGO_PKG_VERSION") },
+        }),
+    )
+}
+
+fn initialized_notification() -> Value {
+    json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })
+}
+
+fn tools_list_request(id: u64, cursor: Option<&str>) -> Value {
+    let params = match cursor {
+        Some(cursor) => json!({ "cursor": cursor }),
+        None => json!({}),
+    };
+    request(id, "tools/list", params)
+}
+
+/// Returns the `result` of a response, or the JSON-RPC error as text.
+fn response_result(response: &Value, method: &str) -> Result<Value, String> {
+    if let Some(error) = response.get("error") {
+        let message = error
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("unknown error");
+        return Err(format!("{method} failed: {message}"));
+    }
+    Ok(response.get("result").cloned().unwrap_or(Value::Null))
+}
+
+/// Whether `message` is the response to request `id` (not a server request
+/// or notification that happens to reuse the id).
+fn is_response_to(message: &Value, id: u64) -> bool {
+    message.get("method").is_none() && message.get("id").and_then(|v| v.as_u64()) == Some(id)
+}
+
+fn tail(s: &str, max_bytes: usize) -> String {
+    let s = s.trim_end();
+    if s.len() <= max_bytes {
+        return s.to_string();
+    }
+    let mut start = s.len() - max_bytes;
+    while !s.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("…{}", &s[start..])
+}
+
+// ============================================================================
+// stdio
+// ============================================================================
+
+struct StdioSession {
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    deadline: Instant,
+    timeout: Duration,
+}
+
+impl StdioSession {
+    fn send(&mut self, message: &Value) -> Result<(), String> {
+        let method = message
+            .get("method")
+            .and_then(|v| v.as_str())
+            .unwrap_or("request");
+        let mut line = message.to_string();
+        line.push('\n');
+        self.stdin
+            .write_all(line.as_bytes())
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::BrokenPipe => {
+                    format!("MCP server exited before answering {method}")
+                }
+                _ => format!("Failed to write to MCP server: {e}"),
+            })
+    }
+
+    fn call(&mut self, message: Value) -> Result<Value, String> {
+        let id = message.get("id").and_then(|v| v.as_u64()).unwrap_or(0);
+        let method = message
+            .get("method")
+            .and_then(|v| v.as_str())
+            .unwrap_or("request")
+            .to_string();
+        self.send(&message)?;
+
+        loop {
+            let remaining = self.deadline.saturating_duration_since(Instant::now());
+            let line = match self.lines.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(format!(
+                        "Timed out waiting for {method} after {} seconds",
+                        self.timeout.as_secs()
+                    ))
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(format!("MCP server exited before answering {method}"))
+                }
+            };
+            // Servers may log to stdout or send their own requests; skip those.
+            let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            if is_response_to(&message, id) {
+                return response_result(&message, &method);
+            }
+        }
+    }
+}
+
+fn run_stdio_handshake(
+    session: &mut StdioSession,
+    handshake: &mut Handshake,
+    started_at: Instant,
+) -> Result<(), String> {
+    let init = session.call(initialize_request())?;
+    handshake.apply_initialize(&init, started_at);
+    session.send(&initialized_notification())?;
+
+    let mut cursor: Option<String> = None;
+    for page in 0..MAX_TOOL_PAGES {
+        let result = session.call(tools_list_request(2 + page as u64, cursor.as_deref()))?;
+        cursor = handshake.apply_tools_page(&result);
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Starts `command` in its own process group so [`stop_child`] also reaches
+/// the processes it spawns.
+pub(crate) fn isolate_process_group(command: &mut Command) -> &mut Command {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    command
+}
+
+/// Kills `child` together with its process group (see
+/// [`isolate_process_group`]) and reaps it.
+pub(crate) fn stop_child(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pgid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: kill(2) with a negative pid only signals the group the
+        // child leads; no memory is shared with the callee.
+        unsafe {
+            libc::kill(-pgid, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn probe_stdio(config: &McpServerConfig, timeout: Duration) -> Result<McpProbeResult, String> {
+    let command = config
+        .command
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| "MCP server has no command".to_string())?;
+
+    let started_at = Instant::now();
+    let mut child = match isolate_process_group(&mut Command::new(command))
+        .args(config.args.iter().flatten())
+        .envs(config.env.iter().flatten())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            return Ok(Handshake::default()
+                .into_result(Some(format!("Failed to start `{command}`: {e}")), None))
+        }
+    };
+
+    let (tx, lines) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    let (stderr_tx, stderr_rx) = mpsc::channel();
+    if let Some(mut pipe) = child.stderr.take() {
+        std::thread::spawn(move || {
+            let mut buf = String::new();
+            let _ = pipe.read_to_string(&mut buf);
+            let _ = stderr_tx.send(buf);
+        });
+    }
+    let Some(stdin) = child.stdin.take() else {
+        stop_child(&mut child);
+        return Err("Failed to open MCP server stdin".to_string());
+    };
+
+    let mut session = StdioSession {
+        stdin,
+        lines,
+        deadline: started_at + timeout,
+        timeout,
+    };
+    let mut handshake = Handshake::default();
+    let outcome = run_stdio_handshake(&mut session, &mut handshake, started_at);
+
+    drop(session);
+    stop_child(&mut child);
+    // Anything that escaped the process group may still hold the pipe open.
+    let stderr = stderr_rx
+        .recv_timeout(STDERR_DRAIN_TIMEOUT)
+        .unwrap_or_default();
+
+    Ok(match outcome {
+        Ok(()) => handshake.into_result(None, None),
+        Err(e) => {
+            let stderr = (!stderr.trim().is_empty()).then(|| tail(&stderr, STDERR_TAIL_BYTES));
+            handshake.into_result(Some(e), stderr)
+        }
+    })
+}
+
+// ============================================================================
+// HTTP and SSE
+// ============================================================================
+
+/// Incremental reader for a `text/event-stream` body.
+struct EventStream {
+    resp: reqwest::Response,
+    buf: String,
+}
+
+impl EventStream {
+    fn new(resp: reqwest::Response) -> Self {
+        Self {
+            resp,
+            buf: String::new(),
+        }
+    }
+
+    /// Next `(event, data)` pair, or `None` when the stream ends.
+    async fn next_event(&mut self) -> Result<Option<(String, String)>, String> {
+        loop {
+            if let Some(pos) = self.buf.find("\n\n") {
+                let raw: String = self.buf.drain(..pos + 2).collect();
+                let mut event = String::new();
+                let mut data: Vec<&str> = Vec::new();
+                for line in raw.lines() {
+                    if let Some(v) = line.strip_prefix("event:") {
+                        event = v.trim().to_string();
+                    } else if let Some(v) = line.strip_prefix("data:") {
+                        data.push(v.strip_prefix(' ').unwrap_or(v));
+                    }
+                }
+                if data.is_empty() {
+                    continue;
+                }
+                if event.is_empty() {
+                    event = "message".to_string();
+                }
+                return Ok(Some((event, data.join("\n"))));
+            }
+            match self.resp.chunk().await {
+                Ok(Some(bytes)) => self
+                    .buf
+                    .push_str(&String::from_utf8_lossy(&bytes).replace("\r\n", "\n")),
+                Ok(None) => return Ok(None),
+                Err(e) => return Err(format!("Event stream failed: {e}")),
+            }
+        }
+    }
+
+    /// Reads events until the JSON-RPC response to `id` arrives.
+    async fn response_to(&mut self, id: u64) -> Result<Option<Value>, String> {
+        while let Some((_, data)) = self.next_event().await? {
+            if let Ok(message) = serde_json::from_str::<Value>(&data) {
+                if is_response_to(&message, id) {
+                    return Ok(Some(message));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn is_event_stream(resp: &reqwest::Response) -> bool {
+    resp.headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"))
+}
+
+fn send_error(e: reqwest::Error) -> String {
+    if e.is_timeout() {
+        "Timed out waiting for MCP server".to_string()
+    } else {
+        format!("Request failed: {e}")
+    }
+}
+
+async fn error_for_status(resp: reqwest::Response) -> Result<reqwest::Response, String> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let body = resp.text().await.unwrap_or_default();
+    Err(format!("HTTP {status}: {}", tail(&body, 500)))
+}
+
+struct HttpSession {
+    client: reqwest::Client,
+    url: reqwest::Url,
+    headers: HashMap<String, String>,
+    session_id: Option<String>,
+    protocol_version: Option<String>,
+    /// Open event stream and its POST endpoint (SSE transport only)
+    sse: Option<(EventStream, reqwest::Url)>,
+}
+
+impl HttpSession {
+    fn post_request(&self, url: reqwest::Url, message: &Value) -> reqwest::RequestBuilder {
+        let mut req = self
+            .client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json, text/event-stream")
+            .json(message);
+        for (key, value) in &self.headers {
+            req = req.header(key.as_str(), value.as_str());
+        }
+        if let Some(session_id) = &self.session_id {
+            req = req.header("Mcp-Session-Id", session_id.as_str());
+        }
+        if let Some(version) = &self.protocol_version {
+            req = req.header("MCP-Protocol-Version", version.as_str());
+        }
+        req
+    }
+
+    /// Opens the SSE stream and waits for the `endpoint` event.
+    async fn connect_sse(&mut self) -> Result<(), String> {
+        let mut req = self
+            .client
+            .get(self.url.clone())
+            .header("Accept", "text/event-stream");
+        for (key, value) in &self.headers {
+            req = req.header(key.as_str(), value.as_str());
+        }
+        let resp = error_for_status(req.send().await.map_err(send_error)?).await?;
+        let mut stream = EventStream::new(resp);
+        while let Some((event, data)) = stream.next_event().await? {
+            if event == "endpoint" {
+                let endpoint = self
+                    .url
+                    .join(data.trim())
+                    .map_err(|e| format!("Invalid SSE endpoint `{data}`: {e}"))?;
+                self.sse = Some((stream, endpoint));
+                return Ok(());
+            }
+        }
+        Err("SSE stream closed before sending an endpoint".to_string())
+    }
+
+    /// Sends `message`; returns the matching response for requests.
+    async fn post(&mut self, message: &Value) -> Result<Option<Value>, String> {
+        let id = message.get("id").and_then(|v| v.as_u64());
+        let target = match &self.sse {
+            Some((_, endpoint)) => endpoint.clone(),
+            None => self.url.clone(),
+        };
+        let resp = self
+            .post_request(target, message)
+            .send()
+            .await
+            .map_err(send_error)?;
+        let resp = error_for_status(resp).await?;
+        if let Some(session_id) = resp
+            .headers()
+            .get("mcp-session-id")
+            .and_then(|v| v.to_str().ok())
+        {
+            self.session_id = Some(session_id.to_string());
+        }
+        let Some(id) = id else {
+            return Ok(None);
+        };
+
+        // SSE transport: the answer arrives on the long-lived event stream.
+        if let Some((stream, _)) = self.sse.as_mut() {
+            return stream
+                .response_to(id)
+                .await?
+                .map(Some)
+                .ok_or_else(|| "SSE stream closed before the response arrived".to_string());
+        }
+
+        if is_event_stream(&resp) {
+            return EventStream::new(resp)
+                .response_to(id)
+                .await?
+                .map(Some)
+                .ok_or_else(|| "Event stream ended without a JSON-RPC response".to_string());
+        }
+        let body = resp
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read response: {e}"))?;
+        serde_json::from_str::<Value>(&body)
+            .ok()
+            .filter(|m| is_response_to(m, id))
+            .map(Some)
+            .ok_or_else(|| format!("No JSON-RPC response in: {}", tail(&body, 500)))
+    }
+
+    async fn call(&mut self, message: Value) -> Result<Value, String> {
+        let method = message
+            .get("method")
+            .and_then(|v| v.as_str())
+            .unwrap_or("request")
+            .to_string();
+        let response = self.post(&message).await?.unwrap_or(Value::Null);
+        response_result(&response, &method)
+    }
+}
+
+async fn run_http_handshake(
+    session: &mut HttpSession,
+    transport: McpServerType,
+    handshake: &mut Handshake,
+    started_at: Instant,
+) -> Result<(), String> {
+    if transport == McpServerType::Sse {
+        session.connect_sse().await?;
+    }
+    let init = session.call(initialize_request()).await?;
+    handshake.apply_initialize(&init, started_at);
+    session.protocol_version = handshake.protocol_version.clone();
+    session.post(&initialized_notification()).await?;
+
+    let mut cursor: Option<String> = None;
+    for page in 0..MAX_TOOL_PAGES {
+        let result = session
+            .call(tools_list_request(2 + page as u64, cursor.as_deref()))
+            .await?;
+        cursor = handshake.apply_tools_page(&result);
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn probe_http(
+    home_dir: &Path,
+    config: &McpServerConfig,
+    timeout: Duration,
+) -> Result<McpProbeResult, String> {
+    let url = config
+        .url
+        .as_deref()
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+        .ok_or_else(|| "MCP server has no URL".to_string())?;
+    let url = reqwest::Url::parse(url).map_err(|e| format!("Invalid MCP server URL: {e}"))?;
+    let headers = match mcp::resolve_mcp_headers_for_home(home_dir, config) {
+        Ok(headers) => headers,
+        Err(e) => return Ok(Handshake::default().into_result(Some(e), None)),
+    };
+    let client = reqwest::Client::builder()
+        .connect_timeout(timeout)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("Failed to create tokio runtime: {e}"))?;
+
+    let mut session = HttpSession {
+        client,
+        url,
+        headers,
+        session_id: None,
+        protocol_version: None,
+        sse: None,
+    };
+    let mut handshake = Handshake::default();
+    let started_at = Instant::now();
+    let outcome = runtime.block_on(async {
+        tokio::time::timeout(
+            timeout,
+            run_http_handshake(
+                &mut session,
+                config.server_type.clone(),
+                &mut handshake,
+                started_at,
+            ),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(format!(
+                "Timed out waiting for MCP server after {} seconds",
+                timeout.as_secs()
+            ))
+        })
+    });
+    Ok(handshake.into_result(outcome.err(), None))
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+fn probe_with_timeout(
+    home_dir: &Path,
+    config: &McpServerConfig,
+    timeout: Duration,
+) -> Result<McpProbeResult, String> {
+    match config.server_type {
+        McpServerType::Stdio => probe_stdio(config, timeout),
+        McpServerType::Http | McpServerType::Sse => probe_http(home_dir, config, timeout),
+    }
+}
+
+/// Probes an MCP server: `initialize` then `tools/list`, within the
+/// server's startup timeout (30 seconds when unset).
+pub fn probe_mcp_server_for_home(
+    home_dir: &Path,
+    config: &McpServerConfig,
+) -> Result<McpProbeResult, String> {
+    let timeout = config
+        .startup_timeout_sec
+        .map(|secs| Duration::from_secs(u64::from(secs)))
+        .unwrap_or(MCP_PROBE_TIMEOUT);
+    probe_with_timeout(home_dir, config, timeout)
+}
+
+pub fn probe_mcp_server(config: &McpServerConfig) -> Result<McpProbeResult, String> {
+    probe_mcp_server_for_home(&system_home_dir()?, config)
+}
+
+fn system_home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::{McpHeaderSource, McpHeaderSourceKind};
+    use std::collections::HashMap;
+    use std::net::TcpListener;
+    use std::path::{Path, PathBuf};
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    fn write_executable_script(temp: &TempDir, contents: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp.path().join("fake-mcp");
+        std::fs::write(&path, contents).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o700)).unwrap();
+        path
+    }
+
+    fn stdio_config(command: &Path, env: HashMap<String, String>) -> McpServerConfig {
+        McpServerConfig {
+            server_type: McpServerType::Stdio,
+            disabled: false,
+            command: Some(command.to_string_lossy().to_string()),
+            args: Some(vec!["--flag".to_string()]),
+            env: Some(env),
+            ..Default::default()
+        }
+    }
+
+    /// Answers `initialize` and a paginated `tools/list` over stdio.
+    const FAKE_SERVER: &str = r#"#!/bin/sh
+echo "fake mcp booting with $1" >&2
+while IFS= read -r line; do
+  case "$line" in
+    *'"initialize"'*)
+      echo 'log line that is not JSON'
+      printf '%s\n' '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{"tools":{}},"serverInfo":{"name":"'"$FAKE_NAME"'","version":"0.3.1"}}}' ;;
+    *'"cursor":"p2"'*)
+      printf '%s\n' '{"jsonrpc":"2.0","id":3,"result":{"tools":[{"name":"add","inputSchema":{}}]}}' ;;
+    *'"tools/list"'*)
+      printf '%s\n' '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"echo","inputSchema":{}}],"nextCursor":"p2"}}' ;;
+  esac
+done
+"#;
+
+    #[cfg(unix)]
+    #[test]
+    fn stdio_probe_reports_server_info_and_tools() {
+        let temp = TempDir::new().unwrap();
+        let script = write_executable_script(&temp, FAKE_SERVER);
+        let env = HashMap::from([("FAKE_NAME".to_string(), "fake-server".to_string())]);
+
+        let result = probe_with_timeout(
+            Path::new("/"),
+            &stdio_config(&script, env),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+
+        assert!(result.success, "{result:?}");
+        assert_eq!(result.server_name.as_deref(), Some("fake-server"));
+        assert_eq!(result.server_version.as_deref(), Some("0.3.1"));
+        assert_eq!(result.protocol_version.as_deref(), Some("2025-06-18"));
+        assert_eq!(result.tool_names, vec!["echo", "add"]);
+        assert_eq!(result.tool_count, 2);
+        assert!(result.stderr.is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stdio_probe_keeps_stderr_when_server_exits() {
+        let temp = TempDir::new().unwrap();
+        let script = write_executable_script(
+            &temp,
+            "#!/bin/sh\necho 'Error: missing API key' >&2\nexit 1\n",
+        );
+
+        let result = probe_with_timeout(
+            Path::new("/"),
+            &stdio_config(&script, HashMap::new()),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+
+        assert!(!result.success);
+        assert!(result
+            .error
+            .unwrap()
+            .contains("exited before answering initialize"));
+        assert_eq!(result.stderr.as_deref(), Some("Error: missing API key"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stdio_probe_times_out_on_silent_server() {
+        let temp = TempDir::new().unwrap();
+        let script = write_executable_script(&temp, "#!/bin/sh\nexec sleep 30\n");
+
+        let result = probe_with_timeout(
+            Path::new("/"),
+            &stdio_config(&script, HashMap::new()),
+            Duration::from_millis(200),
+        )
+        .unwrap();
+
+        assert!(!result.success);
+        assert!(result
+            .error
+            .unwrap()
+            .contains("Timed out waiting for initialize"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stdio_probe_timeout_kills_grandchildren_holding_stderr() {
+        let temp = TempDir::new().unwrap();
+        // Without `exec` the shell forks `sleep`, which inherits stderr.
+        let script = write_executable_script(&temp, "#!/bin/sh\nsh -c 'sleep 30; true'\n");
+
+        let started = Instant::now();
+        let result = probe_with_timeout(
+            Path::new("/"),
+            &stdio_config(&script, HashMap::new()),
+            Duration::from_millis(200),
+        )
+        .unwrap();
+
+        assert!(!result.success);
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn stdio_probe_reports_missing_executable() {
+        let config = stdio_config(Path::new("/nonexistent/droidgear-mcp"), HashMap::new());
+        let result = probe_with_timeout(Path::new("/"), &config, Duration::from_secs(1)).unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().starts_with("Failed to start"));
+    }
+
+    fn read_http_request(stream: &mut std::net::TcpStream) -> String {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut content_length = 0;
+        let mut headers = String::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" || line.is_empty() {
+                break;
+            }
+            if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                content_length = v.trim().parse().unwrap();
+            }
+            headers.push_str(&line);
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        headers + &String::from_utf8(body).unwrap()
+    }
+
+    #[test]
+    fn http_probe_handles_sse_responses_and_session_ids() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+
+        let server = std::thread::spawn(move || {
+            let mut seen = Vec::new();
+            for stream in listener.incoming().take(3) {
+                let mut stream = stream.unwrap();
+                let req = read_http_request(&mut stream);
+                let (status, content_type, body) = if req.contains("\"initialize\"") {
+                    (
+                        "200 OK",
+                        "text/event-stream",
+                        "event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"protocolVersion\":\"2025-03-26\",\"serverInfo\":{\"name\":\"remote\",\"version\":\"2.0\"}}}\n\n".to_string(),
+                    )
+                } else if req.contains("tools/list") {
+                    (
+                        "200 OK",
+                        "application/json",
+                        r#"{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"search"}]}}"#
+                            .to_string(),
+                    )
+                } else {
+                    ("202 Accepted", "application/json", String::new())
+                };
+                seen.push(req);
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nMcp-Session-Id: s-123\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            seen
+        });
+
+        let config = McpServerConfig {
+            server_type: McpServerType::Http,
+            url: Some(url),
+            headers: Some(HashMap::from([("X-Static".to_string(), "1".to_string())])),
+            header_sources: Some(HashMap::from([(
+                "Authorization".to_string(),
+                McpHeaderSource {
+                    kind: McpHeaderSourceKind::Command,
+                    value: "echo fresh-token".to_string(),
+                    prefix: Some("Bearer ".to_string()),
+                },
+            )])),
+            ..Default::default()
+        };
+        let result = probe_with_timeout(Path::new("/"), &config, Duration::from_secs(5)).unwrap();
+        let seen = server.join().unwrap();
+
+        assert!(result.success, "{result:?}");
+        assert_eq!(result.server_name.as_deref(), Some("remote"));
+        assert_eq!(result.tool_names, vec!["search"]);
+        let tools_req = seen[2].to_ascii_lowercase();
+        assert!(tools_req.contains("authorization: bearer fresh-token"));
+        assert!(tools_req.contains("x-static: 1"));
+        assert!(tools_req.contains("mcp-session-id: s-123"));
+        assert!(tools_req.contains("mcp-protocol-version: 2025-03-26"));
+    }
+
+    #[test]
+    fn sse_probe_posts_to_endpoint_and_reads_stream() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/sse", listener.local_addr().unwrap());
+
+        let server = std::thread::spawn(move || {
+            let mut incoming = listener.incoming();
+            let mut events = incoming.next().unwrap().unwrap();
+            let get = read_http_request(&mut events);
+            assert!(get.starts_with("GET /sse"));
+            events
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\nevent: endpoint\ndata: /messages?sid=7\n\n",
+                )
+                .unwrap();
+
+            for stream in incoming.take(3) {
+                let mut stream = stream.unwrap();
+                let req = read_http_request(&mut stream);
+                assert!(req.starts_with("POST /messages?sid=7"));
+                stream
+                    .write_all(
+                        b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    )
+                    .unwrap();
+                let reply = if req.contains("\"initialize\"") {
+                    r#"{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2024-11-05","serverInfo":{"name":"legacy","version":"1.0"}}}"#
+                } else if req.contains("tools/list") {
+                    r#"{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"a"},{"name":"b"}]}}"#
+                } else {
+                    continue;
+                };
+                events
+                    .write_all(format!("event: message\ndata: {reply}\n\n").as_bytes())
+                    .unwrap();
+            }
+        });
+
+        let config = McpServerConfig {
+            server_type: McpServerType::Sse,
+            url: Some(url),
+            ..Default::default()
+        };
+        let result = probe_with_timeout(Path::new("/"), &config, Duration::from_secs(5)).unwrap();
+        server.join().unwrap();
+
+        assert!(result.success, "{result:?}");
+        assert_eq!(result.server_name.as_deref(), Some("legacy"));
+        assert_eq!(result.tool_names, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn probe_reports_unresolvable_header_source() {
+        let config = McpServerConfig {
+            server_type: McpServerType::Http,
+            url: Some("http://127.0.0.1:9/mcp".to_string()),
+            header_sources: Some(HashMap::from([(
+                "Authorization".to_string(),
+                McpHeaderSource {
+                    kind: McpHeaderSourceKind::Env,
+                    value: "DROIDGEAR_TEST_UNSET_TOKEN".to_string(),
+                    prefix: None,
+                },
+            )])),
+            ..Default::default()
+        };
+        let result = probe_with_timeout(Path::new("/"), &config, Duration::from_secs(1)).unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .unwrap()
+            .contains("DROIDGEAR_TEST_UNSET_TOKEN is not set"));
+    }
+}
@@ -0,0 +1,954 @@
+//! Renders the MCP catalog into each tool's config format (core).
+//!
+//! DroidGear remembers which entries it wrote to each target in
+//! `~/.droidgear/mcp-sync.json`. A sync only adds, updates or removes those
+//! entries; everything else in the target file is left alone, and a catalog
+//! server whose name is already used by an unmanaged entry is reported as a
+//! conflict instead of being overwritten.
+//!
+//! Dynamic header sources are only resolved by an explicit sync (and by
+//! probes); catalog edits keep Factory in step without running them.
+
+use json_comments::StripComments;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use specta::Type;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::mcp::{self, McpServer, McpServerType};
+use crate::{paths, storage};
+
+/// Tool config an MCP catalog can be synced into
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum McpSyncTarget {
+    /// `~/.factory/mcp.json` (`mcpServers`)
+    Factory,
+    /// `~/.claude.json` (`mcpServers`)
+    Claude,
+    /// `~/.codex/config.toml` (`[mcp_servers]`)
+    Codex,
+    /// `~/.config/opencode/opencode.json` (`mcp`)
+    OpenCode,
+    /// `~/.pi/agent/mcp.json` (`mcpServers`, read by pi-mcp-adapter)
+    Pi,
+    /// `~/.openclaw/openclaw.json` (`mcp.servers`)
+    OpenClaw,
+}
+
+impl McpSyncTarget {
+    pub const ALL: [McpSyncTarget; 6] = [
+        McpSyncTarget::Factory,
+        McpSyncTarget::Claude,
+        McpSyncTarget::Codex,
+        McpSyncTarget::OpenCode,
+        McpSyncTarget::Pi,
+        McpSyncTarget::OpenClaw,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            McpSyncTarget::Factory => "Factory Droid",
+            McpSyncTarget::Claude => "Claude Code",
+            McpSyncTarget::Codex => "Codex",
+            McpSyncTarget::OpenCode => "OpenCode",
+            McpSyncTarget::Pi => "Pi",
+            McpSyncTarget::OpenClaw => "OpenClaw",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            McpSyncTarget::Factory => "factory",
+            McpSyncTarget::Claude => "claude",
+            McpSyncTarget::Codex => "codex",
+            McpSyncTarget::OpenCode => "opencode",
+            McpSyncTarget::Pi => "pi",
+            McpSyncTarget::OpenClaw => "openclaw",
+        }
+    }
+
+    /// Object path of the server map inside the target's JSON document
+    fn container_path(self) -> &'static [&'static str] {
+        match self {
+            McpSyncTarget::Factory | McpSyncTarget::Claude | McpSyncTarget::Pi => &["mcpServers"],
+            McpSyncTarget::Codex => &["mcp_servers"],
+            McpSyncTarget::OpenCode => &["mcp"],
+            McpSyncTarget::OpenClaw => &["mcp", "servers"],
+        }
+    }
+}
+
+/// What a sync changes (or would change) in one target
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSyncPreview {
+    pub target: McpSyncTarget,
+    pub path: String,
+    /// File contents before the sync (`None` if the file does not exist)
+    pub before: Option<String>,
+    /// File contents after the sync (equal to `before` when nothing changes)
+    pub after: Option<String>,
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    /// Catalog servers skipped because an unmanaged entry uses the name
+    pub conflicts: Vec<String>,
+    /// Entries in the target that DroidGear does not manage
+    pub unmanaged: Vec<String>,
+}
+
+impl McpSyncPreview {
+    pub fn has_changes(&self) -> bool {
+        !(self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty())
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    #[serde(default)]
+    owned: BTreeMap<String, BTreeSet<String>>,
+}
+
+// ============================================================================
+// Paths and state
+// ============================================================================
+
+fn sync_state_path_for_home(home_dir: &Path) -> PathBuf {
+    paths::droidgear_dir_from_home(home_dir).join("mcp-sync.json")
+}
+
+fn read_state_for_home(home_dir: &Path) -> Result<SyncState, String> {
+    let path = sync_state_path_for_home(home_dir);
+    if !path.exists() {
+        return Ok(SyncState::default());
+    }
+    let s = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read MCP sync state: {e}"))?;
+    serde_json::from_str(&s).map_err(|e| format!("Failed to parse MCP sync state: {e}"))
+}
+
+fn write_state_for_home(home_dir: &Path, state: &SyncState) -> Result<(), String> {
+    let s = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize MCP sync state: {e}"))?;
+    storage::atomic_write(&sync_state_path_for_home(home_dir), s.as_bytes())
+}
+
+/// Adds `names` to the entries DroidGear manages in `target`.
+pub(crate) fn adopt_owned_for_home(
+    home_dir: &Path,
+    target: McpSyncTarget,
+    names: &[String],
+) -> Result<(), String> {
+    let mut state = read_state_for_home(home_dir)?;
+    state
+        .owned
+        .entry(target.key().to_string())
+        .or_default()
+        .extend(names.iter().cloned());
+    write_state_for_home(home_dir, &state)
+}
+
+fn target_path_for_home(home_dir: &Path, target: McpSyncTarget) -> Result<PathBuf, String> {
+    let config_paths = paths::load_config_paths_for_home(home_dir);
+    Ok(match target {
+        McpSyncTarget::Factory => mcp::factory_mcp_path_for_home(home_dir)?,
+        // `.claude.json` sits next to `~/.claude/`, or inside CLAUDE_CONFIG_DIR
+        McpSyncTarget::Claude => match &config_paths.claude {
+            Some(custom) => PathBuf::from(custom).join(".claude.json"),
+            None => home_dir.join(".claude.json"),
+        },
+        McpSyncTarget::Codex => {
+            paths::get_codex_home_for_home(home_dir, &config_paths)?.join("config.toml")
+        }
+        McpSyncTarget::OpenCode => {
+            let dir = paths::get_opencode_config_dir_for_home(home_dir, &config_paths)?;
+            let jsonc = dir.join("opencode.jsonc");
+            if jsonc.exists() {
+                jsonc
+            } else {
+                dir.join("opencode.json")
+            }
+        }
+        McpSyncTarget::Pi => paths::get_pi_home_for_home(home_dir, &config_paths)?.join("mcp.json"),
+        McpSyncTarget::OpenClaw => {
+            paths::get_openclaw_home_for_home(home_dir, &config_paths)?.join("openclaw.json")
+        }
+    })
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+fn sorted_map(map: &HashMap<String, String>) -> Value {
+    let sorted: BTreeMap<&String, &String> = map.iter().collect();
+    serde_json::to_value(sorted).unwrap_or(Value::Null)
+}
+
+fn insert_map(obj: &mut Map<String, Value>, key: &str, map: Option<&HashMap<String, String>>) {
+    if let Some(map) = map.filter(|m| !m.is_empty()) {
+        obj.insert(key.to_string(), sorted_map(map));
+    }
+}
+
+/// Renders one catalog server in `target`'s format. Targets without a
+/// disabled flag simply omit disabled servers, and targets without an SSE
+/// transport omit SSE servers. `headers` are the resolved header values.
+pub(crate) fn render_server(
+    target: McpSyncTarget,
+    server: &McpServer,
+    headers: &HashMap<String, String>,
+) -> Option<Value> {
+    let c = &server.config;
+    let command = c.command.clone().unwrap_or_default();
+    let args = c.args.clone().unwrap_or_default();
+    let url = c.url.clone().unwrap_or_default();
+    let headers = Some(headers);
+    let mut obj = Map::new();
+
+    match target {
+        McpSyncTarget::Factory => {
+            // Factory only knows stdio and streamable HTTP, and none of the
+            // DroidGear-only fields.
+            if c.server_type == McpServerType::Sse {
+                return None;
+            }
+            let mut config = c.clone();
+            config.header_sources = None;
+            config.startup_timeout_sec = None;
+            config.tool_timeout_sec = None;
+            if c.server_type.is_remote() {
+                config.headers = headers.filter(|h| !h.is_empty()).cloned();
+            }
+            let mut value = serde_json::to_value(&config).ok()?;
+            // Stable key order keeps diffs quiet across runs.
+            for key in ["env", "headers"] {
+                if let Some(map) = value.get(key).cloned() {
+                    let map: HashMap<String, String> = serde_json::from_value(map).ok()?;
+                    value[key] = sorted_map(&map);
+                }
+            }
+            return Some(value);
+        }
+        McpSyncTarget::Claude => {
+            if c.disabled {
+                return None;
+            }
+            match c.server_type {
+                McpServerType::Stdio => {
+                    obj.insert("type".into(), "stdio".into());
+                    obj.insert("command".into(), command.into());
+                    obj.insert("args".into(), args.into());
+                    insert_map(&mut obj, "env", c.env.as_ref());
+                }
+                McpServerType::Http | McpServerType::Sse => {
+                    let kind = if c.server_type == McpServerType::Sse {
+                        "sse"
+                    } else {
+                        "http"
+                    };
+                    obj.insert("type".into(), kind.into());
+                    obj.insert("url".into(), url.into());
+                    insert_map(&mut obj, "headers", headers);
+                }
+            }
+        }
+        McpSyncTarget::Codex => {
+            match c.server_type {
+                McpServerType::Stdio => {
+                    obj.insert("command".into(), command.into());
+                    if !args.is_empty() {
+                        obj.insert("args".into(), args.into());
+                    }
+                    insert_map(&mut obj, "env", c.env.as_ref());
+                }
+                McpServerType::Http => {
+                    obj.insert("url".into(), url.into());
+                    insert_map(&mut obj, "http_headers", headers);
+                }
+                // Codex only speaks stdio and streamable HTTP.
+                McpServerType::Sse => return None,
+            }
+            if let Some(secs) = c.startup_timeout_sec {
+                obj.insert("startup_timeout_sec".into(), secs.into());
+            }
+            if let Some(secs) = c.tool_timeout_sec {
+                obj.insert("tool_timeout_sec".into(), secs.into());
+            }
+            if c.disabled {
+                obj.insert("enabled".into(), false.into());
+            }
+        }
+        McpSyncTarget::OpenCode => {
+            match c.server_type {
+                McpServerType::Stdio => {
+                    let mut cmd = vec![command];
+                    cmd.extend(args);
+                    obj.insert("type".into(), "local".into());
+                    obj.insert("command".into(), cmd.into());
+                    insert_map(&mut obj, "environment", c.env.as_ref());
+                }
+                // OpenCode's remote servers fall back from streamable HTTP to SSE.
+                McpServerType::Http | McpServerType::Sse => {
+                    obj.insert("type".into(), "remote".into());
+                    obj.insert("url".into(), url.into());
+                    insert_map(&mut obj, "headers", headers);
+                }
+            }
+            obj.insert("enabled".into(), (!c.disabled).into());
+            if let Some(secs) = c.startup_timeout_sec {
+                obj.insert("timeout".into(), (u64::from(secs) * 1000).into());
+            }
+        }
+        McpSyncTarget::Pi | McpSyncTarget::OpenClaw => {
+            if c.disabled {
+                return None;
+            }
+            match c.server_type {
+                McpServerType::Stdio => {
+                    obj.insert("command".into(), command.into());
+                    if !args.is_empty() {
+                        obj.insert("args".into(), args.into());
+                    }
+                    insert_map(&mut obj, "env", c.env.as_ref());
+                }
+                McpServerType::Http | McpServerType::Sse => {
+                    obj.insert("url".into(), url.into());
+                    insert_map(&mut obj, "headers", headers);
+                }
+            }
+        }
+    }
+    Some(Value::Object(obj))
+}
+
+/// How `header_sources` are treated while rendering
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HeaderMode {
+    /// Read env vars and files and run commands (explicit syncs only)
+    Resolve,
+    /// Touch nothing: only static headers are rendered, plus Codex's own
+    /// env-var header settings where a source maps onto them
+    Deferred,
+}
+
+fn has_header_sources(server: &McpServer) -> bool {
+    server.config.server_type.is_remote()
+        && server
+            .config
+            .header_sources
+            .as_ref()
+            .is_some_and(|s| !s.is_empty())
+}
+
+/// Maps env-var header sources onto Codex's `env_http_headers` and
+/// `bearer_token_env_var`, which Codex resolves itself at startup.
+fn add_codex_env_headers(server: &McpServer, obj: &mut Map<String, Value>) -> Result<(), String> {
+    let mut env_headers = BTreeMap::new();
+    for (header, source) in server.config.header_sources.iter().flatten() {
+        let prefix = source.prefix.as_deref().unwrap_or("");
+        match source.kind {
+            mcp::McpHeaderSourceKind::Env if prefix.is_empty() => {
+                env_headers.insert(header.clone(), source.value.clone());
+            }
+            mcp::McpHeaderSourceKind::Env
+                if header.eq_ignore_ascii_case("authorization") && prefix == "Bearer " =>
+            {
+                obj.insert("bearer_token_env_var".into(), source.value.clone().into());
+            }
+            _ => {
+                return Err(format!(
+                    "{}: Codex can only read header {header} from an environment variable without a prefix",
+                    server.name
+                ))
+            }
+        }
+    }
+    if !env_headers.is_empty() {
+        obj.insert(
+            "env_http_headers".into(),
+            serde_json::to_value(env_headers).unwrap_or(Value::Null),
+        );
+    }
+    Ok(())
+}
+
+/// Renders every catalog server for `target`.
+pub(crate) fn render_servers(
+    home_dir: &Path,
+    target: McpSyncTarget,
+    servers: &[McpServer],
+    mode: HeaderMode,
+) -> Result<Vec<(String, Value)>, String> {
+    let mut rendered = Vec::new();
+    for server in servers {
+        let headers = match mode {
+            HeaderMode::Resolve if server.config.server_type.is_remote() => {
+                mcp::resolve_mcp_headers_for_home(home_dir, &server.config)
+                    .map_err(|e| format!("{}: {e}", server.name))?
+            }
+            _ => server.config.headers.clone().unwrap_or_default(),
+        };
+        let Some(mut value) = render_server(target, server, &headers) else {
+            continue;
+        };
+        if mode == HeaderMode::Deferred
+            && target == McpSyncTarget::Codex
+            && has_header_sources(server)
+        {
+            if let Some(obj) = value.as_object_mut() {
+                add_codex_env_headers(server, obj)?;
+            }
+        }
+        rendered.push((server.name.clone(), value));
+    }
+    Ok(rendered)
+}
+
+#[derive(Debug, Default)]
+struct MergeOutcome {
+    added: Vec<String>,
+    updated: Vec<String>,
+    removed: Vec<String>,
+    conflicts: Vec<String>,
+    unmanaged: Vec<String>,
+    owned: BTreeSet<String>,
+}
+
+/// Applies `rendered` to `container`, touching only entries in `owned`.
+/// Owned entries named in `keep` stay as they are.
+fn merge_owned(
+    container: &mut Map<String, Value>,
+    rendered: Vec<(String, Value)>,
+    owned: &BTreeSet<String>,
+    keep: &BTreeSet<String>,
+) -> MergeOutcome {
+    let mut outcome = MergeOutcome::default();
+    let rendered_names: BTreeSet<&String> = rendered.iter().map(|(name, _)| name).collect();
+
+    for name in owned {
+        if !rendered_names.contains(name) && container.shift_remove(name).is_some() {
+            outcome.removed.push(name.clone());
+        }
+    }
+
+    for (name, value) in rendered {
+        match container.get(&name) {
+            Some(_) if !owned.contains(&name) => {
+                outcome.conflicts.push(name);
+                continue;
+            }
+            Some(existing) if *existing == value || keep.contains(&name) => {}
+            Some(_) => {
+                outcome.updated.push(name.clone());
+                container.insert(name.clone(), value);
+            }
+            None => {
+                outcome.added.push(name.clone());
+                container.insert(name.clone(), value);
+            }
+        }
+        outcome.owned.insert(name);
+    }
+
+    outcome.unmanaged = container
+        .keys()
+        .filter(|name| !outcome.owned.contains(*name))
+        .cloned()
+        .collect();
+    outcome
+}
+
+// ============================================================================
+// Documents
+// ============================================================================
+
+fn parse_document(target: McpSyncTarget, raw: Option<&str>) -> Result<Value, String> {
+    let Some(raw) = raw.filter(|s| !s.trim().is_empty()) else {
+        return Ok(Value::Object(Map::new()));
+    };
+    match target {
+        McpSyncTarget::Codex => {
+            let table: toml::map::Map<String, toml::Value> =
+                toml::from_str(raw).map_err(|e| format!("Failed to parse config.toml: {e}"))?;
+            serde_json::to_value(table).map_err(|e| format!("Failed to read config.toml: {e}"))
+        }
+        McpSyncTarget::OpenCode => {
+            let mut buf = String::new();
+            StripComments::new(raw.as_bytes())
+                .read_to_string(&mut buf)
+                .map_err(|e| format!("Failed to read OpenCode config: {e}"))?;
+            serde_json::from_str(&buf).map_err(|e| format!("Invalid OpenCode config JSON: {e}"))
+        }
+        _ => serde_json::from_str(raw)
+            .map_err(|e| format!("Invalid {} config JSON: {e}", target.label())),
+    }
+}
+
+fn serialize_document(target: McpSyncTarget, doc: &Value) -> Result<String, String> {
+    match target {
+        McpSyncTarget::Codex => {
+            let table = toml::Value::try_from(doc)
+                .map_err(|e| format!("Failed to convert config.toml: {e}"))?;
+            toml::to_string_pretty(&table)
+                .map_err(|e| format!("Failed to serialize config.toml: {e}"))
+        }
+        _ => serde_json::to_string_pretty(doc)
+            .map_err(|e| format!("Failed to serialize {} config: {e}", target.label())),
+    }
+}
+
+fn take_container(doc: &Value, path: &[&str]) -> Option<Map<String, Value>> {
+    path.iter()
+        .try_fold(doc, |v, key| v.get(*key))
+        .and_then(|v| v.as_object())
+        .cloned()
+}
+
+fn put_container(doc: &mut Value, path: &[&str], container: Map<String, Value>) {
+    let mut cur = doc;
+    for key in &path[..path.len() - 1] {
+        if !cur.get(*key).is_some_and(Value::is_object) {
+            cur[*key] = Value::Object(Map::new());
+        }
+        cur = &mut cur[*key];
+    }
+    cur[path[path.len() - 1]] = Value::Object(container);
+}
+
+fn plan_target(
+    home_dir: &Path,
+    target: McpSyncTarget,
+    servers: &[McpServer],
+    state: &SyncState,
+    mode: HeaderMode,
+) -> Result<(McpSyncPreview, BTreeSet<String>), String> {
+    let path = target_path_for_home(home_dir, target)?;
+    let before = if path.exists() {
+        Some(
+            std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {e}", path.display()))?,
+        )
+    } else {
+        None
+    };
+
+    let mut doc = parse_document(target, before.as_deref())?;
+    if !doc.is_object() {
+        return Err(format!("{} is not a JSON object", path.display()));
+    }
+    let container_path = target.container_path();
+    let existing = take_container(&doc, container_path);
+    let mut container = existing.clone().unwrap_or_default();
+
+    let rendered = render_servers(home_dir, target, servers, mode)?;
+    let owned = state.owned.get(target.key()).cloned().unwrap_or_default();
+    // Without resolved values, what the last explicit sync wrote stays.
+    let keep: BTreeSet<String> = match mode {
+        HeaderMode::Resolve => BTreeSet::new(),
+        HeaderMode::Deferred => servers
+            .iter()
+            .filter(|s| has_header_sources(s))
+            .map(|s| s.name.clone())
+            .collect(),
+    };
+    let outcome = merge_owned(&mut container, rendered, &owned, &keep);
+
+    let changed =
+        !(outcome.added.is_empty() && outcome.updated.is_empty() && outcome.removed.is_empty());
+    let after = if changed {
+        put_container(&mut doc, container_path, container);
+        Some(serialize_document(target, &doc)?)
+    } else {
+        before.clone()
+    };
+
+    let preview = McpSyncPreview {
+        target,
+        path: path.to_string_lossy().to_string(),
+        before,
+        after,
+        added: outcome.added,
+        updated: outcome.updated,
+        removed: outcome.removed,
+        conflicts: outcome.conflicts,
+        unmanaged: outcome.unmanaged,
+    };
+    Ok((preview, outcome.owned))
+}
+
+/// Entries of `target`'s server map that DroidGear does not manage.
+pub(crate) fn unmanaged_entries_for_home(
+    home_dir: &Path,
+    target: McpSyncTarget,
+) -> Result<Map<String, Value>, String> {
+    let path = target_path_for_home(home_dir, target)?;
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let doc = parse_document(target, Some(&raw))?;
+    let owned = read_state_for_home(home_dir)?
+        .owned
+        .remove(target.key())
+        .unwrap_or_default();
+    let mut container = take_container(&doc, target.container_path()).unwrap_or_default();
+    container.retain(|name, _| !owned.contains(name));
+    Ok(container)
+}
+
+fn write_target(path: &Path, contents: &str) -> Result<(), String> {
+    let actual_path = if path.is_symlink() {
+        std::fs::canonicalize(path).map_err(|e| format!("Failed to resolve symlink: {e}"))?
+    } else {
+        path.to_path_buf()
+    };
+    storage::atomic_write(&actual_path, contents.as_bytes())
+}
+
+fn system_home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Shows what syncing the catalog into `targets` would change, without writing.
+pub fn preview_mcp_sync_for_home(
+    home_dir: &Path,
+    targets: &[McpSyncTarget],
+) -> Result<Vec<McpSyncPreview>, String> {
+    let catalog = mcp::read_catalog_for_home(home_dir)?;
+    let mut state = read_state_for_home(home_dir)?;
+    // Imported Factory servers count as owned once adopted.
+    state
+        .owned
+        .entry(McpSyncTarget::Factory.key().to_string())
+        .or_default()
+        .extend(catalog.imported.iter().cloned());
+    let servers = catalog.servers;
+    targets
+        .iter()
+        .map(|t| {
+            plan_target(home_dir, *t, &servers, &state, HeaderMode::Resolve)
+                .map(|(preview, _)| preview)
+        })
+        .collect()
+}
+
+pub fn preview_mcp_sync(targets: &[McpSyncTarget]) -> Result<Vec<McpSyncPreview>, String> {
+    preview_mcp_sync_for_home(&system_home_dir()?, targets)
+}
+
+/// Syncs the catalog into `targets` and returns what changed in each.
+/// Dynamic headers are resolved here and by probes only.
+pub fn sync_mcp_servers_for_home(
+    home_dir: &Path,
+    targets: &[McpSyncTarget],
+) -> Result<Vec<McpSyncPreview>, String> {
+    sync_targets_for_home(home_dir, targets, HeaderMode::Resolve)
+}
+
+pub(crate) fn sync_targets_for_home(
+    home_dir: &Path,
+    targets: &[McpSyncTarget],
+    mode: HeaderMode,
+) -> Result<Vec<McpSyncPreview>, String> {
+    let catalog = mcp::read_catalog_for_home(home_dir)?;
+    mcp::adopt_imported_for_home(home_dir, &catalog)?;
+    let servers = catalog.servers;
+    let mut state = read_state_for_home(home_dir)?;
+    let mut results = Vec::new();
+
+    for target in targets {
+        let (preview, owned) = plan_target(home_dir, *target, &servers, &state, mode)?;
+        if preview.has_changes() {
+            if let Some(after) = &preview.after {
+                write_target(Path::new(&preview.path), after)?;
+            }
+        }
+        state.owned.insert(target.key().to_string(), owned);
+        results.push(preview);
+    }
+
+    write_state_for_home(home_dir, &state)?;
+    Ok(results)
+}
+
+pub fn sync_mcp_servers(targets: &[McpSyncTarget]) -> Result<Vec<McpSyncPreview>, String> {
+    sync_mcp_servers_for_home(&system_home_dir()?, targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::McpServerConfig;
+    use tempfile::TempDir;
+
+    fn stdio(name: &str, disabled: bool) -> McpServer {
+        McpServer {
+            name: name.to_string(),
+            config: McpServerConfig {
+                server_type: McpServerType::Stdio,
+                disabled,
+                command: Some("npx".to_string()),
+                args: Some(vec!["-y".to_string(), format!("{name}-mcp")]),
+                env: Some(HashMap::from([("TOKEN".to_string(), "t".to_string())])),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn read_json(path: &Path) -> Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn sync_preserves_unmanaged_entries_and_reports_conflicts() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        std::fs::write(
+            home.join(".claude.json"),
+            r#"{"numStartups": 3, "mcpServers": {"mine": {"command": "x"}, "clash": {"command": "y"}}}"#,
+        )
+        .unwrap();
+
+        mcp::save_mcp_server_for_home(home, stdio("exa", false)).unwrap();
+        mcp::save_mcp_server_for_home(home, stdio("clash", false)).unwrap();
+
+        let preview = preview_mcp_sync_for_home(home, &[McpSyncTarget::Claude]).unwrap();
+        assert_eq!(preview[0].added, vec!["exa"]);
+        assert_eq!(preview[0].conflicts, vec!["clash"]);
+        assert_eq!(preview[0].unmanaged, vec!["mine", "clash"]);
+        assert!(!read_json(&home.join(".claude.json"))["mcpServers"]
+            .as_object()
+            .unwrap()
+            .contains_key("exa"));
+
+        sync_mcp_servers_for_home(home, &[McpSyncTarget::Claude]).unwrap();
+        let claude = read_json(&home.join(".claude.json"));
+        assert_eq!(claude["numStartups"], 3);
+        assert_eq!(claude["mcpServers"]["exa"]["type"], "stdio");
+        assert_eq!(claude["mcpServers"]["exa"]["env"]["TOKEN"], "t");
+        assert_eq!(claude["mcpServers"]["clash"]["command"], "y");
+
+        // Dropping a server from the catalog removes only what DroidGear wrote.
+        mcp::delete_mcp_server_for_home(home, "exa").unwrap();
+        let results = sync_mcp_servers_for_home(home, &[McpSyncTarget::Claude]).unwrap();
+        assert_eq!(results[0].removed, vec!["exa"]);
+        let claude = read_json(&home.join(".claude.json"));
+        let names: Vec<&String> = claude["mcpServers"].as_object().unwrap().keys().collect();
+        assert_eq!(names, vec!["mine", "clash"]);
+    }
+
+    #[test]
+    fn renders_codex_opencode_and_openclaw_formats() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        std::fs::create_dir_all(home.join(".codex")).unwrap();
+        std::fs::write(
+            home.join(".codex").join("config.toml"),
+            "model = \"gpt-5\"\n\n[mcp_servers.keep]\ncommand = \"keep\"\n",
+        )
+        .unwrap();
+
+        mcp::save_mcp_server_for_home(home, stdio("exa", false)).unwrap();
+        mcp::save_mcp_server_for_home(home, stdio("off", true)).unwrap();
+
+        let targets = [
+            McpSyncTarget::Codex,
+            McpSyncTarget::OpenCode,
+            McpSyncTarget::OpenClaw,
+        ];
+        sync_mcp_servers_for_home(home, &targets).unwrap();
+
+        let codex: toml::Value =
+            toml::from_str(&std::fs::read_to_string(home.join(".codex/config.toml")).unwrap())
+                .unwrap();
+        assert_eq!(codex["model"].as_str(), Some("gpt-5"));
+        assert_eq!(
+            codex["mcp_servers"]["keep"]["command"].as_str(),
+            Some("keep")
+        );
+        assert_eq!(codex["mcp_servers"]["exa"]["command"].as_str(), Some("npx"));
+        assert_eq!(
+            codex["mcp_servers"]["off"]["enabled"].as_bool(),
+            Some(false)
+        );
+
+        let opencode = read_json(&home.join(".config/opencode/opencode.json"));
+        assert_eq!(
+            opencode["mcp"]["exa"]["command"],
+            serde_json::json!(["npx", "-y", "exa-mcp"])
+        );
+        assert_eq!(opencode["mcp"]["exa"]["environment"]["TOKEN"], "t");
+        assert_eq!(opencode["mcp"]["off"]["enabled"], false);
+
+        let openclaw = read_json(&home.join(".openclaw/openclaw.json"));
+        assert_eq!(openclaw["mcp"]["servers"]["exa"]["command"], "npx");
+        assert!(openclaw["mcp"]["servers"].get("off").is_none());
+
+        // A second sync is a no-op.
+        let again = preview_mcp_sync_for_home(home, &targets).unwrap();
+        assert!(again
+            .iter()
+            .all(|p| !p.has_changes() && p.before == p.after));
+    }
+
+    #[test]
+    fn remote_servers_resolve_headers_and_respect_transport_support() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        std::fs::write(home.join("token"), "secret\n").unwrap();
+
+        let sources = HashMap::from([(
+            "Authorization".to_string(),
+            mcp::McpHeaderSource {
+                kind: mcp::McpHeaderSourceKind::File,
+                value: "~/token".to_string(),
+                prefix: Some("Bearer ".to_string()),
+            },
+        )]);
+        let remote = |name: &str, server_type| McpServer {
+            name: name.to_string(),
+            config: McpServerConfig {
+                server_type,
+                url: Some(format!("https://{name}.example/mcp")),
+                header_sources: Some(sources.clone()),
+                startup_timeout_sec: Some(20),
+                ..Default::default()
+            },
+        };
+        mcp::save_mcp_server_for_home(home, remote("web", McpServerType::Http)).unwrap();
+        mcp::save_mcp_server_for_home(home, remote("legacy", McpServerType::Sse)).unwrap();
+
+        // The catalog keeps the references; saving never resolves them.
+        let catalog = read_json(&mcp::mcp_catalog_path_for_home(home));
+        assert_eq!(
+            catalog["mcpServers"]["web"]["headerSources"]["Authorization"]["kind"],
+            "file"
+        );
+        let factory = read_json(&home.join(".factory/mcp.json"));
+        assert!(factory["mcpServers"]["web"].get("headers").is_none());
+
+        // An explicit sync writes the resolved value, and later saves keep it.
+        sync_mcp_servers_for_home(home, &[McpSyncTarget::Factory]).unwrap();
+        mcp::save_mcp_server_for_home(home, stdio("exa", false)).unwrap();
+        let factory = read_json(&home.join(".factory/mcp.json"));
+        assert_eq!(
+            factory["mcpServers"]["web"]["headers"]["Authorization"],
+            "Bearer secret"
+        );
+        assert!(factory["mcpServers"]["web"].get("headerSources").is_none());
+        assert!(factory["mcpServers"]["web"]
+            .get("startupTimeoutSec")
+            .is_none());
+        assert!(factory["mcpServers"].get("legacy").is_none());
+
+        sync_mcp_servers_for_home(home, &[McpSyncTarget::Claude, McpSyncTarget::Codex]).unwrap();
+        let claude = read_json(&home.join(".claude.json"));
+        assert_eq!(claude["mcpServers"]["legacy"]["type"], "sse");
+        assert_eq!(
+            claude["mcpServers"]["web"]["headers"]["Authorization"],
+            "Bearer secret"
+        );
+        let codex: toml::Value =
+            toml::from_str(&std::fs::read_to_string(home.join(".codex/config.toml")).unwrap())
+                .unwrap();
+        assert_eq!(
+            codex["mcp_servers"]["web"]["startup_timeout_sec"].as_integer(),
+            Some(20)
+        );
+        assert!(codex["mcp_servers"].get("legacy").is_none());
+    }
+
+    #[test]
+    fn failing_header_sources_do_not_block_unrelated_saves() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let broken = McpServer {
+            name: "broken".to_string(),
+            config: McpServerConfig {
+                server_type: McpServerType::Http,
+                url: Some("https://broken.example/mcp".to_string()),
+                header_sources: Some(HashMap::from([(
+                    "Authorization".to_string(),
+                    mcp::McpHeaderSource {
+                        kind: mcp::McpHeaderSourceKind::Command,
+                        value: "exit 1".to_string(),
+                        prefix: None,
+                    },
+                )])),
+                ..Default::default()
+            },
+        };
+        mcp::save_mcp_server_for_home(home, broken).unwrap();
+        mcp::save_mcp_server_for_home(home, stdio("exa", false)).unwrap();
+
+        let factory = read_json(&home.join(".factory/mcp.json"));
+        assert_eq!(factory["mcpServers"]["exa"]["command"], "npx");
+        assert_eq!(
+            factory["mcpServers"]["broken"]["url"],
+            "https://broken.example/mcp"
+        );
+        assert!(sync_mcp_servers_for_home(home, &[McpSyncTarget::Factory]).is_err());
+    }
+
+    #[test]
+    fn deferred_codex_rendering_maps_env_sources_to_codex_settings() {
+        let temp = TempDir::new().unwrap();
+        let source = |kind, value: &str, prefix: Option<&str>| mcp::McpHeaderSource {
+            kind,
+            value: value.to_string(),
+            prefix: prefix.map(String::from),
+        };
+        let server = |sources: HashMap<String, mcp::McpHeaderSource>| McpServer {
+            name: "web".to_string(),
+            config: McpServerConfig {
+                server_type: McpServerType::Http,
+                url: Some("https://web.example/mcp".to_string()),
+                header_sources: Some(sources),
+                ..Default::default()
+            },
+        };
+
+        let ok = server(HashMap::from([
+            (
+                "Authorization".to_string(),
+                source(mcp::McpHeaderSourceKind::Env, "WEB_TOKEN", Some("Bearer ")),
+            ),
+            (
+                "X-Team".to_string(),
+                source(mcp::McpHeaderSourceKind::Env, "WEB_TEAM", None),
+            ),
+        ]));
+        let rendered = render_servers(
+            temp.path(),
+            McpSyncTarget::Codex,
+            &[ok],
+            HeaderMode::Deferred,
+        )
+        .unwrap();
+        let value = &rendered[0].1;
+        assert_eq!(value["bearer_token_env_var"], "WEB_TOKEN");
+        assert_eq!(value["env_http_headers"]["X-Team"], "WEB_TEAM");
+        assert!(value.get("http_headers").is_none());
+
+        let command = server(HashMap::from([(
+            "Authorization".to_string(),
+            source(mcp::McpHeaderSourceKind::Command, "echo t", None),
+        )]));
+        assert!(render_servers(
+            temp.path(),
+            McpSyncTarget::Codex,
+            &[command],
+            HeaderMode::Deferred
+        )
+        .is_err());
+    }
+}
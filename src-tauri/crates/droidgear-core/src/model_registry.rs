@@ -0,0 +1,48 @@
+//! DroidGear model registry (core).
+//!
+//! Model metadata bundled from `src/lib/model-registry-data.json`, shared
+//! with the frontend.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const MODEL_REGISTRY_JSON: &str = include_str!("../../../../src/lib/model-registry-data.json");
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RegistryModel {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub reasoning: bool,
+    pub input: Vec<String>,
+    #[serde(default)]
+    pub thinking_level_map: Option<HashMap<String, Option<String>>>,
+    pub context_window: u32,
+    pub max_output_tokens: Option<u32>,
+    #[serde(default)]
+    pub reasoning_config: Option<RegistryReasoningConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RegistryReasoningConfig {
+    #[serde(default)]
+    pub efforts: Vec<String>,
+}
+
+impl RegistryModel {
+    pub fn matches(&self, model: &str) -> bool {
+        self.id == model || self.aliases.iter().any(|alias| alias == model)
+    }
+}
+
+pub(crate) fn registry_models() -> &'static [RegistryModel] {
+    static MODELS: std::sync::OnceLock<Vec<RegistryModel>> = std::sync::OnceLock::new();
+    MODELS.get_or_init(|| serde_json::from_str(MODEL_REGISTRY_JSON).unwrap_or_default())
+}
+
+/// Registry entry whose id or one of its aliases is `model`.
+pub(crate) fn find_registry_model(model: &str) -> Option<&'static RegistryModel> {
+    registry_models().iter().find(|entry| entry.matches(model))
+}
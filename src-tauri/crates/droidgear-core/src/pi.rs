@@ -13,7 +13,7 @@ use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-use crate::{paths, storage};
+use crate::{model_registry, paths, storage};
 
 // ============================================================================
 // Types
@@ -534,33 +534,8 @@ fn default_max_tokens() -> u32 {
 // DroidGear Model Registry
 // ============================================================================
 
-const MODEL_REGISTRY_JSON: &str = include_str!("../../../../src/lib/model-registry-data.json");
-
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct RegistryModel {
-    id: String,
-    name: String,
-    #[serde(default)]
-    aliases: Vec<String>,
-    reasoning: bool,
-    input: Vec<String>,
-    #[serde(default)]
-    thinking_level_map: Option<HashMap<String, Option<String>>>,
-    context_window: u32,
-    max_output_tokens: Option<u32>,
-}
-
-fn registry_models() -> &'static [RegistryModel] {
-    static MODELS: std::sync::OnceLock<Vec<RegistryModel>> = std::sync::OnceLock::new();
-    MODELS.get_or_init(|| serde_json::from_str(MODEL_REGISTRY_JSON).unwrap_or_default())
-}
-
 pub fn enrich_pi_model_from_registry(model: &mut PiModel) -> bool {
-    let Some(metadata) = registry_models()
-        .iter()
-        .find(|entry| entry.id == model.id || entry.aliases.iter().any(|alias| alias == &model.id))
-    else {
+    let Some(metadata) = model_registry::find_registry_model(&model.id) else {
         return false;
     };
 
@@ -0,0 +1,323 @@
+//! Token usage analytics across sessions (core).
+//!
+//! Rolls the per-session [`TokenUsage`] of every session source up by day,
+//! ISO week, project or model, and exports the result as CSV or JSON.
+//! Dates are bucketed in UTC so reports are stable across machines.
+
+use chrono::{DateTime, Datelike};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::sessions::{self, SessionSummary, TokenUsage};
+use crate::storage;
+
+/// Dimension to roll usage up by
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageGroupBy {
+    #[default]
+    Day,
+    Week,
+    Project,
+    Model,
+}
+
+impl UsageGroupBy {
+    pub const ALL: [UsageGroupBy; 4] = [
+        UsageGroupBy::Day,
+        UsageGroupBy::Week,
+        UsageGroupBy::Project,
+        UsageGroupBy::Model,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            UsageGroupBy::Day => "day",
+            UsageGroupBy::Week => "week",
+            UsageGroupBy::Project => "project",
+            UsageGroupBy::Model => "model",
+        }
+    }
+
+    /// Time buckets read chronologically; the others rank by volume.
+    fn is_time(self) -> bool {
+        matches!(self, UsageGroupBy::Day | UsageGroupBy::Week)
+    }
+}
+
+/// Usage rolled up for one bucket
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageBucket {
+    /// Day (`YYYY-MM-DD`), ISO week (`YYYY-Www`), project or model
+    pub key: String,
+    pub session_count: u32,
+    pub token_usage: TokenUsage,
+    pub total_tokens: f64,
+    /// Cache read tokens over all prompt tokens, `0.0..=1.0`
+    pub cache_hit_ratio: f64,
+}
+
+impl UsageBucket {
+    fn new(key: String) -> Self {
+        Self {
+            key,
+            session_count: 0,
+            token_usage: TokenUsage::default(),
+            total_tokens: 0.0,
+            cache_hit_ratio: 0.0,
+        }
+    }
+
+    fn add(&mut self, usage: &TokenUsage) {
+        self.session_count += 1;
+        self.token_usage.add(usage);
+        self.total_tokens = self.token_usage.total();
+        self.cache_hit_ratio = self.token_usage.cache_hit_ratio();
+    }
+}
+
+/// Usage report for one grouping
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub group_by: UsageGroupBy,
+    pub buckets: Vec<UsageBucket>,
+    pub totals: UsageBucket,
+}
+
+/// Report export format
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl UsageExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            UsageExportFormat::Csv => "csv",
+            UsageExportFormat::Json => "json",
+        }
+    }
+}
+
+fn bucket_key(session: &SessionSummary, group_by: UsageGroupBy) -> String {
+    let date = || DateTime::from_timestamp_millis(session.modified_at as i64);
+    match group_by {
+        UsageGroupBy::Day => date()
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "unknown".to_string()),
+        UsageGroupBy::Week => date()
+            .map(|d| {
+                let week = d.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            })
+            .unwrap_or_else(|| "unknown".to_string()),
+        UsageGroupBy::Project => session.project.clone(),
+        UsageGroupBy::Model => session.model.clone(),
+    }
+}
+
+/// Rolls `sessions` up by `group_by`. Day and week buckets are sorted
+/// oldest first; project and model buckets by total tokens, largest first.
+pub fn aggregate_usage(sessions: &[SessionSummary], group_by: UsageGroupBy) -> UsageReport {
+    let mut buckets: HashMap<String, UsageBucket> = HashMap::new();
+    let mut totals = UsageBucket::new("total".to_string());
+
+    for session in sessions {
+        let key = bucket_key(session, group_by);
+        buckets
+            .entry(key.clone())
+            .or_insert_with(|| UsageBucket::new(key))
+            .add(&session.token_usage);
+        totals.add(&session.token_usage);
+    }
+
+    let mut buckets: Vec<UsageBucket> = buckets.into_values().collect();
+    if group_by.is_time() {
+        buckets.sort_by(|a, b| a.key.cmp(&b.key));
+    } else {
+        buckets.sort_by(|a, b| {
+            b.total_tokens
+                .partial_cmp(&a.total_tokens)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.key.cmp(&b.key))
+        });
+    }
+
+    UsageReport {
+        group_by,
+        buckets,
+        totals,
+    }
+}
+
+/// Builds a report over Droid, Claude Code and Codex sessions.
+pub fn usage_report_for_home(
+    home_dir: &Path,
+    group_by: UsageGroupBy,
+) -> Result<UsageReport, String> {
+    let sessions = sessions::list_all_sessions_with_usage_for_home(home_dir)?;
+    Ok(aggregate_usage(&sessions, group_by))
+}
+
+pub fn usage_report(group_by: UsageGroupBy) -> Result<UsageReport, String> {
+    usage_report_for_home(&system_home_dir()?, group_by)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Renders a report as CSV with a trailing `total` row.
+pub fn render_usage_csv(report: &UsageReport) -> String {
+    let mut out = format!(
+        "{},sessions,input_tokens,output_tokens,cache_creation_tokens,cache_read_tokens,thinking_tokens,total_tokens,cache_hit_ratio\n",
+        report.group_by.label()
+    );
+    for bucket in report.buckets.iter().chain(std::iter::once(&report.totals)) {
+        let u = &bucket.token_usage;
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{:.4}\n",
+            csv_field(&bucket.key),
+            bucket.session_count,
+            u.input_tokens as u64,
+            u.output_tokens as u64,
+            u.cache_creation_tokens as u64,
+            u.cache_read_tokens as u64,
+            u.thinking_tokens as u64,
+            bucket.total_tokens as u64,
+            bucket.cache_hit_ratio
+        ));
+    }
+    out
+}
+
+pub fn render_usage_json(report: &UsageReport) -> Result<String, String> {
+    serde_json::to_string_pretty(report)
+        .map(|s| s + "\n")
+        .map_err(|e| format!("Failed to serialize usage report: {e}"))
+}
+
+/// Builds a report and writes it to `output_path`.
+pub fn export_usage_report_for_home(
+    home_dir: &Path,
+    group_by: UsageGroupBy,
+    format: UsageExportFormat,
+    output_path: &Path,
+) -> Result<(), String> {
+    let report = usage_report_for_home(home_dir, group_by)?;
+    let rendered = match format {
+        UsageExportFormat::Csv => render_usage_csv(&report),
+        UsageExportFormat::Json => render_usage_json(&report)?,
+    };
+    storage::atomic_write(output_path, rendered.as_bytes())
+}
+
+pub fn export_usage_report(
+    group_by: UsageGroupBy,
+    format: UsageExportFormat,
+    output_path: &Path,
+) -> Result<(), String> {
+    export_usage_report_for_home(&system_home_dir()?, group_by, format, output_path)
+}
+
+fn system_home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sessions::SessionSource;
+
+    fn session(
+        project: &str,
+        model: &str,
+        modified_at: f64,
+        input: f64,
+        cached: f64,
+    ) -> SessionSummary {
+        SessionSummary {
+            id: format!("{project}-{modified_at}"),
+            title: String::new(),
+            project: project.to_string(),
+            model: model.to_string(),
+            modified_at,
+            token_usage: TokenUsage {
+                input_tokens: input,
+                output_tokens: 10.0,
+                cache_creation_tokens: 0.0,
+                cache_read_tokens: cached,
+                thinking_tokens: 0.0,
+            },
+            path: String::new(),
+            source: SessionSource::Droid,
+        }
+    }
+
+    // 2026-01-05 (Mon, W02) and 2026-01-11 (Sun, W02), 2026-01-12 (Mon, W03).
+    const JAN_05: f64 = 1_767_571_200_000.0;
+    const JAN_11: f64 = 1_768_089_600_000.0;
+    const JAN_12: f64 = 1_768_176_000_000.0;
+
+    fn sample() -> Vec<SessionSummary> {
+        vec![
+            session("app", "opus", JAN_12, 100.0, 300.0),
+            session("app", "sonnet", JAN_05, 50.0, 0.0),
+            session("web", "opus", JAN_11, 1000.0, 0.0),
+        ]
+    }
+
+    #[test]
+    fn rolls_up_by_day_and_iso_week_chronologically() {
+        let days = aggregate_usage(&sample(), UsageGroupBy::Day);
+        let keys: Vec<&str> = days.buckets.iter().map(|b| b.key.as_str()).collect();
+        assert_eq!(keys, vec!["2026-01-05", "2026-01-11", "2026-01-12"]);
+
+        let weeks = aggregate_usage(&sample(), UsageGroupBy::Week);
+        let keys: Vec<(&str, u32)> = weeks
+            .buckets
+            .iter()
+            .map(|b| (b.key.as_str(), b.session_count))
+            .collect();
+        assert_eq!(keys, vec![("2026-W02", 2), ("2026-W03", 1)]);
+        assert_eq!(weeks.totals.session_count, 3);
+        assert_eq!(weeks.totals.total_tokens, 1480.0);
+    }
+
+    #[test]
+    fn ranks_projects_and_models_and_computes_cache_hit_ratio() {
+        let models = aggregate_usage(&sample(), UsageGroupBy::Model);
+        assert_eq!(models.buckets[0].key, "opus");
+        assert_eq!(models.buckets[0].token_usage.input_tokens, 1100.0);
+        assert_eq!(models.buckets[0].cache_hit_ratio, 300.0 / 1400.0);
+
+        let projects = aggregate_usage(&sample(), UsageGroupBy::Project);
+        assert_eq!(projects.buckets[0].key, "web");
+        assert_eq!(projects.buckets[1].cache_hit_ratio, 300.0 / 450.0);
+    }
+
+    #[test]
+    fn csv_has_header_rows_and_total() {
+        let mut sessions = sample();
+        sessions[0].project = "a,b".to_string();
+        let csv = render_usage_csv(&aggregate_usage(&sessions, UsageGroupBy::Project));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("project,sessions,input_tokens"));
+        assert_eq!(lines.len(), 5);
+        assert!(lines
+            .iter()
+            .any(|l| l.starts_with("\"a,b\",1,100,10,0,300,0,410,0.7500")));
+        assert!(lines[4].starts_with("total,3,"));
+    }
+}
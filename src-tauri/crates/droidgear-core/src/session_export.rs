@@ -0,0 +1,721 @@
+//! Session export (core).
+//!
+//! Renders a [`SessionDetail`] as Markdown, a self-contained HTML page or
+//! normalized JSON, optionally redacting secrets and personal data first so
+//! transcripts can be shared.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::path::{Path, PathBuf};
+
+use crate::sessions::{self, ContentBlock, SessionDetail};
+use crate::storage;
+
+/// Version of the normalized JSON export envelope
+const JSON_EXPORT_VERSION: u32 = 1;
+
+/// Common API key / token shapes (OpenAI, Anthropic, Factory, GitHub, AWS,
+/// Google, Slack) plus bearer tokens in headers.
+const API_KEY_PATTERNS: &[&str] = &[
+    r"\b(?:sk|fk)-[A-Za-z0-9_\-]{16,}",
+    r"\b(?:ghp|gho|ghu|ghs|ghr)_[A-Za-z0-9]{20,}",
+    r"\bgithub_pat_[A-Za-z0-9_]{20,}",
+    r"\bAKIA[0-9A-Z]{16}\b",
+    r"\bAIza[0-9A-Za-z_\-]{35}",
+    r"\bxox[abprs]-[A-Za-z0-9\-]{10,}",
+    r"(?i)\bbearer\s+[A-Za-z0-9._~+/=\-]{16,}",
+];
+
+const EMAIL_PATTERN: &str = r"[A-Za-z0-9._%+\-]+@[A-Za-z0-9.\-]+\.[A-Za-z]{2,}";
+
+/// Export output format
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionExportFormat {
+    #[default]
+    Markdown,
+    Html,
+    Json,
+}
+
+impl SessionExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            SessionExportFormat::Markdown => "md",
+            SessionExportFormat::Html => "html",
+            SessionExportFormat::Json => "json",
+        }
+    }
+}
+
+/// What to redact before rendering. Missing fields take the [`Default`],
+/// which enables every built-in rule.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RedactionOptions {
+    /// Replace API keys and bearer tokens
+    pub api_keys: bool,
+    /// Replace the home directory with `~`
+    pub home_paths: bool,
+    /// Replace email addresses
+    pub emails: bool,
+    /// Extra regular expressions whose matches are replaced
+    pub custom_patterns: Vec<String>,
+}
+
+impl Default for RedactionOptions {
+    fn default() -> Self {
+        Self {
+            api_keys: true,
+            home_paths: true,
+            emails: true,
+            custom_patterns: Vec::new(),
+        }
+    }
+}
+
+impl RedactionOptions {
+    /// Options that leave the transcript untouched.
+    pub fn none() -> Self {
+        Self {
+            api_keys: false,
+            home_paths: false,
+            emails: false,
+            custom_patterns: Vec::new(),
+        }
+    }
+}
+
+/// Export request
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExportOptions {
+    pub format: SessionExportFormat,
+    #[serde(default)]
+    pub redaction: RedactionOptions,
+}
+
+/// Whether `rest` (the text after a matched path) starts a new path segment
+/// with `separator` or ends the path, so `/home/al` never matches `/home/alice`.
+fn ends_path_prefix(rest: &str, separator: char) -> bool {
+    match rest.chars().next() {
+        None => true,
+        Some(c) => c == separator || !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')),
+    }
+}
+
+/// Replaces `prefix` with `~` wherever it appears as a whole path prefix.
+fn replace_path_prefix(text: &str, prefix: &str, separator: char) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(prefix) {
+        let after = &rest[pos + prefix.len()..];
+        out.push_str(&rest[..pos]);
+        if ends_path_prefix(after, separator) {
+            out.push('~');
+        } else {
+            out.push_str(prefix);
+        }
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Compiled redaction rules.
+pub struct Redactor {
+    rules: Vec<(Regex, &'static str)>,
+    home: Option<(String, String)>,
+}
+
+impl Redactor {
+    pub fn new(options: &RedactionOptions, home_dir: &Path) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        if options.api_keys {
+            for pattern in API_KEY_PATTERNS {
+                rules.push((
+                    Regex::new(pattern).expect("valid pattern"),
+                    "[REDACTED_KEY]",
+                ));
+            }
+        }
+        if options.emails {
+            rules.push((
+                Regex::new(EMAIL_PATTERN).expect("valid pattern"),
+                "[REDACTED_EMAIL]",
+            ));
+        }
+        for pattern in &options.custom_patterns {
+            let re = Regex::new(pattern)
+                .map_err(|e| format!("Invalid redaction pattern '{pattern}': {e}"))?;
+            rules.push((re, "[REDACTED]"));
+        }
+
+        let home_str = home_dir.to_string_lossy().trim_end_matches('/').to_string();
+        // Session project names encode the cwd with '-' separators.
+        let home = (options.home_paths && !home_str.is_empty())
+            .then(|| (home_str.clone(), sessions::project_name_for_cwd(&home_str)));
+
+        Ok(Self { rules, home })
+    }
+
+    pub fn redact(&self, text: &str) -> String {
+        let mut out = match &self.home {
+            Some((home, _)) => replace_path_prefix(text, home, '/'),
+            None => text.to_string(),
+        };
+        for (re, replacement) in &self.rules {
+            if re.is_match(&out) {
+                out = re.replace_all(&out, *replacement).into_owned();
+            }
+        }
+        out
+    }
+
+    fn redact_project(&self, project: &str) -> String {
+        match &self.home {
+            Some((_, encoded))
+                if project.starts_with(encoded.as_str())
+                    && ends_path_prefix(&project[encoded.len()..], '-') =>
+            {
+                format!("~{}", &project[encoded.len()..])
+            }
+            _ => self.redact(project),
+        }
+    }
+
+    fn redact_value(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.redact(s)),
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|v| self.redact_value(v)).collect())
+            }
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.redact_value(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    fn redact_block(&self, block: &ContentBlock) -> ContentBlock {
+        match block {
+            ContentBlock::Text { text } => ContentBlock::Text {
+                text: self.redact(text),
+            },
+            ContentBlock::Thinking { thinking } => ContentBlock::Thinking {
+                thinking: self.redact(thinking),
+            },
+            ContentBlock::ToolUse { id, name, input } => ContentBlock::ToolUse {
+                id: id.clone(),
+                name: name.clone(),
+                input: self.redact_value(input),
+            },
+            ContentBlock::ToolResult {
+                tool_use_id,
+                output,
+                is_error,
+            } => ContentBlock::ToolResult {
+                tool_use_id: tool_use_id.clone(),
+                output: self.redact(output),
+                is_error: *is_error,
+            },
+            ContentBlock::Image { media_type, url } => ContentBlock::Image {
+                media_type: media_type.clone(),
+                url: url.as_deref().map(|u| self.redact(u)),
+            },
+            ContentBlock::FileEdit {
+                id,
+                tool_name,
+                path,
+                old_text,
+                new_text,
+            } => ContentBlock::FileEdit {
+                id: id.clone(),
+                tool_name: tool_name.clone(),
+                path: self.redact(path),
+                old_text: old_text.as_deref().map(|t| self.redact(t)),
+                new_text: new_text.as_deref().map(|t| self.redact(t)),
+            },
+        }
+    }
+
+    /// Returns a copy of `detail` with every free-text field redacted.
+    pub fn redact_session(&self, detail: &SessionDetail) -> SessionDetail {
+        let mut out = detail.clone();
+        out.title = self.redact(&detail.title);
+        out.project = self.redact_project(&detail.project);
+        out.cwd = self.redact(&detail.cwd);
+        for message in &mut out.messages {
+            message.content = message
+                .content
+                .iter()
+                .map(|b| self.redact_block(b))
+                .collect();
+        }
+        out
+    }
+}
+
+fn fence_for(text: &str) -> String {
+    // Use a fence longer than any backtick run inside the text.
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn code_block(lang: &str, text: &str) -> String {
+    let fence = fence_for(text);
+    format!("{fence}{lang}\n{}\n{fence}\n", text.trim_end_matches('\n'))
+}
+
+fn format_timestamp(ms: f64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms as i64)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default()
+}
+
+fn pretty_json(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// Renders a session as Markdown.
+pub fn render_markdown(detail: &SessionDetail) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("# {}\n\n", detail.title));
+    out.push_str(&format!("- **Source:** {}\n", detail.source.label()));
+    out.push_str(&format!("- **Project:** {}\n", detail.project));
+    out.push_str(&format!("- **Model:** {}\n", detail.model));
+    if !detail.cwd.is_empty() {
+        out.push_str(&format!("- **Working directory:** `{}`\n", detail.cwd));
+    }
+    out.push_str(&format!(
+        "- **Last modified:** {}\n",
+        format_timestamp(detail.modified_at)
+    ));
+    let usage = &detail.token_usage;
+    out.push_str(&format!(
+        "- **Tokens:** {} in / {} out / {} cache read / {} cache write\n",
+        usage.input_tokens as u64,
+        usage.output_tokens as u64,
+        usage.cache_read_tokens as u64,
+        usage.cache_creation_tokens as u64
+    ));
+
+    for message in &detail.messages {
+        out.push_str(&format!("\n## {}", message.role));
+        if !message.timestamp.is_empty() {
+            out.push_str(&format!(" · {}", message.timestamp));
+        }
+        out.push_str("\n\n");
+        for block in &message.content {
+            match block {
+                ContentBlock::Text { text } => {
+                    out.push_str(text.trim_end());
+                    out.push_str("\n\n");
+                }
+                ContentBlock::Thinking { thinking } => {
+                    for line in thinking.trim_end().lines() {
+                        out.push_str(&format!("> {line}\n"));
+                    }
+                    out.push('\n');
+                }
+                ContentBlock::ToolUse { name, input, .. } => {
+                    out.push_str(&format!("**Tool call:** `{name}`\n\n"));
+                    out.push_str(&code_block("json", &pretty_json(input)));
+                    out.push('\n');
+                }
+                ContentBlock::ToolResult {
+                    output, is_error, ..
+                } => {
+                    let label = if *is_error {
+                        "Tool error"
+                    } else {
+                        "Tool result"
+                    };
+                    out.push_str(&format!("**{label}:**\n\n"));
+                    out.push_str(&code_block("", output));
+                    out.push('\n');
+                }
+                ContentBlock::Image { media_type, url } => {
+                    match url {
+                        Some(url) => out.push_str(&format!("![{media_type}]({url})\n\n")),
+                        None => out.push_str(&format!("_[image: {media_type}]_\n\n")),
+                    };
+                }
+                ContentBlock::FileEdit {
+                    tool_name,
+                    path,
+                    old_text,
+                    new_text,
+                    ..
+                } => {
+                    out.push_str(&format!("**{tool_name}:** `{path}`\n\n"));
+                    let diff: String = old_text
+                        .as_deref()
+                        .unwrap_or("")
+                        .lines()
+                        .map(|l| format!("-{l}\n"))
+                        .chain(
+                            new_text
+                                .as_deref()
+                                .unwrap_or("")
+                                .lines()
+                                .map(|l| format!("+{l}\n")),
+                        )
+                        .collect();
+                    out.push_str(&code_block("diff", &diff));
+                    out.push('\n');
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;max-width:960px;margin:2rem auto;padding:0 1rem;color:#1f2328;background:#fff}\
+h1{font-size:1.5rem}dl{display:grid;grid-template-columns:max-content 1fr;gap:.25rem 1rem;color:#59636e}dt{font-weight:600}dd{margin:0}\
+.msg{border:1px solid #d1d9e0;border-radius:6px;margin:1rem 0;padding:.75rem 1rem}.msg.user{background:#f6f8fa}\
+.role{font-weight:600;text-transform:capitalize}.ts{color:#59636e;font-size:.85em;margin-left:.5rem}\
+.text{white-space:pre-wrap}.thinking{white-space:pre-wrap;color:#59636e;border-left:3px solid #d1d9e0;padding-left:.75rem}\
+pre{background:#f6f8fa;padding:.5rem;border-radius:4px;overflow-x:auto}details{margin:.5rem 0}summary{cursor:pointer}\
+.error summary{color:#d1242f}.del{color:#d1242f}.add{color:#1a7f37}\
+@media (prefers-color-scheme:dark){body{background:#0d1117;color:#e6edf3}.msg{border-color:#3d444d}.msg.user,pre{background:#151b23}}";
+
+/// Renders a session as a self-contained HTML page (inline CSS, no scripts).
+pub fn render_html(detail: &SessionDetail) -> String {
+    let mut body = String::new();
+    body.push_str(&format!("<h1>{}</h1>\n<dl>", escape_html(&detail.title)));
+    let usage = &detail.token_usage;
+    let meta = [
+        ("Source", detail.source.label().to_string()),
+        ("Project", detail.project.clone()),
+        ("Model", detail.model.clone()),
+        ("Working directory", detail.cwd.clone()),
+        ("Last modified", format_timestamp(detail.modified_at)),
+        (
+            "Tokens",
+            format!(
+                "{} in / {} out / {} cache read / {} cache write",
+                usage.input_tokens as u64,
+                usage.output_tokens as u64,
+                usage.cache_read_tokens as u64,
+                usage.cache_creation_tokens as u64
+            ),
+        ),
+    ];
+    for (label, value) in meta {
+        if !value.is_empty() {
+            body.push_str(&format!("<dt>{label}</dt><dd>{}</dd>", escape_html(&value)));
+        }
+    }
+    body.push_str("</dl>\n");
+
+    for message in &detail.messages {
+        body.push_str(&format!(
+            "<section class=\"msg {}\"><div><span class=\"role\">{}</span><span class=\"ts\">{}</span></div>\n",
+            escape_html(&message.role),
+            escape_html(&message.role),
+            escape_html(&message.timestamp)
+        ));
+        for block in &message.content {
+            match block {
+                ContentBlock::Text { text } => {
+                    body.push_str(&format!(
+                        "<div class=\"text\">{}</div>\n",
+                        escape_html(text)
+                    ));
+                }
+                ContentBlock::Thinking { thinking } => {
+                    body.push_str(&format!(
+                        "<details><summary>Thinking</summary><div class=\"thinking\">{}</div></details>\n",
+                        escape_html(thinking)
+                    ));
+                }
+                ContentBlock::ToolUse { name, input, .. } => {
+                    body.push_str(&format!(
+                        "<details><summary>Tool call: {}</summary><pre>{}</pre></details>\n",
+                        escape_html(name),
+                        escape_html(&pretty_json(input))
+                    ));
+                }
+                ContentBlock::ToolResult {
+                    output, is_error, ..
+                } => {
+                    let (class, label) = if *is_error {
+                        (" class=\"error\"", "Tool error")
+                    } else {
+                        ("", "Tool result")
+                    };
+                    body.push_str(&format!(
+                        "<details{class}><summary>{label}</summary><pre>{}</pre></details>\n",
+                        escape_html(output)
+                    ));
+                }
+                // Only inline data URLs are embedded; the export never
+                // fetches anything when opened.
+                ContentBlock::Image { media_type, url } => match url {
+                    Some(url) if url.starts_with("data:image/") => body.push_str(&format!(
+                        "<p><img src=\"{}\" alt=\"{}\" style=\"max-width:100%\"></p>\n",
+                        escape_html(url),
+                        escape_html(media_type)
+                    )),
+                    Some(url) => body.push_str(&format!(
+                        "<p><em>[image: {}]</em> <code>{}</code></p>\n",
+                        escape_html(media_type),
+                        escape_html(url)
+                    )),
+                    None => body.push_str(&format!(
+                        "<p><em>[image: {}]</em></p>\n",
+                        escape_html(media_type)
+                    )),
+                },
+                ContentBlock::FileEdit {
+                    tool_name,
+                    path,
+                    old_text,
+                    new_text,
+                    ..
+                } => {
+                    let mut diff = String::new();
+                    for line in old_text.as_deref().unwrap_or("").lines() {
+                        diff.push_str(&format!(
+                            "<span class=\"del\">-{}</span>\n",
+                            escape_html(line)
+                        ));
+                    }
+                    for line in new_text.as_deref().unwrap_or("").lines() {
+                        diff.push_str(&format!(
+                            "<span class=\"add\">+{}</span>\n",
+                            escape_html(line)
+                        ));
+                    }
+                    body.push_str(&format!(
+                        "<details><summary>{}: {}</summary><pre>{diff}</pre></details>\n",
+                        escape_html(tool_name),
+                        escape_html(path)
+                    ));
+                }
+            }
+        }
+        body.push_str("</section>\n");
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape_html(&detail.title)
+    )
+}
+
+/// Renders a session as normalized JSON wrapped in a versioned envelope.
+pub fn render_json(detail: &SessionDetail) -> Result<String, String> {
+    let envelope = serde_json::json!({
+        "format": "droidgear-session",
+        "version": JSON_EXPORT_VERSION,
+        "session": detail,
+    });
+    serde_json::to_string_pretty(&envelope)
+        .map(|s| s + "\n")
+        .map_err(|e| format!("Failed to serialize session: {e}"))
+}
+
+fn system_home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+/// Redacts and renders `detail` in the requested format.
+pub fn render_session(
+    home_dir: &Path,
+    detail: &SessionDetail,
+    options: &SessionExportOptions,
+) -> Result<String, String> {
+    let redacted = Redactor::new(&options.redaction, home_dir)?.redact_session(detail);
+    match options.format {
+        SessionExportFormat::Markdown => Ok(render_markdown(&redacted)),
+        SessionExportFormat::Html => Ok(render_html(&redacted)),
+        SessionExportFormat::Json => render_json(&redacted),
+    }
+}
+
+/// Loads the session at `session_path` (any source) and renders it.
+pub fn export_session_for_home(
+    home_dir: &Path,
+    session_path: &str,
+    options: &SessionExportOptions,
+) -> Result<String, String> {
+    let detail = sessions::get_session_detail_for_home(home_dir, session_path)?;
+    render_session(home_dir, &detail, options)
+}
+
+pub fn export_session(
+    session_path: &str,
+    options: &SessionExportOptions,
+) -> Result<String, String> {
+    export_session_for_home(&system_home_dir()?, session_path, options)
+}
+
+/// Renders the session and writes it to `output_path`.
+pub fn export_session_to_file_for_home(
+    home_dir: &Path,
+    session_path: &str,
+    options: &SessionExportOptions,
+    output_path: &Path,
+) -> Result<(), String> {
+    let rendered = export_session_for_home(home_dir, session_path, options)?;
+    storage::atomic_write(output_path, rendered.as_bytes())
+}
+
+pub fn export_session_to_file(
+    session_path: &str,
+    options: &SessionExportOptions,
+    output_path: &Path,
+) -> Result<(), String> {
+    export_session_to_file_for_home(&system_home_dir()?, session_path, options, output_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sessions::{SessionMessage, SessionSource, TokenUsage};
+
+    fn sample() -> SessionDetail {
+        SessionDetail {
+            id: "s1".to_string(),
+            title: "Fix <login> for alice@example.com".to_string(),
+            project: "-home-alice-app".to_string(),
+            model: "m".to_string(),
+            cwd: "/home/alice/app".to_string(),
+            modified_at: 0.0,
+            token_usage: TokenUsage::default(),
+            messages: vec![SessionMessage {
+                id: "m1".to_string(),
+                role: "assistant".to_string(),
+                content: vec![
+                    ContentBlock::Text {
+                        text: "Use key sk-ant-REDACTED and ```code```".to_string(),
+                    },
+                    ContentBlock::ToolUse {
+                        id: "t1".to_string(),
+                        name: "Read".to_string(),
+                        input: serde_json::json!({"file_path": "/home/alice/app/secret.txt"}),
+                    },
+                ],
+                timestamp: "t".to_string(),
+                token_usage: None,
+            }],
+            source: SessionSource::Droid,
+        }
+    }
+
+    #[test]
+    fn redaction_covers_keys_home_paths_emails_and_custom_patterns() {
+        let options = RedactionOptions {
+            custom_patterns: vec!["secret\\.txt".to_string()],
+            ..RedactionOptions::default()
+        };
+        let redactor = Redactor::new(&options, &PathBuf::from("/home/alice")).unwrap();
+        let out = redactor.redact_session(&sample());
+
+        assert_eq!(out.title, "Fix <login> for [REDACTED_EMAIL]");
+        assert_eq!(out.project, "~-app");
+        assert_eq!(out.cwd, "~/app");
+        assert_eq!(
+            out.messages[0].content[0],
+            ContentBlock::Text {
+                text: "Use key [REDACTED_KEY] and ```code```".to_string()
+            }
+        );
+        assert!(matches!(
+            &out.messages[0].content[1],
+            ContentBlock::ToolUse { input, .. } if input["file_path"] == "~/app/[REDACTED]"
+        ));
+
+        let short =
+            Redactor::new(&RedactionOptions::default(), &PathBuf::from("/home/al")).unwrap();
+        assert_eq!(
+            short.redact("/home/alice/x and /home/al/y and /home/al"),
+            "/home/alice/x and ~/y and ~"
+        );
+        assert_eq!(short.redact_project("-home-alice-app"), "-home-alice-app");
+        assert_eq!(short.redact_project("-home-al-app"), "~-app");
+
+        let bad = RedactionOptions {
+            custom_patterns: vec!["(".to_string()],
+            ..RedactionOptions::none()
+        };
+        assert!(Redactor::new(&bad, &PathBuf::from("/home/alice")).is_err());
+    }
+
+    #[test]
+    fn redaction_options_default_to_builtin_rules() {
+        let options: RedactionOptions =
+            serde_json::from_str(r#"{"customPatterns": ["x"]}"#).unwrap();
+        assert!(options.api_keys && options.home_paths && options.emails);
+        assert_eq!(options.custom_patterns, vec!["x".to_string()]);
+    }
+
+    #[test]
+    fn renders_markdown_html_and_json() {
+        let detail = sample();
+
+        let md = render_markdown(&detail);
+        assert!(md.starts_with("# Fix <login>"));
+        assert!(md.contains("**Tool call:** `Read`"));
+
+        let html = render_html(&detail);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Fix &lt;login&gt;"));
+        assert!(!html.contains("<script"));
+
+        let json: Value = serde_json::from_str(&render_json(&detail).unwrap()).unwrap();
+        assert_eq!(json["format"], "droidgear-session");
+        assert_eq!(
+            json["session"]["messages"][0]["content"][1]["type"],
+            "tool_use"
+        );
+    }
+
+    #[test]
+    fn html_embeds_only_inline_images() {
+        let mut detail = sample();
+        detail.messages[0].content = vec![
+            ContentBlock::Image {
+                media_type: "image/png".to_string(),
+                url: Some("https://tracker.example/pixel.png".to_string()),
+            },
+            ContentBlock::Image {
+                media_type: "image/png".to_string(),
+                url: Some("data:image/png;base64,AAAA".to_string()),
+            },
+        ];
+
+        let html = render_html(&detail);
+        assert!(!html.contains("<img src=\"https://"));
+        assert!(html.contains("<code>https://tracker.example/pixel.png</code>"));
+        assert!(html.contains("<img src=\"data:image/png;base64,AAAA\""));
+    }
+
+    #[test]
+    fn code_fences_outgrow_embedded_backticks() {
+        assert_eq!(fence_for("no ticks"), "```");
+        assert_eq!(fence_for("has ```` four"), "`````");
+    }
+}
@@ -0,0 +1,508 @@
+//! Session retention rules and bulk cleanup (core).
+//!
+//! A [`RetentionPolicy`] (stored in `~/.droidgear/session-retention.json`)
+//! selects Droid sessions by age, emptiness, token volume or a vanished
+//! project directory. Claude Code and Codex transcripts belong to those tools
+//! and are never selected. Matching sessions can be listed as a dry run, deleted, or moved
+//! into a `.tar.gz` under `~/.droidgear/archive/` whose entries are relative
+//! to the home directory, so `tar -xzf <archive> -C ~` restores them.
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::sessions::{self, SessionSummary};
+use crate::storage;
+
+const POLICY_FILE: &str = "session-retention.json";
+const DAY_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// Which sessions a cleanup selects. A session matches if any enabled rule
+/// matches; with no rule enabled nothing is selected.
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionPolicy {
+    /// Sessions not modified for more than this many days
+    pub max_age_days: Option<u32>,
+    /// Sessions without any user or assistant message
+    pub empty: bool,
+    /// Sessions whose total tokens are below this threshold
+    pub min_total_tokens: Option<f64>,
+    /// Sessions whose working directory no longer exists
+    pub missing_project_dir: bool,
+}
+
+impl RetentionPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.max_age_days.is_none()
+            && !self.empty
+            && self.min_total_tokens.is_none()
+            && !self.missing_project_dir
+    }
+}
+
+/// What to do with matching sessions
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionAction {
+    #[default]
+    Archive,
+    Delete,
+}
+
+/// Why a session was selected
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+#[serde(tag = "rule", rename_all = "camelCase")]
+pub enum RetentionReason {
+    #[serde(rename_all = "camelCase")]
+    OlderThan {
+        age_days: u32,
+    },
+    Empty,
+    #[serde(rename_all = "camelCase")]
+    BelowTokenThreshold {
+        total_tokens: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    MissingProjectDir {
+        cwd: String,
+    },
+}
+
+impl RetentionReason {
+    pub fn describe(&self) -> String {
+        match self {
+            RetentionReason::OlderThan { age_days } => format!("{age_days}d old"),
+            RetentionReason::Empty => "empty".to_string(),
+            RetentionReason::BelowTokenThreshold { total_tokens } => {
+                format!("{} tokens", *total_tokens as u64)
+            }
+            RetentionReason::MissingProjectDir { cwd } => format!("missing {cwd}"),
+        }
+    }
+}
+
+/// A session selected by the policy
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionCandidate {
+    pub session: SessionSummary,
+    pub reasons: Vec<RetentionReason>,
+    /// Files that will be archived or removed
+    pub files: Vec<String>,
+    pub size_bytes: u64,
+}
+
+/// Result of a cleanup run (or of a dry run when `dry_run` is set)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionReport {
+    pub action: RetentionAction,
+    pub dry_run: bool,
+    pub candidates: Vec<RetentionCandidate>,
+    pub total_bytes: u64,
+    /// Archive written by this run, if any
+    pub archive_path: Option<String>,
+}
+
+pub fn retention_policy_path_for_home(home_dir: &Path) -> PathBuf {
+    paths::droidgear_dir_from_home(home_dir).join(POLICY_FILE)
+}
+
+pub fn archive_dir_for_home(home_dir: &Path) -> PathBuf {
+    paths::droidgear_dir_from_home(home_dir).join("archive")
+}
+
+pub fn load_retention_policy_for_home(home_dir: &Path) -> Result<RetentionPolicy, String> {
+    let path = retention_policy_path_for_home(home_dir);
+    if !path.exists() {
+        return Ok(RetentionPolicy::default());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read retention policy: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid retention policy: {e}"))
+}
+
+pub fn load_retention_policy() -> Result<RetentionPolicy, String> {
+    load_retention_policy_for_home(&system_home_dir()?)
+}
+
+pub fn save_retention_policy_for_home(
+    home_dir: &Path,
+    policy: &RetentionPolicy,
+) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(policy)
+        .map_err(|e| format!("Failed to serialize retention policy: {e}"))?;
+    storage::atomic_write(
+        &retention_policy_path_for_home(home_dir),
+        content.as_bytes(),
+    )
+}
+
+pub fn save_retention_policy(policy: &RetentionPolicy) -> Result<(), String> {
+    save_retention_policy_for_home(&system_home_dir()?, policy)
+}
+
+fn session_files(session_path: &str) -> Vec<PathBuf> {
+    [".jsonl", ".settings.json"]
+        .iter()
+        .map(|ext| PathBuf::from(format!("{session_path}{ext}")))
+        .filter(|p| p.exists())
+        .collect()
+}
+
+fn evaluate(
+    home_dir: &Path,
+    session: &SessionSummary,
+    policy: &RetentionPolicy,
+    now_ms: f64,
+) -> Vec<RetentionReason> {
+    let mut reasons = Vec::new();
+
+    if let Some(max_age) = policy.max_age_days {
+        let age_days = ((now_ms - session.modified_at) / DAY_MS).floor();
+        if age_days > max_age as f64 {
+            reasons.push(RetentionReason::OlderThan {
+                age_days: age_days as u32,
+            });
+        }
+    }
+
+    if let Some(threshold) = policy.min_total_tokens {
+        let total = session.token_usage.total();
+        if total < threshold {
+            reasons.push(RetentionReason::BelowTokenThreshold {
+                total_tokens: total,
+            });
+        }
+    }
+
+    // Emptiness and cwd need the transcript, so only parse it when asked.
+    if policy.empty || policy.missing_project_dir {
+        if let Ok(detail) = sessions::get_session_detail_for_home(home_dir, &session.path) {
+            if policy.empty && detail.messages.is_empty() {
+                reasons.push(RetentionReason::Empty);
+            }
+            if policy.missing_project_dir
+                && !detail.cwd.is_empty()
+                && !Path::new(&detail.cwd).exists()
+            {
+                reasons.push(RetentionReason::MissingProjectDir { cwd: detail.cwd });
+            }
+        }
+    }
+
+    reasons
+}
+
+/// Lists the Droid sessions `policy` selects, as of `now_ms`.
+pub fn plan_retention_for_home(
+    home_dir: &Path,
+    policy: &RetentionPolicy,
+    now_ms: f64,
+) -> Result<Vec<RetentionCandidate>, String> {
+    if policy.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut candidates = Vec::new();
+    for session in sessions::list_sessions_for_home(home_dir, None)? {
+        let reasons = evaluate(home_dir, &session, policy, now_ms);
+        if reasons.is_empty() {
+            continue;
+        }
+        let files = session_files(&session.path);
+        let size_bytes = files
+            .iter()
+            .filter_map(|f| fs::metadata(f).ok())
+            .map(|m| m.len())
+            .sum();
+        candidates.push(RetentionCandidate {
+            session,
+            reasons,
+            files: files
+                .iter()
+                .map(|f| f.to_string_lossy().to_string())
+                .collect(),
+            size_bytes,
+        });
+    }
+    Ok(candidates)
+}
+
+/// Name of a file inside the archive: relative to home when possible.
+fn archive_entry_name(home_dir: &Path, file: &Path) -> PathBuf {
+    match file.strip_prefix(home_dir) {
+        Ok(rel) => rel.to_path_buf(),
+        Err(_) => file.components().skip(1).collect(),
+    }
+}
+
+fn write_archive(
+    home_dir: &Path,
+    candidates: &[RetentionCandidate],
+    now_ms: f64,
+) -> Result<PathBuf, String> {
+    let dir = archive_dir_for_home(home_dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create archive directory: {e}"))?;
+
+    let stamp = chrono::DateTime::from_timestamp_millis(now_ms as i64)
+        .map(|d| d.format("%Y%m%d-%H%M%S").to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let mut archive_path = dir.join(format!("sessions-{stamp}.tar.gz"));
+    let mut n = 1;
+    while archive_path.exists() {
+        archive_path = dir.join(format!("sessions-{stamp}-{n}.tar.gz"));
+        n += 1;
+    }
+
+    // Write next to the target and rename, so a failed run leaves no partial archive.
+    let tmp = tempfile::NamedTempFile::new_in(&dir)
+        .map_err(|e| format!("Failed to create archive: {e}"))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(tmp.as_file(), Compression::default()));
+    for file in candidates.iter().flat_map(|c| c.files.iter()) {
+        let file = Path::new(file);
+        builder
+            .append_path_with_name(file, archive_entry_name(home_dir, file))
+            .map_err(|e| format!("Failed to archive {}: {e}", file.display()))?;
+    }
+    builder
+        .into_inner()
+        .and_then(|gz| gz.finish())
+        .map_err(|e| format!("Failed to write archive: {e}"))?;
+    tmp.persist(&archive_path)
+        .map_err(|e| format!("Failed to save archive: {e}"))?;
+
+    Ok(archive_path)
+}
+
+/// Applies `policy` as of `now_ms`. With `dry_run` nothing is touched and the
+/// report only lists what would happen. Archiving writes the tarball before
+/// removing any session file.
+pub fn apply_retention_for_home(
+    home_dir: &Path,
+    policy: &RetentionPolicy,
+    action: RetentionAction,
+    dry_run: bool,
+    now_ms: f64,
+) -> Result<RetentionReport, String> {
+    let candidates = plan_retention_for_home(home_dir, policy, now_ms)?;
+    let total_bytes = candidates.iter().map(|c| c.size_bytes).sum();
+    let mut report = RetentionReport {
+        action,
+        dry_run,
+        candidates,
+        total_bytes,
+        archive_path: None,
+    };
+    if dry_run || report.candidates.is_empty() {
+        return Ok(report);
+    }
+
+    if action == RetentionAction::Archive {
+        let archive = write_archive(home_dir, &report.candidates, now_ms)?;
+        report.archive_path = Some(archive.to_string_lossy().to_string());
+    }
+    for file in report.candidates.iter().flat_map(|c| c.files.iter()) {
+        fs::remove_file(file).map_err(|e| format!("Failed to delete {file}: {e}"))?;
+    }
+
+    Ok(report)
+}
+
+pub fn apply_retention(
+    policy: &RetentionPolicy,
+    action: RetentionAction,
+    dry_run: bool,
+) -> Result<RetentionReport, String> {
+    apply_retention_for_home(
+        &system_home_dir()?,
+        policy,
+        action,
+        dry_run,
+        chrono::Utc::now().timestamp_millis() as f64,
+    )
+}
+
+fn system_home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use tempfile::TempDir;
+
+    const NOW: f64 = 1_800_000_000_000.0;
+
+    fn write_droid_session(home: &Path, name: &str, cwd: &str, age_days: f64, input: u64) {
+        let dir = home.join(".factory/sessions/-proj");
+        fs::create_dir_all(&dir).unwrap();
+        let jsonl = dir.join(format!("{name}.jsonl"));
+        let mut lines = vec![format!(
+            r#"{{"type":"session_start","title":"{name}","cwd":"{cwd}"}}"#
+        )];
+        if input > 0 {
+            lines.push(r#"{"type":"message","id":"m1","timestamp":"t","message":{"role":"user","content":[{"type":"text","text":"hi"}]}}"#.to_string());
+        }
+        fs::write(&jsonl, lines.join("\n")).unwrap();
+        fs::write(
+            dir.join(format!("{name}.settings.json")),
+            format!(r#"{{"model":"m","tokenUsage":{{"inputTokens":{input}}}}}"#),
+        )
+        .unwrap();
+        let mtime =
+            filetime::FileTime::from_unix_time(((NOW - age_days * DAY_MS) / 1000.0) as i64, 0);
+        filetime::set_file_mtime(&jsonl, mtime).unwrap();
+    }
+
+    fn setup() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let live = home.join("live");
+        fs::create_dir_all(&live).unwrap();
+        let live = live.to_string_lossy().to_string();
+        write_droid_session(home, "old", &live, 100.0, 5000);
+        write_droid_session(home, "empty", &live, 1.0, 0);
+        write_droid_session(home, "small", &live, 1.0, 10);
+        write_droid_session(home, "gone", "/nonexistent/droidgear-test", 1.0, 5000);
+        write_droid_session(home, "keep", &live, 1.0, 5000);
+        temp
+    }
+
+    fn names(candidates: &[RetentionCandidate]) -> Vec<String> {
+        let mut names: Vec<String> = candidates.iter().map(|c| c.session.id.clone()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn each_rule_selects_its_sessions() {
+        let temp = setup();
+        let home = temp.path();
+        let plan = |policy: RetentionPolicy| plan_retention_for_home(home, &policy, NOW).unwrap();
+
+        assert!(plan(RetentionPolicy::default()).is_empty());
+        assert_eq!(
+            names(&plan(RetentionPolicy {
+                max_age_days: Some(30),
+                ..Default::default()
+            })),
+            vec!["old"]
+        );
+        assert_eq!(
+            names(&plan(RetentionPolicy {
+                empty: true,
+                ..Default::default()
+            })),
+            vec!["empty"]
+        );
+        assert_eq!(
+            names(&plan(RetentionPolicy {
+                min_total_tokens: Some(100.0),
+                ..Default::default()
+            })),
+            vec!["empty", "small"]
+        );
+        let missing = plan(RetentionPolicy {
+            missing_project_dir: true,
+            ..Default::default()
+        });
+        assert_eq!(names(&missing), vec!["gone"]);
+        assert_eq!(missing[0].files.len(), 2);
+    }
+
+    #[test]
+    fn other_tools_sessions_are_never_selected() {
+        let temp = setup();
+        let home = temp.path();
+        let claude = home.join(".claude/projects/-proj");
+        fs::create_dir_all(&claude).unwrap();
+        fs::write(
+            claude.join("c1.jsonl"),
+            r#"{"type":"user","uuid":"u1","cwd":"/nonexistent/droidgear-test","message":{"role":"user","content":"hi"}}"#,
+        )
+        .unwrap();
+        let codex = home.join(".codex/sessions/2020/01/01");
+        fs::create_dir_all(&codex).unwrap();
+        fs::write(
+            codex.join("rollout-2020-01-01T00-00-00-x.jsonl"),
+            r#"{"timestamp":"t","type":"session_meta","payload":{"id":"x","cwd":"/nonexistent"}}"#,
+        )
+        .unwrap();
+
+        let policy = RetentionPolicy {
+            max_age_days: Some(30),
+            empty: true,
+            min_total_tokens: Some(100.0),
+            missing_project_dir: true,
+        };
+        let plan = plan_retention_for_home(home, &policy, NOW + 365.0 * DAY_MS).unwrap();
+        assert!(plan
+            .iter()
+            .all(|c| c.session.source == sessions::SessionSource::Droid));
+        assert_eq!(plan.len(), 5);
+    }
+
+    #[test]
+    fn dry_run_touches_nothing_and_archive_moves_files_into_tarball() {
+        let temp = setup();
+        let home = temp.path();
+        let policy = RetentionPolicy {
+            max_age_days: Some(30),
+            empty: true,
+            ..Default::default()
+        };
+
+        let dry =
+            apply_retention_for_home(home, &policy, RetentionAction::Archive, true, NOW).unwrap();
+        assert_eq!(dry.candidates.len(), 2);
+        assert!(dry.total_bytes > 0);
+        assert!(dry.archive_path.is_none());
+        assert!(home.join(".factory/sessions/-proj/old.jsonl").exists());
+
+        let report =
+            apply_retention_for_home(home, &policy, RetentionAction::Archive, false, NOW).unwrap();
+        assert!(!home.join(".factory/sessions/-proj/old.jsonl").exists());
+        assert!(!home
+            .join(".factory/sessions/-proj/empty.settings.json")
+            .exists());
+        assert!(home.join(".factory/sessions/-proj/keep.jsonl").exists());
+
+        let archive = PathBuf::from(report.archive_path.unwrap());
+        assert!(archive.starts_with(archive_dir_for_home(home)));
+        let mut entries: Vec<String> =
+            tar::Archive::new(GzDecoder::new(fs::File::open(&archive).unwrap()))
+                .entries()
+                .unwrap()
+                .map(|e| e.unwrap().path().unwrap().to_string_lossy().to_string())
+                .collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                ".factory/sessions/-proj/empty.jsonl",
+                ".factory/sessions/-proj/empty.settings.json",
+                ".factory/sessions/-proj/old.jsonl",
+                ".factory/sessions/-proj/old.settings.json",
+            ]
+        );
+    }
+
+    #[test]
+    fn policy_round_trips_through_droidgear_dir() {
+        let temp = TempDir::new().unwrap();
+        let policy = RetentionPolicy {
+            max_age_days: Some(90),
+            missing_project_dir: true,
+            ..Default::default()
+        };
+        save_retention_policy_for_home(temp.path(), &policy).unwrap();
+        assert_eq!(load_retention_policy_for_home(temp.path()).unwrap(), policy);
+    }
+}
@@ -1,15 +1,37 @@
 //! Sessions management (core).
 //!
-//! Handles reading session files from Factory sessions directory.
+//! Handles reading session files from Factory sessions directory. Claude Code
+//! and Codex transcripts are parsed by `claude_sessions` / `codex_sessions`
+//! and merged here into one timeline.
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use specta::Type;
 use std::fs;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 
-use crate::paths;
+use crate::{claude_sessions, codex_sessions, paths};
+
+/// Tool that produced a session transcript
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionSource {
+    #[default]
+    Droid,
+    ClaudeCode,
+    Codex,
+}
+
+impl SessionSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            SessionSource::Droid => "droid",
+            SessionSource::ClaudeCode => "claude",
+            SessionSource::Codex => "codex",
+        }
+    }
+}
 
 /// Session project (directory containing sessions)
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -36,6 +58,32 @@ pub struct TokenUsage {
     pub thinking_tokens: f64,
 }
 
+impl TokenUsage {
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.thinking_tokens += other.thinking_tokens;
+    }
+
+    /// Billable tokens: input, output and both cache directions. Thinking
+    /// tokens are already counted in output by every provider we read.
+    pub fn total(&self) -> f64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+
+    /// Share of prompt tokens served from cache, in `0.0..=1.0`.
+    pub fn cache_hit_ratio(&self) -> f64 {
+        let prompt = self.input_tokens + self.cache_creation_tokens + self.cache_read_tokens;
+        if prompt > 0.0 {
+            self.cache_read_tokens / prompt
+        } else {
+            0.0
+        }
+    }
+}
+
 /// Session summary for list view
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
@@ -54,18 +102,53 @@ pub struct SessionSummary {
     pub token_usage: TokenUsage,
     /// Full path to the session files (without extension)
     pub path: String,
+    /// Tool that wrote the session
+    #[serde(default)]
+    pub source: SessionSource,
 }
 
 /// Message content block
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
-#[serde(rename_all = "camelCase")]
-pub struct ContentBlock {
-    #[serde(rename = "type")]
-    pub content_type: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub text: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub thinking: Option<String>,
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    Thinking {
+        thinking: String,
+    },
+    /// Tool invocation requested by the assistant
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    /// Output returned to the assistant for a previous tool call
+    ToolResult {
+        #[serde(rename = "toolUseId")]
+        tool_use_id: String,
+        output: String,
+        #[serde(rename = "isError")]
+        is_error: bool,
+    },
+    /// Image attachment (the payload itself is not kept)
+    Image {
+        #[serde(rename = "mediaType")]
+        media_type: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+    /// Tool call that creates or edits a file
+    FileEdit {
+        id: String,
+        #[serde(rename = "toolName")]
+        tool_name: String,
+        path: String,
+        #[serde(rename = "oldText", skip_serializing_if = "Option::is_none")]
+        old_text: Option<String>,
+        #[serde(rename = "newText", skip_serializing_if = "Option::is_none")]
+        new_text: Option<String>,
+    },
 }
 
 /// Session message
@@ -76,6 +159,9 @@ pub struct SessionMessage {
     pub role: String,
     pub content: Vec<ContentBlock>,
     pub timestamp: String,
+    /// Token usage reported for this message, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_usage: Option<TokenUsage>,
 }
 
 /// Session detail with messages
@@ -90,9 +176,11 @@ pub struct SessionDetail {
     pub modified_at: f64,
     pub token_usage: TokenUsage,
     pub messages: Vec<SessionMessage>,
+    #[serde(default)]
+    pub source: SessionSource,
 }
 
-fn sessions_dir_for_home(home_dir: &Path) -> Result<PathBuf, String> {
+pub(crate) fn sessions_dir_for_home(home_dir: &Path) -> Result<PathBuf, String> {
     let config_paths = paths::load_config_paths_for_home(home_dir);
     let factory_dir = paths::get_factory_home_for_home(home_dir, &config_paths)?;
     Ok(factory_dir.join("sessions"))
@@ -102,6 +190,247 @@ fn system_home_dir() -> Result<PathBuf, String> {
     dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
 }
 
+/// Last modified time of a file in milliseconds since the epoch (0 if unknown).
+pub(crate) fn modified_at_ms(path: &Path) -> f64 {
+    fs::metadata(path)
+        .ok()
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
+
+/// Project key for a working directory, using the same dash-separated form
+/// Factory and Claude Code use for their per-project directories.
+pub(crate) fn project_name_for_cwd(cwd: &str) -> String {
+    cwd.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Title derived from the first line of a prompt, truncated for list views.
+pub(crate) fn title_from_prompt(text: &str) -> String {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty());
+    match line {
+        Some(l) if l.chars().count() > 80 => format!("{}…", l.chars().take(80).collect::<String>()),
+        Some(l) => l.to_string(),
+        None => "Untitled".to_string(),
+    }
+}
+
+/// Lines read from the start of a transcript when building a list summary.
+pub(crate) const SUMMARY_HEAD_LINES: usize = 64;
+/// Bytes read from the end of a transcript when building a list summary.
+pub(crate) const SUMMARY_TAIL_BYTES: u64 = 64 * 1024;
+
+/// Parsed lines from both ends of a JSONL transcript.
+pub(crate) struct JsonlEnds {
+    pub head: Vec<Value>,
+    pub tail: Vec<Value>,
+}
+
+/// Reads the first `head_lines` lines and the lines in the last `tail_bytes`
+/// of a JSONL file, so list views don't parse whole transcripts. The tail
+/// never overlaps the head; unparsable lines are skipped.
+pub(crate) fn read_jsonl_ends(
+    path: &Path,
+    head_lines: usize,
+    tail_bytes: u64,
+) -> Result<JsonlEnds, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open session file: {e}"))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read session file: {e}"))?
+        .len();
+    let mut reader = BufReader::new(file);
+
+    let mut head = Vec::new();
+    let mut consumed = 0u64;
+    let mut line = String::new();
+    while head.len() < head_lines {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| format!("Failed to read session file: {e}"))?;
+        if read == 0 {
+            break;
+        }
+        consumed += read as u64;
+        if let Ok(json) = serde_json::from_str::<Value>(line.trim_end()) {
+            head.push(json);
+        }
+    }
+
+    let start = len.saturating_sub(tail_bytes).max(consumed);
+    let mut tail = Vec::new();
+    if start < len {
+        reader
+            .seek(SeekFrom::Start(start))
+            .map_err(|e| format!("Failed to read session file: {e}"))?;
+        let mut rest = Vec::new();
+        reader
+            .read_to_end(&mut rest)
+            .map_err(|e| format!("Failed to read session file: {e}"))?;
+        let rest = String::from_utf8_lossy(&rest);
+        let mut lines = rest.lines();
+        if start > consumed {
+            // Seeking landed mid-line; drop the partial line.
+            lines.next();
+        }
+        tail.extend(lines.filter_map(|l| serde_json::from_str::<Value>(l).ok()));
+    }
+
+    Ok(JsonlEnds { head, tail })
+}
+
+fn json_f64(value: &Value, keys: &[&str]) -> Option<f64> {
+    keys.iter()
+        .find_map(|k| value.get(*k).and_then(|v| v.as_f64()))
+}
+
+/// Parses a token usage object, accepting both Droid's camelCase keys and the
+/// snake_case keys used by the Anthropic API.
+pub(crate) fn token_usage_from_json(value: &Value) -> Option<TokenUsage> {
+    if !value.is_object() {
+        return None;
+    }
+    Some(TokenUsage {
+        input_tokens: json_f64(value, &["inputTokens", "input_tokens"]).unwrap_or(0.0),
+        output_tokens: json_f64(value, &["outputTokens", "output_tokens"]).unwrap_or(0.0),
+        cache_creation_tokens: json_f64(
+            value,
+            &["cacheCreationTokens", "cache_creation_input_tokens"],
+        )
+        .unwrap_or(0.0),
+        cache_read_tokens: json_f64(value, &["cacheReadTokens", "cache_read_input_tokens"])
+            .unwrap_or(0.0),
+        thinking_tokens: json_f64(value, &["thinkingTokens", "thinking_tokens"]).unwrap_or(0.0),
+    })
+}
+
+/// Reads model and token usage from a `<session>.settings.json` file.
+fn read_session_settings(settings_path: &Path) -> (String, TokenUsage) {
+    let Ok(content) = fs::read_to_string(settings_path) else {
+        return ("unknown".to_string(), TokenUsage::default());
+    };
+    let json: Value = serde_json::from_str(&content).unwrap_or_default();
+    let model = json["model"].as_str().unwrap_or("unknown").to_string();
+    let token_usage = token_usage_from_json(&json["tokenUsage"]).unwrap_or_default();
+    (model, token_usage)
+}
+
+/// Tool names whose calls write a file, mapped to a `FileEdit` block.
+const FILE_EDIT_TOOLS: &[&str] = &[
+    "Edit",
+    "MultiEdit",
+    "Create",
+    "Write",
+    "ApplyPatch",
+    "apply_patch",
+];
+
+fn tool_result_output(content: &Value) -> String {
+    match content {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| match item["type"].as_str() {
+                Some("image") => Some("[image]".to_string()),
+                _ => item["text"].as_str().map(|s| s.to_string()),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn patch_target_path(patch: &str) -> Option<String> {
+    patch.lines().find_map(|line| {
+        ["*** Update File: ", "*** Add File: ", "*** Delete File: "]
+            .iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+            .map(|p| p.trim().to_string())
+    })
+}
+
+pub(crate) fn file_edit_block(id: &str, name: &str, input: &Value) -> Option<ContentBlock> {
+    if !FILE_EDIT_TOOLS.contains(&name) {
+        return None;
+    }
+    let str_field = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| input.get(*k).and_then(|v| v.as_str()))
+            .map(|s| s.to_string())
+    };
+    let patch = str_field(&["patch", "input"]).or_else(|| input.as_str().map(|s| s.to_string()));
+    let path = str_field(&["file_path", "filePath", "path"])
+        .or_else(|| patch.as_deref().and_then(patch_target_path))?;
+
+    let (old_text, new_text) = match name {
+        "Create" | "Write" => (None, str_field(&["content", "file_text"])),
+        "ApplyPatch" | "apply_patch" => (None, patch),
+        _ => (
+            str_field(&["old_str", "old_string"]),
+            str_field(&["new_str", "new_string"]),
+        ),
+    };
+
+    Some(ContentBlock::FileEdit {
+        id: id.to_string(),
+        tool_name: name.to_string(),
+        path,
+        old_text,
+        new_text,
+    })
+}
+
+/// Converts one entry of a message's `content` array into a [`ContentBlock`].
+pub(crate) fn parse_content_block(item: &Value) -> Option<ContentBlock> {
+    let content_type = item["type"].as_str().unwrap_or("text");
+    match content_type {
+        "text" => item["text"].as_str().map(|text| ContentBlock::Text {
+            text: text.to_string(),
+        }),
+        "thinking" => item["thinking"]
+            .as_str()
+            .map(|thinking| ContentBlock::Thinking {
+                thinking: thinking.to_string(),
+            }),
+        "tool_use" => {
+            let id = item["id"].as_str().unwrap_or("");
+            let name = item["name"].as_str().unwrap_or("");
+            let input = item.get("input").cloned().unwrap_or(Value::Null);
+            file_edit_block(id, name, &input).or_else(|| {
+                Some(ContentBlock::ToolUse {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    input,
+                })
+            })
+        }
+        "tool_result" => Some(ContentBlock::ToolResult {
+            tool_use_id: item["tool_use_id"].as_str().unwrap_or("").to_string(),
+            output: tool_result_output(&item["content"]),
+            is_error: item["is_error"].as_bool().unwrap_or(false),
+        }),
+        "image" => {
+            let source = &item["source"];
+            Some(ContentBlock::Image {
+                media_type: source["media_type"].as_str().unwrap_or("image").to_string(),
+                url: source["url"].as_str().map(|s| s.to_string()),
+            })
+        }
+        _ => None,
+    }
+}
+
 pub fn list_session_projects_for_home(home_dir: &Path) -> Result<Vec<SessionProject>, String> {
     let sessions_dir = sessions_dir_for_home(home_dir)?;
 
@@ -145,12 +474,7 @@ pub fn list_session_projects_for_home(home_dir: &Path) -> Result<Vec<SessionProj
             continue;
         }
 
-        let modified_at = fs::metadata(&path)
-            .ok()
-            .and_then(|m| m.modified().ok())
-            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-            .map(|d| d.as_millis() as f64)
-            .unwrap_or(0.0);
+        let modified_at = modified_at_ms(&path);
 
         projects.push(SessionProject {
             name,
@@ -226,33 +550,7 @@ pub fn list_sessions_for_home(
 
             let settings_path = project_dir.join(format!("{session_id}.settings.json"));
 
-            let (model, token_usage) = if settings_path.exists() {
-                match fs::read_to_string(&settings_path) {
-                    Ok(content) => {
-                        let json: Value = serde_json::from_str(&content).unwrap_or_default();
-                        let model = json["model"].as_str().unwrap_or("unknown").to_string();
-                        let tu = TokenUsage {
-                            input_tokens: json["tokenUsage"]["inputTokens"].as_f64().unwrap_or(0.0),
-                            output_tokens: json["tokenUsage"]["outputTokens"]
-                                .as_f64()
-                                .unwrap_or(0.0),
-                            cache_creation_tokens: json["tokenUsage"]["cacheCreationTokens"]
-                                .as_f64()
-                                .unwrap_or(0.0),
-                            cache_read_tokens: json["tokenUsage"]["cacheReadTokens"]
-                                .as_f64()
-                                .unwrap_or(0.0),
-                            thinking_tokens: json["tokenUsage"]["thinkingTokens"]
-                                .as_f64()
-                                .unwrap_or(0.0),
-                        };
-                        (model, tu)
-                    }
-                    Err(_) => ("unknown".to_string(), TokenUsage::default()),
-                }
-            } else {
-                ("unknown".to_string(), TokenUsage::default())
-            };
+            let (model, token_usage) = read_session_settings(&settings_path);
 
             // Read first line of jsonl for session title
             let title = match fs::File::open(&path) {
@@ -272,12 +570,7 @@ pub fn list_sessions_for_home(
                 Err(_) => "Untitled".to_string(),
             };
 
-            let modified_at = fs::metadata(&path)
-                .ok()
-                .and_then(|m| m.modified().ok())
-                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                .map(|d| d.as_millis() as f64)
-                .unwrap_or(0.0);
+            let modified_at = modified_at_ms(&path);
 
             sessions.push(SessionSummary {
                 id: session_id.clone(),
@@ -287,6 +580,7 @@ pub fn list_sessions_for_home(
                 modified_at,
                 token_usage,
                 path: path.with_extension("").to_string_lossy().to_string(),
+                source: SessionSource::Droid,
             });
         }
     }
@@ -300,14 +594,112 @@ pub fn list_sessions_for_home(
     Ok(sessions)
 }
 
+/// Path (without extension) of the Droid session with `session_id`, if any.
+pub fn find_session_path_for_home(
+    home_dir: &Path,
+    session_id: &str,
+) -> Result<Option<String>, String> {
+    let sessions_dir = sessions_dir_for_home(home_dir)?;
+    let Ok(entries) = fs::read_dir(&sessions_dir) else {
+        return Ok(None);
+    };
+    let file_name = format!("{session_id}.jsonl");
+    Ok(entries
+        .flatten()
+        .map(|e| e.path().join(&file_name))
+        .find(|p| p.is_file())
+        .map(|p| p.with_extension("").to_string_lossy().to_string()))
+}
+
 pub fn list_sessions(project: Option<&str>) -> Result<Vec<SessionSummary>, String> {
     list_sessions_for_home(&system_home_dir()?, project)
 }
 
+fn sessions_or_skip(
+    source: SessionSource,
+    listed: Result<Vec<SessionSummary>, String>,
+) -> Vec<SessionSummary> {
+    listed.unwrap_or_else(|e| {
+        log::warn!("Sessions: skipping {} sessions: {e}", source.label());
+        Vec::new()
+    })
+}
+
+/// Lists Droid, Claude Code and Codex sessions as one timeline, newest first.
+/// A tool whose sessions directory cannot be read is skipped.
+///
+/// Claude Code and Codex summaries are built from the head and tail of each
+/// transcript. Claude Code only records per-message usage, so its summaries
+/// carry no token totals here; use [`list_all_sessions_with_usage_for_home`]
+/// when totals matter.
+pub fn list_all_sessions_for_home(home_dir: &Path) -> Result<Vec<SessionSummary>, String> {
+    let mut sessions =
+        sessions_or_skip(SessionSource::Droid, list_sessions_for_home(home_dir, None));
+    sessions.extend(sessions_or_skip(
+        SessionSource::ClaudeCode,
+        claude_sessions::list_sessions_for_home(home_dir),
+    ));
+    sessions.extend(sessions_or_skip(
+        SessionSource::Codex,
+        codex_sessions::list_sessions_for_home(home_dir),
+    ));
+
+    sessions.sort_by(|a, b| {
+        b.modified_at
+            .partial_cmp(&a.modified_at)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    Ok(sessions)
+}
+
+pub fn list_all_sessions() -> Result<Vec<SessionSummary>, String> {
+    list_all_sessions_for_home(&system_home_dir()?)
+}
+
+/// Like [`list_all_sessions_for_home`], but also sums the token usage of every
+/// Claude Code transcript, which requires reading each one in full.
+pub fn list_all_sessions_with_usage_for_home(
+    home_dir: &Path,
+) -> Result<Vec<SessionSummary>, String> {
+    let mut sessions = list_all_sessions_for_home(home_dir)?;
+    for session in sessions
+        .iter_mut()
+        .filter(|s| s.source == SessionSource::ClaudeCode)
+    {
+        let path = PathBuf::from(format!("{}.jsonl", session.path));
+        if let Ok(usage) = claude_sessions::transcript_token_usage(&path) {
+            session.token_usage = usage;
+        }
+    }
+    Ok(sessions)
+}
+
+/// Works out which tool wrote the session at `session_path` from where it lives.
+pub fn session_source_for_path(home_dir: &Path, session_path: &str) -> SessionSource {
+    let path = Path::new(session_path);
+    if claude_sessions::projects_dir_for_home(home_dir).is_ok_and(|dir| path.starts_with(dir)) {
+        SessionSource::ClaudeCode
+    } else if codex_sessions::sessions_dir_for_home(home_dir).is_ok_and(|dir| path.starts_with(dir))
+    {
+        SessionSource::Codex
+    } else {
+        SessionSource::Droid
+    }
+}
+
 pub fn get_session_detail_for_home(
-    _home_dir: &Path,
+    home_dir: &Path,
     session_path: &str,
 ) -> Result<SessionDetail, String> {
+    match session_source_for_path(home_dir, session_path) {
+        SessionSource::Droid => get_droid_session_detail(session_path),
+        SessionSource::ClaudeCode => claude_sessions::get_session_detail(session_path),
+        SessionSource::Codex => codex_sessions::get_session_detail(session_path),
+    }
+}
+
+fn get_droid_session_detail(session_path: &str) -> Result<SessionDetail, String> {
     let jsonl_path = PathBuf::from(format!("{session_path}.jsonl"));
     let settings_path = PathBuf::from(format!("{session_path}.settings.json"));
 
@@ -322,36 +714,9 @@ pub fn get_session_detail_for_home(
         .unwrap_or("")
         .to_string();
 
-    let (model, token_usage) = if settings_path.exists() {
-        match fs::read_to_string(&settings_path) {
-            Ok(content) => {
-                let json: Value = serde_json::from_str(&content).unwrap_or_default();
-                let model = json["model"].as_str().unwrap_or("unknown").to_string();
-                let tu = TokenUsage {
-                    input_tokens: json["tokenUsage"]["inputTokens"].as_f64().unwrap_or(0.0),
-                    output_tokens: json["tokenUsage"]["outputTokens"].as_f64().unwrap_or(0.0),
-                    cache_creation_tokens: json["tokenUsage"]["cacheCreationTokens"]
-                        .as_f64()
-                        .unwrap_or(0.0),
-                    cache_read_tokens: json["tokenUsage"]["cacheReadTokens"]
-                        .as_f64()
-                        .unwrap_or(0.0),
-                    thinking_tokens: json["tokenUsage"]["thinkingTokens"].as_f64().unwrap_or(0.0),
-                };
-                (model, tu)
-            }
-            Err(_) => ("unknown".to_string(), TokenUsage::default()),
-        }
-    } else {
-        ("unknown".to_string(), TokenUsage::default())
-    };
+    let (model, token_usage) = read_session_settings(&settings_path);
 
-    let modified_at = fs::metadata(&jsonl_path)
-        .ok()
-        .and_then(|m| m.modified().ok())
-        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-        .map(|d| d.as_millis() as f64)
-        .unwrap_or(0.0);
+    let modified_at = modified_at_ms(&jsonl_path);
 
     let file =
         fs::File::open(&jsonl_path).map_err(|e| format!("Failed to open session file: {e}"))?;
@@ -390,27 +755,14 @@ pub fn get_session_detail_for_home(
                 let timestamp = json["timestamp"].as_str().unwrap_or("").to_string();
                 let role = json["message"]["role"].as_str().unwrap_or("").to_string();
 
-                let content_arr = json["message"]["content"].as_array();
-                let mut content_blocks: Vec<ContentBlock> = Vec::new();
-
-                if let Some(arr) = content_arr {
-                    for item in arr {
-                        let content_type = item["type"].as_str().unwrap_or("text").to_string();
-                        let text = item["text"].as_str().map(|s| s.to_string());
-                        let thinking = item["thinking"].as_str().map(|s| s.to_string());
-
-                        // Skip tool_use and tool_result for cleaner display
-                        if content_type == "tool_use" || content_type == "tool_result" {
-                            continue;
-                        }
-
-                        content_blocks.push(ContentBlock {
-                            content_type,
-                            text,
-                            thinking,
-                        });
-                    }
-                }
+                let content_blocks: Vec<ContentBlock> = json["message"]["content"]
+                    .as_array()
+                    .map(|arr| arr.iter().filter_map(parse_content_block).collect())
+                    .unwrap_or_default();
+                let token_usage = json["message"]
+                    .get("usage")
+                    .or_else(|| json.get("usage"))
+                    .and_then(token_usage_from_json);
 
                 if !content_blocks.is_empty() {
                     messages.push(SessionMessage {
@@ -418,6 +770,7 @@ pub fn get_session_detail_for_home(
                         role,
                         content: content_blocks,
                         timestamp,
+                        token_usage,
                     });
                 }
             }
@@ -434,12 +787,12 @@ pub fn get_session_detail_for_home(
         modified_at,
         token_usage,
         messages,
+        source: SessionSource::Droid,
     })
 }
 
 pub fn get_session_detail(session_path: &str) -> Result<SessionDetail, String> {
-    let _home_dir = system_home_dir()?;
-    get_session_detail_for_home(&_home_dir, session_path)
+    get_session_detail_for_home(&system_home_dir()?, session_path)
 }
 
 pub fn delete_session(session_path: &str) -> Result<(), String> {
@@ -457,3 +810,169 @@ pub fn delete_session(session_path: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        get_session_detail_for_home, list_all_sessions_for_home, read_jsonl_ends, ContentBlock,
+        SessionSource,
+    };
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn write_session(dir: &Path, lines: &[&str]) -> String {
+        let project = dir.join(".factory/sessions/-tmp-demo");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join("s1.jsonl"), lines.join("\n")).unwrap();
+        project.join("s1").to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn detail_keeps_tool_calls_results_and_file_edits() {
+        let temp = TempDir::new().unwrap();
+        let path = write_session(
+            temp.path(),
+            &[
+                r#"{"type":"session_start","id":"s1","title":"Demo","cwd":"/tmp/demo"}"#,
+                r#"{"type":"message","id":"m1","timestamp":"t1","message":{"role":"assistant","usage":{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":3},"content":[{"type":"text","text":"Looking"},{"type":"tool_use","id":"tu1","name":"Read","input":{"file_path":"a.rs"}},{"type":"tool_use","id":"tu2","name":"Edit","input":{"file_path":"a.rs","old_str":"x","new_str":"y"}}]}}"#,
+                r#"{"type":"message","id":"m2","timestamp":"t2","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"tu1","content":[{"type":"text","text":"fn main() {}"}]},{"type":"tool_result","tool_use_id":"tu2","content":"denied","is_error":true},{"type":"image","source":{"type":"base64","media_type":"image/png","data":"AAAA"}}]}}"#,
+            ],
+        );
+
+        let detail = get_session_detail_for_home(temp.path(), &path).unwrap();
+        assert_eq!(detail.messages.len(), 2);
+
+        let first = &detail.messages[0];
+        let usage = first.token_usage.as_ref().unwrap();
+        assert_eq!(usage.input_tokens, 10.0);
+        assert_eq!(usage.cache_read_tokens, 3.0);
+        assert_eq!(
+            first.content[1],
+            ContentBlock::ToolUse {
+                id: "tu1".to_string(),
+                name: "Read".to_string(),
+                input: serde_json::json!({"file_path": "a.rs"}),
+            }
+        );
+        assert_eq!(
+            first.content[2],
+            ContentBlock::FileEdit {
+                id: "tu2".to_string(),
+                tool_name: "Edit".to_string(),
+                path: "a.rs".to_string(),
+                old_text: Some("x".to_string()),
+                new_text: Some("y".to_string()),
+            }
+        );
+
+        let second = &detail.messages[1];
+        assert!(second.token_usage.is_none());
+        assert_eq!(
+            second.content[0],
+            ContentBlock::ToolResult {
+                tool_use_id: "tu1".to_string(),
+                output: "fn main() {}".to_string(),
+                is_error: false,
+            }
+        );
+        assert!(matches!(
+            &second.content[1],
+            ContentBlock::ToolResult { is_error: true, output, .. } if output == "denied"
+        ));
+        assert!(matches!(
+            &second.content[2],
+            ContentBlock::Image { media_type, url: None } if media_type == "image/png"
+        ));
+    }
+
+    #[test]
+    fn content_blocks_serialize_with_type_tag() {
+        let text = serde_json::to_value(ContentBlock::Text {
+            text: "hi".to_string(),
+        })
+        .unwrap();
+        assert_eq!(text, serde_json::json!({"type": "text", "text": "hi"}));
+
+        let result = serde_json::to_value(ContentBlock::ToolResult {
+            tool_use_id: "tu1".to_string(),
+            output: "ok".to_string(),
+            is_error: false,
+        })
+        .unwrap();
+        assert_eq!(
+            result,
+            serde_json::json!({"type": "tool_result", "toolUseId": "tu1", "output": "ok", "isError": false})
+        );
+    }
+
+    #[test]
+    fn all_sessions_merge_sources_and_dispatch_detail() {
+        let temp = TempDir::new().unwrap();
+        write_session(
+            temp.path(),
+            &[
+                r#"{"type":"message","id":"m1","timestamp":"t","message":{"role":"user","content":[{"type":"text","text":"hi droid"}]}}"#,
+            ],
+        );
+        let claude = temp.path().join(".claude/projects/-tmp-demo");
+        std::fs::create_dir_all(&claude).unwrap();
+        std::fs::write(
+            claude.join("c1.jsonl"),
+            r#"{"type":"user","uuid":"u1","cwd":"/tmp/demo","message":{"role":"user","content":"hi claude"}}"#,
+        )
+        .unwrap();
+
+        let sessions = list_all_sessions_for_home(temp.path()).unwrap();
+        let mut sources: Vec<SessionSource> = sessions.iter().map(|s| s.source).collect();
+        sources.sort_by_key(|s| s.label());
+        assert_eq!(
+            sources,
+            vec![SessionSource::ClaudeCode, SessionSource::Droid]
+        );
+
+        let claude_summary = sessions
+            .iter()
+            .find(|s| s.source == SessionSource::ClaudeCode)
+            .unwrap();
+        let detail = get_session_detail_for_home(temp.path(), &claude_summary.path).unwrap();
+        assert_eq!(detail.source, SessionSource::ClaudeCode);
+        assert_eq!(detail.title, "hi claude");
+    }
+
+    #[test]
+    fn unreadable_droid_sessions_are_skipped() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join(".factory")).unwrap();
+        std::fs::write(temp.path().join(".factory/sessions"), "not a dir").unwrap();
+        let claude = temp.path().join(".claude/projects/-tmp-demo");
+        std::fs::create_dir_all(&claude).unwrap();
+        std::fs::write(
+            claude.join("c1.jsonl"),
+            r#"{"type":"user","uuid":"u1","cwd":"/tmp/demo","message":{"role":"user","content":"hi claude"}}"#,
+        )
+        .unwrap();
+
+        let sessions = list_all_sessions_for_home(temp.path()).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].source, SessionSource::ClaudeCode);
+    }
+
+    #[test]
+    fn jsonl_ends_skip_partial_lines_and_never_overlap() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("t.jsonl");
+        let lines: Vec<String> = (0..100).map(|i| format!(r#"{{"n":{i}}}"#)).collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let ends = read_jsonl_ends(&path, 3, 40).unwrap();
+        let head: Vec<i64> = ends.head.iter().map(|v| v["n"].as_i64().unwrap()).collect();
+        assert_eq!(head, vec![0, 1, 2]);
+        let tail: Vec<i64> = ends.tail.iter().map(|v| v["n"].as_i64().unwrap()).collect();
+        assert_eq!(tail.last(), Some(&99));
+        assert!(tail.len() < 10 && tail[0] > 2);
+
+        let small = read_jsonl_ends(&path, 200, 1 << 20).unwrap();
+        assert_eq!(small.head.len(), 100);
+        assert!(small.tail.is_empty());
+    }
+}
@@ -0,0 +1,272 @@
+//! Spec templates (core).
+//!
+//! A `default` template ships with DroidGear; more can be added as `*.md`
+//! files under `~/.droidgear/spec-templates/`, and a user file named
+//! `default.md` replaces the bundled one. Templates may reference `${date}`,
+//! `${project}`, `${author}` and `${title}`; other `${...}` text is kept as is.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::specs::{self, SpecFile};
+
+const BUNDLED_TEMPLATE: &str = include_str!("../res/spec-template.md");
+const DEFAULT_TEMPLATE: &str = "default";
+
+/// A spec template
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecTemplate {
+    /// File stem, e.g. `default`
+    pub name: String,
+    /// False for the template bundled with DroidGear
+    pub user: bool,
+    pub content: String,
+}
+
+/// Values substituted into a template. Blank values fall back to defaults:
+/// today's date, the `USER` environment variable and the spec name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpecTemplateVars {
+    pub date: Option<String>,
+    pub project: Option<String>,
+    pub author: Option<String>,
+    pub title: Option<String>,
+}
+
+fn system_home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+pub fn spec_templates_dir_for_home(home_dir: &Path) -> PathBuf {
+    paths::droidgear_dir_from_home(home_dir).join("spec-templates")
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// Lowercase `name` and join its words with `-`, for use in file names.
+fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn substitute(text: &str, values: &[(&str, String)], quote: bool) -> String {
+    let mut out = text.to_string();
+    for (key, value) in values {
+        let value = if quote && !value.is_empty() {
+            specs::yaml_scalar(value)
+        } else {
+            value.clone()
+        };
+        out = out.replace(&format!("${{{key}}}"), &value);
+    }
+    out
+}
+
+/// Substitutes `vars` into `content`. Values in the front matter are quoted
+/// as needed so they stay valid YAML.
+pub fn render_spec_template(content: &str, vars: &SpecTemplateVars) -> String {
+    let values = [
+        ("date", non_empty(&vars.date).unwrap_or_default()),
+        ("project", non_empty(&vars.project).unwrap_or_default()),
+        ("author", non_empty(&vars.author).unwrap_or_default()),
+        ("title", non_empty(&vars.title).unwrap_or_default()),
+    ];
+    match specs::split_front_matter(content) {
+        Some((yaml, body)) => {
+            let fence = &content[..content.find('\n').unwrap_or(0) + 1];
+            format!(
+                "{fence}{}---\n{}",
+                substitute(yaml, &values, true),
+                substitute(body, &values, false)
+            )
+        }
+        None => substitute(content, &values, false),
+    }
+}
+
+pub fn list_spec_templates_for_home(home_dir: &Path) -> Result<Vec<SpecTemplate>, String> {
+    let mut templates = vec![SpecTemplate {
+        name: DEFAULT_TEMPLATE.to_string(),
+        user: false,
+        content: BUNDLED_TEMPLATE.to_string(),
+    }];
+    let Ok(entries) = fs::read_dir(spec_templates_dir_for_home(home_dir)) else {
+        return Ok(templates);
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string();
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let template = SpecTemplate {
+            name,
+            user: true,
+            content,
+        };
+        match templates.iter_mut().find(|t| t.name == template.name) {
+            Some(existing) => *existing = template,
+            None => templates.push(template),
+        }
+    }
+    Ok(templates)
+}
+
+pub fn list_spec_templates() -> Result<Vec<SpecTemplate>, String> {
+    list_spec_templates_for_home(&system_home_dir()?)
+}
+
+/// Creates `YYYY-MM-DD-<name>.md` in the Factory specs dir from `template`.
+/// `name` is slugified for the file name and used as the default title.
+pub fn create_spec_from_template_for_home(
+    home_dir: &Path,
+    template: &str,
+    name: &str,
+    vars: &SpecTemplateVars,
+) -> Result<SpecFile, String> {
+    let slug = slugify(name);
+    if slug.is_empty() {
+        return Err("Spec name cannot be empty".to_string());
+    }
+    let template = list_spec_templates_for_home(home_dir)?
+        .into_iter()
+        .find(|t| t.name == template)
+        .ok_or_else(|| format!("Spec template '{template}' not found"))?;
+
+    // The date becomes part of the file name, so only a real date is accepted.
+    let date = match non_empty(&vars.date) {
+        Some(date) => chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date '{date}', expected YYYY-MM-DD"))?,
+        None => chrono::Local::now().date_naive(),
+    }
+    .format("%Y-%m-%d")
+    .to_string();
+    let vars = SpecTemplateVars {
+        author: non_empty(&vars.author).or_else(|| {
+            std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .ok()
+        }),
+        title: non_empty(&vars.title).or_else(|| Some(name.trim().to_string())),
+        project: vars.project.clone(),
+        date: Some(date.clone()),
+    };
+
+    let specs_dir = specs::specs_dir_for_home(home_dir)?;
+    fs::create_dir_all(&specs_dir).map_err(|e| format!("Failed to create specs directory: {e}"))?;
+    let path = specs_dir.join(format!("{date}-{slug}.md"));
+    if path.exists() {
+        return Err("A file with this name already exists".to_string());
+    }
+    specs::write_spec_for_home(
+        home_dir,
+        &path,
+        &render_spec_template(&template.content, &vars),
+        &format!("create from template {}", template.name),
+    )
+}
+
+pub fn create_spec_from_template(
+    template: &str,
+    name: &str,
+    vars: &SpecTemplateVars,
+) -> Result<SpecFile, String> {
+    create_spec_from_template_for_home(&system_home_dir()?, template, name, vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::SpecStatus;
+    use tempfile::TempDir;
+
+    #[test]
+    fn creates_dated_spec_from_bundled_template() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let vars = SpecTemplateVars {
+            date: Some("2025-03-04".to_string()),
+            project: Some("/repo".to_string()),
+            author: Some("dana".to_string()),
+            title: Some("Login: SSO".to_string()),
+        };
+
+        let spec =
+            create_spec_from_template_for_home(home, "default", "Login SSO!", &vars).unwrap();
+
+        assert_eq!(spec.name, "2025-03-04-login-sso.md");
+        assert_eq!(spec.meta.title.as_deref(), Some("Login: SSO"));
+        assert_eq!(spec.meta.status, Some(SpecStatus::Draft));
+        assert_eq!(spec.meta.project.as_deref(), Some("/repo"));
+        assert!(spec.content.contains("author: dana\n"));
+        assert!(spec.content.contains("# Login: SSO\n"));
+        assert!(spec.content.contains("## Non-goals"));
+        assert!(create_spec_from_template_for_home(home, "default", "login sso", &vars).is_err());
+    }
+
+    #[test]
+    fn rejects_dates_that_are_not_calendar_dates() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        for date in ["../../.bashrc", "2025-02-30", "2025/01/02", "tomorrow"] {
+            let vars = SpecTemplateVars {
+                date: Some(date.to_string()),
+                ..Default::default()
+            };
+            let err = create_spec_from_template_for_home(home, "default", "x", &vars).unwrap_err();
+            assert!(err.contains("Invalid date"), "{date}: {err}");
+        }
+        assert!(!specs::specs_dir_for_home(home).unwrap().exists());
+    }
+
+    #[test]
+    fn user_templates_extend_and_override_bundled_ones() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let dir = spec_templates_dir_for_home(home);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("default.md"), "# ${title}\n").unwrap();
+        fs::write(
+            dir.join("rfc.md"),
+            "RFC by ${author} on ${date}, see ${other}\n",
+        )
+        .unwrap();
+
+        let templates = list_spec_templates_for_home(home).unwrap();
+        let names: Vec<_> = templates
+            .iter()
+            .map(|t| (t.name.as_str(), t.user))
+            .collect();
+        assert_eq!(names, [("default", true), ("rfc", true)]);
+
+        let vars = SpecTemplateVars {
+            date: Some("2025-01-02".to_string()),
+            author: Some("kim".to_string()),
+            ..Default::default()
+        };
+        let spec = create_spec_from_template_for_home(home, "rfc", "cache", &vars).unwrap();
+        assert_eq!(spec.content, "RFC by kim on 2025-01-02, see ${other}\n");
+        assert!(create_spec_from_template_for_home(home, "missing", "x", &vars).is_err());
+    }
+}
@@ -1,13 +1,65 @@
 //! Specs management (core).
 //!
-//! Handles reading spec files from Factory specs directory.
+//! Handles reading spec files from Factory specs directory. Specs may start
+//! with a YAML front-matter block (`---` ... `---`) carrying a title, status,
+//! tags, the originating session and project.
 
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::fs;
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 
-use crate::paths;
+use crate::{paths, sessions, specs_history};
+
+/// Spec lifecycle status
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum SpecStatus {
+    Draft,
+    Approved,
+    Implemented,
+}
+
+impl SpecStatus {
+    pub const ALL: [SpecStatus; 3] = [
+        SpecStatus::Draft,
+        SpecStatus::Approved,
+        SpecStatus::Implemented,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SpecStatus::Draft => "draft",
+            SpecStatus::Approved => "approved",
+            SpecStatus::Implemented => "implemented",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|s| s.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+/// Metadata from a spec's YAML front matter
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<SpecStatus>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Droid session that produced the spec
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Working directory the spec belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+}
 
 /// Spec file metadata
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -21,14 +73,354 @@ pub struct SpecFile {
     pub content: String,
     /// Last modified timestamp in milliseconds
     pub modified_at: f64,
+    /// Parsed front matter
+    #[serde(default)]
+    pub meta: SpecMeta,
+}
+
+/// A spec without its content, for listing
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecSummary {
+    pub name: String,
+    pub path: String,
+    pub modified_at: f64,
+    pub meta: SpecMeta,
+}
+
+impl SpecSummary {
+    /// Front-matter title, falling back to the file name
+    pub fn display_title(&self) -> &str {
+        self.meta.title.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// Field to order specs by
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SpecSortKey {
+    #[default]
+    Modified,
+    Name,
+    Title,
+    Status,
+}
+
+impl SpecSortKey {
+    pub const ALL: [SpecSortKey; 4] = [
+        SpecSortKey::Modified,
+        SpecSortKey::Name,
+        SpecSortKey::Title,
+        SpecSortKey::Status,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SpecSortKey::Modified => "modified",
+            SpecSortKey::Name => "name",
+            SpecSortKey::Title => "title",
+            SpecSortKey::Status => "status",
+        }
+    }
+}
+
+/// Filters and ordering for [`list_spec_summaries_for_home`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<SpecStatus>,
+    /// Case-insensitive tag match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Case-insensitive substring of the name or title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub sort: SpecSortKey,
+    /// Reverses the natural order (newest first for `modified`, A-Z otherwise)
+    #[serde(default)]
+    pub reverse: bool,
+}
+
+impl SpecQuery {
+    fn matches(&self, spec: &SpecSummary) -> bool {
+        if self.status.is_some() && spec.meta.status != self.status {
+            return false;
+        }
+        if let Some(tag) = self.tag.as_deref().filter(|t| !t.is_empty()) {
+            if !spec.meta.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                return false;
+            }
+        }
+        if let Some(project) = self.project.as_deref().filter(|p| !p.is_empty()) {
+            if spec.meta.project.as_deref() != Some(project) {
+                return false;
+            }
+        }
+        if let Some(text) = self.text.as_deref().filter(|t| !t.is_empty()) {
+            let text = text.to_lowercase();
+            if !spec.name.to_lowercase().contains(&text)
+                && !spec.display_title().to_lowercase().contains(&text)
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Upper bound on front-matter lines read while listing
+const MAX_FRONT_MATTER_LINES: usize = 200;
+
+fn is_fence(line: &str) -> bool {
+    line.trim_end() == "---"
+}
+
+/// Splits `content` into its front-matter YAML and body. Content without a
+/// closed `---` block has no front matter.
+pub(crate) fn split_front_matter(content: &str) -> Option<(&str, &str)> {
+    let first_end = content.find('\n')?;
+    if !is_fence(&content[..first_end]) {
+        return None;
+    }
+    let yaml_start = first_end + 1;
+    let mut offset = yaml_start;
+    for line in content[yaml_start..].split_inclusive('\n') {
+        if is_fence(line.trim_end_matches(['\r', '\n'])) {
+            return Some((
+                &content[yaml_start..offset],
+                &content[offset + line.len()..],
+            ));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn yaml_string(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Parses front-matter YAML leniently: unknown keys are ignored and `tags`
+/// may be a list or a comma-separated string.
+fn parse_meta(yaml: &str) -> SpecMeta {
+    let Ok(serde_yaml::Value::Mapping(map)) = serde_yaml::from_str::<serde_yaml::Value>(yaml)
+    else {
+        return SpecMeta::default();
+    };
+    let get = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| map.get(serde_yaml::Value::from(*k)))
+            .cloned()
+    };
+    let tags = match get(&["tags"]) {
+        Some(serde_yaml::Value::Sequence(items)) => items.iter().filter_map(yaml_string).collect(),
+        Some(serde_yaml::Value::String(s)) => s
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    };
+    SpecMeta {
+        title: get(&["title"]).as_ref().and_then(yaml_string),
+        status: get(&["status"])
+            .as_ref()
+            .and_then(yaml_string)
+            .and_then(|s| SpecStatus::parse(&s)),
+        tags,
+        session_id: get(&["session_id", "sessionId"])
+            .as_ref()
+            .and_then(yaml_string),
+        project: get(&["project"]).as_ref().and_then(yaml_string),
+    }
+}
+
+pub fn parse_spec_meta(content: &str) -> SpecMeta {
+    split_front_matter(content)
+        .map(|(yaml, _)| parse_meta(yaml))
+        .unwrap_or_default()
+}
+
+/// Reads only the front matter of a spec, stopping at the closing fence.
+fn read_meta_from_file(path: &Path) -> SpecMeta {
+    let Ok(file) = fs::File::open(path) else {
+        return SpecMeta::default();
+    };
+    let mut lines = BufReader::new(file).lines();
+    match lines.next() {
+        Some(Ok(first)) if is_fence(&first) => {}
+        _ => return SpecMeta::default(),
+    }
+    let mut yaml = String::new();
+    for line in lines.take(MAX_FRONT_MATTER_LINES) {
+        let Ok(line) = line else {
+            break;
+        };
+        if is_fence(&line) {
+            return parse_meta(&yaml);
+        }
+        yaml.push_str(&line);
+        yaml.push('\n');
+    }
+    SpecMeta::default()
 }
 
-fn specs_dir_for_home(home_dir: &Path) -> Result<PathBuf, String> {
+/// Quotes `value` when it would not survive as a plain YAML scalar.
+pub(crate) fn yaml_scalar(value: &str) -> String {
+    let plain = !value.is_empty()
+        && !value.starts_with([
+            ' ', '-', '?', ':', '[', '{', '!', '&', '*', '#', '|', '>', '@', '`', '"', '\'',
+        ])
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.ends_with(' ');
+    if plain {
+        value.to_string()
+    } else {
+        serde_json::to_string(value).unwrap_or_default()
+    }
+}
+
+/// Sets `key: value` in the front matter, adding a block if there is none.
+/// The body is left byte-for-byte intact.
+fn set_front_matter_field(content: &str, key: &str, value: &str) -> String {
+    let new_line = format!("{key}: {value}");
+    let Some((yaml, body)) = split_front_matter(content) else {
+        return format!("---\n{new_line}\n---\n{content}");
+    };
+    let prefix = format!("{key}:");
+    let mut replaced = false;
+    let mut lines: Vec<String> = yaml
+        .lines()
+        .map(|line| {
+            if !replaced && line.starts_with(&prefix) {
+                replaced = true;
+                new_line.clone()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(new_line);
+    }
+    let mut out = String::from("---\n");
+    for line in lines {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out.push_str("---\n");
+    out.push_str(body);
+    out
+}
+
+pub(crate) fn specs_dir_for_home(home_dir: &Path) -> Result<PathBuf, String> {
     let config_paths = paths::load_config_paths_for_home(home_dir);
     let factory_dir = paths::get_factory_home_for_home(home_dir, &config_paths)?;
     Ok(factory_dir.join("specs"))
 }
 
+/// The Droid session a spec was written from
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecSessionLink {
+    /// Spec file name
+    pub spec_name: String,
+    pub session_id: String,
+    /// Session path without extension, as used by `sessions`
+    pub session_path: String,
+    /// Working directory of the session
+    pub project: String,
+    /// Timestamp of the first write, used to pick the originating session
+    #[serde(skip)]
+    written_at: String,
+}
+
+/// Spec file name if `path` (possibly `~`-relative) points into the specs dir.
+fn spec_name_for_path(path: &str, home_dir: &Path, specs_dir: &Path) -> Option<String> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => home_dir.join(rest),
+        None => PathBuf::from(path),
+    };
+    let name = path.file_name()?.to_str()?;
+    (name.ends_with(".md") && path.parent()? == specs_dir).then(|| name.to_string())
+}
+
+/// Scans one session transcript for file writes into the specs dir.
+fn scan_session_for_specs(
+    jsonl_path: &Path,
+    home_dir: &Path,
+    specs_dir: &Path,
+    links: &mut std::collections::HashMap<String, SpecSessionLink>,
+) {
+    let Ok(file) = fs::File::open(jsonl_path) else {
+        return;
+    };
+    let mut session_id = jsonl_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_string();
+    let mut cwd = String::new();
+
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        // Cheap pre-filter: only start lines and lines mentioning specs matter.
+        let is_start = line.contains("\"session_start\"");
+        if !is_start && !line.contains("specs") {
+            continue;
+        }
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        if json["type"] == "session_start" {
+            if let Some(id) = json["id"].as_str().filter(|id| !id.is_empty()) {
+                session_id = id.to_string();
+            }
+            cwd = json["cwd"].as_str().unwrap_or("").to_string();
+            continue;
+        }
+        let timestamp = json["timestamp"].as_str().unwrap_or("").to_string();
+        for item in json["message"]["content"].as_array().into_iter().flatten() {
+            if item["type"] != "tool_use" {
+                continue;
+            }
+            let Some(sessions::ContentBlock::FileEdit { path, .. }) = sessions::file_edit_block(
+                item["id"].as_str().unwrap_or(""),
+                item["name"].as_str().unwrap_or(""),
+                &item["input"],
+            ) else {
+                continue;
+            };
+            let Some(spec_name) = spec_name_for_path(&path, home_dir, specs_dir) else {
+                continue;
+            };
+            let earlier = links
+                .get(&spec_name)
+                .is_none_or(|existing| timestamp < existing.written_at);
+            if earlier {
+                links.insert(
+                    spec_name.clone(),
+                    SpecSessionLink {
+                        spec_name,
+                        session_id: session_id.clone(),
+                        session_path: jsonl_path.with_extension("").to_string_lossy().to_string(),
+                        project: cwd.clone(),
+                        written_at: timestamp.clone(),
+                    },
+                );
+            }
+        }
+    }
+}
+
 fn system_home_dir() -> Result<PathBuf, String> {
     dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
 }
@@ -84,6 +476,7 @@ pub fn list_specs_for_home(home_dir: &Path) -> Result<Vec<SpecFile>, String> {
         specs.push(SpecFile {
             name,
             path: path.to_string_lossy().to_string(),
+            meta: parse_spec_meta(&content),
             content,
             modified_at,
         });
@@ -129,6 +522,7 @@ pub fn read_spec(path: &str) -> Result<SpecFile, String> {
     Ok(SpecFile {
         name,
         path: path.to_string(),
+        meta: parse_spec_meta(&content),
         content,
         modified_at,
     })
@@ -173,7 +567,17 @@ pub fn rename_spec_for_home(
 
     fs::rename(&old_path_buf, &new_path).map_err(|e| format!("Failed to rename file: {e}"))?;
 
-    read_spec(&new_path.to_string_lossy())
+    let spec = read_spec(&new_path.to_string_lossy())?;
+    let old_name = old_path_buf
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+    if old_name != new_name {
+        if let Err(e) = specs_history::record_rename(home_dir, old_name, &new_name, &spec.content) {
+            log::warn!("Specs: failed to record rename in history: {e}");
+        }
+    }
+    Ok(spec)
 }
 
 pub fn rename_spec(old_path: &str, new_name: &str) -> Result<SpecFile, String> {
@@ -214,10 +618,445 @@ pub fn update_spec_for_home(
         return Err("Spec file not found".to_string());
     }
 
-    fs::write(&path_buf, content).map_err(|e| format!("Failed to write file: {e}"))?;
-    read_spec(path)
+    write_spec_for_home(home_dir, &path_buf, content, "edit")
+}
+
+/// Writes a spec and records the change in its revision history. History
+/// failures are logged rather than failing the write.
+pub(crate) fn write_spec_for_home(
+    home_dir: &Path,
+    path: &Path,
+    content: &str,
+    reason: &str,
+) -> Result<SpecFile, String> {
+    let before = fs::read_to_string(path).ok();
+    fs::write(path, content).map_err(|e| format!("Failed to write file: {e}"))?;
+    let spec = read_spec(&path.to_string_lossy())?;
+    if let Err(e) =
+        specs_history::record_write(home_dir, &spec.name, before.as_deref(), content, reason)
+    {
+        log::warn!("Specs: failed to record revision of {}: {e}", spec.name);
+    }
+    Ok(spec)
 }
 
 pub fn update_spec(path: &str, content: &str) -> Result<SpecFile, String> {
     update_spec_for_home(&system_home_dir()?, path, content)
 }
+
+/// Lists specs matching `query` from their front matter alone; bodies are
+/// never read.
+pub fn list_spec_summaries_for_home(
+    home_dir: &Path,
+    query: &SpecQuery,
+) -> Result<Vec<SpecSummary>, String> {
+    let specs_dir = specs_dir_for_home(home_dir)?;
+    if !specs_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(&specs_dir).map_err(|e| format!("Failed to read specs directory: {e}"))?;
+
+    let mut specs = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("md") {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let modified_at = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as f64)
+            .unwrap_or(0.0);
+        let summary = SpecSummary {
+            name: entry.file_name().to_string_lossy().to_string(),
+            meta: read_meta_from_file(&path),
+            path: path.to_string_lossy().to_string(),
+            modified_at,
+        };
+        if query.matches(&summary) {
+            specs.push(summary);
+        }
+    }
+
+    specs.sort_by(|a, b| {
+        let ordering = match query.sort {
+            SpecSortKey::Modified => b
+                .modified_at
+                .partial_cmp(&a.modified_at)
+                .unwrap_or(std::cmp::Ordering::Equal),
+            SpecSortKey::Name => a.name.cmp(&b.name),
+            SpecSortKey::Title => a
+                .display_title()
+                .to_lowercase()
+                .cmp(&b.display_title().to_lowercase()),
+            // Specs without a status sort last.
+            SpecSortKey::Status => match (a.meta.status, b.meta.status) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            },
+        };
+        ordering.then_with(|| a.name.cmp(&b.name))
+    });
+    if query.reverse {
+        specs.reverse();
+    }
+    Ok(specs)
+}
+
+pub fn list_spec_summaries(query: &SpecQuery) -> Result<Vec<SpecSummary>, String> {
+    list_spec_summaries_for_home(&system_home_dir()?, query)
+}
+
+/// Updates the front-matter `status` without touching the body.
+pub fn set_spec_status_for_home(
+    home_dir: &Path,
+    path: &str,
+    status: SpecStatus,
+) -> Result<SpecFile, String> {
+    let spec = read_spec(path)?;
+    let content = set_front_matter_field(&spec.content, "status", status.as_str());
+    update_spec_for_home(home_dir, path, &content)
+}
+
+pub fn set_spec_status(path: &str, status: SpecStatus) -> Result<SpecFile, String> {
+    set_spec_status_for_home(&system_home_dir()?, path, status)
+}
+
+/// Finds, for each spec, the Droid session whose transcript first wrote it.
+pub fn correlate_spec_sessions_for_home(home_dir: &Path) -> Result<Vec<SpecSessionLink>, String> {
+    let specs_dir = specs_dir_for_home(home_dir)?;
+    let sessions_dir = sessions::sessions_dir_for_home(home_dir)?;
+    let mut links = std::collections::HashMap::new();
+    let Ok(projects) = fs::read_dir(&sessions_dir) else {
+        return Ok(Vec::new());
+    };
+    for project in projects.flatten() {
+        let Ok(entries) = fs::read_dir(project.path()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
+                scan_session_for_specs(&path, home_dir, &specs_dir, &mut links);
+            }
+        }
+    }
+    let mut links: Vec<SpecSessionLink> = links.into_values().collect();
+    links.sort_by(|a, b| a.spec_name.cmp(&b.spec_name));
+    Ok(links)
+}
+
+pub fn correlate_spec_sessions() -> Result<Vec<SpecSessionLink>, String> {
+    correlate_spec_sessions_for_home(&system_home_dir()?)
+}
+
+/// Working directory of the Droid session `session_id`, if it still exists.
+fn session_cwd_for_home(home_dir: &Path, session_id: &str) -> Option<String> {
+    let path = sessions::find_session_path_for_home(home_dir, session_id).ok()??;
+    let detail = sessions::get_session_detail_for_home(home_dir, &path).ok()?;
+    Some(detail.cwd).filter(|cwd| !cwd.is_empty())
+}
+
+/// Records `session_id` and `project` in the front matter of specs that lack
+/// them. A spec that already names a session only gets that session's
+/// project. Returns how many specs were updated.
+pub fn link_specs_to_sessions_for_home(home_dir: &Path) -> Result<usize, String> {
+    let specs_dir = specs_dir_for_home(home_dir)?;
+    let mut updated = 0;
+    for link in correlate_spec_sessions_for_home(home_dir)? {
+        let path = specs_dir.join(&link.spec_name);
+        if !path.is_file() {
+            continue;
+        }
+        let path = path.to_string_lossy().to_string();
+        let spec = read_spec(&path)?;
+        let mut content = spec.content.clone();
+        let project = match spec.meta.session_id.as_deref() {
+            None => {
+                content = set_front_matter_field(&content, "session_id", &link.session_id);
+                Some(link.project.clone())
+            }
+            Some(id) if id == link.session_id => Some(link.project.clone()),
+            Some(_) if spec.meta.project.is_some() => None,
+            Some(id) => session_cwd_for_home(home_dir, id),
+        };
+        if let Some(project) = project.filter(|p| !p.is_empty()) {
+            if spec.meta.project.is_none() {
+                content = set_front_matter_field(&content, "project", &yaml_scalar(&project));
+            }
+        }
+        if content != spec.content {
+            update_spec_for_home(home_dir, &path, &content)?;
+            updated += 1;
+        }
+    }
+    Ok(updated)
+}
+
+pub fn link_specs_to_sessions() -> Result<usize, String> {
+    link_specs_to_sessions_for_home(&system_home_dir()?)
+}
+
+/// Specs recorded against `project` (a session working directory).
+pub fn list_specs_for_project_for_home(
+    home_dir: &Path,
+    project: &str,
+) -> Result<Vec<SpecSummary>, String> {
+    let query = SpecQuery {
+        project: Some(project.to_string()),
+        ..Default::default()
+    };
+    list_spec_summaries_for_home(home_dir, &query)
+}
+
+pub fn list_specs_for_project(project: &str) -> Result<Vec<SpecSummary>, String> {
+    list_specs_for_project_for_home(&system_home_dir()?, project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_spec(home: &Path, name: &str, content: &str) -> PathBuf {
+        let dir = home.join(".factory").join("specs");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn parses_front_matter_leniently() {
+        let meta = parse_spec_meta(
+            "---\ntitle: Login flow\nstatus: Approved\ntags: [auth, ui]\nsession_id: abc\nproject: /repo\nextra: 1\n---\n# Body\n",
+        );
+        assert_eq!(meta.title.as_deref(), Some("Login flow"));
+        assert_eq!(meta.status, Some(SpecStatus::Approved));
+        assert_eq!(meta.tags, vec!["auth", "ui"]);
+        assert_eq!(meta.session_id.as_deref(), Some("abc"));
+        assert_eq!(meta.project.as_deref(), Some("/repo"));
+
+        assert_eq!(
+            parse_spec_meta("tags: a, b\n# no fence"),
+            SpecMeta::default()
+        );
+        assert_eq!(
+            parse_spec_meta("---\ntitle: unclosed\n"),
+            SpecMeta::default()
+        );
+        assert_eq!(
+            parse_spec_meta("---\ntags: a, b\n---\n").tags,
+            vec!["a", "b"]
+        );
+    }
+
+    #[test]
+    fn summaries_filter_and_sort_by_front_matter() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        write_spec(
+            home,
+            "a.md",
+            "---\ntitle: Zeta\nstatus: draft\ntags: [api]\n---\nA\n",
+        );
+        write_spec(
+            home,
+            "b.md",
+            "---\ntitle: Alpha\nstatus: implemented\n---\nB\n",
+        );
+        write_spec(home, "c.md", "No front matter\n");
+
+        let by_title = SpecQuery {
+            sort: SpecSortKey::Title,
+            ..Default::default()
+        };
+        let names: Vec<String> = list_spec_summaries_for_home(home, &by_title)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["b.md", "c.md", "a.md"]);
+
+        let by_status = SpecQuery {
+            sort: SpecSortKey::Status,
+            reverse: true,
+            ..Default::default()
+        };
+        let names: Vec<String> = list_spec_summaries_for_home(home, &by_status)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["c.md", "b.md", "a.md"]);
+
+        let filtered = SpecQuery {
+            status: Some(SpecStatus::Draft),
+            tag: Some("API".to_string()),
+            ..Default::default()
+        };
+        let specs = list_spec_summaries_for_home(home, &filtered).unwrap();
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].display_title(), "Zeta");
+    }
+
+    #[test]
+    fn set_status_keeps_body_and_other_fields() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let body = "# Plan\n\n---\n\nstatus: not front matter\n";
+        let path = write_spec(
+            home,
+            "s.md",
+            &format!("---\ntitle: T\nstatus: draft\n---\n{body}"),
+        );
+        let path = path.to_string_lossy().to_string();
+
+        let spec = set_spec_status_for_home(home, &path, SpecStatus::Approved).unwrap();
+        assert_eq!(
+            spec.content,
+            format!("---\ntitle: T\nstatus: approved\n---\n{body}")
+        );
+        assert_eq!(spec.meta.status, Some(SpecStatus::Approved));
+
+        let bare = write_spec(home, "bare.md", body)
+            .to_string_lossy()
+            .to_string();
+        let spec = set_spec_status_for_home(home, &bare, SpecStatus::Implemented).unwrap();
+        assert_eq!(
+            spec.content,
+            format!("---\nstatus: implemented\n---\n{body}")
+        );
+    }
+
+    fn write_session(home: &Path, project: &str, id: &str, lines: &[serde_json::Value]) {
+        let dir = home.join(".factory").join("sessions").join(project);
+        fs::create_dir_all(&dir).unwrap();
+        let body: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        fs::write(dir.join(format!("{id}.jsonl")), body.join("\n")).unwrap();
+    }
+
+    fn create_call(path: &str, timestamp: &str) -> serde_json::Value {
+        serde_json::json!({
+            "type": "message",
+            "timestamp": timestamp,
+            "message": {"role": "assistant", "content": [{
+                "type": "tool_use", "id": "t1", "name": "Create",
+                "input": {"file_path": path, "content": "# Spec"}
+            }]}
+        })
+    }
+
+    #[test]
+    fn links_specs_to_the_session_that_first_wrote_them() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let spec = write_spec(home, "2025-01-01-auth.md", "# Auth\n");
+        write_spec(
+            home,
+            "2025-01-02-other.md",
+            "---\nsession_id: keep\n---\n# Other\n",
+        );
+        let spec_path = spec.to_string_lossy().to_string();
+
+        write_session(
+            home,
+            "-repo-app",
+            "s-early",
+            &[
+                serde_json::json!({"type": "session_start", "id": "s-early", "cwd": "/repo/app"}),
+                create_call(&spec_path, "2025-01-01T10:00:00Z"),
+            ],
+        );
+        write_session(
+            home,
+            "-repo-keep",
+            "keep",
+            &[serde_json::json!({"type": "session_start", "id": "keep", "cwd": "/repo/keep"})],
+        );
+        write_session(
+            home,
+            "-repo-other",
+            "s-late",
+            &[
+                serde_json::json!({"type": "session_start", "id": "s-late", "cwd": "/repo/other"}),
+                create_call(
+                    "~/.factory/specs/2025-01-01-auth.md",
+                    "2025-01-03T10:00:00Z",
+                ),
+                create_call(
+                    "~/.factory/specs/2025-01-02-other.md",
+                    "2025-01-03T11:00:00Z",
+                ),
+                // A same-named file in a repo is not the user's spec.
+                create_call(
+                    "/repo/other/.factory/specs/2025-01-01-auth.md",
+                    "2024-12-01T00:00:00Z",
+                ),
+            ],
+        );
+
+        let links = correlate_spec_sessions_for_home(home).unwrap();
+        let names: Vec<&str> = links.iter().map(|l| l.spec_name.as_str()).collect();
+        assert_eq!(names, vec!["2025-01-01-auth.md", "2025-01-02-other.md"]);
+        assert_eq!(links[0].session_id, "s-early");
+        assert_eq!(links[0].project, "/repo/app");
+
+        assert_eq!(link_specs_to_sessions_for_home(home).unwrap(), 2);
+        let linked = read_spec(&spec_path).unwrap();
+        assert_eq!(linked.meta.session_id.as_deref(), Some("s-early"));
+        assert!(linked.content.ends_with("---\n# Auth\n"));
+        // An existing session id is kept, and the project comes from that
+        // session rather than from the later one that also wrote the spec.
+        let other = read_spec(
+            &home
+                .join(".factory/specs/2025-01-02-other.md")
+                .to_string_lossy(),
+        )
+        .unwrap();
+        assert_eq!(other.meta.session_id.as_deref(), Some("keep"));
+        assert_eq!(other.meta.project.as_deref(), Some("/repo/keep"));
+        assert_eq!(link_specs_to_sessions_for_home(home).unwrap(), 0);
+
+        // A recorded session that no longer exists leaves the spec alone.
+        let orphan = write_spec(
+            home,
+            "2025-01-04-orphan.md",
+            "---\nsession_id: gone\n---\n# Orphan\n",
+        );
+        write_session(
+            home,
+            "-repo-other",
+            "s-orphan",
+            &[
+                serde_json::json!({"type": "session_start", "id": "s-orphan", "cwd": "/repo/other"}),
+                create_call(&orphan.to_string_lossy(), "2025-01-04T10:00:00Z"),
+            ],
+        );
+        assert_eq!(link_specs_to_sessions_for_home(home).unwrap(), 0);
+        assert!(read_spec(&orphan.to_string_lossy())
+            .unwrap()
+            .meta
+            .project
+            .is_none());
+
+        let for_app = list_specs_for_project_for_home(home, "/repo/app").unwrap();
+        assert_eq!(for_app.len(), 1);
+        assert_eq!(
+            sessions::find_session_path_for_home(home, "s-early")
+                .unwrap()
+                .as_deref(),
+            Some(links[0].session_path.as_str())
+        );
+    }
+}
@@ -0,0 +1,357 @@
+//! Spec revision history (core).
+//!
+//! Every write through `specs` snapshots the spec under
+//! `~/.droidgear/specs-history/<spec name>/`, as `<id>.md` files plus an
+//! `index.json` describing them. Renames move the directory so the history
+//! follows the spec; deleting a spec keeps its history.
+
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::specs::{self, SpecFile};
+use crate::storage;
+
+const HISTORY_DIR: &str = "specs-history";
+const INDEX_FILE: &str = "index.json";
+
+/// One stored version of a spec
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecRevision {
+    /// Sequence number, starting at 1
+    pub id: u32,
+    /// Spec file name when the revision was taken
+    pub name: String,
+    /// Unix time in milliseconds
+    pub created_at: f64,
+    /// Why the revision was taken, e.g. `edit` or `restore 3`
+    pub reason: String,
+}
+
+fn system_home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+fn now_ms() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
+
+fn history_dir_for_home(home_dir: &Path, name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || name.contains('/') || name.contains('\\') || name.starts_with('.') {
+        return Err("Invalid spec name".to_string());
+    }
+    Ok(paths::droidgear_dir_from_home(home_dir)
+        .join(HISTORY_DIR)
+        .join(name))
+}
+
+fn snapshot_path(dir: &Path, id: u32) -> PathBuf {
+    dir.join(format!("{id:04}.md"))
+}
+
+fn load_index(dir: &Path) -> Result<Vec<SpecRevision>, String> {
+    let path = dir.join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read spec history: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid spec history: {e}"))
+}
+
+fn save_index(dir: &Path, revisions: &[SpecRevision]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(revisions)
+        .map_err(|e| format!("Failed to serialize spec history: {e}"))?;
+    storage::atomic_write(&dir.join(INDEX_FILE), content.as_bytes())
+}
+
+fn append_revision(
+    dir: &Path,
+    revisions: &mut Vec<SpecRevision>,
+    name: &str,
+    content: &str,
+    reason: &str,
+    created_at: f64,
+) -> Result<SpecRevision, String> {
+    let id = revisions.last().map(|r| r.id + 1).unwrap_or(1);
+    storage::atomic_write(&snapshot_path(dir, id), content.as_bytes())?;
+    let revision = SpecRevision {
+        id,
+        name: name.to_string(),
+        created_at,
+        reason: reason.to_string(),
+    };
+    revisions.push(revision.clone());
+    save_index(dir, revisions)?;
+    Ok(revision)
+}
+
+/// Appends `content` unless it matches the latest revision.
+fn record_if_changed(
+    dir: &Path,
+    revisions: &mut Vec<SpecRevision>,
+    name: &str,
+    content: &str,
+    reason: &str,
+) -> Result<Option<SpecRevision>, String> {
+    if let Some(last) = revisions.last() {
+        if fs::read_to_string(snapshot_path(dir, last.id))
+            .ok()
+            .as_deref()
+            == Some(content)
+        {
+            return Ok(None);
+        }
+    }
+    append_revision(dir, revisions, name, content, reason, now_ms()).map(Some)
+}
+
+/// Records a write of `after` over `before`. If `before` was never recorded
+/// (first write, or an edit made outside DroidGear) it is stored first.
+pub(crate) fn record_write(
+    home_dir: &Path,
+    name: &str,
+    before: Option<&str>,
+    after: &str,
+    reason: &str,
+) -> Result<(), String> {
+    let dir = history_dir_for_home(home_dir, name)?;
+    let mut revisions = load_index(&dir)?;
+    if let Some(before) = before {
+        let before_reason = if revisions.is_empty() {
+            "initial"
+        } else {
+            "external edit"
+        };
+        record_if_changed(&dir, &mut revisions, name, before, before_reason)?;
+    }
+    record_if_changed(&dir, &mut revisions, name, after, reason)?;
+    Ok(())
+}
+
+/// Moves the history of `old_name` to `new_name` and records the rename.
+/// Revisions already stored under `new_name` are kept and come first.
+pub(crate) fn record_rename(
+    home_dir: &Path,
+    old_name: &str,
+    new_name: &str,
+    content: &str,
+) -> Result<(), String> {
+    let old_dir = history_dir_for_home(home_dir, old_name)?;
+    let new_dir = history_dir_for_home(home_dir, new_name)?;
+    let old_revisions = load_index(&old_dir)?;
+    let mut revisions = load_index(&new_dir)?;
+    if revisions.is_empty() && old_dir.exists() {
+        if let Some(parent) = new_dir.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create history directory: {e}"))?;
+        }
+        if new_dir.exists() {
+            fs::remove_dir_all(&new_dir)
+                .map_err(|e| format!("Failed to replace history directory: {e}"))?;
+        }
+        fs::rename(&old_dir, &new_dir).map_err(|e| format!("Failed to move spec history: {e}"))?;
+        revisions = old_revisions;
+    } else {
+        for revision in &old_revisions {
+            let snapshot = fs::read_to_string(snapshot_path(&old_dir, revision.id))
+                .map_err(|e| format!("Failed to read spec revision: {e}"))?;
+            append_revision(
+                &new_dir,
+                &mut revisions,
+                &revision.name,
+                &snapshot,
+                &revision.reason,
+                revision.created_at,
+            )?;
+        }
+        if old_dir.exists() {
+            fs::remove_dir_all(&old_dir)
+                .map_err(|e| format!("Failed to remove old spec history: {e}"))?;
+        }
+    }
+    append_revision(
+        &new_dir,
+        &mut revisions,
+        new_name,
+        content,
+        &format!("rename from {old_name}"),
+        now_ms(),
+    )?;
+    Ok(())
+}
+
+/// Snapshots the spec at `path` if it changed since the last revision, e.g.
+/// after it was edited in an external editor. The first snapshot of a spec is
+/// always recorded as `initial`.
+pub fn record_spec_revision_for_home(
+    home_dir: &Path,
+    path: &str,
+    reason: &str,
+) -> Result<Option<SpecRevision>, String> {
+    if !Path::new(path).starts_with(specs::specs_dir_for_home(home_dir)?) {
+        return Err("Invalid file path".to_string());
+    }
+    let spec = specs::read_spec(path)?;
+    let dir = history_dir_for_home(home_dir, &spec.name)?;
+    let mut revisions = load_index(&dir)?;
+    let reason = if revisions.is_empty() {
+        "initial"
+    } else {
+        reason
+    };
+    record_if_changed(&dir, &mut revisions, &spec.name, &spec.content, reason)
+}
+
+pub fn record_spec_revision(path: &str, reason: &str) -> Result<Option<SpecRevision>, String> {
+    record_spec_revision_for_home(&system_home_dir()?, path, reason)
+}
+
+/// Revisions of the spec named `name`, newest first.
+pub fn list_spec_revisions_for_home(
+    home_dir: &Path,
+    name: &str,
+) -> Result<Vec<SpecRevision>, String> {
+    let mut revisions = load_index(&history_dir_for_home(home_dir, name)?)?;
+    revisions.reverse();
+    Ok(revisions)
+}
+
+pub fn list_spec_revisions(name: &str) -> Result<Vec<SpecRevision>, String> {
+    list_spec_revisions_for_home(&system_home_dir()?, name)
+}
+
+pub fn read_spec_revision_for_home(home_dir: &Path, name: &str, id: u32) -> Result<String, String> {
+    let dir = history_dir_for_home(home_dir, name)?;
+    if !load_index(&dir)?.iter().any(|r| r.id == id) {
+        return Err(format!("Revision {id} not found"));
+    }
+    fs::read_to_string(snapshot_path(&dir, id))
+        .map_err(|e| format!("Failed to read spec revision: {e}"))
+}
+
+pub fn read_spec_revision(name: &str, id: u32) -> Result<String, String> {
+    read_spec_revision_for_home(&system_home_dir()?, name, id)
+}
+
+/// Unified diff from revision `from` to revision `to`, or to the current
+/// file when `to` is `None`.
+pub fn diff_spec_revisions_for_home(
+    home_dir: &Path,
+    name: &str,
+    from: u32,
+    to: Option<u32>,
+) -> Result<String, String> {
+    let before = read_spec_revision_for_home(home_dir, name, from)?;
+    let (after, to_label) = match to {
+        Some(id) => (
+            read_spec_revision_for_home(home_dir, name, id)?,
+            format!("{name}@{id}"),
+        ),
+        None => {
+            let path = specs::specs_dir_for_home(home_dir)?.join(name);
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            (content, format!("{name} (current)"))
+        }
+    };
+    Ok(TextDiff::from_lines(&before, &after)
+        .unified_diff()
+        .header(&format!("{name}@{from}"), &to_label)
+        .to_string())
+}
+
+pub fn diff_spec_revisions(name: &str, from: u32, to: Option<u32>) -> Result<String, String> {
+    diff_spec_revisions_for_home(&system_home_dir()?, name, from, to)
+}
+
+/// Writes revision `id` back to the spec, recreating it if it was deleted.
+/// The restore itself becomes a new revision.
+pub fn restore_spec_revision_for_home(
+    home_dir: &Path,
+    name: &str,
+    id: u32,
+) -> Result<SpecFile, String> {
+    let content = read_spec_revision_for_home(home_dir, name, id)?;
+    let path = specs::specs_dir_for_home(home_dir)?.join(name);
+    specs::write_spec_for_home(home_dir, &path, &content, &format!("restore {id}"))
+}
+
+pub fn restore_spec_revision(name: &str, id: u32) -> Result<SpecFile, String> {
+    restore_spec_revision_for_home(&system_home_dir()?, name, id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_spec(home: &Path, name: &str, content: &str) -> String {
+        let dir = home.join(".factory").join("specs");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn records_edits_and_restores_revisions() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let path = write_spec(home, "a.md", "one\n");
+
+        specs::update_spec_for_home(home, &path, "two\n").unwrap();
+        // Edited outside DroidGear, then saved again.
+        fs::write(&path, "three\n").unwrap();
+        specs::update_spec_for_home(home, &path, "four\n").unwrap();
+        specs::update_spec_for_home(home, &path, "four\n").unwrap();
+
+        let revisions = list_spec_revisions_for_home(home, "a.md").unwrap();
+        let reasons: Vec<_> = revisions.iter().map(|r| r.reason.as_str()).collect();
+        assert_eq!(reasons, ["edit", "external edit", "edit", "initial"]);
+        assert_eq!(revisions[0].id, 4);
+
+        let diff = diff_spec_revisions_for_home(home, "a.md", 1, Some(2)).unwrap();
+        assert!(diff.contains("--- a.md@1\n+++ a.md@2\n"));
+        assert!(diff.contains("-one\n+two\n"));
+
+        let restored = restore_spec_revision_for_home(home, "a.md", 1).unwrap();
+        assert_eq!(restored.content, "one\n");
+        let latest = &list_spec_revisions_for_home(home, "a.md").unwrap()[0];
+        assert_eq!((latest.id, latest.reason.as_str()), (5, "restore 1"));
+        assert_eq!(
+            diff_spec_revisions_for_home(home, "a.md", 1, None).unwrap(),
+            ""
+        );
+        assert!(read_spec_revision_for_home(home, "a.md", 9).is_err());
+    }
+
+    #[test]
+    fn history_follows_renames() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let path = write_spec(home, "old.md", "one\n");
+        specs::update_spec_for_home(home, &path, "two\n").unwrap();
+
+        specs::rename_spec_for_home(home, &path, "new").unwrap();
+
+        assert!(list_spec_revisions_for_home(home, "old.md")
+            .unwrap()
+            .is_empty());
+        let revisions = list_spec_revisions_for_home(home, "new.md").unwrap();
+        assert_eq!(revisions.len(), 3);
+        assert_eq!(revisions[0].reason, "rename from old.md");
+        assert_eq!(revisions[0].name, "new.md");
+        assert_eq!(revisions[2].name, "old.md");
+        assert_eq!(
+            read_spec_revision_for_home(home, "new.md", 1).unwrap(),
+            "one\n"
+        );
+    }
+}
@@ -91,6 +91,10 @@ base_url = "https://api.openai.com/v1"
         model_reasoning_effort: Some("medium".to_string()),
         api_key: Some("sk-profile-level".to_string()),
         auth_profile_name: None,
+        execution: Default::default(),
+        mcp_servers: Vec::new(),
+        features: Default::default(),
+        web_search: None,
     };
     let profile_json = serde_json::to_string_pretty(&profile).unwrap();
     write_file(
@@ -276,6 +280,10 @@ fn codex_apply_can_remove_openai_api_key_without_destroying_official_auth() {
         model_reasoning_effort: None,
         api_key: None,
         auth_profile_name: None,
+        execution: Default::default(),
+        mcp_servers: Vec::new(),
+        features: Default::default(),
+        web_search: None,
     };
     write_file(
         &home
@@ -323,6 +331,10 @@ fn codex_apply_openai_mode_deletes_auth_json_when_only_api_key_exists() {
         // Residual key must be ignored in openai mode.
         api_key: Some("sk-residual".to_string()),
         auth_profile_name: None,
+        execution: Default::default(),
+        mcp_servers: Vec::new(),
+        features: Default::default(),
+        web_search: None,
     };
     write_file(
         &home
@@ -367,6 +379,10 @@ fn codex_apply_openai_mode_preserves_auth_mode_session() {
         model_reasoning_effort: None,
         api_key: Some("sk-ignored".to_string()),
         auth_profile_name: None,
+        execution: Default::default(),
+        mcp_servers: Vec::new(),
+        features: Default::default(),
+        web_search: None,
     };
     write_file(
         &home
@@ -455,6 +471,10 @@ model = ""
         model_reasoning_effort: None,
         api_key: Some("sk-ignored".to_string()),
         auth_profile_name: Some("sub1".to_string()),
+        execution: Default::default(),
+        mcp_servers: Vec::new(),
+        features: Default::default(),
+        web_search: None,
     };
     write_file(
         &home
@@ -789,6 +809,10 @@ model = "existing-live-model"
         model_reasoning_effort: Some("medium".to_string()),
         api_key: Some("sk-profile".to_string()),
         auth_profile_name: None,
+        execution: Default::default(),
+        mcp_servers: Vec::new(),
+        features: Default::default(),
+        web_search: None,
     };
     write_file(
         &home
@@ -1,4 +1,6 @@
-use droidgear_core::mcp::{self, McpServer, McpServerConfig, McpServerType};
+use droidgear_core::mcp::{
+    self, McpProjectFile, McpScope, McpServer, McpServerConfig, McpServerType,
+};
 use std::collections::HashMap;
 use tempfile::TempDir;
 
@@ -18,8 +20,7 @@ fn mcp_save_load_and_toggle_roundtrip() {
             command: Some("npx".to_string()),
             args: Some(vec!["-y".to_string(), "exa-mcp-server".to_string()]),
             env: Some(env),
-            url: None,
-            headers: None,
+            ..Default::default()
         },
     };
 
@@ -48,3 +49,187 @@ fn mcp_save_load_and_toggle_roundtrip() {
     let loaded2 = loaded2.into_iter().find(|s| s.name == "test").unwrap();
     assert!(loaded2.config.disabled);
 }
+
+#[test]
+fn mcp_catalog_imports_factory_servers_and_keeps_factory_in_step() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let factory = home.join(".factory").join("mcp.json");
+    std::fs::create_dir_all(factory.parent().unwrap()).unwrap();
+    std::fs::write(
+        &factory,
+        r#"{"mcpServers":{"legacy":{"type":"http","url":"https://example.com/mcp"}}}"#,
+    )
+    .unwrap();
+
+    let servers = mcp::load_mcp_servers_for_home(home).unwrap();
+    assert_eq!(servers.len(), 1);
+    assert_eq!(servers[0].name, "legacy");
+    // Reading never writes.
+    assert!(!home.join(".droidgear").join("mcp-servers.json").exists());
+    assert!(!home.join(".droidgear").join("mcp-sync.json").exists());
+
+    // Imported servers are owned, so deleting one removes it from Factory too.
+    mcp::delete_mcp_server_for_home(home, "legacy").unwrap();
+    let contents = std::fs::read_to_string(&factory).unwrap();
+    assert!(!contents.contains("legacy"));
+}
+
+#[test]
+fn mcp_catalog_picks_up_servers_added_to_factory_later() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let stdio = |name: &str| McpServer {
+        name: name.to_string(),
+        config: McpServerConfig {
+            command: Some("npx".to_string()),
+            ..Default::default()
+        },
+    };
+    mcp::save_mcp_server_for_home(home, stdio("exa")).unwrap();
+
+    let factory = home.join(".factory").join("mcp.json");
+    let mut value: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&factory).unwrap()).unwrap();
+    value["mcpServers"]["manual"] = serde_json::json!({"type": "stdio", "command": "manual"});
+    std::fs::write(&factory, value.to_string()).unwrap();
+
+    let names: Vec<String> = mcp::load_mcp_servers_for_home(home)
+        .unwrap()
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(names, vec!["exa", "manual"]);
+
+    // The next write adopts it, so a later delete removes it from Factory.
+    mcp::save_mcp_server_for_home(home, stdio("git")).unwrap();
+    mcp::delete_mcp_server_for_home(home, "manual").unwrap();
+    let contents = std::fs::read_to_string(&factory).unwrap();
+    assert!(!contents.contains("manual"));
+    assert!(contents.contains("exa") && contents.contains("git"));
+}
+
+#[test]
+fn mcp_save_rejects_names_held_by_unmanaged_factory_entries() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let server = McpServer {
+        name: "clash".to_string(),
+        config: McpServerConfig {
+            command: Some("npx".to_string()),
+            ..Default::default()
+        },
+    };
+    mcp::save_mcp_server_for_home(home, server.clone()).unwrap();
+
+    // Simulate another tool re-adding the name after DroidGear lost track of it.
+    std::fs::write(home.join(".droidgear").join("mcp-sync.json"), "{}").unwrap();
+    std::fs::write(
+        home.join(".factory").join("mcp.json"),
+        r#"{"mcpServers":{"clash":{"type":"stdio","command":"theirs"}}}"#,
+    )
+    .unwrap();
+
+    let err = mcp::save_mcp_server_for_home(home, server).unwrap_err();
+    assert!(err.contains("does not manage"), "{err}");
+    let contents = std::fs::read_to_string(home.join(".factory").join("mcp.json")).unwrap();
+    assert!(contents.contains("theirs"));
+}
+
+#[test]
+fn mcp_corrupt_catalog_is_an_error_not_an_empty_catalog() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+    let server = McpServer {
+        name: "exa".to_string(),
+        config: McpServerConfig {
+            command: Some("npx".to_string()),
+            ..Default::default()
+        },
+    };
+    mcp::save_mcp_server_for_home(home, server.clone()).unwrap();
+    std::fs::write(home.join(".droidgear").join("mcp-servers.json"), "{ broken").unwrap();
+
+    assert!(mcp::load_mcp_servers_for_home(home).is_err());
+    let mut other = server;
+    other.name = "git".to_string();
+    assert!(mcp::save_mcp_server_for_home(home, other).is_err());
+    let contents = std::fs::read_to_string(home.join(".factory").join("mcp.json")).unwrap();
+    assert!(contents.contains("exa"));
+}
+
+#[test]
+fn mcp_project_scope_merges_with_origins_and_moves_between_scopes() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().join("home");
+    let project = temp.path().join("repo");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(
+        project.join(".mcp.json"),
+        r#"{"mcpServers": {"docs": {"url": "https://docs.example/mcp"}, "exa": {"command": "exa-local"}}}"#,
+    )
+    .unwrap();
+
+    let stdio = |name: &str, command: &str| McpServer {
+        name: name.to_string(),
+        config: McpServerConfig {
+            command: Some(command.to_string()),
+            ..Default::default()
+        },
+    };
+    mcp::save_mcp_server_for_home(&home, stdio("exa", "npx")).unwrap();
+    mcp::save_mcp_server_for_home(&home, stdio("git", "uvx")).unwrap();
+    mcp::save_project_mcp_server(&project, McpProjectFile::Factory, stdio("exa", "exa-repo"))
+        .unwrap();
+
+    let configs = mcp::load_project_mcp_configs(&project).unwrap();
+    assert!(configs.iter().all(|c| c.exists));
+    let claude = configs
+        .iter()
+        .find(|c| c.file == McpProjectFile::Claude)
+        .unwrap();
+    assert_eq!(claude.servers.len(), 2);
+
+    let effective = mcp::effective_mcp_servers_for_home(&home, &project).unwrap();
+    let names: Vec<&str> = effective.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["exa", "git", "docs"]);
+    let exa = &effective[0];
+    assert_eq!(exa.config.command.as_deref(), Some("exa-repo"));
+    assert_eq!(exa.origin.file, Some(McpProjectFile::Factory));
+    let shadowed: Vec<_> = exa.shadowed.iter().map(|o| (o.scope, o.file)).collect();
+    assert_eq!(
+        shadowed,
+        vec![
+            (McpScope::User, None),
+            (McpScope::Project, Some(McpProjectFile::Claude)),
+        ]
+    );
+    assert_eq!(effective[1].origin.scope, McpScope::User);
+    assert_eq!(effective[2].config.server_type, McpServerType::Http);
+
+    // Promote moves the entry out of the project file into the catalog.
+    mcp::promote_mcp_server_for_home(&home, &project, McpProjectFile::Claude, "docs").unwrap();
+    let user = mcp::load_mcp_servers_for_home(&home).unwrap();
+    assert!(user.iter().any(|s| s.name == "docs"));
+    let claude_file: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(project.join(".mcp.json")).unwrap()).unwrap();
+    assert!(claude_file["mcpServers"].get("docs").is_none());
+
+    // Demote refuses to clobber a project entry, then moves cleanly.
+    let err = mcp::demote_mcp_server_for_home(&home, &project, McpProjectFile::Factory, "exa")
+        .unwrap_err();
+    assert!(err.contains("already defines"));
+    mcp::demote_mcp_server_for_home(&home, &project, McpProjectFile::Factory, "git").unwrap();
+    assert!(!mcp::load_mcp_servers_for_home(&home)
+        .unwrap()
+        .iter()
+        .any(|s| s.name == "git"));
+    let factory_file: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(project.join(".factory/mcp.json")).unwrap())
+            .unwrap();
+    assert_eq!(factory_file["mcpServers"]["git"]["command"], "uvx");
+    let user_factory: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(home.join(".factory/mcp.json")).unwrap())
+            .unwrap();
+    assert!(user_factory["mcpServers"].get("git").is_none());
+}
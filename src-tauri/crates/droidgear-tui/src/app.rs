@@ -1,22 +1,33 @@
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::PathBuf,
+    rc::Rc,
+};
 
 use droidgear_core::{
     channel::Channel,
-    claude_settings_files::ClaudeSettingsFileInfo,
+    claude_agents::{ClaudeAsset, ClaudeAssetKind, ClaudeAssetScope},
+    claude_settings_files::{ClaudeSettingsFileInfo, ClaudeSettingsScope},
     codex::CodexProfile,
     codex_auth_profiles::CodexAuthProfile,
     droid_settings_files::SettingsFileInfo,
     factory_auth_profiles::AuthProfile,
     factory_settings::{CustomModel, MissionModelSettings},
+    file_watcher::{FileWatcher, WatchKind},
     hermes::HermesProfile,
     mcp::McpServer,
+    mcp_presets::McpPreset,
+    mcp_sync::McpSyncTarget,
     omp::OmpProfile,
     openclaw::{OpenClawProfile, OpenClawSubAgent},
     opencode::OpenCodeProfile,
     paths::{EffectivePath, EffectivePaths},
     pi::PiProfile,
-    sessions::SessionSummary,
-    specs::SpecFile,
+    session_analytics::{UsageGroupBy, UsageReport},
+    session_retention::{RetentionCandidate, RetentionPolicy},
+    sessions::{SessionDetail, SessionSummary},
+    specs::{SpecQuery, SpecSummary},
+    specs_history::SpecRevision,
     trusted_folders::TrustedFolder,
 };
 use serde_json::Value as JsonValue;
@@ -36,6 +47,7 @@ pub enum Screen {
     McpKeyValues,
     ClaudeSettings,
     ClaudeSettingsDetail,
+    ClaudeAgents,
     Codex,
     CodexProfile,
     CodexProvider,
@@ -60,7 +72,11 @@ pub enum Screen {
     HermesProfile,
     HermesProvider,
     Sessions,
+    SessionDetail,
+    SessionAnalytics,
+    SessionRetention,
     Specs,
+    SpecHistory,
     Channels,
     ChannelsEdit,
     Missions,
@@ -111,10 +127,18 @@ pub enum ConfirmAction {
     ClaudeSettingsApply {
         name: String,
     },
+    /// Merge into global, appending hooks and permission rules.
+    ClaudeSettingsApplyAppend {
+        name: String,
+    },
     ClaudeSettingsDelete {
         name: String,
     },
     ClaudeSettingsDiscardDetail,
+    /// Save custom models edited while settings.json changed on disk
+    FactoryModelsOverwrite {
+        models: Vec<CustomModel>,
+    },
     CodexApply {
         id: String,
     },
@@ -124,6 +148,12 @@ pub enum ConfirmAction {
     CodexDelete {
         id: String,
     },
+    /// Write the profile into config.toml as a native `[profiles.*]` table,
+    /// replacing an existing table of that name when `overwrite` is set
+    CodexExportNative {
+        id: String,
+        overwrite: bool,
+    },
     CodexDeleteProvider {
         profile_id: String,
         provider_id: String,
@@ -165,6 +195,13 @@ pub enum ConfirmAction {
     McpDelete {
         name: String,
     },
+    McpSync {
+        targets: Vec<McpSyncTarget>,
+    },
+    McpMoveScope {
+        project_dir: PathBuf,
+        scope_move: McpScopeMove,
+    },
     FactorySetDefaultModel {
         model_id: String,
     },
@@ -174,9 +211,16 @@ pub enum ConfirmAction {
     SessionDelete {
         path: String,
     },
+    SessionRetentionApply {
+        action: droidgear_core::session_retention::RetentionAction,
+    },
     SpecDelete {
         path: String,
     },
+    SpecRestoreRevision {
+        name: String,
+        id: u32,
+    },
     ChannelDelete {
         id: String,
     },
@@ -232,12 +276,18 @@ pub enum ConfirmAction {
         name: String,
         label: String,
     },
+    CodexAuthRotate,
     TrustedFolderDelete {
         path: String,
     },
     TrustedFoldersDelete {
         paths: Vec<String>,
     },
+    ClaudeAssetDelete {
+        kind: ClaudeAssetKind,
+        scope: ClaudeAssetScope,
+        name: String,
+    },
     OmpTestAll,
 }
 
@@ -250,6 +300,9 @@ pub enum InputAction {
     ClaudeSettingsDuplicate {
         name: String,
     },
+    ClaudeSimulatePermission {
+        name: String,
+    },
     ClaudeSettingsEditField {
         field_index: usize,
     },
@@ -257,6 +310,16 @@ pub enum InputAction {
         channel_id: String,
     },
     TrustedFolderAdd,
+    ClaudeAssetCreate {
+        kind: ClaudeAssetKind,
+        scope: ClaudeAssetScope,
+    },
+    /// Comma-separated `tools` / `allowed-tools`.
+    ClaudeAssetSetTools {
+        kind: ClaudeAssetKind,
+        scope: ClaudeAssetScope,
+        name: String,
+    },
     CodexCreateProfile,
     CodexDuplicate {
         id: String,
@@ -406,6 +469,18 @@ pub enum InputAction {
     FactoryDraftSetExtraArgs,
     FactoryDraftSetExtraHeaders,
     McpCreateServer,
+    SpecTagFilter,
+    SpecTextFilter,
+    SpecFromTemplate {
+        template: String,
+    },
+    McpPresetName {
+        preset: Box<McpPreset>,
+        variant_id: String,
+    },
+    McpPresetInput {
+        install: Box<McpPresetInstall>,
+    },
     McpDraftSetName,
     McpDraftSetCommand,
     McpDraftSetUrl,
@@ -537,6 +612,14 @@ pub enum InputAction {
     CodexAuthRename {
         name: String,
     },
+    SessionExportPath {
+        session_path: String,
+        format: droidgear_core::session_export::SessionExportFormat,
+    },
+    AnalyticsExportPath {
+        group_by: UsageGroupBy,
+        format: droidgear_core::session_analytics::UsageExportFormat,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -549,6 +632,13 @@ pub enum SelectAction {
     ClaudeSettingsImportChannel,
     ClaudeSettingsImportToken,
     ClaudeSettingsImportModel,
+    ClaudeAssetSetModel {
+        kind: ClaudeAssetKind,
+        scope: ClaudeAssetScope,
+        name: String,
+    },
+    /// Import a config.toml `[profiles.*]` table, or all of them
+    CodexImportNative,
     CodexSetProfileModelProvider {
         id: String,
     },
@@ -558,6 +648,12 @@ pub enum SelectAction {
     CodexSetProfileReasoningEffort {
         id: String,
     },
+    CodexSetProfileSandboxMode {
+        id: String,
+    },
+    CodexSetProfileApprovalPolicy {
+        id: String,
+    },
     CodexSetProviderWireApi {
         profile_id: String,
         provider_id: String,
@@ -627,6 +723,63 @@ pub enum SelectAction {
     HermesSetProfileReasoningEffort {
         id: String,
     },
+    SessionExportFormat {
+        session_path: String,
+    },
+    AnalyticsExportFormat {
+        group_by: UsageGroupBy,
+    },
+    McpSyncTarget,
+    McpMoveScope {
+        project_dir: PathBuf,
+        moves: Vec<McpScopeMove>,
+    },
+    McpPreset {
+        presets: Vec<McpPreset>,
+    },
+    SpecStatusFilter,
+    SpecProjectFilter,
+    SpecTemplate,
+    SpecSetStatus {
+        path: String,
+    },
+    McpPresetVariant {
+        preset: Box<McpPreset>,
+    },
+}
+
+/// A preset install in progress, prompting for one input at a time
+#[derive(Debug, Clone)]
+pub struct McpPresetInstall {
+    pub preset: McpPreset,
+    pub variant_id: String,
+    pub name: String,
+    pub values: std::collections::HashMap<String, String>,
+    /// Index of the input being prompted
+    pub next: usize,
+}
+
+/// Moving one MCP server between the user catalog and a project file
+#[derive(Debug, Clone)]
+pub struct McpScopeMove {
+    pub name: String,
+    pub file: droidgear_core::mcp::McpProjectFile,
+    /// Project → user when true, user → project otherwise
+    pub promote: bool,
+}
+
+impl McpScopeMove {
+    pub fn label(&self) -> String {
+        if self.promote {
+            format!(
+                "Promote '{}' from {} to user",
+                self.name,
+                self.file.relative_path()
+            )
+        } else {
+            format!("Demote '{}' to {}", self.name, self.file.relative_path())
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -696,6 +849,12 @@ pub struct App {
     pub claude_detail_field_index: usize,
     /// Whether the detail view has unsaved edits (mirrors the GUI's hasChanges).
     pub claude_detail_dirty: bool,
+    /// Project layer shown in the detail view instead of a named file.
+    pub claude_detail_layer: Option<(PathBuf, ClaudeSettingsScope)>,
+    /// Whether the agents screen shows subagents or slash commands.
+    pub claude_assets_kind: ClaudeAssetKind,
+    pub claude_assets: Vec<ClaudeAsset>,
+    pub claude_assets_index: usize,
     /// Pending channel-import values, filled by the import flow in modal.rs.
     pub claude_import_pending_base_url: Option<String>,
     pub claude_import_pending_api_key: Option<String>,
@@ -712,6 +871,9 @@ pub struct App {
     pub codex_detail_provider_index: usize,
     pub codex_provider_id: Option<String>,
     pub codex_provider_field_index: usize,
+    /// History handling for temporary runs chosen with `h`; the app's saved
+    /// run preferences apply until then.
+    pub codex_run_options: Option<droidgear_core::codex_runtime::CodexRunOptions>,
 
     pub opencode_profiles: Vec<OpenCodeProfile>,
     pub opencode_active_id: Option<String>,
@@ -797,9 +959,32 @@ pub struct App {
 
     pub sessions: Vec<SessionSummary>,
     pub sessions_index: usize,
+    pub session_detail: Option<SessionDetail>,
+    pub session_detail_index: usize,
+    /// Rows (see `session_detail_rows`) currently shown expanded.
+    pub session_detail_expanded: HashSet<usize>,
+    pub analytics_group_by: UsageGroupBy,
+    pub analytics_report: Option<UsageReport>,
+    pub analytics_index: usize,
+    pub retention_policy: RetentionPolicy,
+    /// Dry-run result of `retention_policy`
+    pub retention_candidates: Vec<RetentionCandidate>,
+    pub retention_index: usize,
 
-    pub specs: Vec<SpecFile>,
+    pub specs: Vec<SpecSummary>,
     pub specs_index: usize,
+    /// Filters and ordering applied to `specs`
+    pub spec_query: SpecQuery,
+    /// Spec whose revisions `SpecHistory` shows
+    pub spec_history_name: String,
+    /// Revisions of `spec_history_name`, newest first
+    pub spec_revisions: Vec<SpecRevision>,
+    pub spec_revisions_index: usize,
+
+    /// Watches sessions, specs and settings files; started by `tui::run`
+    pub watcher: Option<Rc<FileWatcher>>,
+    /// Changed kinds not yet shown on screen
+    pub pending_watch: BTreeSet<WatchKind>,
 
     pub channels: Vec<Channel>,
     pub channels_index: usize,
@@ -873,6 +1058,10 @@ impl App {
             claude_detail_json: None,
             claude_detail_field_index: 0,
             claude_detail_dirty: false,
+            claude_detail_layer: None,
+            claude_assets_kind: ClaudeAssetKind::Agent,
+            claude_assets: Vec::new(),
+            claude_assets_index: 0,
             claude_import_pending_base_url: None,
             claude_import_pending_api_key: None,
             claude_import_pending_platform: None,
@@ -887,6 +1076,7 @@ impl App {
             codex_detail_provider_index: 0,
             codex_provider_id: None,
             codex_provider_field_index: 0,
+            codex_run_options: None,
             opencode_profiles: Vec::new(),
             opencode_active_id: None,
             opencode_index: 0,
@@ -958,8 +1148,23 @@ impl App {
             pi_import_pending_api_type: None,
             sessions: Vec::new(),
             sessions_index: 0,
+            session_detail: None,
+            session_detail_index: 0,
+            session_detail_expanded: HashSet::new(),
+            analytics_group_by: UsageGroupBy::Day,
+            analytics_report: None,
+            analytics_index: 0,
+            retention_policy: RetentionPolicy::default(),
+            retention_candidates: Vec::new(),
+            retention_index: 0,
             specs: Vec::new(),
             specs_index: 0,
+            spec_query: SpecQuery::default(),
+            spec_history_name: String::new(),
+            spec_revisions: Vec::new(),
+            spec_revisions_index: 0,
+            watcher: None,
+            pending_watch: BTreeSet::new(),
             channels: Vec::new(),
             channels_index: 0,
             channels_edit_draft: None,
@@ -1028,7 +1233,10 @@ impl App {
             },
             NavGroup {
                 label: "Claude",
-                items: &[("Settings", Screen::ClaudeSettings)],
+                items: &[
+                    ("Settings", Screen::ClaudeSettings),
+                    ("Agents & Commands", Screen::ClaudeAgents),
+                ],
                 system: false,
             },
             NavGroup {
@@ -1145,6 +1353,10 @@ impl App {
             Screen::HermesProfile => Screen::Hermes,
             Screen::HermesProvider => Screen::HermesProfile,
             Screen::ChannelsEdit => Screen::Channels,
+            Screen::SessionDetail => Screen::Sessions,
+            Screen::SessionAnalytics => Screen::Sessions,
+            Screen::SessionRetention => Screen::Sessions,
+            Screen::SpecHistory => Screen::Specs,
             _ => Screen::Main,
         }
     }
@@ -1168,6 +1380,44 @@ impl App {
         self.toast = None;
     }
 
+    /// Flattened (message index, block index) pairs of the loaded session
+    /// detail; each pair is one collapsible row in the viewer.
+    pub fn session_detail_rows(&self) -> Vec<(usize, usize)> {
+        self.session_detail
+            .as_ref()
+            .map(|detail| {
+                detail
+                    .messages
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(m, msg)| (0..msg.content.len()).map(move |b| (m, b)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Load a session into the detail viewer. Text blocks start expanded;
+    /// thinking, tool and attachment blocks start collapsed.
+    pub fn open_session_detail(&mut self, detail: SessionDetail) {
+        self.session_detail = Some(detail);
+        self.session_detail_index = 0;
+        let rows = self.session_detail_rows();
+        self.session_detail_expanded = rows
+            .iter()
+            .enumerate()
+            .filter(|(_, (m, b))| {
+                self.session_detail.as_ref().is_some_and(|d| {
+                    matches!(
+                        d.messages[*m].content[*b],
+                        droidgear_core::sessions::ContentBlock::Text { .. }
+                    )
+                })
+            })
+            .map(|(i, _)| i)
+            .collect();
+        self.screen = Screen::SessionDetail;
+    }
+
     /// Get the provider ID at the current pi_provider_index.
     pub fn pi_current_provider_id(&self) -> Option<String> {
         let detail = self.pi_detail.as_ref()?;
@@ -1225,6 +1475,9 @@ impl App {
         if self.trusted_folders_index >= self.trusted_folders.len() {
             self.trusted_folders_index = self.trusted_folders.len().saturating_sub(1);
         }
+        if self.claude_assets_index >= self.claude_assets.len() {
+            self.claude_assets_index = self.claude_assets.len().saturating_sub(1);
+        }
         let claude_fields_count = 14;
         if self.claude_detail_field_index >= claude_fields_count {
             self.claude_detail_field_index = claude_fields_count.saturating_sub(1);
@@ -1244,7 +1497,8 @@ impl App {
             .as_ref()
             .map(|s| match s.config.server_type {
                 droidgear_core::mcp::McpServerType::Stdio => 6,
-                droidgear_core::mcp::McpServerType::Http => 5,
+                droidgear_core::mcp::McpServerType::Http
+                | droidgear_core::mcp::McpServerType::Sse => 5,
             })
             .unwrap_or(0);
         if self.mcp_edit_field_index >= mcp_edit_fields_count {
@@ -1275,7 +1529,7 @@ impl App {
         if self.codex_index >= self.codex_profiles.len() {
             self.codex_index = self.codex_profiles.len().saturating_sub(1);
         }
-        let codex_fields_count = 6;
+        let codex_fields_count = 9;
         if self.codex_detail_field_index >= codex_fields_count {
             self.codex_detail_field_index = codex_fields_count.saturating_sub(1);
         }
@@ -1437,9 +1691,27 @@ impl App {
         if self.sessions_index >= self.sessions.len() {
             self.sessions_index = self.sessions.len().saturating_sub(1);
         }
+        let session_detail_rows = self.session_detail_rows().len();
+        if self.session_detail_index >= session_detail_rows {
+            self.session_detail_index = session_detail_rows.saturating_sub(1);
+        }
+        let analytics_rows = self
+            .analytics_report
+            .as_ref()
+            .map(|r| r.buckets.len())
+            .unwrap_or(0);
+        if self.analytics_index >= analytics_rows {
+            self.analytics_index = analytics_rows.saturating_sub(1);
+        }
+        if self.retention_index >= self.retention_candidates.len() {
+            self.retention_index = self.retention_candidates.len().saturating_sub(1);
+        }
         if self.specs_index >= self.specs.len() {
             self.specs_index = self.specs.len().saturating_sub(1);
         }
+        if self.spec_revisions_index >= self.spec_revisions.len() {
+            self.spec_revisions_index = self.spec_revisions.len().saturating_sub(1);
+        }
         if self.channels_index >= self.channels.len() {
             self.channels_index = self.channels.len().saturating_sub(1);
         }
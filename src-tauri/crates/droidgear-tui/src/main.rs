@@ -5,6 +5,7 @@ mod ui;
 
 use anyhow::{bail, Context};
 use clap::{Parser, Subcommand};
+use droidgear_core::codex_runtime::CodexHistoryMode;
 use std::path::PathBuf;
 
 #[derive(Debug, Parser)]
@@ -27,6 +28,55 @@ enum Command {
         #[command(subcommand)]
         target: RunTarget,
     },
+    /// Manage Droid, Claude Code and Codex sessions without the UI
+    Sessions {
+        #[command(subcommand)]
+        command: SessionsCommand,
+    },
+    /// Manage saved Codex login profiles without the UI
+    CodexAuth {
+        #[command(subcommand)]
+        command: CodexAuthCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum CodexAuthCommand {
+    /// Mark the active login as limited and switch to the next saved login
+    /// that is not limited
+    Rotate {
+        /// How long the current login stays marked as limited (default 300)
+        #[arg(long, value_name = "MINUTES")]
+        cooldown: Option<u32>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum SessionsCommand {
+    /// Archive (default) or delete Droid sessions matching retention rules.
+    /// Without rule flags the rules saved in
+    /// ~/.droidgear/session-retention.json apply. Claude Code and Codex
+    /// transcripts are left alone.
+    Cleanup {
+        /// Sessions not modified for more than this many days
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u32>,
+        /// Sessions without any message
+        #[arg(long)]
+        empty: bool,
+        /// Sessions with fewer total tokens than this
+        #[arg(long, value_name = "TOKENS")]
+        min_tokens: Option<f64>,
+        /// Sessions whose project directory no longer exists
+        #[arg(long)]
+        missing_project: bool,
+        /// Delete instead of archiving to ~/.droidgear/archive
+        #[arg(long)]
+        delete: bool,
+        /// Only report what would happen
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -36,6 +86,13 @@ enum RunTarget {
         #[arg(long)]
         list: bool,
         profile: Option<String>,
+        /// Where the run keeps its sessions and history: shared, isolated or
+        /// ephemeral (defaults to the app's saved preference)
+        #[arg(long, value_name = "MODE", value_parser = parse_history_mode)]
+        history: Option<CodexHistoryMode>,
+        /// Merge an isolated or ephemeral run's history into the live one on exit
+        #[arg(long)]
+        merge_history: bool,
     },
     /// Run a Claude settings file by name (use `global` for ~/.claude/settings.json)
     Claude {
@@ -54,6 +111,13 @@ enum RunTarget {
     },
 }
 
+fn parse_history_mode(value: &str) -> Result<CodexHistoryMode, String> {
+    CodexHistoryMode::ALL
+        .into_iter()
+        .find(|mode| mode.label() == value)
+        .ok_or_else(|| format!("expected one of: shared, isolated, ephemeral (got '{value}')"))
+}
+
 fn main() -> anyhow::Result<()> {
     let raw_args: Vec<String> = std::env::args().skip(1).collect();
     if droidgear_core::claude_runtime::matches_internal_launcher_args(&raw_args) {
@@ -74,10 +138,15 @@ fn main() -> anyhow::Result<()> {
 
     match cli.command {
         Some(Command::Run { target }) => match target {
-            RunTarget::Codex { list, profile } => {
+            RunTarget::Codex {
+                list,
+                profile,
+                history,
+                merge_history,
+            } => {
                 if list {
-                    if profile.is_some() {
-                        bail!("`--list` cannot be combined with a Codex target");
+                    if profile.is_some() || history.is_some() || merge_history {
+                        bail!("`--list` cannot be combined with other Codex run arguments");
                     }
                     println!("{}", tui::list_codex_temporary_run_targets(&home_dir)?);
                     Ok(())
@@ -85,7 +154,8 @@ fn main() -> anyhow::Result<()> {
                     let profile = profile.context(
                         "Missing Codex target. Use `droidgear-tui run codex --list` to inspect available profiles.",
                     )?;
-                    tui::run_codex_temporary_run_for_selector(&home_dir, &profile)
+                    let options = tui::codex_cli_run_options(history, merge_history)?;
+                    tui::run_codex_temporary_run_for_selector(&home_dir, &profile, options)
                 }
             }
             RunTarget::Claude {
@@ -133,6 +203,52 @@ fn main() -> anyhow::Result<()> {
                 }
             }
         },
+        Some(Command::Sessions { command }) => match command {
+            SessionsCommand::Cleanup {
+                older_than,
+                empty,
+                min_tokens,
+                missing_project,
+                delete,
+                dry_run,
+            } => {
+                use droidgear_core::session_retention::{RetentionAction, RetentionPolicy};
+
+                let flags = RetentionPolicy {
+                    max_age_days: older_than,
+                    empty,
+                    min_total_tokens: min_tokens,
+                    missing_project_dir: missing_project,
+                };
+                let policy = (!flags.is_empty()).then_some(flags);
+                let action = if delete {
+                    RetentionAction::Delete
+                } else {
+                    RetentionAction::Archive
+                };
+                println!(
+                    "{}",
+                    tui::run_session_cleanup(&home_dir, policy, action, dry_run)?
+                );
+                Ok(())
+            }
+        },
+        Some(Command::CodexAuth { command }) => match command {
+            CodexAuthCommand::Rotate { cooldown } => {
+                let rotation = droidgear_core::codex_auth_profiles::rotate_profile_for_home(
+                    &home_dir, cooldown,
+                )
+                .map_err(anyhow::Error::msg)?;
+                if let (Some(from), Some(until)) = (&rotation.from, &rotation.limited_until) {
+                    println!("Marked '{from}' as limited until {until}");
+                }
+                if !rotation.skipped.is_empty() {
+                    println!("Skipped limited: {}", rotation.skipped.join(", "));
+                }
+                println!("Switched to '{}'", rotation.to);
+                Ok(())
+            }
+        },
         None => {
             let mut app = app::App::new(home_dir);
             tui::run(&mut app)
@@ -142,7 +258,7 @@ fn main() -> anyhow::Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::{Cli, Command, RunTarget};
+    use super::{Cli, CodexAuthCommand, CodexHistoryMode, Command, RunTarget, SessionsCommand};
     use clap::Parser;
     use std::path::PathBuf;
 
@@ -168,10 +284,18 @@ mod tests {
         assert_eq!(cli.home, Some(PathBuf::from("/tmp/demo-home")));
         match cli.command {
             Some(Command::Run {
-                target: RunTarget::Codex { list, profile },
+                target:
+                    RunTarget::Codex {
+                        list,
+                        profile,
+                        history,
+                        merge_history,
+                    },
             }) => {
                 assert!(!list);
                 assert_eq!(profile.as_deref(), Some("profile-a"));
+                assert_eq!(history, None);
+                assert!(!merge_history);
             }
             _ => panic!("expected codex run subcommand"),
         }
@@ -196,13 +320,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_parses_codex_run_history_options() {
+        let cli = Cli::parse_from([
+            "droidgear-tui",
+            "run",
+            "codex",
+            "profile-a",
+            "--history",
+            "ephemeral",
+            "--merge-history",
+        ]);
+
+        match cli.command {
+            Some(Command::Run {
+                target:
+                    RunTarget::Codex {
+                        history,
+                        merge_history,
+                        ..
+                    },
+            }) => {
+                assert_eq!(history, Some(CodexHistoryMode::Ephemeral));
+                assert!(merge_history);
+            }
+            _ => panic!("expected codex run subcommand"),
+        }
+
+        assert!(
+            Cli::try_parse_from(["droidgear-tui", "run", "codex", "a", "--history", "none"])
+                .is_err()
+        );
+    }
+
     #[test]
     fn cli_parses_codex_list_subcommand() {
         let cli = Cli::parse_from(["droidgear-tui", "run", "codex", "--list"]);
 
         match cli.command {
             Some(Command::Run {
-                target: RunTarget::Codex { list, profile },
+                target: RunTarget::Codex { list, profile, .. },
             }) => {
                 assert!(list);
                 assert!(profile.is_none());
@@ -308,4 +465,48 @@ mod tests {
             _ => panic!("expected claude preview subcommand"),
         }
     }
+
+    #[test]
+    fn cli_parses_sessions_cleanup_subcommand() {
+        let cli = Cli::parse_from([
+            "droidgear-tui",
+            "sessions",
+            "cleanup",
+            "--older-than",
+            "30",
+            "--empty",
+            "--dry-run",
+        ]);
+
+        match cli.command {
+            Some(Command::Sessions {
+                command:
+                    SessionsCommand::Cleanup {
+                        older_than,
+                        empty,
+                        min_tokens,
+                        missing_project,
+                        delete,
+                        dry_run,
+                    },
+            }) => {
+                assert_eq!(older_than, Some(30));
+                assert!(empty && dry_run);
+                assert!(min_tokens.is_none() && !missing_project && !delete);
+            }
+            _ => panic!("expected sessions cleanup subcommand"),
+        }
+    }
+
+    #[test]
+    fn cli_parses_codex_auth_rotate() {
+        let cli = Cli::parse_from(["droidgear-tui", "codex-auth", "rotate", "--cooldown", "60"]);
+
+        match cli.command {
+            Some(Command::CodexAuth {
+                command: CodexAuthCommand::Rotate { cooldown },
+            }) => assert_eq!(cooldown, Some(60)),
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
 }
@@ -61,12 +61,14 @@ pub(super) fn run_action(app: &mut app::App, action: Action) -> anyhow::Result<(
             Ok(())
         }
         Action::PreviewCodexRun { id } => {
-            let preview = preview_codex_temporary_run(&app.home_dir, &id)?;
+            let options = codex_run_options(app)?;
+            let preview = preview_codex_temporary_run(&app.home_dir, &id, &options)?;
             open_text_in_pager(&preview)?;
             Ok(())
         }
         Action::RunCodexRun { id } => {
-            run_codex_temporary_run(&app.home_dir, &id)?;
+            let options = codex_run_options(app)?;
+            run_codex_temporary_run(&app.home_dir, &id, &options)?;
             app.should_quit = true;
             Ok(())
         }
@@ -75,6 +77,52 @@ pub(super) fn run_action(app: &mut app::App, action: Action) -> anyhow::Result<(
             open_text_in_pager(&diff)?;
             Ok(())
         }
+        Action::PreviewMcpSync => {
+            let diff = preview_mcp_sync(&app.home_dir)?;
+            open_text_in_pager(&diff)?;
+            Ok(())
+        }
+        Action::ShowEffectiveMcpServers { project_dir } => {
+            let servers =
+                droidgear_core::mcp::effective_mcp_servers_for_home(&app.home_dir, &project_dir)
+                    .map_err(anyhow::Error::msg)?;
+            open_text_in_pager(&format_effective_mcp_servers(&project_dir, &servers))?;
+            Ok(())
+        }
+        Action::ShowClaudeEffectiveSettings { project_dir } => {
+            let effective = droidgear_core::claude_settings_files::effective_settings_for_home(
+                &app.home_dir,
+                &project_dir,
+            )
+            .map_err(anyhow::Error::msg)?;
+            open_text_in_pager(&format_claude_effective_settings(&project_dir, &effective))?;
+            Ok(())
+        }
+        Action::EditClaudeAsset { path } => {
+            editor::open_in_editor(Path::new(&path))?;
+            Ok(())
+        }
+        Action::ShowClaudeAssetReport { asset } => {
+            open_text_in_pager(&format_claude_asset_report(&asset))?;
+            Ok(())
+        }
+        Action::ShowClaudePermissionReport { name } => {
+            let issues =
+                droidgear_core::claude_permissions::validate_settings_file_permissions_for_home(
+                    &app.home_dir,
+                    &name,
+                )
+                .map_err(anyhow::Error::msg)?;
+            open_text_in_pager(&format_claude_permission_report(&name, &issues))?;
+            Ok(())
+        }
+        Action::ProbeMcpServer { server } => {
+            let result =
+                droidgear_core::mcp_probe::probe_mcp_server_for_home(&app.home_dir, &server.config)
+                    .map_err(anyhow::Error::msg)?;
+            open_text_in_pager(&format_mcp_probe_report(&server.name, &result))?;
+            Ok(())
+        }
         Action::PreviewOpenClawApply { id } => {
             let diff = preview_openclaw_apply(&app.home_dir, &id)?;
             open_text_in_pager(&diff)?;
@@ -116,9 +164,43 @@ pub(super) fn run_action(app: &mut app::App, action: Action) -> anyhow::Result<(
             editor::open_in_pager(temp.path())?;
             Ok(())
         }
+        Action::EditRetentionPolicy => {
+            let policy =
+                droidgear_core::session_retention::load_retention_policy_for_home(&app.home_dir)
+                    .map_err(anyhow::Error::msg)?;
+            let edited = edit_json_in_editor(&policy)?;
+            droidgear_core::session_retention::save_retention_policy_for_home(
+                &app.home_dir,
+                &edited,
+            )
+            .map_err(anyhow::Error::msg)?;
+            app.set_toast("Saved", false);
+            Ok(())
+        }
         Action::EditSpec { path } => {
-            let path = PathBuf::from(path);
-            editor::open_in_editor(&path)?;
+            use droidgear_core::specs_history::record_spec_revision_for_home;
+
+            // Snapshot both sides so edits made in the editor land in history.
+            record_spec_revision_for_home(&app.home_dir, &path, "external edit")
+                .map_err(anyhow::Error::msg)?;
+            editor::open_in_editor(Path::new(&path))?;
+            record_spec_revision_for_home(&app.home_dir, &path, "edit")
+                .map_err(anyhow::Error::msg)?;
+            Ok(())
+        }
+        Action::ShowSpecDiff { name, from, to } => {
+            let diff = droidgear_core::specs_history::diff_spec_revisions_for_home(
+                &app.home_dir,
+                &name,
+                from,
+                to,
+            )
+            .map_err(anyhow::Error::msg)?;
+            if diff.is_empty() {
+                app.set_toast("No changes", false);
+            } else {
+                open_text_in_pager(&diff)?;
+            }
             Ok(())
         }
         Action::EditChannels => {
@@ -164,9 +246,22 @@ pub(super) fn run_action(app: &mut app::App, action: Action) -> anyhow::Result<(
     }
 }
 
+/// Run options picked with `h`, else the app's saved run preferences.
+fn codex_run_options(
+    app: &app::App,
+) -> anyhow::Result<droidgear_core::codex_runtime::CodexRunOptions> {
+    match app.codex_run_options {
+        Some(options) => Ok(options),
+        None => load_codex_run_preferences(),
+    }
+}
+
 pub(super) fn format_session_detail(detail: &droidgear_core::sessions::SessionDetail) -> String {
+    use droidgear_core::sessions::ContentBlock;
+
     let mut out = String::new();
     out.push_str(&format!("Title: {}\n", detail.title));
+    out.push_str(&format!("Source: {}\n", detail.source.label()));
     out.push_str(&format!("Project: {}\n", detail.project));
     out.push_str(&format!("Model: {}\n", detail.model));
     out.push_str(&format!("CWD: {}\n", detail.cwd));
@@ -175,18 +270,44 @@ pub(super) fn format_session_detail(detail: &droidgear_core::sessions::SessionDe
     for m in &detail.messages {
         out.push_str(&format!("[{}] {}\n", m.role, m.timestamp));
         for block in &m.content {
-            if let Some(text) = block.text.as_deref() {
-                out.push_str(text);
-                if !text.ends_with('\n') {
-                    out.push('\n');
+            let body = match block {
+                ContentBlock::Text { text } => text.clone(),
+                ContentBlock::Thinking { thinking } => format!("(thinking)\n{thinking}"),
+                ContentBlock::ToolUse { name, input, .. } => format!(
+                    "(tool call: {name})\n{}",
+                    serde_json::to_string_pretty(input).unwrap_or_default()
+                ),
+                ContentBlock::ToolResult {
+                    output, is_error, ..
+                } => {
+                    let label = if *is_error {
+                        "tool error"
+                    } else {
+                        "tool result"
+                    };
+                    format!("({label})\n{output}")
                 }
-            }
-            if let Some(thinking) = block.thinking.as_deref() {
-                out.push_str("(thinking)\n");
-                out.push_str(thinking);
-                if !thinking.ends_with('\n') {
-                    out.push('\n');
+                ContentBlock::Image { media_type, .. } => format!("(image: {media_type})"),
+                ContentBlock::FileEdit {
+                    tool_name,
+                    path,
+                    old_text,
+                    new_text,
+                    ..
+                } => {
+                    let mut s = format!("({tool_name}: {path})\n");
+                    for l in old_text.as_deref().unwrap_or("").lines() {
+                        s.push_str(&format!("- {l}\n"));
+                    }
+                    for l in new_text.as_deref().unwrap_or("").lines() {
+                        s.push_str(&format!("+ {l}\n"));
+                    }
+                    s
                 }
+            };
+            out.push_str(&body);
+            if !body.ends_with('\n') {
+                out.push('\n');
             }
         }
         out.push('\n');
@@ -198,7 +319,23 @@ pub(super) fn format_session_detail(detail: &droidgear_core::sessions::SessionDe
 pub(super) fn edit_factory_models(app: &mut app::App) -> anyhow::Result<()> {
     let models = droidgear_core::factory_settings::load_custom_models_for_home(&app.home_dir)
         .map_err(anyhow::Error::msg)?;
+    let settings_path = droidgear_core::factory_settings::get_config_path_for_home(&app.home_dir)
+        .map_err(anyhow::Error::msg)?;
+    // Only changes made while the editor is open count as a conflict.
+    collect_watch_events(app);
     let edited: Vec<droidgear_core::factory_settings::CustomModel> = edit_json_in_editor(&models)?;
+    if collect_watch_events(app)
+        .iter()
+        .any(|e| e.path == Path::new(&settings_path))
+    {
+        app.modal = Some(app::Modal::Confirm {
+            message:
+                "settings.json changed on disk while you were editing. Save your models anyway?"
+                    .to_string(),
+            action: app::ConfirmAction::FactoryModelsOverwrite { models: edited },
+        });
+        return Ok(());
+    }
     droidgear_core::factory_settings::save_custom_models_for_home(&app.home_dir, edited)
         .map_err(anyhow::Error::msg)?;
     app.set_toast("Saved", false);
@@ -1,4 +1,6 @@
 use super::*;
+use droidgear_core::claude_settings_files::ClaudeSettingsScope;
+use serde_json::Value as JsonValue;
 
 pub(super) fn handle_claude_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
     match code {
@@ -34,6 +36,37 @@ pub(super) fn handle_claude_key(app: &mut app::App, code: KeyCode) -> Option<Act
                 });
             }
         }
+        KeyCode::Char('A') => {
+            if let Some(file) = app.claude_files.get(app.claude_index) {
+                app.modal = Some(app::Modal::Confirm {
+                    message: format!(
+                        "Merge Claude settings '{}' into global, appending hooks and permissions?",
+                        file.name
+                    ),
+                    action: app::ConfirmAction::ClaudeSettingsApplyAppend {
+                        name: file.name.clone(),
+                    },
+                });
+            }
+        }
+        KeyCode::Char('o') => open_claude_project_layer(app, ClaudeSettingsScope::Project),
+        KeyCode::Char('O') => open_claude_project_layer(app, ClaudeSettingsScope::Local),
+        KeyCode::Char('E') => match std::env::current_dir() {
+            Ok(project_dir) => return Some(Action::ShowClaudeEffectiveSettings { project_dir }),
+            Err(e) => app.set_toast(format!("Failed to read current directory: {e}"), true),
+        },
+        KeyCode::Char('v') => {
+            if let Some(file) = app.claude_files.get(app.claude_index) {
+                return Some(Action::ShowClaudePermissionReport {
+                    name: file.name.clone(),
+                });
+            }
+        }
+        KeyCode::Char('P') => {
+            if let Some(file) = app.claude_files.get(app.claude_index) {
+                open_permission_simulator(app, file.name.clone());
+            }
+        }
         KeyCode::Char('d') => {
             if let Some(file) = app.claude_files.get(app.claude_index) {
                 if file.is_global {
@@ -136,6 +169,75 @@ fn claude_skip_permissions_disabled(app: &app::App, name: &str) -> bool {
 /// Saves the detail JSON to disk when it has unsaved edits. Returns true when
 /// nothing was pending or the save succeeded. Mirrors the GUI launch flow,
 /// which auto-saves before running.
+/// Prompts for a tool call to check against the settings layers of the
+/// current directory plus `name`.
+fn open_permission_simulator(app: &mut app::App, name: String) {
+    app.modal = Some(app::Modal::Input {
+        title: "Simulate tool call, e.g. Bash(git push)".to_string(),
+        value: String::new(),
+        cursor: 0,
+        is_secret: false,
+        action: app::InputAction::ClaudeSimulatePermission { name },
+    });
+}
+
+/// Opens `<cwd>/.claude/settings.json` or `settings.local.json` in the
+/// detail editor.
+fn open_claude_project_layer(app: &mut app::App, scope: ClaudeSettingsScope) {
+    let project_dir = match std::env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            app.set_toast(format!("Failed to read current directory: {e}"), true);
+            return;
+        }
+    };
+    let path = match droidgear_core::claude_settings_files::project_settings_layer_path(
+        &project_dir,
+        scope,
+    ) {
+        Ok(path) => path,
+        Err(e) => {
+            app.set_toast(e, true);
+            return;
+        }
+    };
+    if let Some(watcher) = &app.watcher {
+        watcher.watch(droidgear_core::file_watcher::WatchTarget {
+            kind: WatchKind::ClaudeSettings,
+            dir: project_dir.join(".claude"),
+            files: vec![
+                "settings.json".to_string(),
+                "settings.local.json".to_string(),
+            ],
+            recursive: false,
+        });
+    }
+    app.claude_detail_name = Some(path.display().to_string());
+    app.claude_detail_layer = Some((project_dir, scope));
+    app.claude_detail_field_index = 0;
+    app.claude_detail_dirty = false;
+    app.screen = app::Screen::ClaudeSettingsDetail;
+    refresh_claude_detail(app);
+}
+
+/// Writes the detail JSON back to its named file or project layer.
+fn save_claude_detail_json(app: &app::App, name: &str, json: JsonValue) -> Result<(), String> {
+    match &app.claude_detail_layer {
+        Some((project_dir, scope)) => {
+            droidgear_core::claude_settings_files::save_project_settings_layer(
+                project_dir,
+                *scope,
+                json,
+            )
+        }
+        None => droidgear_core::claude_settings_files::save_settings_file_for_home(
+            &app.home_dir,
+            name,
+            json,
+        ),
+    }
+}
+
 fn claude_save_detail_if_dirty(app: &mut app::App) -> bool {
     if !app.claude_detail_dirty {
         return true;
@@ -146,11 +248,7 @@ fn claude_save_detail_if_dirty(app: &mut app::App) -> bool {
     let Some(json) = app.claude_detail_json.clone() else {
         return true;
     };
-    match droidgear_core::claude_settings_files::save_settings_file_for_home(
-        &app.home_dir,
-        &name,
-        json,
-    ) {
+    match save_claude_detail_json(app, &name, json) {
         Ok(()) => {
             app.claude_detail_dirty = false;
             app.set_toast(format!("Saved '{}'", name), false);
@@ -168,6 +266,7 @@ pub(super) fn exit_claude_detail(app: &mut app::App) {
     app.claude_detail_name = None;
     app.claude_detail_json = None;
     app.claude_detail_dirty = false;
+    app.claude_detail_layer = None;
 }
 
 pub(super) fn handle_claude_settings_detail_key(
@@ -177,6 +276,15 @@ pub(super) fn handle_claude_settings_detail_key(
     let Some(name) = app.claude_detail_name.clone() else {
         return None;
     };
+    if app.claude_detail_layer.is_some()
+        && matches!(
+            code,
+            KeyCode::Char('t' | 'T' | 'p' | 'a' | 'A' | 'v' | 'P' | 'l')
+        )
+    {
+        app.set_toast("Not available for project settings layers", true);
+        return None;
+    }
 
     match code {
         KeyCode::Esc | KeyCode::Char('q') => {
@@ -196,12 +304,8 @@ pub(super) fn handle_claude_settings_detail_key(
             app.claude_detail_field_index = app.claude_detail_field_index.saturating_sub(1)
         }
         KeyCode::Char('s') => {
-            if let Some(ref json) = app.claude_detail_json {
-                match droidgear_core::claude_settings_files::save_settings_file_for_home(
-                    &app.home_dir,
-                    &name,
-                    json.clone(),
-                ) {
+            if let Some(json) = app.claude_detail_json.clone() {
+                match save_claude_detail_json(app, &name, json) {
                     Ok(()) => {
                         app.claude_detail_dirty = false;
                         app.set_toast(format!("Saved '{}'", name), false);
@@ -247,6 +351,26 @@ pub(super) fn handle_claude_settings_detail_key(
                 action: app::ConfirmAction::ClaudeSettingsApply { name },
             });
         }
+        KeyCode::Char('A') => {
+            app.modal = Some(app::Modal::Confirm {
+                message: format!(
+                    "Merge Claude settings '{name}' into global, appending hooks and permissions?"
+                ),
+                action: app::ConfirmAction::ClaudeSettingsApplyAppend { name },
+            });
+        }
+        KeyCode::Char('v') => {
+            if !claude_save_detail_if_dirty(app) {
+                return None;
+            }
+            return Some(Action::ShowClaudePermissionReport { name });
+        }
+        KeyCode::Char('P') => {
+            if !claude_save_detail_if_dirty(app) {
+                return None;
+            }
+            open_permission_simulator(app, name);
+        }
         KeyCode::Char('l') => match claude_load_from_live_config(app, &name) {
             Ok(()) => {
                 app.set_toast(format!("Loaded live config into '{name}'"), false);
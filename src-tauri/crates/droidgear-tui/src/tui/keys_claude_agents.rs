@@ -0,0 +1,135 @@
+use super::*;
+use droidgear_core::claude_agents::{ClaudeAssetKind, ClaudeAssetScope, CLAUDE_MODEL_ALIASES};
+
+pub(super) fn handle_claude_agents_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.go_back(),
+        KeyCode::Down => app.claude_assets_index = app.claude_assets_index.saturating_add(1),
+        KeyCode::Up => app.claude_assets_index = app.claude_assets_index.saturating_sub(1),
+        KeyCode::Char('r') => refresh_claude_assets(app),
+        KeyCode::Tab => {
+            app.claude_assets_kind = match app.claude_assets_kind {
+                ClaudeAssetKind::Agent => ClaudeAssetKind::Command,
+                ClaudeAssetKind::Command => ClaudeAssetKind::Agent,
+            };
+            app.claude_assets_index = 0;
+            refresh_claude_assets(app);
+        }
+        KeyCode::Char('n') => open_claude_asset_create(app, ClaudeAssetScope::User),
+        KeyCode::Char('N') => open_claude_asset_create(app, ClaudeAssetScope::Project),
+        KeyCode::Enter | KeyCode::Char('e') => {
+            if let Some(asset) = app.claude_assets.get(app.claude_assets_index) {
+                return Some(Action::EditClaudeAsset {
+                    path: asset.path.clone(),
+                });
+            }
+        }
+        KeyCode::Char('v') => {
+            if let Some(asset) = app.claude_assets.get(app.claude_assets_index) {
+                return Some(Action::ShowClaudeAssetReport {
+                    asset: Box::new(asset.clone()),
+                });
+            }
+        }
+        KeyCode::Char('t') => {
+            if let Some(asset) = app.claude_assets.get(app.claude_assets_index) {
+                app.modal = Some(app::Modal::Input {
+                    title: format!(
+                        "Tools for '{}' (comma-separated, empty to inherit)",
+                        asset.name
+                    ),
+                    value: asset.tools.join(", "),
+                    cursor: usize::MAX,
+                    is_secret: false,
+                    action: app::InputAction::ClaudeAssetSetTools {
+                        kind: asset.kind,
+                        scope: asset.scope,
+                        name: asset.name.clone(),
+                    },
+                });
+            }
+        }
+        KeyCode::Char('m') => {
+            if let Some(asset) = app.claude_assets.get(app.claude_assets_index) {
+                let mut options = vec!["(unset)".to_string()];
+                options.extend(CLAUDE_MODEL_ALIASES.iter().map(|m| m.to_string()));
+                let index = asset
+                    .model
+                    .as_deref()
+                    .and_then(|m| options.iter().position(|o| o == m))
+                    .unwrap_or(0);
+                app.modal = Some(app::Modal::Select {
+                    title: format!("Model for '{}'", asset.name),
+                    options,
+                    index,
+                    action: app::SelectAction::ClaudeAssetSetModel {
+                        kind: asset.kind,
+                        scope: asset.scope,
+                        name: asset.name.clone(),
+                    },
+                });
+            }
+        }
+        KeyCode::Char('d') => {
+            if let Some(asset) = app.claude_assets.get(app.claude_assets_index) {
+                app.modal = Some(app::Modal::Confirm {
+                    message: format!(
+                        "Delete {} {} '{}'?",
+                        asset.scope.label(),
+                        asset.kind.label(),
+                        asset.name
+                    ),
+                    action: app::ConfirmAction::ClaudeAssetDelete {
+                        kind: asset.kind,
+                        scope: asset.scope,
+                        name: asset.name.clone(),
+                    },
+                });
+            }
+        }
+        _ => {}
+    }
+    app.clamp_indices();
+    None
+}
+
+fn open_claude_asset_create(app: &mut app::App, scope: ClaudeAssetScope) {
+    let kind = app.claude_assets_kind;
+    app.modal = Some(app::Modal::Input {
+        title: format!("New {} {} name", scope.label(), kind.label()),
+        value: String::new(),
+        cursor: 0,
+        is_secret: false,
+        action: app::InputAction::ClaudeAssetCreate { kind, scope },
+    });
+}
+
+/// Reads an asset from the current project (for project scope), applies
+/// `update` and saves it back.
+pub(super) fn update_claude_asset(
+    app: &mut app::App,
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    name: &str,
+    update: impl FnOnce(&mut droidgear_core::claude_agents::ClaudeAsset),
+) -> anyhow::Result<()> {
+    let project_dir = std::env::current_dir().ok();
+    let mut asset = droidgear_core::claude_agents::read_asset_for_home(
+        &app.home_dir,
+        kind,
+        scope,
+        project_dir.as_deref(),
+        name,
+    )
+    .map_err(anyhow::Error::msg)?;
+    update(&mut asset);
+    droidgear_core::claude_agents::save_asset_for_home(
+        &app.home_dir,
+        project_dir.as_deref(),
+        &asset,
+    )
+    .map_err(anyhow::Error::msg)?;
+    refresh_claude_assets(app);
+    app.set_toast(format!("Saved '{name}'"), false);
+    Ok(())
+}
@@ -1,4 +1,31 @@
 use super::*;
+use droidgear_core::codex::{CODEX_APPROVAL_POLICIES, CODEX_SANDBOX_MODES};
+
+/// Steps temporary runs through shared, isolated, isolated with merge on
+/// exit, and ephemeral history.
+pub(super) fn cycle_codex_history_mode(app: &mut app::App) {
+    use droidgear_core::codex_runtime::{CodexHistoryMode, CodexRunOptions};
+
+    let current = match app.codex_run_options {
+        Some(options) => options,
+        None => load_codex_run_preferences().unwrap_or_default(),
+    };
+    let next = match (current.history_mode, current.merge_history_on_exit) {
+        (CodexHistoryMode::Shared, _) => (CodexHistoryMode::Isolated, false),
+        (CodexHistoryMode::Isolated, false) => (CodexHistoryMode::Isolated, true),
+        (CodexHistoryMode::Isolated, true) => (CodexHistoryMode::Ephemeral, false),
+        (CodexHistoryMode::Ephemeral, _) => (CodexHistoryMode::Shared, false),
+    };
+    let options = CodexRunOptions {
+        history_mode: next.0,
+        merge_history_on_exit: next.1,
+    };
+    app.codex_run_options = Some(options);
+    app.set_toast(
+        format!("Temporary run history: {}", codex_history_label(&options)),
+        false,
+    );
+}
 
 pub(super) fn handle_codex_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
     match code {
@@ -21,6 +48,7 @@ pub(super) fn handle_codex_key(app: &mut app::App, code: KeyCode) -> Option<Acti
                 return Some(Action::RunCodexRun { id: p.id.clone() });
             }
         }
+        KeyCode::Char('h') => cycle_codex_history_mode(app),
         KeyCode::Char('E') => {
             if let Some(p) = app.codex_profiles.get(app.codex_index) {
                 return Some(Action::EditCodexProfile { id: p.id.clone() });
@@ -82,6 +110,51 @@ pub(super) fn handle_codex_key(app: &mut app::App, code: KeyCode) -> Option<Acti
                 });
             }
         }
+        KeyCode::Char('i') => {
+            match droidgear_core::codex_native_profiles::list_codex_native_profiles_for_home(
+                &app.home_dir,
+            ) {
+                Ok(native) if native.is_empty() => {
+                    app.set_toast("No [profiles.*] tables in config.toml", true);
+                }
+                Ok(native) => {
+                    let mut options = vec!["(all)".to_string()];
+                    options.extend(native.into_iter().map(|p| p.name));
+                    app.modal = Some(app::Modal::Select {
+                        title: "Import Codex profile from config.toml".to_string(),
+                        options,
+                        index: 0,
+                        action: app::SelectAction::CodexImportNative,
+                    });
+                }
+                Err(e) => app.set_toast(e, true),
+            }
+        }
+        KeyCode::Char('o') => {
+            if let Some(p) = app.codex_profiles.get(app.codex_index) {
+                let name = droidgear_core::codex_native_profiles::native_profile_name(&p.name);
+                let exists =
+                    droidgear_core::codex_native_profiles::list_codex_native_profiles_for_home(
+                        &app.home_dir,
+                    )
+                    .is_ok_and(|natives| natives.iter().any(|n| n.name == name));
+                let message = if exists {
+                    format!(
+                        "[profiles.{name}] already exists in config.toml. Replace it with '{}'?",
+                        p.name
+                    )
+                } else {
+                    format!("Export '{}' to config.toml as [profiles.{name}]?", p.name)
+                };
+                app.modal = Some(app::Modal::Confirm {
+                    message,
+                    action: app::ConfirmAction::CodexExportNative {
+                        id: p.id.clone(),
+                        overwrite: exists,
+                    },
+                });
+            }
+        }
         KeyCode::Char('c') => {
             if let Some(p) = app.codex_profiles.get(app.codex_index) {
                 app.modal = Some(app::Modal::Input {
@@ -350,6 +423,38 @@ pub(super) fn handle_codex_profile_key(app: &mut app::App, code: KeyCode) -> Opt
                         action: app::InputAction::CodexSetProfileApiKey { id: profile_id },
                     });
                 }
+                7 => {
+                    let mut options = vec!["(none)".to_string()];
+                    options.extend(CODEX_SANDBOX_MODES.iter().map(|m| m.to_string()));
+                    let index = profile
+                        .execution
+                        .sandbox_mode
+                        .as_deref()
+                        .and_then(|v| options.iter().position(|o| o == v))
+                        .unwrap_or(0);
+                    app.modal = Some(app::Modal::Select {
+                        title: "Sandbox mode".to_string(),
+                        options,
+                        index,
+                        action: app::SelectAction::CodexSetProfileSandboxMode { id: profile_id },
+                    });
+                }
+                8 => {
+                    let mut options = vec!["(none)".to_string()];
+                    options.extend(CODEX_APPROVAL_POLICIES.iter().map(|p| p.to_string()));
+                    let index = profile
+                        .execution
+                        .approval_policy
+                        .as_deref()
+                        .and_then(|v| options.iter().position(|o| o == v))
+                        .unwrap_or(0);
+                    app.modal = Some(app::Modal::Select {
+                        title: "Approval policy".to_string(),
+                        options,
+                        index,
+                        action: app::SelectAction::CodexSetProfileApprovalPolicy { id: profile_id },
+                    });
+                }
                 _ => {}
             },
             app::CodexDetailFocus::Providers => {
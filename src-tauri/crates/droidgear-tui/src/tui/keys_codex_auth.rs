@@ -102,6 +102,69 @@ pub(super) fn handle_codex_auth_key(app: &mut app::App, code: KeyCode) -> Option
                 });
             }
         }
+        KeyCode::Char('n') => {
+            let current = app
+                .codex_auth_active
+                .as_deref()
+                .and_then(|name| app.codex_auth_profiles.iter().find(|p| p.name == name));
+            let message = match current {
+                Some(profile) => format!(
+                    "Mark '{}' as limited and switch to the next saved login?",
+                    profile.label
+                ),
+                None => "Switch to the first saved login that is not limited?".to_string(),
+            };
+            app.modal = Some(app::Modal::Confirm {
+                message,
+                action: app::ConfirmAction::CodexAuthRotate,
+            });
+        }
+        KeyCode::Char('K') | KeyCode::Char('J') => {
+            let index = app.codex_auth_index;
+            let target = if code == KeyCode::Char('K') {
+                index.checked_sub(1)
+            } else {
+                Some(index + 1).filter(|i| *i < app.codex_auth_profiles.len())
+            };
+            let Some(target) = target else {
+                return None;
+            };
+            let mut names: Vec<String> = app
+                .codex_auth_profiles
+                .iter()
+                .map(|p| p.name.clone())
+                .collect();
+            names.swap(index, target);
+            match droidgear_core::codex_auth_profiles::set_rotation_order_for_home(
+                &app.home_dir,
+                &names,
+            ) {
+                Ok(()) => {
+                    refresh_codex_auth(app);
+                    app.codex_auth_index = target;
+                }
+                Err(e) => app.set_toast(format!("Failed to reorder: {e}"), true),
+            }
+        }
+        KeyCode::Char('c') => {
+            if let Some(profile) = app.codex_auth_profiles.get(app.codex_auth_index) {
+                if profile.limited_until.is_none() {
+                    return None;
+                }
+                let name = profile.name.clone();
+                match droidgear_core::codex_auth_profiles::set_profile_limited_until_for_home(
+                    &app.home_dir,
+                    &name,
+                    None,
+                ) {
+                    Ok(()) => {
+                        refresh_codex_auth(app);
+                        app.set_toast(format!("Cleared limit on '{name}'"), false);
+                    }
+                    Err(e) => app.set_toast(format!("Failed to clear limit: {e}"), true),
+                }
+            }
+        }
         KeyCode::Char('d') | KeyCode::Delete => {
             if let Some(profile) = app.codex_auth_profiles.get(app.codex_auth_index) {
                 if app.codex_auth_active.as_deref() != Some(&profile.name) {
@@ -20,6 +20,7 @@ pub(super) fn handle_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
         app::Screen::McpKeyValues => handle_mcp_key_values_key(app, code),
         app::Screen::ClaudeSettings => handle_claude_key(app, code),
         app::Screen::ClaudeSettingsDetail => handle_claude_settings_detail_key(app, code),
+        app::Screen::ClaudeAgents => handle_claude_agents_key(app, code),
         app::Screen::Codex => handle_codex_key(app, code),
         app::Screen::CodexProfile => handle_codex_profile_key(app, code),
         app::Screen::CodexProvider => handle_codex_provider_key(app, code),
@@ -44,7 +45,11 @@ pub(super) fn handle_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
         app::Screen::HermesProfile => handle_hermes_profile_key(app, code),
         app::Screen::HermesProvider => handle_hermes_provider_key(app, code),
         app::Screen::Sessions => handle_sessions_key(app, code),
+        app::Screen::SessionDetail => handle_session_detail_key(app, code),
+        app::Screen::SessionAnalytics => handle_session_analytics_key(app, code),
+        app::Screen::SessionRetention => handle_session_retention_key(app, code),
         app::Screen::Specs => handle_specs_key(app, code),
+        app::Screen::SpecHistory => handle_spec_history_key(app, code),
         app::Screen::Channels => handle_channels_key(app, code),
         app::Screen::ChannelsEdit => handle_channels_edit_key(app, code),
         app::Screen::Missions => handle_missions_key(app, code),
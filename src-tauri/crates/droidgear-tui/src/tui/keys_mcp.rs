@@ -1,4 +1,5 @@
 use super::*;
+use droidgear_core::mcp_sync::McpSyncTarget;
 
 pub(super) fn handle_mcp_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
     match code {
@@ -44,6 +45,42 @@ pub(super) fn handle_mcp_key(app: &mut app::App, code: KeyCode) -> Option<Action
                 });
             }
         }
+        KeyCode::Char('p') => return Some(Action::PreviewMcpSync),
+        KeyCode::Char('o') => match std::env::current_dir() {
+            Ok(project_dir) => return Some(Action::ShowEffectiveMcpServers { project_dir }),
+            Err(e) => app.set_toast(format!("Failed to read current directory: {e}"), true),
+        },
+        KeyCode::Char('m') => open_mcp_move_scope(app),
+        KeyCode::Char('i') => {
+            match droidgear_core::mcp_presets::load_mcp_presets_for_home(&app.home_dir) {
+                Ok(presets) => {
+                    app.modal = Some(app::Modal::Select {
+                        title: "Install MCP preset".to_string(),
+                        options: presets.iter().map(mcp_preset_label).collect(),
+                        index: 0,
+                        action: app::SelectAction::McpPreset { presets },
+                    });
+                }
+                Err(e) => app.set_toast(e, true),
+            }
+        }
+        KeyCode::Char('c') => {
+            if let Some(server) = app.mcp_servers.get(app.mcp_index) {
+                return Some(Action::ProbeMcpServer {
+                    server: Box::new(server.clone()),
+                });
+            }
+        }
+        KeyCode::Char('s') => {
+            let mut options = vec!["All tools".to_string()];
+            options.extend(McpSyncTarget::ALL.iter().map(|t| t.label().to_string()));
+            app.modal = Some(app::Modal::Select {
+                title: "Sync MCP servers to".to_string(),
+                options,
+                index: 0,
+                action: app::SelectAction::McpSyncTarget,
+            });
+        }
         KeyCode::Char('d') => {
             if let Some(server) = app.mcp_servers.get(app.mcp_index) {
                 app.modal = Some(app::Modal::Confirm {
@@ -88,6 +125,7 @@ pub(super) fn handle_mcp_server_key(app: &mut app::App, code: KeyCode) -> Option
                 droidgear_core::mcp::McpServerType::Stdio => {
                     server.config.url = None;
                     server.config.headers = None;
+                    server.config.header_sources = None;
 
                     server.config.command = server
                         .config
@@ -119,7 +157,8 @@ pub(super) fn handle_mcp_server_key(app: &mut app::App, code: KeyCode) -> Option
                         server.config.env = (!cleaned.is_empty()).then_some(cleaned);
                     }
                 }
-                droidgear_core::mcp::McpServerType::Http => {
+                droidgear_core::mcp::McpServerType::Http
+                | droidgear_core::mcp::McpServerType::Sse => {
                     server.config.command = None;
                     server.config.args = None;
                     server.config.env = None;
@@ -187,10 +226,11 @@ pub(super) fn handle_mcp_server_key(app: &mut app::App, code: KeyCode) -> Option
                     });
                 }
                 1 => {
-                    let options = vec!["stdio".to_string(), "http".to_string()];
+                    let options = vec!["stdio".to_string(), "http".to_string(), "sse".to_string()];
                     let index = match draft.config.server_type {
                         droidgear_core::mcp::McpServerType::Stdio => 0,
                         droidgear_core::mcp::McpServerType::Http => 1,
+                        droidgear_core::mcp::McpServerType::Sse => 2,
                     };
                     app.modal = Some(app::Modal::Select {
                         title: "Server type".to_string(),
@@ -224,50 +264,54 @@ pub(super) fn handle_mcp_server_key(app: &mut app::App, code: KeyCode) -> Option
                 }
                 _ => {}
             },
-            droidgear_core::mcp::McpServerType::Http => match app.mcp_edit_field_index {
-                0 => {
-                    app.modal = Some(app::Modal::Input {
-                        title: "Server name".to_string(),
-                        value: draft.name.clone(),
-                        cursor: usize::MAX,
-                        is_secret: false,
-                        action: app::InputAction::McpDraftSetName,
-                    });
-                }
-                1 => {
-                    let options = vec!["stdio".to_string(), "http".to_string()];
-                    let index = match draft.config.server_type {
-                        droidgear_core::mcp::McpServerType::Stdio => 0,
-                        droidgear_core::mcp::McpServerType::Http => 1,
-                    };
-                    app.modal = Some(app::Modal::Select {
-                        title: "Server type".to_string(),
-                        options,
-                        index,
-                        action: app::SelectAction::McpDraftSetType,
-                    });
-                }
-                2 => {
-                    if let Some(server) = app.mcp_edit_draft.as_mut() {
-                        server.config.disabled = !server.config.disabled;
+            droidgear_core::mcp::McpServerType::Http | droidgear_core::mcp::McpServerType::Sse => {
+                match app.mcp_edit_field_index {
+                    0 => {
+                        app.modal = Some(app::Modal::Input {
+                            title: "Server name".to_string(),
+                            value: draft.name.clone(),
+                            cursor: usize::MAX,
+                            is_secret: false,
+                            action: app::InputAction::McpDraftSetName,
+                        });
                     }
+                    1 => {
+                        let options =
+                            vec!["stdio".to_string(), "http".to_string(), "sse".to_string()];
+                        let index = match draft.config.server_type {
+                            droidgear_core::mcp::McpServerType::Stdio => 0,
+                            droidgear_core::mcp::McpServerType::Http => 1,
+                            droidgear_core::mcp::McpServerType::Sse => 2,
+                        };
+                        app.modal = Some(app::Modal::Select {
+                            title: "Server type".to_string(),
+                            options,
+                            index,
+                            action: app::SelectAction::McpDraftSetType,
+                        });
+                    }
+                    2 => {
+                        if let Some(server) = app.mcp_edit_draft.as_mut() {
+                            server.config.disabled = !server.config.disabled;
+                        }
+                    }
+                    3 => {
+                        app.modal = Some(app::Modal::Input {
+                            title: "URL".to_string(),
+                            value: draft.config.url.clone().unwrap_or_default(),
+                            cursor: usize::MAX,
+                            is_secret: false,
+                            action: app::InputAction::McpDraftSetUrl,
+                        });
+                    }
+                    4 => {
+                        app.mcp_kv_mode = app::McpKeyValuesMode::Headers;
+                        app.mcp_kv_index = 0;
+                        app.screen = app::Screen::McpKeyValues;
+                    }
+                    _ => {}
                 }
-                3 => {
-                    app.modal = Some(app::Modal::Input {
-                        title: "URL".to_string(),
-                        value: draft.config.url.clone().unwrap_or_default(),
-                        cursor: usize::MAX,
-                        is_secret: false,
-                        action: app::InputAction::McpDraftSetUrl,
-                    });
-                }
-                4 => {
-                    app.mcp_kv_mode = app::McpKeyValuesMode::Headers;
-                    app.mcp_kv_index = 0;
-                    app.screen = app::Screen::McpKeyValues;
-                }
-                _ => {}
-            },
+            }
         },
         _ => {}
     }
@@ -275,6 +319,66 @@ pub(super) fn handle_mcp_server_key(app: &mut app::App, code: KeyCode) -> Option
     None
 }
 
+pub(super) fn mcp_preset_label(preset: &droidgear_core::mcp_presets::McpPreset) -> String {
+    if preset.description.is_empty() {
+        preset.name.clone()
+    } else {
+        format!("{} - {}", preset.name, preset.description)
+    }
+}
+
+/// Offers to demote the selected user server into, or promote a server out
+/// of, the project files of the current directory.
+fn open_mcp_move_scope(app: &mut app::App) {
+    use droidgear_core::mcp::McpProjectFile;
+
+    let project_dir = match std::env::current_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            app.set_toast(format!("Failed to read current directory: {e}"), true);
+            return;
+        }
+    };
+    let projects = match droidgear_core::mcp::load_project_mcp_configs(&project_dir) {
+        Ok(projects) => projects,
+        Err(e) => {
+            app.set_toast(e, true);
+            return;
+        }
+    };
+
+    let mut moves = Vec::new();
+    if let Some(server) = app.mcp_servers.get(app.mcp_index) {
+        for file in McpProjectFile::ALL.into_iter().rev() {
+            moves.push(app::McpScopeMove {
+                name: server.name.clone(),
+                file,
+                promote: false,
+            });
+        }
+    }
+    for project in projects {
+        for server in project.servers {
+            moves.push(app::McpScopeMove {
+                name: server.name,
+                file: project.file,
+                promote: true,
+            });
+        }
+    }
+    if moves.is_empty() {
+        app.set_toast("No MCP servers to move", true);
+        return;
+    }
+
+    app.modal = Some(app::Modal::Select {
+        title: format!("Move MCP server ({})", project_dir.display()),
+        options: moves.iter().map(|m| m.label()).collect(),
+        index: 0,
+        action: app::SelectAction::McpMoveScope { project_dir, moves },
+    });
+}
+
 pub(super) fn handle_mcp_args_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
     match code {
         KeyCode::Esc | KeyCode::Char('q') => app.go_back(),
@@ -7,12 +7,36 @@ pub(super) fn handle_sessions_key(app: &mut app::App, code: KeyCode) -> Option<A
         KeyCode::Up => app.sessions_index = app.sessions_index.saturating_sub(1),
         KeyCode::Char('r') => refresh_sessions(app),
         KeyCode::Enter | KeyCode::Char('v') => {
+            if let Some(s) = app.sessions.get(app.sessions_index) {
+                match droidgear_core::sessions::get_session_detail_for_home(&app.home_dir, &s.path)
+                {
+                    Ok(detail) => app.open_session_detail(detail),
+                    Err(e) => app.set_toast(e, true),
+                }
+            }
+        }
+        KeyCode::Char('p') => {
             if let Some(s) = app.sessions.get(app.sessions_index) {
                 return Some(Action::ViewSession {
                     path: s.path.clone(),
                 });
             }
         }
+        KeyCode::Char('x') => {
+            if let Some(path) = app.sessions.get(app.sessions_index).map(|s| s.path.clone()) {
+                open_session_export(app, path);
+            }
+        }
+        KeyCode::Char('c') => {
+            app.screen = app::Screen::SessionRetention;
+            app.retention_index = 0;
+            refresh_retention(app);
+        }
+        KeyCode::Char('a') => {
+            app.screen = app::Screen::SessionAnalytics;
+            app.analytics_index = 0;
+            refresh_analytics(app);
+        }
         KeyCode::Char('d') => {
             if let Some(s) = app.sessions.get(app.sessions_index) {
                 app.modal = Some(app::Modal::Confirm {
@@ -27,3 +51,108 @@ pub(super) fn handle_sessions_key(app: &mut app::App, code: KeyCode) -> Option<A
     }
     None
 }
+
+pub(super) fn handle_session_detail_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.go_back(),
+        KeyCode::Down => {
+            app.session_detail_index = app.session_detail_index.saturating_add(1);
+        }
+        KeyCode::Up => {
+            app.session_detail_index = app.session_detail_index.saturating_sub(1);
+        }
+        KeyCode::Enter | KeyCode::Char(' ') => {
+            let row = app.session_detail_index;
+            if !app.session_detail_expanded.remove(&row) {
+                app.session_detail_expanded.insert(row);
+            }
+        }
+        KeyCode::Char('e') => {
+            app.session_detail_expanded = (0..app.session_detail_rows().len()).collect();
+        }
+        KeyCode::Char('c') => app.session_detail_expanded.clear(),
+        KeyCode::Char('p') => {
+            if let Some(path) = app.sessions.get(app.sessions_index).map(|s| s.path.clone()) {
+                return Some(Action::ViewSession { path });
+            }
+        }
+        KeyCode::Char('x') => {
+            if let Some(path) = app.sessions.get(app.sessions_index).map(|s| s.path.clone()) {
+                open_session_export(app, path);
+            }
+        }
+        _ => {}
+    }
+    None
+}
+
+pub(super) fn handle_session_analytics_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
+    use droidgear_core::session_analytics::UsageGroupBy;
+
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.go_back(),
+        KeyCode::Down => app.analytics_index = app.analytics_index.saturating_add(1),
+        KeyCode::Up => app.analytics_index = app.analytics_index.saturating_sub(1),
+        KeyCode::Char('r') => refresh_analytics(app),
+        KeyCode::Tab | KeyCode::Char('g') => {
+            let all = UsageGroupBy::ALL;
+            let pos = all
+                .iter()
+                .position(|g| *g == app.analytics_group_by)
+                .unwrap_or(0);
+            app.analytics_group_by = all[(pos + 1) % all.len()];
+            app.analytics_index = 0;
+            refresh_analytics(app);
+        }
+        KeyCode::Char('x') => {
+            app.modal = Some(app::Modal::Select {
+                title: "Export format".to_string(),
+                options: vec!["CSV".to_string(), "JSON".to_string()],
+                index: 0,
+                action: app::SelectAction::AnalyticsExportFormat {
+                    group_by: app.analytics_group_by,
+                },
+            });
+        }
+        _ => {}
+    }
+    None
+}
+
+pub(super) fn handle_session_retention_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
+    use droidgear_core::session_retention::RetentionAction;
+
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.go_back(),
+        KeyCode::Down => app.retention_index = app.retention_index.saturating_add(1),
+        KeyCode::Up => app.retention_index = app.retention_index.saturating_sub(1),
+        KeyCode::Char('r') => refresh_retention(app),
+        KeyCode::Char('e') => return Some(Action::EditRetentionPolicy),
+        KeyCode::Char('A') | KeyCode::Char('D') if !app.retention_candidates.is_empty() => {
+            let (action, verb) = if code == KeyCode::Char('A') {
+                (RetentionAction::Archive, "Archive")
+            } else {
+                (RetentionAction::Delete, "Permanently delete")
+            };
+            app.modal = Some(app::Modal::Confirm {
+                message: format!("{verb} {} sessions?", app.retention_candidates.len()),
+                action: app::ConfirmAction::SessionRetentionApply { action },
+            });
+        }
+        _ => {}
+    }
+    None
+}
+
+fn open_session_export(app: &mut app::App, session_path: String) {
+    app.modal = Some(app::Modal::Select {
+        title: "Export format".to_string(),
+        options: vec![
+            "Markdown".to_string(),
+            "HTML".to_string(),
+            "JSON".to_string(),
+        ],
+        index: 0,
+        action: app::SelectAction::SessionExportFormat { session_path },
+    });
+}
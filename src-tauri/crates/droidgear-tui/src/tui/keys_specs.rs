@@ -1,4 +1,5 @@
 use super::*;
+use droidgear_core::specs::{SpecQuery, SpecSortKey, SpecStatus};
 
 pub(super) fn handle_specs_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
     match code {
@@ -13,6 +14,147 @@ pub(super) fn handle_specs_key(app: &mut app::App, code: KeyCode) -> Option<Acti
                 });
             }
         }
+        KeyCode::Char('a') => {
+            if let Some(s) = app.specs.get(app.specs_index) {
+                let index = s
+                    .meta
+                    .status
+                    .and_then(|st| SpecStatus::ALL.iter().position(|x| *x == st))
+                    .unwrap_or(0);
+                app.modal = Some(app::Modal::Select {
+                    title: format!("Status of '{}'", s.display_title()),
+                    options: SpecStatus::ALL
+                        .iter()
+                        .map(|st| st.as_str().to_string())
+                        .collect(),
+                    index,
+                    action: app::SelectAction::SpecSetStatus {
+                        path: s.path.clone(),
+                    },
+                });
+            }
+        }
+        KeyCode::Char('f') => {
+            let mut options = vec!["all".to_string()];
+            options.extend(SpecStatus::ALL.iter().map(|st| st.as_str().to_string()));
+            let index = app
+                .spec_query
+                .status
+                .and_then(|st| SpecStatus::ALL.iter().position(|x| *x == st))
+                .map(|i| i + 1)
+                .unwrap_or(0);
+            app.modal = Some(app::Modal::Select {
+                title: "Filter specs by status".to_string(),
+                options,
+                index,
+                action: app::SelectAction::SpecStatusFilter,
+            });
+        }
+        KeyCode::Char('t') => {
+            app.modal = Some(app::Modal::Input {
+                title: "Filter specs by tag (empty for all)".to_string(),
+                value: app.spec_query.tag.clone().unwrap_or_default(),
+                cursor: usize::MAX,
+                is_secret: false,
+                action: app::InputAction::SpecTagFilter,
+            });
+        }
+        KeyCode::Char('/') => {
+            app.modal = Some(app::Modal::Input {
+                title: "Search spec names and titles".to_string(),
+                value: app.spec_query.text.clone().unwrap_or_default(),
+                cursor: usize::MAX,
+                is_secret: false,
+                action: app::InputAction::SpecTextFilter,
+            });
+        }
+        KeyCode::Char('o') => {
+            let keys = SpecSortKey::ALL;
+            let next = keys
+                .iter()
+                .position(|k| *k == app.spec_query.sort)
+                .map(|i| (i + 1) % keys.len())
+                .unwrap_or(0);
+            app.spec_query.sort = keys[next];
+            app.specs_index = 0;
+            refresh_specs(app);
+        }
+        KeyCode::Char('O') => {
+            app.spec_query.reverse = !app.spec_query.reverse;
+            app.specs_index = 0;
+            refresh_specs(app);
+        }
+        KeyCode::Char('p') => {
+            let all = droidgear_core::specs::list_spec_summaries_for_home(
+                &app.home_dir,
+                &SpecQuery::default(),
+            );
+            match all {
+                Ok(specs) => {
+                    let projects: std::collections::BTreeSet<String> =
+                        specs.into_iter().filter_map(|s| s.meta.project).collect();
+                    let mut options = vec!["all projects".to_string()];
+                    options.extend(projects);
+                    let index = app
+                        .spec_query
+                        .project
+                        .as_ref()
+                        .and_then(|p| options.iter().position(|o| o == p))
+                        .unwrap_or(0);
+                    app.modal = Some(app::Modal::Select {
+                        title: "Filter specs by project".to_string(),
+                        options,
+                        index,
+                        action: app::SelectAction::SpecProjectFilter,
+                    });
+                }
+                Err(e) => app.set_toast(e, true),
+            }
+        }
+        KeyCode::Char('l') => {
+            match droidgear_core::specs::link_specs_to_sessions_for_home(&app.home_dir) {
+                Ok(count) => {
+                    refresh_specs(app);
+                    app.set_toast(format!("Linked {count} spec(s) to sessions"), false);
+                }
+                Err(e) => app.set_toast(e, true),
+            }
+        }
+        KeyCode::Char('g') => {
+            let Some(s) = app.specs.get(app.specs_index) else {
+                return None;
+            };
+            let Some(session_id) = s.meta.session_id.clone() else {
+                app.set_toast("No session linked (press l to link)", true);
+                return None;
+            };
+            match droidgear_core::sessions::find_session_path_for_home(&app.home_dir, &session_id) {
+                Ok(Some(path)) => return Some(Action::ViewSession { path }),
+                Ok(None) => app.set_toast(format!("Session {session_id} not found"), true),
+                Err(e) => app.set_toast(e, true),
+            }
+        }
+        KeyCode::Char('n') => {
+            match droidgear_core::spec_templates::list_spec_templates_for_home(&app.home_dir) {
+                Ok(templates) => {
+                    app.modal = Some(app::Modal::Select {
+                        title: "New spec from template".to_string(),
+                        options: templates.into_iter().map(|t| t.name).collect(),
+                        index: 0,
+                        action: app::SelectAction::SpecTemplate,
+                    });
+                }
+                Err(e) => app.set_toast(e, true),
+            }
+        }
+        KeyCode::Char('h') => {
+            if let Some(s) = app.specs.get(app.specs_index) {
+                app.spec_history_name = s.name.clone();
+                app.spec_revisions_index = 0;
+                app.screen = app::Screen::SpecHistory;
+                refresh_spec_history(app);
+            }
+        }
         KeyCode::Char('d') => {
             if let Some(s) = app.specs.get(app.specs_index) {
                 app.modal = Some(app::Modal::Confirm {
@@ -27,3 +169,55 @@ pub(super) fn handle_specs_key(app: &mut app::App, code: KeyCode) -> Option<Acti
     }
     None
 }
+
+pub(super) fn handle_spec_history_key(app: &mut app::App, code: KeyCode) -> Option<Action> {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.go_back(),
+        KeyCode::Down => app.spec_revisions_index = app.spec_revisions_index.saturating_add(1),
+        KeyCode::Up => app.spec_revisions_index = app.spec_revisions_index.saturating_sub(1),
+        KeyCode::Char('r') => refresh_spec_history(app),
+        KeyCode::Enter | KeyCode::Char('c') => {
+            if let Some(rev) = app.spec_revisions.get(app.spec_revisions_index) {
+                return Some(Action::ShowSpecDiff {
+                    name: app.spec_history_name.clone(),
+                    from: rev.id,
+                    to: None,
+                });
+            }
+        }
+        KeyCode::Char('p') => {
+            // Revisions are newest first, so the previous one is the next row.
+            let index = app.spec_revisions_index;
+            match (
+                app.spec_revisions.get(index),
+                app.spec_revisions.get(index + 1),
+            ) {
+                (Some(rev), Some(prev)) => {
+                    return Some(Action::ShowSpecDiff {
+                        name: app.spec_history_name.clone(),
+                        from: prev.id,
+                        to: Some(rev.id),
+                    });
+                }
+                (Some(_), None) => app.set_toast("No earlier revision", true),
+                _ => {}
+            }
+        }
+        KeyCode::Char('R') => {
+            if let Some(rev) = app.spec_revisions.get(app.spec_revisions_index) {
+                app.modal = Some(app::Modal::Confirm {
+                    message: format!(
+                        "Restore revision {} of '{}'?",
+                        rev.id, app.spec_history_name
+                    ),
+                    action: app::ConfirmAction::SpecRestoreRevision {
+                        name: app.spec_history_name.clone(),
+                        id: rev.id,
+                    },
+                });
+            }
+        }
+        _ => {}
+    }
+    None
+}
@@ -7,18 +7,21 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use droidgear_core::file_watcher::{WatchEvent, WatchKind};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use serde::{de::DeserializeOwned, Serialize};
 use similar::TextDiff;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::rc::Rc;
 use std::time::Duration;
 use tempfile::{NamedTempFile, TempDir};
 
 mod actions;
 mod keys_channels;
 mod keys_claude;
+mod keys_claude_agents;
 mod keys_codex;
 mod keys_codex_auth;
 mod keys_droid_settings;
@@ -43,6 +46,7 @@ mod utils;
 #[cfg(test)]
 mod tests;
 
+pub use utils::codex_cli_run_options;
 pub use utils::list_claude_temporary_run_targets;
 pub use utils::list_codex_temporary_run_targets;
 pub use utils::list_droid_temporary_run_targets;
@@ -50,10 +54,12 @@ pub use utils::preview_claude_temporary_run_from_file;
 pub use utils::run_claude_temporary_run_from_file;
 pub use utils::run_codex_temporary_run_for_selector;
 pub use utils::run_droid_temporary_run_for_settings_name;
+pub use utils::run_session_cleanup;
 
 use actions::{read_to_string_if_exists, run_action};
 use keys_channels::{handle_channels_edit_key, handle_channels_key};
 use keys_claude::{handle_claude_key, handle_claude_settings_detail_key};
+use keys_claude_agents::{handle_claude_agents_key, update_claude_asset};
 use keys_codex::{handle_codex_key, handle_codex_profile_key, handle_codex_provider_key};
 use keys_droid_settings::handle_droid_settings_files_key;
 use keys_factory::{handle_factory_key, handle_factory_model_key, normalize_factory_models};
@@ -75,16 +81,22 @@ use keys_opencode::{
 };
 use keys_paths::handle_paths_key;
 use keys_pi::{handle_pi_key, handle_pi_model_key, handle_pi_profile_key, handle_pi_provider_key};
-use keys_sessions::handle_sessions_key;
-use keys_specs::handle_specs_key;
+use keys_sessions::{
+    handle_session_analytics_key, handle_session_detail_key, handle_session_retention_key,
+    handle_sessions_key,
+};
+use keys_specs::{handle_spec_history_key, handle_specs_key};
 use keys_trusted_folders::handle_trusted_folders_key;
 use modal::handle_modal_key;
 use refresh::*;
 use utils::{
-    apply_factory_reasoning, factory_model_id, factory_reasoning_effort, factory_reasoning_format,
-    insert_char_at, preview_codex_apply, preview_codex_temporary_run, preview_droid_temporary_run,
-    preview_openclaw_apply, preview_opencode_apply, remove_char_at, run_codex_temporary_run,
-    run_droid_temporary_run,
+    apply_factory_reasoning, codex_history_label, factory_model_id, factory_reasoning_effort,
+    factory_reasoning_format, format_claude_asset_report, format_claude_effective_settings,
+    format_claude_permission_decision, format_claude_permission_report,
+    format_effective_mcp_servers, format_mcp_probe_report, format_mcp_sync_summary, insert_char_at,
+    load_codex_run_preferences, now_ms, preview_codex_apply, preview_codex_temporary_run,
+    preview_droid_temporary_run, preview_mcp_sync, preview_openclaw_apply, preview_opencode_apply,
+    remove_char_at, run_codex_temporary_run, run_droid_temporary_run,
 };
 
 type UiTerminal = Terminal<CrosstermBackend<io::Stdout>>;
@@ -153,12 +165,37 @@ enum Action {
         provider_id: String,
         config: Box<droidgear_core::pi::PiProviderConfig>,
     },
+    PreviewMcpSync,
+    ShowEffectiveMcpServers {
+        project_dir: PathBuf,
+    },
+    ShowClaudePermissionReport {
+        name: String,
+    },
+    ShowClaudeEffectiveSettings {
+        project_dir: PathBuf,
+    },
+    EditClaudeAsset {
+        path: String,
+    },
+    ShowClaudeAssetReport {
+        asset: Box<droidgear_core::claude_agents::ClaudeAsset>,
+    },
+    ProbeMcpServer {
+        server: Box<droidgear_core::mcp::McpServer>,
+    },
     ViewSession {
         path: String,
     },
+    EditRetentionPolicy,
     EditSpec {
         path: String,
     },
+    ShowSpecDiff {
+        name: String,
+        from: u32,
+        to: Option<u32>,
+    },
     EditChannels,
     EditChannelAuth {
         id: String,
@@ -177,8 +214,16 @@ pub fn run(app: &mut app::App) -> anyhow::Result<()> {
     let mut terminal = Terminal::new(backend).context("create terminal")?;
 
     refresh_screen_data(app);
+    if app.watcher.is_none() {
+        match droidgear_core::file_watcher::start_file_watcher_for_home(&app.home_dir) {
+            Ok(watcher) => app.watcher = Some(Rc::new(watcher)),
+            Err(e) => app.set_toast(format!("File watching disabled: {e}"), true),
+        }
+    }
 
     while !app.should_quit {
+        let events = collect_watch_events(app);
+        apply_watch_events(app, &events);
         app.clamp_indices();
         terminal.draw(|f| ui::draw(f, app)).context("draw")?;
 
@@ -251,6 +296,7 @@ fn refresh_screen_data(app: &mut app::App) {
         app::Screen::Mcp => refresh_mcp(app),
         app::Screen::McpServer | app::Screen::McpArgs | app::Screen::McpKeyValues => {}
         app::Screen::ClaudeSettings => refresh_claude(app),
+        app::Screen::ClaudeAgents => refresh_claude_assets(app),
         app::Screen::ClaudeSettingsDetail => {
             // The detail JSON is managed in-memory by modal handlers;
             // re-reading the file here would overwrite unsaved edits.
@@ -294,7 +340,13 @@ fn refresh_screen_data(app: &mut app::App) {
             refresh_hermes_detail(app);
         }
         app::Screen::Sessions => refresh_sessions(app),
+        app::Screen::SessionDetail => {
+            // Expanded/collapsed state lives in memory; keep it across redraws.
+        }
+        app::Screen::SessionAnalytics => refresh_analytics(app),
+        app::Screen::SessionRetention => refresh_retention(app),
         app::Screen::Specs => refresh_specs(app),
+        app::Screen::SpecHistory => refresh_spec_history(app),
         app::Screen::Channels => refresh_channels(app),
         app::Screen::ChannelsEdit => {}
         app::Screen::Missions => refresh_missions(app),
@@ -278,6 +278,21 @@ pub(super) fn handle_modal_key(app: &mut app::App, code: KeyCode, modal: app::Mo
     }
 }
 
+fn codex_set_execution_choice(
+    app: &mut app::App,
+    id: &str,
+    selected: Option<String>,
+    set: impl FnOnce(&mut droidgear_core::codex::CodexExecutionSettings, Option<String>),
+) -> anyhow::Result<()> {
+    let mut profile = droidgear_core::codex::get_codex_profile_for_home(&app.home_dir, id)
+        .map_err(anyhow::Error::msg)?;
+    set(&mut profile.execution, selected.filter(|v| v != "(none)"));
+    droidgear_core::codex::save_codex_profile_for_home_and_apply_if_active(&app.home_dir, profile)
+        .map_err(anyhow::Error::msg)?;
+    app.set_toast("Saved", false);
+    Ok(())
+}
+
 pub(super) fn run_select_action(
     app: &mut app::App,
     action: app::SelectAction,
@@ -353,6 +368,10 @@ pub(super) fn run_select_action(
             app.claude_detail_dirty = true;
             Ok(())
         }
+        app::SelectAction::ClaudeAssetSetModel { kind, scope, name } => {
+            let model = selected.filter(|m| m != "(unset)");
+            update_claude_asset(app, kind, scope, &name, |asset| asset.model = model)
+        }
         app::SelectAction::ClaudeSettingsSetThinkingMode => {
             let Some(ref mut json) = app.claude_detail_json else {
                 return Ok(());
@@ -651,6 +670,26 @@ pub(super) fn run_select_action(
             app.set_toast("Saved", false);
             Ok(())
         }
+        app::SelectAction::CodexImportNative => {
+            let names = match selected.as_deref() {
+                Some("(all)") | None => Vec::new(),
+                Some(name) => vec![name.to_string()],
+            };
+            let imported =
+                droidgear_core::codex_native_profiles::import_codex_native_profiles_for_home(
+                    &app.home_dir,
+                    &names,
+                )
+                .map_err(anyhow::Error::msg)?;
+            app.set_toast(format!("Imported {} profile(s)", imported.len()), false);
+            Ok(())
+        }
+        app::SelectAction::CodexSetProfileSandboxMode { id } => {
+            codex_set_execution_choice(app, &id, selected, |e, v| e.sandbox_mode = v)
+        }
+        app::SelectAction::CodexSetProfileApprovalPolicy { id } => {
+            codex_set_execution_choice(app, &id, selected, |e, v| e.approval_policy = v)
+        }
         app::SelectAction::CodexSetProviderWireApi {
             profile_id,
             provider_id,
@@ -936,6 +975,7 @@ pub(super) fn run_select_action(
 
             let server_type = match selected.as_str() {
                 "http" => droidgear_core::mcp::McpServerType::Http,
+                "sse" => droidgear_core::mcp::McpServerType::Sse,
                 _ => droidgear_core::mcp::McpServerType::Stdio,
             };
             server.config.server_type = server_type.clone();
@@ -944,8 +984,10 @@ pub(super) fn run_select_action(
                 droidgear_core::mcp::McpServerType::Stdio => {
                     server.config.url = None;
                     server.config.headers = None;
+                    server.config.header_sources = None;
                 }
-                droidgear_core::mcp::McpServerType::Http => {
+                droidgear_core::mcp::McpServerType::Http
+                | droidgear_core::mcp::McpServerType::Sse => {
                     server.config.command = None;
                     server.config.args = None;
                     server.config.env = None;
@@ -1576,6 +1618,176 @@ pub(super) fn run_select_action(
                 .map_err(anyhow::Error::msg)?;
             Ok(())
         }
+        app::SelectAction::SessionExportFormat { session_path } => {
+            use droidgear_core::session_export::SessionExportFormat;
+
+            let format = match selected.as_deref() {
+                Some("HTML") => SessionExportFormat::Html,
+                Some("JSON") => SessionExportFormat::Json,
+                Some(_) => SessionExportFormat::Markdown,
+                None => return Ok(()),
+            };
+            // Default to `<cwd>/<session-id>.<ext>`.
+            let file_name = format!(
+                "{}.{}",
+                Path::new(&session_path)
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("session"),
+                format.extension()
+            );
+            let value = std::env::current_dir()
+                .map(|d| d.join(&file_name))
+                .unwrap_or_else(|_| PathBuf::from(&file_name))
+                .to_string_lossy()
+                .to_string();
+            app.modal = Some(app::Modal::Input {
+                title: "Export to (keys, home paths and emails are redacted)".to_string(),
+                value,
+                cursor: usize::MAX,
+                is_secret: false,
+                action: app::InputAction::SessionExportPath {
+                    session_path,
+                    format,
+                },
+            });
+            Ok(())
+        }
+        app::SelectAction::McpSyncTarget => {
+            use droidgear_core::mcp_sync::McpSyncTarget;
+
+            let targets: Vec<McpSyncTarget> = match selected.as_deref() {
+                Some("All tools") => McpSyncTarget::ALL.to_vec(),
+                Some(label) => McpSyncTarget::ALL
+                    .into_iter()
+                    .filter(|t| t.label() == label)
+                    .collect(),
+                None => return Ok(()),
+            };
+            let previews =
+                droidgear_core::mcp_sync::preview_mcp_sync_for_home(&app.home_dir, &targets)
+                    .map_err(anyhow::Error::msg)?;
+            let summary = previews
+                .iter()
+                .map(format_mcp_sync_summary)
+                .collect::<Vec<_>>()
+                .join("; ");
+            app.modal = Some(app::Modal::Confirm {
+                message: format!("Sync MCP servers? {summary}"),
+                action: app::ConfirmAction::McpSync { targets },
+            });
+            Ok(())
+        }
+        app::SelectAction::McpMoveScope { project_dir, moves } => {
+            let Some(scope_move) = moves
+                .into_iter()
+                .find(|m| selected.as_deref() == Some(m.label().as_str()))
+            else {
+                return Ok(());
+            };
+            app.modal = Some(app::Modal::Confirm {
+                message: format!("{}?", scope_move.label()),
+                action: app::ConfirmAction::McpMoveScope {
+                    project_dir,
+                    scope_move,
+                },
+            });
+            Ok(())
+        }
+        app::SelectAction::McpPreset { presets } => {
+            let Some(preset) = presets.into_iter().find(|p| {
+                selected.as_deref() == Some(super::keys_mcp::mcp_preset_label(p).as_str())
+            }) else {
+                return Ok(());
+            };
+            if let [variant] = preset.variants.as_slice() {
+                let variant_id = variant.id.clone();
+                open_mcp_preset_name(app, preset, variant_id);
+                return Ok(());
+            }
+            app.modal = Some(app::Modal::Select {
+                title: format!("{} variant", preset.name),
+                options: preset
+                    .variants
+                    .iter()
+                    .map(|v| match &v.description {
+                        Some(d) => format!("{} - {d}", v.id),
+                        None => v.id.clone(),
+                    })
+                    .collect(),
+                index: 0,
+                action: app::SelectAction::McpPresetVariant {
+                    preset: Box::new(preset),
+                },
+            });
+            Ok(())
+        }
+        app::SelectAction::SpecStatusFilter => {
+            let Some(selected) = selected else {
+                return Ok(());
+            };
+            app.spec_query.status = droidgear_core::specs::SpecStatus::parse(&selected);
+            app.specs_index = 0;
+            Ok(())
+        }
+        app::SelectAction::SpecTemplate => {
+            let Some(template) = selected else {
+                return Ok(());
+            };
+            app.modal = Some(spec_name_modal(template, String::new()));
+            Ok(())
+        }
+        app::SelectAction::SpecProjectFilter => {
+            let Some(selected) = selected else {
+                return Ok(());
+            };
+            app.spec_query.project = (selected != "all projects").then_some(selected);
+            app.specs_index = 0;
+            Ok(())
+        }
+        app::SelectAction::SpecSetStatus { path } => {
+            let Some(status) = selected
+                .as_deref()
+                .and_then(droidgear_core::specs::SpecStatus::parse)
+            else {
+                return Ok(());
+            };
+            droidgear_core::specs::set_spec_status_for_home(&app.home_dir, &path, status)
+                .map_err(anyhow::Error::msg)?;
+            app.set_toast(format!("Marked {}", status.as_str()), false);
+            Ok(())
+        }
+        app::SelectAction::McpPresetVariant { preset } => {
+            let Some(selected) = selected else {
+                return Ok(());
+            };
+            let variant_id = selected.split(" - ").next().unwrap_or_default().to_string();
+            open_mcp_preset_name(app, *preset, variant_id);
+            Ok(())
+        }
+        app::SelectAction::AnalyticsExportFormat { group_by } => {
+            use droidgear_core::session_analytics::UsageExportFormat;
+
+            let format = match selected.as_deref() {
+                Some("JSON") => UsageExportFormat::Json,
+                Some(_) => UsageExportFormat::Csv,
+                None => return Ok(()),
+            };
+            let file_name = format!("token-usage-by-{}.{}", group_by.label(), format.extension());
+            let value = std::env::current_dir()
+                .map(|d| d.join(&file_name))
+                .unwrap_or_else(|_| PathBuf::from(&file_name))
+                .to_string_lossy()
+                .to_string();
+            app.modal = Some(app::Modal::Input {
+                title: "Export usage report to".to_string(),
+                value,
+                cursor: usize::MAX,
+                is_secret: false,
+                action: app::InputAction::AnalyticsExportPath { group_by, format },
+            });
+            Ok(())
+        }
     }
 }
 
@@ -1610,6 +1822,15 @@ fn claude_import_fetch_models(
     }
 }
 
+/// Reports a Claude settings merge, listing any warnings it returned.
+fn set_merge_toast(app: &mut app::App, done: &str, warnings: &[String]) {
+    if warnings.is_empty() {
+        app.set_toast(done, false);
+    } else {
+        app.set_toast(format!("{done}; {}", warnings.join("; ")), true);
+    }
+}
+
 /// Try to resolve an API key from a channel's stored authentication.
 /// First checks for a stored API key (CliProxyApi/Ollama/General),
 /// then tries credentials to fetch a live token (NewApi/Sub2Api).
@@ -1691,18 +1912,53 @@ pub(super) fn run_confirm_action(
                 .map_err(anyhow::Error::msg)?;
             Ok(())
         }
+        app::ConfirmAction::ClaudeAssetDelete { kind, scope, name } => {
+            let project_dir = std::env::current_dir().ok();
+            droidgear_core::claude_agents::delete_asset_for_home(
+                &app.home_dir,
+                kind,
+                scope,
+                project_dir.as_deref(),
+                &name,
+            )
+            .map_err(anyhow::Error::msg)?;
+            refresh_claude_assets(app);
+            app.set_toast(format!("Deleted '{name}'"), false);
+            Ok(())
+        }
         app::ConfirmAction::TrustedFoldersDelete { paths } => {
             droidgear_core::trusted_folders::remove_trusted_folders_for_home(&app.home_dir, &paths)
                 .map_err(anyhow::Error::msg)?;
             Ok(())
         }
+        app::ConfirmAction::FactoryModelsOverwrite { models } => {
+            droidgear_core::factory_settings::save_custom_models_for_home(&app.home_dir, models)
+                .map_err(anyhow::Error::msg)?;
+            app.set_toast("Saved", false);
+            Ok(())
+        }
         app::ConfirmAction::ClaudeSettingsApply { name } => {
-            droidgear_core::claude_settings_files::merge_settings_file_to_global_for_home(
+            let warnings =
+                droidgear_core::claude_settings_files::merge_settings_file_to_global_for_home(
+                    &app.home_dir,
+                    &name,
+                )
+                .map_err(anyhow::Error::msg)?;
+            set_merge_toast(app, "Merged into global", &warnings);
+            Ok(())
+        }
+        app::ConfirmAction::ClaudeSettingsApplyAppend { name } => {
+            let warnings = droidgear_core::claude_settings_files::merge_settings_file_to_global_with_options_for_home(
                 &app.home_dir,
                 &name,
+                &droidgear_core::claude_settings_sections::ClaudeMergeOptions::appending(),
             )
             .map_err(anyhow::Error::msg)?;
-            app.set_toast("Merged into global", false);
+            set_merge_toast(
+                app,
+                "Merged into global (hooks and permissions appended)",
+                &warnings,
+            );
             Ok(())
         }
         app::ConfirmAction::ClaudeSettingsDelete { name } => {
@@ -1717,6 +1973,19 @@ pub(super) fn run_confirm_action(
             super::keys_claude::exit_claude_detail(app);
             Ok(())
         }
+        app::ConfirmAction::CodexExportNative { id, overwrite } => {
+            let exported =
+                droidgear_core::codex_native_profiles::export_codex_profiles_to_native_for_home(
+                    &app.home_dir,
+                    &[id],
+                    overwrite,
+                )
+                .map_err(anyhow::Error::msg)?;
+            if let Some(native) = exported.first() {
+                app.set_toast(format!("Exported: codex --profile {}", native.name), false);
+            }
+            Ok(())
+        }
         app::ConfirmAction::CodexApply { id } => {
             droidgear_core::codex::apply_codex_profile_for_home(&app.home_dir, &id)
                 .map_err(anyhow::Error::msg)?;
@@ -1893,6 +2162,36 @@ pub(super) fn run_confirm_action(
                 .map_err(anyhow::Error::msg)?;
             Ok(())
         }
+        app::ConfirmAction::McpSync { targets } => {
+            let results =
+                droidgear_core::mcp_sync::sync_mcp_servers_for_home(&app.home_dir, &targets)
+                    .map_err(anyhow::Error::msg)?;
+            let changed = results.iter().filter(|r| r.has_changes()).count();
+            app.set_toast(
+                format!("Synced MCP servers ({changed} files changed)"),
+                false,
+            );
+            Ok(())
+        }
+        app::ConfirmAction::McpMoveScope {
+            project_dir,
+            scope_move,
+        } => {
+            let move_server = if scope_move.promote {
+                droidgear_core::mcp::promote_mcp_server_for_home
+            } else {
+                droidgear_core::mcp::demote_mcp_server_for_home
+            };
+            move_server(
+                &app.home_dir,
+                &project_dir,
+                scope_move.file,
+                &scope_move.name,
+            )
+            .map_err(anyhow::Error::msg)?;
+            app.set_toast(format!("Moved {}", scope_move.name), false);
+            Ok(())
+        }
         app::ConfirmAction::FactorySetDefaultModel { model_id } => {
             droidgear_core::factory_settings::save_default_model_for_home(&app.home_dir, &model_id)
                 .map_err(anyhow::Error::msg)?;
@@ -1914,11 +2213,39 @@ pub(super) fn run_confirm_action(
             droidgear_core::sessions::delete_session(&path).map_err(anyhow::Error::msg)?;
             Ok(())
         }
+        app::ConfirmAction::SessionRetentionApply { action } => {
+            let policy =
+                droidgear_core::session_retention::load_retention_policy_for_home(&app.home_dir)
+                    .map_err(anyhow::Error::msg)?;
+            let report = droidgear_core::session_retention::apply_retention_for_home(
+                &app.home_dir,
+                &policy,
+                action,
+                false,
+                now_ms(),
+            )
+            .map_err(anyhow::Error::msg)?;
+            let count = report.candidates.len();
+            match report.archive_path {
+                Some(archive) => {
+                    app.set_toast(format!("Archived {count} sessions to {archive}"), false)
+                }
+                None => app.set_toast(format!("Deleted {count} sessions"), false),
+            }
+            Ok(())
+        }
         app::ConfirmAction::SpecDelete { path } => {
             droidgear_core::specs::delete_spec_for_home(&app.home_dir, &path)
                 .map_err(anyhow::Error::msg)?;
             Ok(())
         }
+        app::ConfirmAction::SpecRestoreRevision { name, id } => {
+            droidgear_core::specs_history::restore_spec_revision_for_home(&app.home_dir, &name, id)
+                .map_err(anyhow::Error::msg)?;
+            app.spec_revisions_index = 0;
+            app.set_toast(format!("Restored revision {id}"), false);
+            Ok(())
+        }
         app::ConfirmAction::ChannelDelete { id } => {
             let mut channels = droidgear_core::channel::load_channels_for_home(&app.home_dir)
                 .map_err(anyhow::Error::msg)?;
@@ -2135,6 +2462,20 @@ pub(super) fn run_confirm_action(
             .map_err(anyhow::Error::msg)?;
             Ok(())
         }
+        app::ConfirmAction::CodexAuthRotate => {
+            let rotation =
+                droidgear_core::codex_auth_profiles::rotate_profile_for_home(&app.home_dir, None)
+                    .map_err(anyhow::Error::msg)?;
+            let mut message = format!("Rotated to auth profile '{}'", rotation.to);
+            if !rotation.skipped.is_empty() {
+                message.push_str(&format!(
+                    " (skipped limited: {})",
+                    rotation.skipped.join(", ")
+                ));
+            }
+            app.set_toast(message, false);
+            Ok(())
+        }
     }
 }
 
@@ -2195,6 +2536,50 @@ pub(super) fn run_input_action(
             }
             Ok(())
         }
+        app::InputAction::ClaudeAssetCreate { kind, scope } => {
+            let project_dir = std::env::current_dir().ok();
+            droidgear_core::claude_agents::create_asset_for_home(
+                &app.home_dir,
+                kind,
+                scope,
+                project_dir.as_deref(),
+                trimmed,
+            )
+            .map_err(anyhow::Error::msg)?;
+            refresh_claude_assets(app);
+            if let Some(index) = app
+                .claude_assets
+                .iter()
+                .position(|a| a.scope == scope && a.name == trimmed)
+            {
+                app.claude_assets_index = index;
+            }
+            app.set_toast(format!("Created '{trimmed}'"), false);
+            Ok(())
+        }
+        app::InputAction::ClaudeAssetSetTools { kind, scope, name } => {
+            let tools: Vec<String> = trimmed
+                .split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect();
+            update_claude_asset(app, kind, scope, &name, |asset| asset.tools = tools)
+        }
+        app::InputAction::ClaudeSimulatePermission { name } => {
+            if trimmed.is_empty() {
+                return Err(anyhow::Error::msg("Tool call is required"));
+            }
+            let project_dir = std::env::current_dir().ok();
+            let decision = droidgear_core::claude_permissions::simulate_permission_for_home(
+                &app.home_dir,
+                project_dir.as_deref(),
+                Some(&name),
+                trimmed,
+            )
+            .map_err(anyhow::Error::msg)?;
+            app.set_toast(format_claude_permission_decision(trimmed, &decision), false);
+            Ok(())
+        }
         app::InputAction::ClaudeSettingsEditField { field_index } => {
             let Some(ref mut json) = app.claude_detail_json else {
                 return Ok(());
@@ -2325,6 +2710,10 @@ pub(super) fn run_input_action(
                 model_reasoning_effort: Some("high".to_string()),
                 api_key: Some(String::new()),
                 auth_profile_name: None,
+                execution: Default::default(),
+                mcp_servers: Vec::new(),
+                features: Default::default(),
+                web_search: None,
             };
 
             droidgear_core::codex::save_codex_profile_for_home_and_apply_if_active(
@@ -3367,12 +3756,7 @@ pub(super) fn run_input_action(
                 name: trimmed.to_string(),
                 config: droidgear_core::mcp::McpServerConfig {
                     server_type: droidgear_core::mcp::McpServerType::Stdio,
-                    disabled: false,
-                    command: None,
-                    args: None,
-                    env: None,
-                    url: None,
-                    headers: None,
+                    ..Default::default()
                 },
             });
             app.mcp_edit_field_index = 0;
@@ -3381,6 +3765,69 @@ pub(super) fn run_input_action(
             app.screen = app::Screen::McpServer;
             Ok(())
         }
+        app::InputAction::McpPresetName { preset, variant_id } => {
+            if trimmed.is_empty() {
+                return Err(anyhow::Error::msg("Server name is required"));
+            }
+            prompt_mcp_preset_input(
+                app,
+                app::McpPresetInstall {
+                    preset: *preset,
+                    variant_id,
+                    name: trimmed.to_string(),
+                    values: std::collections::HashMap::new(),
+                    next: 0,
+                },
+            )
+        }
+        app::InputAction::McpPresetInput { mut install } => {
+            let input = install.preset.inputs[install.next].clone();
+            if let Err(e) = droidgear_core::mcp_presets::validate_mcp_preset_input(
+                &app.home_dir,
+                &input,
+                &value,
+            ) {
+                // Ask again rather than dropping the whole install.
+                app.modal = Some(mcp_preset_input_modal(&input, value, install));
+                return Err(anyhow::Error::msg(e));
+            }
+            install.values.insert(input.id, value);
+            install.next += 1;
+            prompt_mcp_preset_input(app, *install)
+        }
+        app::InputAction::SpecTagFilter => {
+            app.spec_query.tag = (!trimmed.is_empty()).then(|| trimmed.to_string());
+            app.specs_index = 0;
+            Ok(())
+        }
+        app::InputAction::SpecFromTemplate { template } => {
+            let vars = droidgear_core::spec_templates::SpecTemplateVars {
+                project: std::env::current_dir()
+                    .ok()
+                    .map(|d| d.to_string_lossy().to_string()),
+                ..Default::default()
+            };
+            match droidgear_core::spec_templates::create_spec_from_template_for_home(
+                &app.home_dir,
+                &template,
+                trimmed,
+                &vars,
+            ) {
+                Ok(spec) => {
+                    app.set_toast(format!("Created {}", spec.name), false);
+                    Ok(())
+                }
+                Err(e) => {
+                    app.modal = Some(spec_name_modal(template, value));
+                    Err(anyhow::Error::msg(e))
+                }
+            }
+        }
+        app::InputAction::SpecTextFilter => {
+            app.spec_query.text = (!trimmed.is_empty()).then(|| trimmed.to_string());
+            app.specs_index = 0;
+            Ok(())
+        }
         app::InputAction::McpDraftSetName => {
             if trimmed.is_empty() {
                 return Err(anyhow::Error::msg("Server name is required"));
@@ -4298,5 +4745,129 @@ pub(super) fn run_input_action(
             app.set_toast("Renamed", false);
             Ok(())
         }
+        app::InputAction::SessionExportPath {
+            session_path,
+            format,
+        } => {
+            if trimmed.is_empty() {
+                return Err(anyhow::Error::msg("Output path is required"));
+            }
+            let options = droidgear_core::session_export::SessionExportOptions {
+                format,
+                redaction: Default::default(),
+            };
+            droidgear_core::session_export::export_session_to_file_for_home(
+                &app.home_dir,
+                &session_path,
+                &options,
+                Path::new(trimmed),
+            )
+            .map_err(anyhow::Error::msg)?;
+            app.set_toast(format!("Exported to {trimmed}"), false);
+            Ok(())
+        }
+        app::InputAction::AnalyticsExportPath { group_by, format } => {
+            if trimmed.is_empty() {
+                return Err(anyhow::Error::msg("Output path is required"));
+            }
+            droidgear_core::session_analytics::export_usage_report_for_home(
+                &app.home_dir,
+                group_by,
+                format,
+                Path::new(trimmed),
+            )
+            .map_err(anyhow::Error::msg)?;
+            app.set_toast(format!("Exported to {trimmed}"), false);
+            Ok(())
+        }
+    }
+}
+
+fn open_mcp_preset_name(
+    app: &mut app::App,
+    preset: droidgear_core::mcp_presets::McpPreset,
+    variant_id: String,
+) {
+    app.modal = Some(app::Modal::Input {
+        title: "Server name".to_string(),
+        value: preset.id.clone(),
+        cursor: usize::MAX,
+        is_secret: false,
+        action: app::InputAction::McpPresetName {
+            preset: Box::new(preset),
+            variant_id,
+        },
+    });
+}
+
+fn mcp_preset_input_modal(
+    input: &droidgear_core::mcp_presets::McpPresetInput,
+    value: String,
+    install: Box<app::McpPresetInstall>,
+) -> app::Modal {
+    use droidgear_core::mcp_presets::McpPresetInputKind;
+
+    let mut hints = Vec::new();
+    if !input.required {
+        hints.push("optional".to_string());
+    }
+    if let Some(default) = &input.default {
+        hints.push(format!("default {default}"));
+    } else if let Some(placeholder) = &input.placeholder {
+        hints.push(placeholder.clone());
+    }
+    if let Some(url) = &input.help_url {
+        hints.push(url.clone());
+    }
+    let title = if hints.is_empty() {
+        input.label.clone()
+    } else {
+        format!("{} ({})", input.label, hints.join(", "))
+    };
+    app::Modal::Input {
+        title,
+        value,
+        cursor: usize::MAX,
+        is_secret: input.kind == McpPresetInputKind::Secret,
+        action: app::InputAction::McpPresetInput { install },
+    }
+}
+
+/// Prompts for the next preset input, or installs once all are collected.
+fn prompt_mcp_preset_input(
+    app: &mut app::App,
+    install: app::McpPresetInstall,
+) -> anyhow::Result<()> {
+    if let Some(input) = install.preset.inputs.get(install.next).cloned() {
+        app.modal = Some(mcp_preset_input_modal(
+            &input,
+            String::new(),
+            Box::new(install),
+        ));
+        return Ok(());
+    }
+    let server = droidgear_core::mcp_presets::install_mcp_preset_for_home(
+        &app.home_dir,
+        &install.preset.id,
+        &install.variant_id,
+        &install.name,
+        &install.values,
+    )
+    .map_err(anyhow::Error::msg)?;
+    refresh_mcp(app);
+    if let Some(idx) = app.mcp_servers.iter().position(|s| s.name == server.name) {
+        app.mcp_index = idx;
+    }
+    app.set_toast(format!("Installed MCP server {}", server.name), false);
+    Ok(())
+}
+
+fn spec_name_modal(template: String, value: String) -> app::Modal {
+    app::Modal::Input {
+        title: format!("New spec name (template: {template})"),
+        value,
+        cursor: usize::MAX,
+        is_secret: false,
+        action: app::InputAction::SpecFromTemplate { template },
     }
 }
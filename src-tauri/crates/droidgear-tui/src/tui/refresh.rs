@@ -67,12 +67,34 @@ pub(super) fn refresh_claude(app: &mut app::App) {
     }
 }
 
+/// Lists the user's assets of the selected kind plus those of the current
+/// directory's project.
+pub(super) fn refresh_claude_assets(app: &mut app::App) {
+    let project_dir = std::env::current_dir().ok();
+    match droidgear_core::claude_agents::list_assets_for_home(
+        &app.home_dir,
+        app.claude_assets_kind,
+        project_dir.as_deref(),
+    ) {
+        Ok(list) => app.claude_assets = list,
+        Err(e) => app.set_toast(e, true),
+    }
+}
+
 pub(super) fn refresh_claude_detail(app: &mut app::App) {
     let Some(name) = app.claude_detail_name.clone() else {
         app.claude_detail_json = None;
         return;
     };
-    match droidgear_core::claude_settings_files::read_settings_file_for_home(&app.home_dir, &name) {
+    let loaded = match &app.claude_detail_layer {
+        Some((project_dir, scope)) => {
+            droidgear_core::claude_settings_files::read_project_settings_layer(project_dir, *scope)
+        }
+        None => {
+            droidgear_core::claude_settings_files::read_settings_file_for_home(&app.home_dir, &name)
+        }
+    };
+    match loaded {
         Ok(json) => app.claude_detail_json = Some(json),
         Err(e) => {
             app.claude_detail_json = None;
@@ -159,6 +181,7 @@ pub(super) fn codex_load_from_live_config(
     profile.model = live.model;
     profile.model_reasoning_effort = live.model_reasoning_effort;
     profile.api_key = live.api_key;
+    profile.execution = live.execution;
     droidgear_core::codex::save_codex_profile_for_home(&app.home_dir, profile)
         .map_err(anyhow::Error::msg)?;
     Ok(())
@@ -352,19 +375,120 @@ pub(super) fn refresh_omp_detail(app: &mut app::App) {
 }
 
 pub(super) fn refresh_sessions(app: &mut app::App) {
-    match droidgear_core::sessions::list_sessions_for_home(&app.home_dir, None) {
+    match droidgear_core::sessions::list_all_sessions_for_home(&app.home_dir) {
         Ok(list) => app.sessions = list,
         Err(e) => app.set_toast(e, true),
     }
 }
 
+pub(super) fn refresh_analytics(app: &mut app::App) {
+    match droidgear_core::session_analytics::usage_report_for_home(
+        &app.home_dir,
+        app.analytics_group_by,
+    ) {
+        Ok(report) => app.analytics_report = Some(report),
+        Err(e) => app.set_toast(e, true),
+    }
+}
+
+pub(super) fn refresh_retention(app: &mut app::App) {
+    use droidgear_core::session_retention;
+
+    let policy = match session_retention::load_retention_policy_for_home(&app.home_dir) {
+        Ok(policy) => policy,
+        Err(e) => {
+            app.set_toast(e, true);
+            return;
+        }
+    };
+    match session_retention::plan_retention_for_home(&app.home_dir, &policy, now_ms()) {
+        Ok(candidates) => app.retention_candidates = candidates,
+        Err(e) => app.set_toast(e, true),
+    }
+    app.retention_policy = policy;
+}
+
 pub(super) fn refresh_specs(app: &mut app::App) {
-    match droidgear_core::specs::list_specs_for_home(&app.home_dir) {
+    match droidgear_core::specs::list_spec_summaries_for_home(&app.home_dir, &app.spec_query) {
         Ok(list) => app.specs = list,
         Err(e) => app.set_toast(e, true),
     }
 }
 
+pub(super) fn refresh_spec_history(app: &mut app::App) {
+    match droidgear_core::specs_history::list_spec_revisions_for_home(
+        &app.home_dir,
+        &app.spec_history_name,
+    ) {
+        Ok(list) => app.spec_revisions = list,
+        Err(e) => app.set_toast(e, true),
+    }
+}
+
+/// The watched kind a screen displays. Detail and draft screens are left out
+/// so a refresh never drops unsaved edits.
+fn screen_watch_kind(screen: app::Screen) -> Option<WatchKind> {
+    match screen {
+        app::Screen::Sessions | app::Screen::SessionAnalytics | app::Screen::SessionRetention => {
+            Some(WatchKind::Sessions)
+        }
+        app::Screen::Specs | app::Screen::SpecHistory => Some(WatchKind::Specs),
+        app::Screen::Factory | app::Screen::Missions | app::Screen::DroidSettingsFiles => {
+            Some(WatchKind::FactorySettings)
+        }
+        app::Screen::Codex | app::Screen::CodexAuth => Some(WatchKind::CodexSettings),
+        app::Screen::ClaudeSettings => Some(WatchKind::ClaudeSettings),
+        _ => None,
+    }
+}
+
+/// Drains the file watcher, remembering which kinds changed.
+pub(super) fn collect_watch_events(app: &mut app::App) -> Vec<WatchEvent> {
+    let events = app.watcher.as_ref().map(|w| w.drain()).unwrap_or_default();
+    app.pending_watch.extend(events.iter().map(|e| e.kind));
+    events
+}
+
+/// Refreshes the current screen when files it shows changed. A change to the
+/// Claude settings file open in the detail screen reloads it, or is flagged as
+/// a conflict when there are unsaved edits.
+pub(super) fn apply_watch_events(app: &mut app::App, events: &[WatchEvent]) {
+    if app.screen == app::Screen::ClaudeSettingsDetail {
+        if let Some(name) = app.claude_detail_name.clone() {
+            let path = match &app.claude_detail_layer {
+                Some((project_dir, scope)) => {
+                    droidgear_core::claude_settings_files::project_settings_layer_path(
+                        project_dir,
+                        *scope,
+                    )
+                }
+                None => droidgear_core::claude_settings_files::get_settings_path_by_name_for_home(
+                    &app.home_dir,
+                    &name,
+                ),
+            };
+            if path.is_ok_and(|p| events.iter().any(|e| e.path == p)) {
+                if app.claude_detail_dirty {
+                    app.set_toast(
+                        format!("Conflict: '{name}' changed on disk; saving will overwrite it"),
+                        true,
+                    );
+                } else {
+                    refresh_claude_detail(app);
+                }
+            }
+        }
+    }
+    if app.modal.is_some() {
+        return;
+    }
+    if let Some(kind) = screen_watch_kind(app.screen) {
+        if app.pending_watch.remove(&kind) {
+            refresh_screen_data(app);
+        }
+    }
+}
+
 pub(super) fn refresh_channels(app: &mut app::App) {
     match droidgear_core::channel::load_channels_for_home(&app.home_dir) {
         Ok(list) => app.channels = list,
@@ -1,6 +1,7 @@
 use super::*;
 use crate::tui::utils::{
-    format_claude_temporary_run_preview, load_droid_run_preferences_from_path,
+    codex_cli_run_options_from_path, format_claude_temporary_run_preview,
+    load_codex_run_preferences_from_path, load_droid_run_preferences_from_path,
     preview_codex_temporary_run, preview_droid_temporary_run,
 };
 use crossterm::event::KeyCode;
@@ -20,9 +21,13 @@ fn claude_screen_is_in_claude_nav_group() {
     let group = app::App::group_of_screen(app::Screen::ClaudeSettings)
         .expect("ClaudeSettings should be a nav item");
     assert_eq!(app::App::nav_groups()[group].label, "Claude");
+    assert!(app::App::nav_targets().iter().any(
+        |(label, screen)| label == "claude: settings" && *screen == app::Screen::ClaudeSettings
+    ));
     assert!(app::App::nav_targets()
         .iter()
-        .any(|(label, screen)| label == "claude" && *screen == app::Screen::ClaudeSettings));
+        .any(|(label, screen)| label == "claude: agents & commands"
+            && *screen == app::Screen::ClaudeAgents));
 }
 
 #[test]
@@ -182,6 +187,159 @@ fn claude_list_s_key_sets_active_custom_file() {
     }
 }
 
+#[test]
+fn claude_list_v_key_routes_to_permission_report() {
+    let mut app = app::App::new(PathBuf::from("/tmp/test-home"));
+    app.claude_files = vec![claude_file("work")];
+
+    let action = super::keys_claude::handle_claude_key(&mut app, KeyCode::Char('v'));
+
+    match action {
+        Some(super::Action::ShowClaudePermissionReport { name }) => assert_eq!(name, "work"),
+        other => panic!("expected ShowClaudePermissionReport action, got {other:?}"),
+    }
+}
+
+#[test]
+fn claude_list_uppercase_p_key_simulates_tool_call() {
+    let home = TempDir::new().unwrap();
+    let mut app = app::App::new(home.path().to_path_buf());
+    app.claude_files = vec![claude_file("work")];
+    write_file(
+        &home.path().join(".droidgear/claude-settings/work.json"),
+        r#"{"permissions":{"allow":["Bash(git:*)"],"deny":["Bash(git push:*)"]}}"#,
+    );
+
+    super::keys_claude::handle_claude_key(&mut app, KeyCode::Char('P'));
+    let action = match app.modal.take() {
+        Some(app::Modal::Input { action, .. }) => action,
+        other => panic!("expected input modal, got {other:?}"),
+    };
+    assert!(matches!(
+        &action,
+        app::InputAction::ClaudeSimulatePermission { name } if name == "work"
+    ));
+
+    super::modal::run_input_action(&mut app, action, "Bash(git push origin)".to_string()).unwrap();
+    let toast = app.toast_message();
+    assert!(toast.contains("denied"), "unexpected toast {toast:?}");
+    assert!(
+        toast.contains("Bash(git push:*)"),
+        "unexpected toast {toast:?}"
+    );
+}
+
+#[test]
+fn claude_list_uppercase_e_key_shows_effective_settings() {
+    let mut app = app::App::new(PathBuf::from("/tmp/test-home"));
+
+    let action = super::keys_claude::handle_claude_key(&mut app, KeyCode::Char('E'));
+
+    match action {
+        Some(super::Action::ShowClaudeEffectiveSettings { project_dir }) => {
+            assert_eq!(project_dir, std::env::current_dir().unwrap());
+        }
+        other => panic!("expected ShowClaudeEffectiveSettings action, got {other:?}"),
+    }
+}
+
+#[test]
+fn claude_detail_saves_project_layer_and_blocks_file_actions() {
+    use droidgear_core::claude_settings_files::ClaudeSettingsScope;
+
+    let home = TempDir::new().unwrap();
+    let project = TempDir::new().unwrap();
+    write_file(
+        &project.path().join(".claude/settings.local.json"),
+        r#"{"autoUpdate":true}"#,
+    );
+    let mut app = app::App::new(home.path().to_path_buf());
+    app.claude_detail_name = Some("settings.local.json".to_string());
+    app.claude_detail_layer = Some((project.path().to_path_buf(), ClaudeSettingsScope::Local));
+    app.screen = app::Screen::ClaudeSettingsDetail;
+    super::refresh::refresh_claude_detail(&mut app);
+    assert_eq!(
+        app.claude_detail_json,
+        Some(serde_json::json!({"autoUpdate": true}))
+    );
+
+    super::keys_claude::handle_claude_settings_detail_key(&mut app, KeyCode::Char('a'));
+    assert!(app.modal.is_none(), "merge should be blocked for layers");
+
+    app.claude_detail_json = Some(serde_json::json!({"autoUpdate": false}));
+    app.claude_detail_dirty = true;
+    super::keys_claude::handle_claude_settings_detail_key(&mut app, KeyCode::Char('s'));
+    assert!(!app.claude_detail_dirty);
+    let saved =
+        std::fs::read_to_string(project.path().join(".claude/settings.local.json")).unwrap();
+    assert!(
+        saved.contains("\"autoUpdate\": false"),
+        "unexpected {saved}"
+    );
+    assert!(!home.path().join(".droidgear/claude-settings").exists());
+
+    super::keys_claude::exit_claude_detail(&mut app);
+    assert!(app.claude_detail_layer.is_none());
+}
+
+#[test]
+fn claude_agents_screen_is_in_claude_nav_group() {
+    let group = app::App::group_of_screen(app::Screen::ClaudeAgents).unwrap();
+    assert_eq!(app::App::nav_groups()[group].label, "Claude");
+    let mut app = app::App::new(PathBuf::from("/tmp/test-home"));
+    app.screen = app::Screen::ClaudeAgents;
+    app.go_back();
+    assert_eq!(app.screen, app::Screen::FeatureList);
+}
+
+#[test]
+fn claude_agents_create_set_model_and_delete() {
+    use droidgear_core::claude_agents::{ClaudeAssetKind, ClaudeAssetScope};
+
+    let home = TempDir::new().unwrap();
+    let mut app = app::App::new(home.path().to_path_buf());
+    app.screen = app::Screen::ClaudeAgents;
+
+    super::keys_claude_agents::handle_claude_agents_key(&mut app, KeyCode::Tab);
+    assert_eq!(app.claude_assets_kind, ClaudeAssetKind::Command);
+    super::keys_claude_agents::handle_claude_agents_key(&mut app, KeyCode::Tab);
+    assert_eq!(app.claude_assets_kind, ClaudeAssetKind::Agent);
+
+    super::keys_claude_agents::handle_claude_agents_key(&mut app, KeyCode::Char('n'));
+    let action = match app.modal.take() {
+        Some(app::Modal::Input { action, .. }) => action,
+        other => panic!("expected input modal, got {other:?}"),
+    };
+    super::modal::run_input_action(&mut app, action, "reviewer".to_string()).unwrap();
+    let path = home.path().join(".claude/agents/reviewer.md");
+    assert!(path.exists());
+    let index = app
+        .claude_assets
+        .iter()
+        .position(|a| a.scope == ClaudeAssetScope::User && a.name == "reviewer")
+        .unwrap();
+    assert_eq!(app.claude_assets_index, index);
+
+    super::keys_claude_agents::handle_claude_agents_key(&mut app, KeyCode::Char('m'));
+    let action = match app.modal.take() {
+        Some(app::Modal::Select { action, .. }) => action,
+        other => panic!("expected select modal, got {other:?}"),
+    };
+    super::modal::run_select_action(&mut app, action, 2, Some("opus".to_string())).unwrap();
+    assert!(std::fs::read_to_string(&path)
+        .unwrap()
+        .contains("model: opus"));
+    assert_eq!(app.claude_assets[index].model.as_deref(), Some("opus"));
+
+    super::keys_claude_agents::handle_claude_agents_key(&mut app, KeyCode::Char('d'));
+    let action = match app.modal.take() {
+        Some(app::Modal::Confirm { action, .. }) => action,
+        other => panic!("expected confirm modal, got {other:?}"),
+    };
+    super::modal::run_confirm_action(&mut app, action).unwrap();
+    assert!(!path.exists());
+}
+
 #[test]
 fn claude_detail_escape_with_dirty_edits_asks_for_confirmation() {
     use std::path::PathBuf;
@@ -653,6 +811,63 @@ fn load_droid_run_preferences_from_path_reads_nested_policy() {
     );
 }
 
+#[test]
+fn load_codex_run_preferences_from_path_reads_history_mode() {
+    let temp = TempDir::new().unwrap();
+    let prefs_path = temp.path().join("preferences.json");
+    write_file(
+        &prefs_path,
+        r#"{"theme": "system", "codex_run": {"historyMode": "ephemeral"}}"#,
+    );
+
+    let prefs = load_codex_run_preferences_from_path(&prefs_path).unwrap();
+    assert_eq!(
+        prefs.history_mode,
+        droidgear_core::codex_runtime::CodexHistoryMode::Ephemeral
+    );
+    assert!(!prefs.merge_history_on_exit);
+    assert_eq!(
+        load_codex_run_preferences_from_path(&temp.path().join("missing.json")).unwrap(),
+        Default::default()
+    );
+}
+
+#[test]
+fn codex_cli_merge_history_keeps_saved_history_mode() {
+    use droidgear_core::codex_runtime::{CodexHistoryMode, CodexRunOptions};
+
+    let temp = TempDir::new().unwrap();
+    let prefs_path = temp.path().join("preferences.json");
+    write_file(
+        &prefs_path,
+        r#"{"theme": "system", "codex_run": {"historyMode": "ephemeral"}}"#,
+    );
+
+    assert_eq!(
+        codex_cli_run_options_from_path(Some(&prefs_path), None, false).unwrap(),
+        None
+    );
+    assert_eq!(
+        codex_cli_run_options_from_path(Some(&prefs_path), None, true).unwrap(),
+        Some(CodexRunOptions {
+            history_mode: CodexHistoryMode::Ephemeral,
+            merge_history_on_exit: true,
+        })
+    );
+    assert_eq!(
+        codex_cli_run_options_from_path(Some(&prefs_path), Some(CodexHistoryMode::Isolated), true)
+            .unwrap()
+            .map(|options| options.history_mode),
+        Some(CodexHistoryMode::Isolated)
+    );
+    assert_eq!(
+        codex_cli_run_options_from_path(Some(&temp.path().join("missing.json")), None, true)
+            .unwrap()
+            .map(|options| options.history_mode),
+        Some(CodexHistoryMode::Isolated)
+    );
+}
+
 #[test]
 fn preview_droid_temporary_run_uses_selected_settings_path_without_dumping_contents() {
     let temp = TempDir::new().unwrap();
@@ -710,6 +925,10 @@ fn list_codex_temporary_run_targets_lists_index_name_and_id() {
             model_reasoning_effort: None,
             api_key: None,
             auth_profile_name: None,
+            execution: Default::default(),
+            mcp_servers: Vec::new(),
+            features: Default::default(),
+            web_search: None,
         },
     )
     .unwrap();
@@ -727,6 +946,10 @@ fn list_codex_temporary_run_targets_lists_index_name_and_id() {
             model_reasoning_effort: None,
             api_key: None,
             auth_profile_name: None,
+            execution: Default::default(),
+            mcp_servers: Vec::new(),
+            features: Default::default(),
+            web_search: None,
         },
     )
     .unwrap();
@@ -757,13 +980,22 @@ fn preview_codex_temporary_run_lists_secret_keys_without_secret_values() {
             model_reasoning_effort: None,
             api_key: Some("sk-secret".to_string()),
             auth_profile_name: None,
+            execution: Default::default(),
+            mcp_servers: Vec::new(),
+            features: Default::default(),
+            web_search: None,
         },
     )
     .unwrap();
 
-    let output = preview_codex_temporary_run(temp.path(), "profile-a").unwrap();
+    let options = droidgear_core::codex_runtime::CodexRunOptions {
+        history_mode: droidgear_core::codex_runtime::CodexHistoryMode::Isolated,
+        merge_history_on_exit: true,
+    };
+    let output = preview_codex_temporary_run(temp.path(), "profile-a", &options).unwrap();
 
     assert!(output.contains("Codex temporary run preview"));
+    assert!(output.contains("isolated (merge on exit)"));
     assert!(output.contains("Runtime CODEX_HOME:"));
     assert!(output.contains("Secret environment keys:"));
     assert!(output.contains("(none)"));
@@ -1113,3 +1345,768 @@ fn nav_picker_filter_narrows_options_and_enter_resolves_by_label() {
     assert!(app.modal.is_none());
     assert!(app.modal_filter.is_empty());
 }
+
+#[test]
+fn session_detail_enter_toggles_collapsible_blocks() {
+    use droidgear_core::sessions::{ContentBlock, SessionDetail, SessionMessage, TokenUsage};
+
+    let mut app = app::App::new(PathBuf::from("/tmp/test-home"));
+    app.open_session_detail(SessionDetail {
+        id: "s1".to_string(),
+        title: "Demo".to_string(),
+        project: "-tmp-demo".to_string(),
+        model: "m".to_string(),
+        cwd: "/tmp/demo".to_string(),
+        modified_at: 0.0,
+        token_usage: TokenUsage::default(),
+        messages: vec![SessionMessage {
+            id: "m1".to_string(),
+            role: "assistant".to_string(),
+            content: vec![
+                ContentBlock::Text {
+                    text: "hello".to_string(),
+                },
+                ContentBlock::ToolUse {
+                    id: "tu1".to_string(),
+                    name: "Read".to_string(),
+                    input: serde_json::json!({"file_path": "a.rs"}),
+                },
+            ],
+            timestamp: "t".to_string(),
+            token_usage: None,
+        }],
+        source: Default::default(),
+    });
+
+    assert_eq!(app.screen, app::Screen::SessionDetail);
+    assert_eq!(app.session_detail_rows().len(), 2);
+    assert!(app.session_detail_expanded.contains(&0));
+    assert!(!app.session_detail_expanded.contains(&1));
+
+    super::keys_sessions::handle_session_detail_key(&mut app, KeyCode::Down);
+    super::keys_sessions::handle_session_detail_key(&mut app, KeyCode::Enter);
+    assert!(app.session_detail_expanded.contains(&1));
+
+    super::keys_sessions::handle_session_detail_key(&mut app, KeyCode::Char('c'));
+    assert!(app.session_detail_expanded.is_empty());
+
+    super::keys_sessions::handle_session_detail_key(&mut app, KeyCode::Esc);
+    assert_eq!(app.screen, app::Screen::Sessions);
+}
+
+#[test]
+fn session_export_picks_format_then_writes_redacted_file() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    let session_dir = home.join(".factory/sessions/-tmp-demo");
+    write_file(
+        &session_dir.join("s1.jsonl"),
+        r#"{"type":"message","id":"m1","timestamp":"t","message":{"role":"user","content":[{"type":"text","text":"key sk-abcdefghijklmnopqrstu"}]}}"#,
+    );
+    let session_path = session_dir.join("s1").to_string_lossy().to_string();
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::Sessions;
+    app.sessions = droidgear_core::sessions::list_all_sessions_for_home(&home).unwrap();
+    super::keys_sessions::handle_sessions_key(&mut app, KeyCode::Char('x'));
+    let Some(app::Modal::Select { action, .. }) = app.modal.take() else {
+        panic!("expected format picker");
+    };
+
+    super::modal::run_select_action(&mut app, action, 1, Some("HTML".to_string())).unwrap();
+    let Some(app::Modal::Input { value, action, .. }) = app.modal.take() else {
+        panic!("expected output path input");
+    };
+    assert!(value.ends_with("s1.html"));
+    assert!(matches!(
+        &action,
+        app::InputAction::SessionExportPath { session_path: p, .. } if *p == session_path
+    ));
+
+    let out = home.join("out/s1.html");
+    super::modal::run_input_action(&mut app, action, out.to_string_lossy().to_string()).unwrap();
+    let html = std::fs::read_to_string(&out).unwrap();
+    assert!(html.contains("[REDACTED_KEY]"));
+    assert!(!html.contains("sk-abcdefghijklmnopqrstu"));
+}
+
+#[test]
+fn session_analytics_cycles_grouping_and_exports_csv() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    write_file(
+        &home.join(".factory/sessions/-tmp-demo/s1.jsonl"),
+        r#"{"type":"session_start","title":"demo"}"#,
+    );
+    write_file(
+        &home.join(".factory/sessions/-tmp-demo/s1.settings.json"),
+        r#"{"model":"m1","tokenUsage":{"inputTokens":100,"outputTokens":10,"cacheReadTokens":100}}"#,
+    );
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::Sessions;
+    super::keys_sessions::handle_sessions_key(&mut app, KeyCode::Char('a'));
+    assert_eq!(app.screen, app::Screen::SessionAnalytics);
+    assert_eq!(
+        app.analytics_group_by,
+        droidgear_core::session_analytics::UsageGroupBy::Day
+    );
+
+    super::keys_sessions::handle_session_analytics_key(&mut app, KeyCode::Tab);
+    super::keys_sessions::handle_session_analytics_key(&mut app, KeyCode::Tab);
+    let report = app.analytics_report.as_ref().unwrap();
+    assert_eq!(
+        report.group_by,
+        droidgear_core::session_analytics::UsageGroupBy::Project
+    );
+    assert_eq!(report.buckets[0].key, "-tmp-demo");
+    assert_eq!(report.totals.cache_hit_ratio, 0.5);
+
+    super::keys_sessions::handle_session_analytics_key(&mut app, KeyCode::Char('x'));
+    let Some(app::Modal::Select { action, .. }) = app.modal.take() else {
+        panic!("expected format picker");
+    };
+    super::modal::run_select_action(&mut app, action, 0, Some("CSV".to_string())).unwrap();
+    let Some(app::Modal::Input { value, action, .. }) = app.modal.take() else {
+        panic!("expected output path input");
+    };
+    assert!(value.ends_with("token-usage-by-project.csv"));
+
+    let out = home.join("usage.csv");
+    super::modal::run_input_action(&mut app, action, out.to_string_lossy().to_string()).unwrap();
+    let csv = std::fs::read_to_string(&out).unwrap();
+    assert!(csv.starts_with("project,sessions,"));
+    assert!(csv.contains("-tmp-demo,1,100,10,0,100,0,210,0.5000"));
+
+    super::keys_sessions::handle_session_analytics_key(&mut app, KeyCode::Esc);
+    assert_eq!(app.screen, app::Screen::Sessions);
+}
+
+#[test]
+fn session_retention_screen_previews_then_archives() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    let session_dir = home.join(".factory/sessions/-tmp-demo");
+    write_file(
+        &session_dir.join("empty.jsonl"),
+        r#"{"type":"session_start","title":"nothing here"}"#,
+    );
+    write_file(
+        &session_dir.join("busy.jsonl"),
+        r#"{"type":"message","id":"m1","timestamp":"t","message":{"role":"user","content":[{"type":"text","text":"hi"}]}}"#,
+    );
+    droidgear_core::session_retention::save_retention_policy_for_home(
+        &home,
+        &droidgear_core::session_retention::RetentionPolicy {
+            empty: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::Sessions;
+    super::keys_sessions::handle_sessions_key(&mut app, KeyCode::Char('c'));
+    assert_eq!(app.screen, app::Screen::SessionRetention);
+    assert_eq!(app.retention_candidates.len(), 1);
+    assert_eq!(app.retention_candidates[0].session.id, "empty");
+
+    super::keys_sessions::handle_session_retention_key(&mut app, KeyCode::Char('A'));
+    let Some(app::Modal::Confirm { action, .. }) = app.modal.take() else {
+        panic!("expected archive confirm");
+    };
+    super::modal::run_confirm_action(&mut app, action).unwrap();
+
+    assert!(!session_dir.join("empty.jsonl").exists());
+    assert!(session_dir.join("busy.jsonl").exists());
+    assert!(app.toast_message().starts_with("Archived 1 sessions"));
+    let archives: Vec<_> = std::fs::read_dir(home.join(".droidgear/archive"))
+        .unwrap()
+        .collect();
+    assert_eq!(archives.len(), 1);
+}
+
+#[test]
+fn mcp_sync_select_confirms_then_writes_target() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    write_file(
+        &home.join(".factory/mcp.json"),
+        r#"{"mcpServers":{"exa":{"type":"stdio","command":"npx","args":["exa-mcp"]}}}"#,
+    );
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::Mcp;
+    super::keys_mcp::handle_mcp_key(&mut app, KeyCode::Char('s'));
+    let Some(app::Modal::Select {
+        options, action, ..
+    }) = app.modal.take()
+    else {
+        panic!("expected target select");
+    };
+    let idx = options.iter().position(|o| o == "Claude Code").unwrap();
+    super::modal::run_select_action(&mut app, action, idx, Some(options[idx].clone())).unwrap();
+
+    let Some(app::Modal::Confirm { message, action }) = app.modal.take() else {
+        panic!("expected sync confirm");
+    };
+    assert!(message.contains("Claude Code: +1 ~0 -0"));
+    super::modal::run_confirm_action(&mut app, action).unwrap();
+
+    let claude = std::fs::read_to_string(home.join(".claude.json")).unwrap();
+    assert!(claude.contains("exa-mcp"));
+    assert!(app.toast_message().starts_with("Synced MCP servers"));
+}
+
+#[test]
+fn mcp_move_scope_select_demotes_into_project_file() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().join("home");
+    let project_dir = temp.path().join("repo");
+    write_file(
+        &home.join(".factory/mcp.json"),
+        r#"{"mcpServers":{"exa":{"type":"stdio","command":"npx","args":["exa-mcp"]}}}"#,
+    );
+    std::fs::create_dir_all(&project_dir).unwrap();
+
+    let mut app = app::App::new(home.clone());
+    let moves = vec![app::McpScopeMove {
+        name: "exa".to_string(),
+        file: droidgear_core::mcp::McpProjectFile::Claude,
+        promote: false,
+    }];
+    let label = moves[0].label();
+    let action = app::SelectAction::McpMoveScope {
+        project_dir: project_dir.clone(),
+        moves,
+    };
+    super::modal::run_select_action(&mut app, action, 0, Some(label)).unwrap();
+
+    let Some(app::Modal::Confirm { message, action }) = app.modal.take() else {
+        panic!("expected move confirm");
+    };
+    assert_eq!(message, "Demote 'exa' to .mcp.json?");
+    super::modal::run_confirm_action(&mut app, action).unwrap();
+
+    let project = std::fs::read_to_string(project_dir.join(".mcp.json")).unwrap();
+    assert!(project.contains("exa-mcp"));
+    assert!(droidgear_core::mcp::load_mcp_servers_for_home(&home)
+        .unwrap()
+        .is_empty());
+    assert_eq!(app.toast_message(), "Moved exa");
+}
+
+#[test]
+fn mcp_preset_picker_prompts_for_inputs_and_installs() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::Mcp;
+
+    super::keys_mcp::handle_mcp_key(&mut app, KeyCode::Char('i'));
+    let Some(app::Modal::Select {
+        options, action, ..
+    }) = app.modal.take()
+    else {
+        panic!("expected preset select");
+    };
+    let idx = options.iter().position(|o| o.starts_with("Exa")).unwrap();
+    super::modal::run_select_action(&mut app, action, idx, Some(options[idx].clone())).unwrap();
+
+    let Some(app::Modal::Select {
+        options, action, ..
+    }) = app.modal.take()
+    else {
+        panic!("expected variant select");
+    };
+    super::modal::run_select_action(&mut app, action, 0, Some(options[0].clone())).unwrap();
+
+    let Some(app::Modal::Input { value, action, .. }) = app.modal.take() else {
+        panic!("expected server name input");
+    };
+    assert_eq!(value, "exa");
+    super::modal::run_input_action(&mut app, action, value).unwrap();
+
+    let Some(app::Modal::Input {
+        is_secret, action, ..
+    }) = app.modal.take()
+    else {
+        panic!("expected API key input");
+    };
+    assert!(is_secret);
+    // An invalid value re-opens the prompt instead of aborting.
+    assert!(super::modal::run_input_action(&mut app, action, "  ".to_string()).is_err());
+    let Some(app::Modal::Input { action, .. }) = app.modal.take() else {
+        panic!("expected API key prompt again");
+    };
+    super::modal::run_input_action(&mut app, action, "exa-123".to_string()).unwrap();
+
+    assert!(app.modal.is_none());
+    assert_eq!(app.toast_message(), "Installed MCP server exa");
+    let server = &app.mcp_servers[app.mcp_index];
+    assert_eq!(
+        server.config.env.as_ref().unwrap()["EXA_API_KEY"],
+        "exa-123"
+    );
+}
+
+#[test]
+fn mcp_c_key_probes_selected_server() {
+    let temp = TempDir::new().unwrap();
+    let mut app = app::App::new(temp.path().to_path_buf());
+    app.screen = app::Screen::Mcp;
+    app.mcp_servers = vec![droidgear_core::mcp::McpServer {
+        name: "exa".to_string(),
+        config: droidgear_core::mcp::McpServerConfig {
+            server_type: droidgear_core::mcp::McpServerType::Http,
+            url: Some("https://mcp.exa.ai/mcp".to_string()),
+            ..Default::default()
+        },
+    }];
+
+    let action = super::keys_mcp::handle_mcp_key(&mut app, KeyCode::Char('c'));
+    assert!(matches!(
+        action,
+        Some(super::Action::ProbeMcpServer { ref server }) if server.name == "exa"
+    ));
+}
+
+#[test]
+fn mcp_probe_report_includes_tools_and_stderr() {
+    let result = droidgear_core::mcp_probe::McpProbeResult {
+        success: false,
+        server_name: Some("fake".to_string()),
+        tool_names: vec!["echo".to_string()],
+        tool_count: 1,
+        error: Some("tools/list failed: boom".to_string()),
+        stderr: Some("trace".to_string()),
+        ..Default::default()
+    };
+    let report = super::utils::format_mcp_probe_report("fake", &result);
+    assert!(report.contains("FAILED"));
+    assert!(report.contains("  - echo"));
+    assert!(report.ends_with("stderr:\ntrace\n"));
+}
+
+#[test]
+fn specs_status_select_updates_front_matter_and_filter_applies() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    let specs_dir = home.join(".factory/specs");
+    write_file(
+        &specs_dir.join("2025-01-01-a.md"),
+        "---\ntitle: Alpha\ntags: [api]\n---\n# A\n",
+    );
+    write_file(&specs_dir.join("2025-01-02-b.md"), "# B\n");
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::Specs;
+    app.spec_query.sort = droidgear_core::specs::SpecSortKey::Name;
+    super::refresh::refresh_specs(&mut app);
+    assert_eq!(app.specs.len(), 2);
+
+    super::keys_specs::handle_specs_key(&mut app, KeyCode::Char('a'));
+    let Some(app::Modal::Select {
+        options, action, ..
+    }) = app.modal.take()
+    else {
+        panic!("expected status select");
+    };
+    let idx = options.iter().position(|o| o == "approved").unwrap();
+    super::modal::run_select_action(&mut app, action, idx, Some(options[idx].clone())).unwrap();
+    let content = std::fs::read_to_string(specs_dir.join("2025-01-01-a.md")).unwrap();
+    assert_eq!(
+        content,
+        "---\ntitle: Alpha\ntags: [api]\nstatus: approved\n---\n# A\n"
+    );
+
+    super::keys_specs::handle_specs_key(&mut app, KeyCode::Char('f'));
+    let Some(app::Modal::Select { action, .. }) = app.modal.take() else {
+        panic!("expected filter select");
+    };
+    super::modal::run_select_action(&mut app, action, 2, Some("approved".to_string())).unwrap();
+    super::refresh::refresh_specs(&mut app);
+    assert_eq!(app.specs.len(), 1);
+    assert_eq!(app.specs[0].display_title(), "Alpha");
+}
+
+#[test]
+fn specs_link_and_go_to_session() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    let spec_path = home.join(".factory/specs/2025-01-01-a.md");
+    write_file(&spec_path, "# A\n");
+    let session = serde_json::json!({"type": "session_start", "id": "s1", "cwd": "/repo"});
+    let write = serde_json::json!({
+        "type": "message",
+        "timestamp": "2025-01-01T00:00:00Z",
+        "message": {"role": "assistant", "content": [{
+            "type": "tool_use", "id": "t", "name": "Create",
+            "input": {"file_path": spec_path.to_string_lossy(), "content": "# A"}
+        }]}
+    });
+    write_file(
+        &home.join(".factory/sessions/-repo/s1.jsonl"),
+        &format!("{session}\n{write}\n"),
+    );
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::Specs;
+    super::refresh::refresh_specs(&mut app);
+    assert!(super::keys_specs::handle_specs_key(&mut app, KeyCode::Char('g')).is_none());
+
+    super::keys_specs::handle_specs_key(&mut app, KeyCode::Char('l'));
+    assert_eq!(app.toast_message(), "Linked 1 spec(s) to sessions");
+    assert_eq!(app.specs[0].meta.project.as_deref(), Some("/repo"));
+
+    match super::keys_specs::handle_specs_key(&mut app, KeyCode::Char('g')) {
+        Some(super::Action::ViewSession { path }) => assert!(path.ends_with("s1")),
+        other => panic!("expected ViewSession, got {other:?}"),
+    }
+}
+
+#[test]
+fn spec_history_lists_and_restores_revisions() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    let spec_path = home.join(".factory/specs/a.md");
+    write_file(&spec_path, "one\n");
+    let path = spec_path.to_string_lossy().to_string();
+    droidgear_core::specs::update_spec_for_home(&home, &path, "two\n").unwrap();
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::Specs;
+    super::refresh::refresh_specs(&mut app);
+    super::keys_specs::handle_specs_key(&mut app, KeyCode::Char('h'));
+    assert_eq!(app.screen, app::Screen::SpecHistory);
+    assert_eq!(app.spec_revisions.len(), 2);
+
+    match super::keys_specs::handle_spec_history_key(&mut app, KeyCode::Char('p')) {
+        Some(super::Action::ShowSpecDiff { name, from, to }) => {
+            assert_eq!((name.as_str(), from, to), ("a.md", 1, Some(2)));
+        }
+        other => panic!("expected ShowSpecDiff, got {other:?}"),
+    }
+
+    app.spec_revisions_index = 1;
+    super::keys_specs::handle_spec_history_key(&mut app, KeyCode::Char('R'));
+    let Some(app::Modal::Confirm { action, .. }) = app.modal.take() else {
+        panic!("expected restore confirmation");
+    };
+    super::modal::run_confirm_action(&mut app, action).unwrap();
+    assert_eq!(std::fs::read_to_string(&spec_path).unwrap(), "one\n");
+    super::refresh::refresh_spec_history(&mut app);
+    assert_eq!(app.spec_revisions[0].reason, "restore 1");
+
+    super::keys_specs::handle_spec_history_key(&mut app, KeyCode::Esc);
+    assert_eq!(app.screen, app::Screen::Specs);
+}
+
+#[test]
+fn specs_new_from_template_creates_dated_file() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::Specs;
+
+    super::keys_specs::handle_specs_key(&mut app, KeyCode::Char('n'));
+    let Some(app::Modal::Select {
+        options, action, ..
+    }) = app.modal.take()
+    else {
+        panic!("expected template picker");
+    };
+    assert_eq!(options, ["default"]);
+    super::modal::run_select_action(&mut app, action, 0, Some(options[0].clone())).unwrap();
+
+    let Some(app::Modal::Input { action, .. }) = app.modal.take() else {
+        panic!("expected name prompt");
+    };
+    assert!(super::modal::run_input_action(&mut app, action.clone(), "  ".to_string()).is_err());
+    assert!(app.modal.is_some());
+    super::modal::run_input_action(&mut app, action, "Cache Layer".to_string()).unwrap();
+
+    super::refresh::refresh_specs(&mut app);
+    assert_eq!(app.specs.len(), 1);
+    assert!(app.specs[0].name.ends_with("-cache-layer.md"));
+    assert_eq!(app.specs[0].meta.title.as_deref(), Some("Cache Layer"));
+    assert_eq!(
+        app.toast_message(),
+        format!("Created {}", app.specs[0].name)
+    );
+}
+
+#[test]
+fn watch_events_refresh_only_the_affected_screen() {
+    use droidgear_core::file_watcher::{WatchEvent, WatchKind};
+
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::Specs;
+    super::refresh::refresh_specs(&mut app);
+    assert!(app.specs.is_empty());
+
+    let spec = home.join(".factory/specs/a.md");
+    write_file(&spec, "# A\n");
+    let event = |kind, path: &Path| WatchEvent {
+        kind,
+        path: path.to_path_buf(),
+    };
+
+    // Other kinds stay pending and leave the specs list alone.
+    app.pending_watch.insert(WatchKind::Sessions);
+    super::refresh::apply_watch_events(&mut app, &[event(WatchKind::Sessions, &home)]);
+    assert!(app.specs.is_empty());
+
+    // Deferred while a modal is open.
+    app.pending_watch.insert(WatchKind::Specs);
+    app.modal = Some(app::Modal::Confirm {
+        message: String::new(),
+        action: app::ConfirmAction::Quit,
+    });
+    super::refresh::apply_watch_events(&mut app, &[event(WatchKind::Specs, &spec)]);
+    assert!(app.specs.is_empty());
+
+    app.modal = None;
+    super::refresh::apply_watch_events(&mut app, &[]);
+    assert_eq!(app.specs.len(), 1);
+    assert_eq!(
+        app.pending_watch.iter().copied().collect::<Vec<_>>(),
+        [WatchKind::Sessions]
+    );
+}
+
+#[test]
+fn watch_event_on_open_claude_settings_flags_conflict_when_dirty() {
+    use droidgear_core::file_watcher::{WatchEvent, WatchKind};
+
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    let settings = home.join(".claude/settings.json");
+    write_file(&settings, "{\"env\": {\"A\": \"1\"}}");
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::ClaudeSettingsDetail;
+    app.claude_detail_name = Some("global".to_string());
+    app.claude_detail_json = Some(serde_json::json!({"env": {}}));
+    app.claude_detail_dirty = true;
+    let events = [WatchEvent {
+        kind: WatchKind::ClaudeSettings,
+        path: settings.clone(),
+    }];
+
+    super::refresh::apply_watch_events(&mut app, &events);
+    assert!(app
+        .toast_message()
+        .starts_with("Conflict: 'global' changed on disk"));
+    assert_eq!(app.claude_detail_json, Some(serde_json::json!({"env": {}})));
+
+    app.claude_detail_dirty = false;
+    super::refresh::apply_watch_events(&mut app, &events);
+    assert_eq!(
+        app.claude_detail_json,
+        Some(serde_json::json!({"env": {"A": "1"}}))
+    );
+}
+
+#[test]
+fn watcher_flags_conflict_on_open_named_claude_settings() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    droidgear_core::claude_settings_files::create_settings_file_for_home(
+        &home,
+        "work".to_string(),
+        false,
+    )
+    .unwrap();
+    let path =
+        droidgear_core::claude_settings_files::get_settings_path_by_name_for_home(&home, "work")
+            .unwrap();
+
+    let mut app = app::App::new(home.clone());
+    app.watcher = Some(std::rc::Rc::new(
+        droidgear_core::file_watcher::start_file_watcher_for_home(&home).unwrap(),
+    ));
+    app.screen = app::Screen::ClaudeSettingsDetail;
+    app.claude_detail_name = Some("work".to_string());
+    app.claude_detail_json = Some(serde_json::json!({"env": {}}));
+    app.claude_detail_dirty = true;
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    let mut flagged = false;
+    while !flagged && std::time::Instant::now() < deadline {
+        write_file(&path, "{\"env\": {\"A\": \"1\"}}");
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let events = super::refresh::collect_watch_events(&mut app);
+        super::refresh::apply_watch_events(&mut app, &events);
+        flagged = app
+            .toast_message()
+            .starts_with("Conflict: 'work' changed on disk");
+    }
+    assert!(flagged);
+}
+
+#[test]
+fn codex_profile_sets_sandbox_mode_from_detail_field() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    let profile = droidgear_core::codex::create_default_codex_profile_for_home(&home).unwrap();
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::CodexProfile;
+    app.codex_detail_id = Some(profile.id.clone());
+    super::refresh::refresh_codex_detail(&mut app);
+    app.codex_detail_field_index = 7;
+    app.clamp_indices();
+    assert_eq!(app.codex_detail_field_index, 7);
+
+    super::keys_codex::handle_codex_profile_key(&mut app, KeyCode::Enter);
+    let Some(app::Modal::Select {
+        options, action, ..
+    }) = app.modal.take()
+    else {
+        panic!("expected sandbox mode select");
+    };
+    assert_eq!(options[1], "read-only");
+    super::modal::run_select_action(&mut app, action, 1, Some(options[1].clone())).unwrap();
+
+    let saved = droidgear_core::codex::get_codex_profile_for_home(&home, &profile.id).unwrap();
+    assert_eq!(saved.execution.sandbox_mode.as_deref(), Some("read-only"));
+}
+
+#[test]
+fn codex_list_imports_and_exports_native_profiles() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    write_file(
+        &home.join(".codex/config.toml"),
+        "model = \"gpt-5\"\n\n[profiles.review]\nsandbox_mode = \"read-only\"\n",
+    );
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::Codex;
+    super::keys_codex::handle_codex_key(&mut app, KeyCode::Char('i'));
+    let Some(app::Modal::Select {
+        options, action, ..
+    }) = app.modal.take()
+    else {
+        panic!("expected import select");
+    };
+    assert_eq!(options, ["(all)", "review"]);
+    super::modal::run_select_action(&mut app, action, 0, Some(options[0].clone())).unwrap();
+    assert_eq!(app.toast_message(), "Imported 1 profile(s)");
+
+    super::refresh::refresh_codex(&mut app);
+    app.codex_index = 0;
+    super::keys_codex::handle_codex_key(&mut app, KeyCode::Char('o'));
+    let Some(app::Modal::Confirm { action, .. }) = app.modal.take() else {
+        panic!("expected export confirm");
+    };
+    super::modal::run_confirm_action(&mut app, action).unwrap();
+    assert_eq!(app.toast_message(), "Exported: codex --profile review");
+}
+
+#[test]
+fn codex_history_key_cycles_run_history_modes() {
+    use droidgear_core::codex_runtime::{CodexHistoryMode, CodexRunOptions};
+
+    let mut app = app::App::new(PathBuf::from("/tmp/test-home"));
+    app.codex_run_options = Some(CodexRunOptions::default());
+
+    let mut seen = Vec::new();
+    for _ in 0..4 {
+        super::keys_codex::handle_codex_key(&mut app, KeyCode::Char('h'));
+        let options = app.codex_run_options.unwrap();
+        seen.push((options.history_mode, options.merge_history_on_exit));
+    }
+    assert_eq!(
+        seen,
+        vec![
+            (CodexHistoryMode::Isolated, false),
+            (CodexHistoryMode::Isolated, true),
+            (CodexHistoryMode::Ephemeral, false),
+            (CodexHistoryMode::Shared, false),
+        ]
+    );
+    assert_eq!(app.toast_message(), "Temporary run history: shared");
+}
+
+#[test]
+fn codex_auth_keys_reorder_and_rotate_profiles() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    for name in ["one", "two"] {
+        write_file(
+            &home.join(".codex/auth.json"),
+            &format!(
+                r#"{{"auth_mode":"chatgpt","tokens":{{"access_token":"{name}","account_id":"{name}"}}}}"#
+            ),
+        );
+        droidgear_core::codex_auth_profiles::save_current_as_profile_for_home(&home, name, name)
+            .unwrap();
+    }
+    droidgear_core::codex_auth_profiles::switch_profile_for_home(&home, "one").unwrap();
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::CodexAuth;
+    super::keys_codex_auth::refresh_codex_auth(&mut app);
+    app.codex_auth_index = 1;
+    super::keys_codex_auth::handle_codex_auth_key(&mut app, KeyCode::Char('K'));
+    let names: Vec<_> = app
+        .codex_auth_profiles
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(names, ["two", "one"]);
+    assert_eq!(app.codex_auth_index, 0);
+
+    super::keys_codex_auth::handle_codex_auth_key(&mut app, KeyCode::Char('n'));
+    let Some(app::Modal::Confirm { action, .. }) = app.modal.take() else {
+        panic!("expected rotate confirmation");
+    };
+    super::modal::run_confirm_action(&mut app, action).unwrap();
+    assert_eq!(app.toast_message(), "Rotated to auth profile 'two'");
+    super::keys_codex_auth::refresh_codex_auth(&mut app);
+    assert_eq!(app.codex_auth_active.as_deref(), Some("two"));
+    assert!(app.codex_auth_profiles[1].is_limited());
+
+    app.codex_auth_index = 1;
+    super::keys_codex_auth::handle_codex_auth_key(&mut app, KeyCode::Char('c'));
+    assert!(!app.codex_auth_profiles[1].is_limited());
+}
+
+#[test]
+fn claude_append_merge_key_appends_hooks_to_global() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path().to_path_buf();
+    write_file(
+        &home.join(".claude/settings.json"),
+        r#"{"hooks":{"Stop":[{"hooks":[{"type":"command","command":"live"}]}]}}"#,
+    );
+    write_file(
+        &home.join(".droidgear/claude-settings/team.json"),
+        r#"{"hooks":{"Stop":[{"hooks":[{"type":"command","command":"team"}]}]}}"#,
+    );
+
+    let mut app = app::App::new(home.clone());
+    app.screen = app::Screen::ClaudeSettings;
+    super::refresh::refresh_claude(&mut app);
+    app.claude_index = app
+        .claude_files
+        .iter()
+        .position(|f| f.name == "team")
+        .unwrap();
+    super::keys_claude::handle_claude_key(&mut app, KeyCode::Char('A'));
+    let Some(app::Modal::Confirm { action, .. }) = app.modal.take() else {
+        panic!("expected merge confirmation");
+    };
+    super::modal::run_confirm_action(&mut app, action).unwrap();
+
+    let global =
+        droidgear_core::claude_settings_files::read_settings_file_for_home(&home, "Global")
+            .unwrap();
+    let stop = global["hooks"]["Stop"].as_array().unwrap();
+    assert_eq!(stop.len(), 2);
+    assert_eq!(stop[0]["hooks"][0]["command"], "live");
+}
@@ -1,4 +1,14 @@
 use super::*;
+use droidgear_core::claude_agents::ClaudeAsset;
+use droidgear_core::claude_permissions::{
+    ClaudePermissionDecision, ClaudePermissionIssue, ClaudePermissionOutcome,
+    ClaudePermissionSeverity,
+};
+use droidgear_core::claude_settings_files::ClaudeEffectiveSettings;
+use droidgear_core::codex_runtime::{CodexHistoryMode, CodexRunOptions};
+use droidgear_core::mcp::{McpEffectiveServer, McpServerOrigin};
+use droidgear_core::mcp_probe::McpProbeResult;
+use droidgear_core::mcp_sync::{McpSyncPreview, McpSyncTarget};
 
 pub(super) fn write_string(path: &Path, content: &str) -> anyhow::Result<()> {
     if let Some(parent) = path.parent() {
@@ -12,24 +22,76 @@ pub(super) fn write_string(path: &Path, content: &str) -> anyhow::Result<()> {
 pub(super) struct StoredPreferences {
     #[serde(default)]
     droid_run: Option<droidgear_core::droid_runtime::DroidRunPreferences>,
+    #[serde(default)]
+    codex_run: Option<CodexRunOptions>,
 }
 
 pub(super) fn preferences_path() -> Option<PathBuf> {
     dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER).join("preferences.json"))
 }
 
-pub(super) fn load_droid_run_preferences_from_path(
-    path: &Path,
-) -> anyhow::Result<droidgear_core::droid_runtime::DroidRunPreferences> {
+fn load_stored_preferences_from_path(path: &Path) -> anyhow::Result<StoredPreferences> {
     if !path.exists() {
-        return Ok(droidgear_core::droid_runtime::DroidRunPreferences::default());
+        return Ok(StoredPreferences::default());
     }
 
     let contents =
         std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
-    let prefs: StoredPreferences =
-        serde_json::from_str(&contents).with_context(|| format!("parse {}", path.display()))?;
-    Ok(prefs.droid_run.unwrap_or_default())
+    serde_json::from_str(&contents).with_context(|| format!("parse {}", path.display()))
+}
+
+pub(super) fn load_droid_run_preferences_from_path(
+    path: &Path,
+) -> anyhow::Result<droidgear_core::droid_runtime::DroidRunPreferences> {
+    Ok(load_stored_preferences_from_path(path)?
+        .droid_run
+        .unwrap_or_default())
+}
+
+pub(super) fn load_codex_run_preferences_from_path(path: &Path) -> anyhow::Result<CodexRunOptions> {
+    Ok(load_stored_preferences_from_path(path)?
+        .codex_run
+        .unwrap_or_default())
+}
+
+/// Run options for the `run codex` flags, or `None` when no flag is set.
+/// `--merge-history` alone keeps the saved history mode and only falls back
+/// to isolated when nothing is saved.
+pub(super) fn codex_cli_run_options_from_path(
+    path: Option<&Path>,
+    history: Option<CodexHistoryMode>,
+    merge_history: bool,
+) -> anyhow::Result<Option<CodexRunOptions>> {
+    if history.is_none() && !merge_history {
+        return Ok(None);
+    }
+    let history_mode = match history {
+        Some(mode) => mode,
+        None => match path {
+            Some(path) => load_stored_preferences_from_path(path)?.codex_run,
+            None => None,
+        }
+        .map_or(CodexHistoryMode::Isolated, |saved| saved.history_mode),
+    };
+    Ok(Some(CodexRunOptions {
+        history_mode,
+        merge_history_on_exit: merge_history,
+    }))
+}
+
+pub fn codex_cli_run_options(
+    history: Option<CodexHistoryMode>,
+    merge_history: bool,
+) -> anyhow::Result<Option<CodexRunOptions>> {
+    codex_cli_run_options_from_path(preferences_path().as_deref(), history, merge_history)
+}
+
+pub(super) fn load_codex_run_preferences() -> anyhow::Result<CodexRunOptions> {
+    let Some(path) = preferences_path() else {
+        return Ok(CodexRunOptions::default());
+    };
+
+    load_codex_run_preferences_from_path(&path)
 }
 
 pub(super) fn load_droid_run_preferences(
@@ -81,14 +143,14 @@ pub(super) fn format_optional_value(value: Option<&str>, empty_label: &str) -> S
     }
 }
 
-pub(super) fn start_command_in_foreground(
+fn build_command(
     program: &str,
     args: &[String],
     env: &[(String, String)],
     secret_env: &[(String, String)],
     unset_env: &[String],
     cwd: Option<&Path>,
-) -> anyhow::Result<()> {
+) -> Command {
     let mut command = Command::new(program);
     command.args(args);
 
@@ -103,6 +165,18 @@ pub(super) fn start_command_in_foreground(
     for (key, value) in env.iter().chain(secret_env.iter()) {
         command.env(key, value);
     }
+    command
+}
+
+pub(super) fn start_command_in_foreground(
+    program: &str,
+    args: &[String],
+    env: &[(String, String)],
+    secret_env: &[(String, String)],
+    unset_env: &[String],
+    cwd: Option<&Path>,
+) -> anyhow::Result<()> {
+    let mut command = build_command(program, args, env, secret_env, unset_env, cwd);
 
     #[cfg(unix)]
     {
@@ -119,6 +193,30 @@ pub(super) fn start_command_in_foreground(
     }
 }
 
+/// Like [`start_command_in_foreground`], but waits for the child so work can
+/// follow its exit. Ctrl-C is left to the child while it runs.
+pub(super) fn run_command_and_wait(
+    program: &str,
+    args: &[String],
+    env: &[(String, String)],
+    secret_env: &[(String, String)],
+    unset_env: &[String],
+    cwd: Option<&Path>,
+) -> anyhow::Result<std::process::ExitStatus> {
+    let mut child = build_command(program, args, env, secret_env, unset_env, cwd)
+        .spawn()
+        .with_context(|| format!("run {program}"))?;
+
+    #[cfg(unix)]
+    let previous = unsafe { libc::signal(libc::SIGINT, libc::SIG_IGN) };
+    let status = child.wait().with_context(|| format!("wait for {program}"));
+    #[cfg(unix)]
+    unsafe {
+        libc::signal(libc::SIGINT, previous);
+    }
+    status
+}
+
 pub(super) fn sanitize_terminal_for_direct_exec() -> anyhow::Result<()> {
     use std::io::IsTerminal;
 
@@ -201,6 +299,255 @@ pub(super) fn format_diff_report(
     out
 }
 
+/// One-line summary such as `Codex: +1 ~0 -2, 1 conflict`.
+pub(super) fn format_mcp_sync_summary(preview: &McpSyncPreview) -> String {
+    let mut out = format!(
+        "{}: +{} ~{} -{}",
+        preview.target.label(),
+        preview.added.len(),
+        preview.updated.len(),
+        preview.removed.len()
+    );
+    if !preview.conflicts.is_empty() {
+        out.push_str(&format!(
+            ", {} conflict{} ({})",
+            preview.conflicts.len(),
+            if preview.conflicts.len() == 1 {
+                ""
+            } else {
+                "s"
+            },
+            preview.conflicts.join(", ")
+        ));
+    }
+    out
+}
+
+pub(super) fn format_mcp_probe_report(name: &str, result: &McpProbeResult) -> String {
+    let mut out = format!("MCP server check: {name}\n\n");
+    if result.success {
+        out.push_str("Status:   OK\n");
+    } else {
+        out.push_str(&format!(
+            "Status:   FAILED\nError:    {}\n",
+            result.error.as_deref().unwrap_or("unknown error")
+        ));
+    }
+    if let Some(server) = &result.server_name {
+        out.push_str(&format!(
+            "Server:   {server} {}\n",
+            result.server_version.as_deref().unwrap_or("")
+        ));
+    }
+    if let Some(protocol) = &result.protocol_version {
+        out.push_str(&format!("Protocol: {protocol}\n"));
+    }
+    out.push_str(&format!("Latency:  {}ms\n", result.latency_ms));
+    out.push_str(&format!("Tools:    {}\n", result.tool_count));
+    for tool in &result.tool_names {
+        out.push_str(&format!("  - {tool}\n"));
+    }
+    if let Some(stderr) = &result.stderr {
+        out.push_str("\nstderr:\n");
+        out.push_str(stderr);
+        out.push('\n');
+    }
+    out
+}
+
+/// Front matter and validation issues of a subagent or slash command.
+pub(super) fn format_claude_asset_report(asset: &ClaudeAsset) -> String {
+    let mut out = format!(
+        "{} {} '{}'\n{}\n\n",
+        asset.scope.label(),
+        asset.kind.label(),
+        asset.name,
+        asset.path
+    );
+    let unset = "(unset)".to_string();
+    out.push_str(&format!(
+        "description: {}\n",
+        asset.description.as_ref().unwrap_or(&unset)
+    ));
+    let tools = if asset.tools.is_empty() {
+        "(inherit)".to_string()
+    } else {
+        asset.tools.join(", ")
+    };
+    out.push_str(&format!("tools:       {tools}\n"));
+    out.push_str(&format!(
+        "model:       {}\n",
+        asset.model.as_ref().unwrap_or(&unset)
+    ));
+    if let Some(hint) = &asset.argument_hint {
+        out.push_str(&format!("arguments:   {hint}\n"));
+    }
+    out.push('\n');
+    if asset.issues.is_empty() {
+        out.push_str("No issues found\n");
+    }
+    for issue in &asset.issues {
+        let severity = match issue.severity {
+            ClaudePermissionSeverity::Error => "error",
+            ClaudePermissionSeverity::Warning => "warning",
+        };
+        out.push_str(&format!(
+            "{severity:<8} {:<14} {}\n",
+            issue.field, issue.message
+        ));
+    }
+    out
+}
+
+/// User/project/local Claude settings merged, each key tagged with the
+/// layers that set it (the last one wins).
+pub(super) fn format_claude_effective_settings(
+    project_dir: &Path,
+    effective: &ClaudeEffectiveSettings,
+) -> String {
+    let mut out = format!(
+        "Effective Claude settings for {}\n\n",
+        project_dir.display()
+    );
+    for layer in &effective.layers {
+        let state = if layer.exists { "" } else { " (missing)" };
+        out.push_str(&format!(
+            "{:<8} {}{state}\n",
+            layer.scope.label(),
+            layer.path
+        ));
+    }
+    out.push('\n');
+    if effective.sources.is_empty() {
+        out.push_str("(no settings)\n");
+    }
+    for source in &effective.sources {
+        let scopes: Vec<&str> = source.scopes.iter().map(|s| s.label()).collect();
+        out.push_str(&format!(
+            "{} = {}  [{}]\n",
+            source.key,
+            source.value,
+            scopes.join(" < ")
+        ));
+    }
+    out
+}
+
+/// Permission rule issues of a Claude settings file, one per line.
+pub(super) fn format_claude_permission_report(
+    name: &str,
+    issues: &[ClaudePermissionIssue],
+) -> String {
+    let mut out = format!("Permission rules in {name}\n\n");
+    if issues.is_empty() {
+        out.push_str("No issues found\n");
+    }
+    for issue in issues {
+        let severity = match issue.severity {
+            ClaudePermissionSeverity::Error => "error",
+            ClaudePermissionSeverity::Warning => "warning",
+        };
+        out.push_str(&format!(
+            "{severity:<8} {:<6} {}\n{:<16}{}\n",
+            issue.list.key(),
+            issue.rule,
+            "",
+            issue.message
+        ));
+    }
+    out
+}
+
+/// One-line summary of a simulated permission decision.
+pub(super) fn format_claude_permission_decision(
+    call: &str,
+    decision: &ClaudePermissionDecision,
+) -> String {
+    let outcome = match decision.outcome {
+        ClaudePermissionOutcome::Allow => "allowed",
+        ClaudePermissionOutcome::Ask => "asks",
+        ClaudePermissionOutcome::Deny => "denied",
+        ClaudePermissionOutcome::Default => {
+            let mode = decision.default_mode.as_deref().unwrap_or("default");
+            return format!("{call}: no rule matched (defaultMode: {mode})");
+        }
+    };
+    format!(
+        "{call}: {outcome} by `{}` ({})",
+        decision.rule.as_deref().unwrap_or_default(),
+        decision.scope.as_deref().unwrap_or_default()
+    )
+}
+
+/// Merged user + project servers, annotated with where each one comes from.
+pub(super) fn format_effective_mcp_servers(
+    project_dir: &Path,
+    servers: &[McpEffectiveServer],
+) -> String {
+    fn origin_label(origin: &McpServerOrigin) -> &'static str {
+        match origin.file {
+            Some(file) => file.relative_path(),
+            None => "user",
+        }
+    }
+
+    let mut out = format!(
+        "Effective MCP servers for {}
+
+",
+        project_dir.display()
+    );
+    if servers.is_empty() {
+        out.push_str(
+            "(none)
+",
+        );
+    }
+    for server in servers {
+        let state = if server.config.disabled {
+            " [disabled]"
+        } else {
+            ""
+        };
+        out.push_str(&format!(
+            "{:<24} {:<18}{state}
+",
+            server.name,
+            origin_label(&server.origin)
+        ));
+        for shadowed in &server.shadowed {
+            out.push_str(&format!(
+                "{:<24} overrides {}
+",
+                "",
+                origin_label(shadowed)
+            ));
+        }
+    }
+    out
+}
+
+pub(super) fn preview_mcp_sync(home_dir: &Path) -> anyhow::Result<String> {
+    let previews =
+        droidgear_core::mcp_sync::preview_mcp_sync_for_home(home_dir, &McpSyncTarget::ALL)
+            .map_err(anyhow::Error::msg)?;
+    let mut title = "MCP sync preview\n".to_string();
+    for preview in &previews {
+        title.push('\n');
+        title.push_str(&format_mcp_sync_summary(preview));
+        if !preview.unmanaged.is_empty() {
+            title.push_str(&format!("  (unmanaged: {})", preview.unmanaged.join(", ")));
+        }
+    }
+    Ok(format_diff_report(
+        &title,
+        previews
+            .into_iter()
+            .map(|p| (p.path, p.before, p.after))
+            .collect(),
+    ))
+}
+
 pub(super) fn build_droid_temporary_run_plan(
     home_dir: &Path,
     settings_path: &Path,
@@ -353,37 +700,86 @@ pub(super) fn preview_codex_apply(home_dir: &Path, profile_id: &str) -> anyhow::
 pub(super) fn build_codex_temporary_run_plan(
     home_dir: &Path,
     profile_id: &str,
+    options: &CodexRunOptions,
 ) -> anyhow::Result<droidgear_core::codex_runtime::CodexTemporaryLaunchPlan> {
     droidgear_core::codex_runtime::cleanup_stale_runtime_homes_for_home(home_dir)
         .map_err(anyhow::Error::msg)?;
     let profile = droidgear_core::codex::get_codex_profile_for_home(home_dir, profile_id)
         .map_err(anyhow::Error::msg)?;
-    droidgear_core::codex_runtime::build_temporary_run_plan_for_home(home_dir, &profile)
-        .map_err(anyhow::Error::msg)
+    droidgear_core::codex_runtime::build_temporary_run_plan_with_options_for_home(
+        home_dir, &profile, options,
+    )
+    .map_err(anyhow::Error::msg)
 }
 
-pub(super) fn run_codex_temporary_run(home_dir: &Path, profile_id: &str) -> anyhow::Result<()> {
-    let plan = build_codex_temporary_run_plan(home_dir, profile_id)?;
-    start_command_in_foreground(
+/// Label of the history handling `options` asks for.
+pub(super) fn codex_history_label(options: &CodexRunOptions) -> String {
+    let label = options.history_mode.label();
+    if options.merge_history_on_exit && options.history_mode != CodexHistoryMode::Shared {
+        format!("{label} (merge on exit)")
+    } else {
+        label.to_string()
+    }
+}
+
+pub(super) fn run_codex_temporary_run(
+    home_dir: &Path,
+    profile_id: &str,
+    options: &CodexRunOptions,
+) -> anyhow::Result<()> {
+    let plan = build_codex_temporary_run_plan(home_dir, profile_id, options)?;
+    if options.history_mode == CodexHistoryMode::Shared {
+        return start_command_in_foreground(
+            &plan.program,
+            &plan.args,
+            &plan.env,
+            &plan.secret_env,
+            &plan.unset_env,
+            None,
+        );
+    }
+
+    let status = run_command_and_wait(
         &plan.program,
         &plan.args,
         &plan.env,
         &plan.secret_env,
         &plan.unset_env,
         None,
-    )
+    );
+    let report = droidgear_core::codex_runtime::finish_temporary_run_for_home(home_dir, &plan)
+        .map_err(anyhow::Error::msg)?;
+    if let Some(report) = report {
+        println!(
+            "Merged {} session(s) and {} history entries into the live Codex history.",
+            report.sessions, report.history_entries
+        );
+    } else if options.history_mode == CodexHistoryMode::Isolated {
+        println!(
+            "Isolated history kept in {}",
+            plan.runtime_home_path.display()
+        );
+    }
+    let status = status?;
+    if !status.success() {
+        anyhow::bail!("codex exited with {status}");
+    }
+    Ok(())
 }
 
 pub(super) fn preview_codex_temporary_run(
     home_dir: &Path,
     profile_id: &str,
+    options: &CodexRunOptions,
 ) -> anyhow::Result<String> {
-    let plan = build_codex_temporary_run_plan(home_dir, profile_id)?;
+    let plan = build_codex_temporary_run_plan(home_dir, profile_id, options)?;
 
     let mut out = String::new();
     out.push_str("Codex temporary run preview\n\n");
     out.push_str("Runtime CODEX_HOME:\n");
     out.push_str(&format!("  {}\n\n", plan.runtime_home_path.display()));
+    out.push_str("History:\n");
+    out.push_str(&format!("  {}\n\n", codex_history_label(options)));
     out.push_str("Program:\n");
     out.push_str(&format!("  {}\n\n", plan.program));
     out.push_str("Args:\n");
@@ -439,12 +835,21 @@ pub fn list_codex_temporary_run_targets(home_dir: &Path) -> anyhow::Result<Strin
     Ok(out)
 }
 
-pub fn run_codex_temporary_run_for_selector(home_dir: &Path, selector: &str) -> anyhow::Result<()> {
+/// Runs a Codex profile; `options` default to the app's saved run preferences.
+pub fn run_codex_temporary_run_for_selector(
+    home_dir: &Path,
+    selector: &str,
+    options: Option<CodexRunOptions>,
+) -> anyhow::Result<()> {
     sanitize_terminal_for_direct_exec()?;
     let profile =
         droidgear_core::codex::resolve_codex_profile_selector_for_home(home_dir, selector)
             .map_err(anyhow::Error::msg)?;
-    run_codex_temporary_run(home_dir, &profile.id)
+    let options = match options {
+        Some(options) => options,
+        None => load_codex_run_preferences()?,
+    };
+    run_codex_temporary_run(home_dir, &profile.id, &options)
 }
 
 pub(super) fn format_claude_temporary_run_preview(
@@ -858,3 +1263,70 @@ pub(super) fn apply_factory_reasoning(
         *extra_args = None;
     }
 }
+
+pub(super) fn now_ms() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
+
+pub(super) fn format_retention_report(
+    report: &droidgear_core::session_retention::RetentionReport,
+) -> String {
+    use droidgear_core::session_retention::RetentionAction;
+
+    let mut out = String::new();
+    for c in &report.candidates {
+        let reasons: Vec<String> = c.reasons.iter().map(|r| r.describe()).collect();
+        out.push_str(&format!(
+            "{:<6} {}  [{}]  {}\n",
+            c.session.source.label(),
+            c.session.title,
+            c.session.project,
+            reasons.join(", ")
+        ));
+    }
+    let verb = match (report.dry_run, report.action) {
+        (true, RetentionAction::Archive) => "Would archive",
+        (true, RetentionAction::Delete) => "Would delete",
+        (false, RetentionAction::Archive) => "Archived",
+        (false, RetentionAction::Delete) => "Deleted",
+    };
+    out.push_str(&format!(
+        "{verb} {} sessions ({} KiB)",
+        report.candidates.len(),
+        report.total_bytes.div_ceil(1024)
+    ));
+    if let Some(archive) = report.archive_path.as_deref() {
+        out.push_str(&format!(" into {archive}"));
+    }
+    out
+}
+
+/// Headless `sessions cleanup`: applies `policy` (or the saved policy when
+/// `None`) and returns a printable report.
+pub fn run_session_cleanup(
+    home_dir: &Path,
+    policy: Option<droidgear_core::session_retention::RetentionPolicy>,
+    action: droidgear_core::session_retention::RetentionAction,
+    dry_run: bool,
+) -> anyhow::Result<String> {
+    use droidgear_core::session_retention;
+
+    let policy = match policy {
+        Some(policy) => policy,
+        None => session_retention::load_retention_policy_for_home(home_dir)
+            .map_err(anyhow::Error::msg)?,
+    };
+    if policy.is_empty() {
+        anyhow::bail!(
+            "No retention rule set. Pass --older-than/--empty/--min-tokens/--missing-project or save rules in {}",
+            session_retention::retention_policy_path_for_home(home_dir).display()
+        );
+    }
+    let report =
+        session_retention::apply_retention_for_home(home_dir, &policy, action, dry_run, now_ms())
+            .map_err(anyhow::Error::msg)?;
+    Ok(format_retention_report(&report))
+}
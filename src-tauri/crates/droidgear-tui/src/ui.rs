@@ -399,6 +399,7 @@ fn draw_main(frame: &mut Frame, app: &app::App, area: Rect) {
         app::Screen::McpKeyValues => draw_mcp_key_values(frame, app, area),
         app::Screen::ClaudeSettings => draw_claude_settings_files(frame, app, area),
         app::Screen::ClaudeSettingsDetail => draw_claude_settings_detail(frame, app, area),
+        app::Screen::ClaudeAgents => draw_claude_agents(frame, app, area),
         app::Screen::Codex => draw_codex_profiles(frame, app, area),
         app::Screen::CodexProfile => draw_codex_profile(frame, app, area),
         app::Screen::CodexProvider => draw_codex_provider(frame, app, area),
@@ -423,7 +424,11 @@ fn draw_main(frame: &mut Frame, app: &app::App, area: Rect) {
         app::Screen::HermesProfile => draw_hermes_profile(frame, app, area),
         app::Screen::HermesProvider => draw_hermes_provider(frame, app, area),
         app::Screen::Sessions => draw_sessions(frame, app, area),
+        app::Screen::SessionDetail => draw_session_detail(frame, app, area),
+        app::Screen::SessionAnalytics => draw_session_analytics(frame, app, area),
+        app::Screen::SessionRetention => draw_session_retention(frame, app, area),
         app::Screen::Specs => draw_specs(frame, app, area),
+        app::Screen::SpecHistory => draw_spec_history(frame, app, area),
         app::Screen::Channels => draw_channels(frame, app, area),
         app::Screen::ChannelsEdit => draw_channels_edit(frame, app, area),
         app::Screen::Missions => draw_missions(frame, app, area),
@@ -653,6 +658,80 @@ fn draw_trusted_folders(frame: &mut Frame, app: &app::App, area: Rect) {
     frame.render_widget(help, chunks[1]);
 }
 
+fn draw_claude_agents(frame: &mut Frame, app: &app::App, area: Rect) {
+    use droidgear_core::claude_agents::ClaudeAssetKind;
+    use droidgear_core::claude_permissions::ClaudePermissionSeverity;
+
+    let t = theme();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(2)].as_ref())
+        .split(area);
+
+    let title = match app.claude_assets_kind {
+        ClaudeAssetKind::Agent => "Subagents",
+        ClaudeAssetKind::Command => "Slash Commands",
+    };
+    let mut lines: Vec<Line> = Vec::new();
+    if app.claude_assets.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("No {}", title.to_lowercase()),
+            t.dim_style(),
+        )));
+    }
+    for (index, asset) in app.claude_assets.iter().enumerate() {
+        let selected = index == app.claude_assets_index;
+        let style = if selected {
+            t.selected_style()
+        } else {
+            Style::default()
+        };
+        let name = match asset.kind {
+            ClaudeAssetKind::Agent => asset.name.clone(),
+            ClaudeAssetKind::Command => format!("/{}", asset.name),
+        };
+        let mut spans = vec![
+            Span::styled(if selected { "▸ " } else { "  " }, style),
+            Span::styled(format!("{:<8} ", asset.scope.label()), t.dim_style()),
+            Span::styled(name, style),
+        ];
+        if let Some(model) = &asset.model {
+            spans.push(Span::styled(format!("  [{model}]"), t.dim_style()));
+        }
+        let errors = asset
+            .issues
+            .iter()
+            .filter(|i| i.severity == ClaudePermissionSeverity::Error)
+            .count();
+        let warnings = asset.issues.len() - errors;
+        if errors > 0 {
+            spans.push(Span::styled(
+                format!("  {errors} error(s)"),
+                t.error_style(),
+            ));
+        } else if warnings > 0 {
+            spans.push(Span::styled(
+                format!("  {warnings} warning(s)"),
+                t.dim_style(),
+            ));
+        }
+        if let Some(description) = &asset.description {
+            spans.push(Span::styled(format!("  {description}"), t.dim_style()));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(block(crumb_title(app, title)))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, chunks[0]);
+
+    let help = help_paragraph(
+        "Tab: agents/commands  Enter/e: edit  v: details  t: tools  m: model  n: new  N: new in project  d: delete  r: refresh  q/Esc: back",
+    );
+    frame.render_widget(help, chunks[1]);
+}
+
 fn draw_factory(frame: &mut Frame, app: &app::App, area: Rect) {
     let t = theme();
     let chunks = Layout::default()
@@ -932,7 +1011,7 @@ fn draw_mcp(frame: &mut Frame, app: &app::App, area: Rect) {
     render_list(frame, list, chunks[0], selected);
 
     let help = help_paragraph(
-        "Up/Down: select  Enter/e: open  n: new  t: toggle  d: delete  r: refresh  q/Esc: back",
+        "Up/Down: select  Enter/e: open  n: new  t: toggle  d: delete  c: check  p: preview sync  s: sync  o: project view  m: move scope  i: install preset  r: refresh  q/Esc: back",
     );
     frame.render_widget(help, chunks[1]);
 }
@@ -954,6 +1033,7 @@ fn draw_mcp_server(frame: &mut Frame, app: &app::App, area: Rect) {
     let server_type = match server.config.server_type {
         droidgear_core::mcp::McpServerType::Stdio => "stdio",
         droidgear_core::mcp::McpServerType::Http => "http",
+        droidgear_core::mcp::McpServerType::Sse => "sse",
     };
 
     let mut fields: Vec<(&str, String)> = vec![
@@ -973,7 +1053,7 @@ fn draw_mcp_server(frame: &mut Frame, app: &app::App, area: Rect) {
             fields.push(("Args", format!("{args_count}")));
             fields.push(("Env", format!("{env_count}")));
         }
-        droidgear_core::mcp::McpServerType::Http => {
+        droidgear_core::mcp::McpServerType::Http | droidgear_core::mcp::McpServerType::Sse => {
             let headers_count = server.config.headers.as_ref().map(|m| m.len()).unwrap_or(0);
             fields.push((
                 "URL",
@@ -1125,7 +1205,7 @@ fn draw_codex_profiles(frame: &mut Frame, app: &app::App, area: Rect) {
             .map(|p| (p.name.as_str(), p.id.as_str())),
         active,
         selected_index,
-        "Up/Down: select  Enter/e: open  E: raw edit  p: preview  a: apply  n: new  c: copy  d: delete  r: refresh  q/Esc: back",
+        "Up/Down: select  Enter/e: open  E: raw edit  p: preview  a: apply  t/x: preview/run temp  h: run history  n: new  c: copy  d: delete  i: import native  o: export native  r: refresh  q/Esc: back",
     );
 }
 
@@ -1145,7 +1225,7 @@ fn draw_claude_settings_files(frame: &mut Frame, app: &app::App, area: Rect) {
             .map(|f| (f.name.as_str(), f.path.as_str())),
         active_name,
         selected_index,
-        "Up/Down: select  Enter/e: open  s: set active  t: temp run  T: temp run (skip)  p: preview  a: merge to global  l: load live  n: new  c: copy  d: delete  r: refresh  q/Esc: back",
+        "Up/Down: select  Enter/e: open  s: set active  t: temp run  T: temp run (skip)  p: preview  a: merge to global  A: merge (append hooks)  v: check rules  P: simulate  o/O: edit project/local  E: effective  l: load live  n: new  c: copy  d: delete  r: refresh  q/Esc: back",
     );
 }
 
@@ -1330,9 +1410,11 @@ fn draw_claude_settings_detail(frame: &mut Frame, app: &app::App, area: Rect) {
         .highlight_style(t.selected_row_style());
     render_list(frame, list, chunks[0], Some(app.claude_detail_field_index));
 
-    let help = help_paragraph(
-        "Up/Down: move  Enter: edit/toggle  s: save  i: import from channel  t: temp run  T: temp run (skip)  p: preview  l: load live  a: merge to global  q/Esc: back",
-    );
+    let help = help_paragraph(if app.claude_detail_layer.is_some() {
+        "Up/Down: move  Enter: edit/toggle  s: save  i: import from channel  q/Esc: back"
+    } else {
+        "Up/Down: move  Enter: edit/toggle  s: save  i: import from channel  t: temp run  T: temp run (skip)  p: preview  l: load live  a: merge to global  A: merge (append hooks)  v: check rules  P: simulate  q/Esc: back"
+    });
     frame.render_widget(help, chunks[1]);
 }
 
@@ -2320,6 +2402,22 @@ fn draw_codex_profile(frame: &mut Frame, app: &app::App, area: Rect) {
                 "(not set)".to_string()
             },
         ),
+        (
+            "Sandbox Mode",
+            profile
+                .execution
+                .sandbox_mode
+                .clone()
+                .unwrap_or_else(|| "(none)".to_string()),
+        ),
+        (
+            "Approval Policy",
+            profile
+                .execution
+                .approval_policy
+                .clone()
+                .unwrap_or_else(|| "(none)".to_string()),
+        ),
     ];
 
     for (i, (label, value)) in fields.into_iter().enumerate() {
@@ -2514,6 +2612,7 @@ fn draw_sessions(frame: &mut Frame, app: &app::App, area: Rect) {
         let selected = i == app.sessions_index;
         if selected {
             items.push(ListItem::new(Line::from(vec![
+                Span::raw(format!("{:<7} ", s.source.label())),
                 Span::raw(s.title.clone()),
                 Span::raw("  "),
                 Span::raw(format!("[{}]", s.project)),
@@ -2522,6 +2621,7 @@ fn draw_sessions(frame: &mut Frame, app: &app::App, area: Rect) {
             ])));
         } else {
             items.push(ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<7} ", s.source.label()), t.dim_style()),
                 Span::raw(s.title.clone()),
                 Span::raw("  "),
                 Span::styled(format!("[{}]", s.project), t.dim_style()),
@@ -2543,7 +2643,366 @@ fn draw_sessions(frame: &mut Frame, app: &app::App, area: Rect) {
         .highlight_style(t.selected_row_style());
     render_list(frame, list, chunks[0], selected);
 
-    let help = help_paragraph("Up/Down: select  Enter/v: view  d: delete  r: refresh  q/Esc: back");
+    let help = help_paragraph(
+        "Up/Down: select  Enter/v: view  p: pager  x: export  a: analytics  c: cleanup  d: delete  r: refresh  q/Esc: back",
+    );
+    frame.render_widget(help, chunks[1]);
+}
+
+/// One-line summary of a session content block, shown whether or not the
+/// block is expanded.
+fn session_block_summary(block: &droidgear_core::sessions::ContentBlock) -> String {
+    use droidgear_core::sessions::ContentBlock;
+    let first_line = |s: &str| s.lines().next().unwrap_or("").to_string();
+    match block {
+        ContentBlock::Text { text } => first_line(text),
+        ContentBlock::Thinking { thinking } => format!("thinking: {}", first_line(thinking)),
+        ContentBlock::ToolUse { name, .. } => format!("tool call: {name}"),
+        ContentBlock::ToolResult {
+            output, is_error, ..
+        } => {
+            let label = if *is_error {
+                "tool error"
+            } else {
+                "tool result"
+            };
+            format!("{label}: {}", first_line(output))
+        }
+        ContentBlock::Image { media_type, .. } => format!("image: {media_type}"),
+        ContentBlock::FileEdit {
+            tool_name, path, ..
+        } => format!("{tool_name}: {path}"),
+    }
+}
+
+/// Body lines of an expanded session content block.
+fn session_block_body(block: &droidgear_core::sessions::ContentBlock) -> Vec<Line<'static>> {
+    use droidgear_core::sessions::ContentBlock;
+    let t = theme();
+    let plain = |s: &str| -> Vec<Line<'static>> {
+        s.lines().map(|l| Line::from(format!("    {l}"))).collect()
+    };
+    match block {
+        ContentBlock::Text { text } => plain(text),
+        ContentBlock::Thinking { thinking } => thinking
+            .lines()
+            .map(|l| Line::from(Span::styled(format!("    {l}"), t.dim_style())))
+            .collect(),
+        ContentBlock::ToolUse { input, .. } => {
+            plain(&serde_json::to_string_pretty(input).unwrap_or_default())
+        }
+        ContentBlock::ToolResult {
+            output, is_error, ..
+        } => {
+            if *is_error {
+                output
+                    .lines()
+                    .map(|l| Line::from(Span::styled(format!("    {l}"), t.error_style())))
+                    .collect()
+            } else {
+                plain(output)
+            }
+        }
+        ContentBlock::Image { url, .. } => url.as_deref().map(plain).unwrap_or_default(),
+        ContentBlock::FileEdit {
+            old_text, new_text, ..
+        } => {
+            let mut lines = Vec::new();
+            for l in old_text.as_deref().unwrap_or("").lines() {
+                lines.push(Line::from(Span::styled(
+                    format!("    - {l}"),
+                    t.error_style(),
+                )));
+            }
+            for l in new_text.as_deref().unwrap_or("").lines() {
+                lines.push(Line::from(Span::styled(
+                    format!("    + {l}"),
+                    t.success_fg_style(),
+                )));
+            }
+            lines
+        }
+    }
+}
+
+/// Token counts in the analytics view: 950, 12.3K, 4.1M, 1.2B.
+fn compact_tokens(value: f64) -> String {
+    if value >= 1e9 {
+        format!("{:.1}B", value / 1e9)
+    } else if value >= 1e6 {
+        format!("{:.1}M", value / 1e6)
+    } else if value >= 1e3 {
+        format!("{:.1}K", value / 1e3)
+    } else {
+        format!("{}", value as u64)
+    }
+}
+
+fn draw_session_analytics(frame: &mut Frame, app: &app::App, area: Rect) {
+    let t = theme();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(4),
+                Constraint::Min(0),
+                Constraint::Length(2),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    let group = app.analytics_group_by.label();
+    let Some(report) = app.analytics_report.as_ref() else {
+        let p = Paragraph::new(Line::from(Span::styled(
+            "No usage data loaded",
+            t.placeholder_style(),
+        )))
+        .block(block("Token usage"));
+        frame.render_widget(p, area);
+        return;
+    };
+
+    let totals = &report.totals;
+    let summary = vec![
+        Line::from(vec![
+            Span::raw("Sessions: "),
+            Span::styled(totals.session_count.to_string(), t.key_style()),
+            Span::raw("   Total: "),
+            Span::styled(compact_tokens(totals.total_tokens), t.key_style()),
+            Span::raw("   Cache hit: "),
+            Span::styled(
+                format!("{:.1}%", totals.cache_hit_ratio * 100.0),
+                t.key_style(),
+            ),
+        ]),
+        Line::from(Span::styled(
+            format!(
+                "in {}  out {}  cache write {}  cache read {}  thinking {}",
+                compact_tokens(totals.token_usage.input_tokens),
+                compact_tokens(totals.token_usage.output_tokens),
+                compact_tokens(totals.token_usage.cache_creation_tokens),
+                compact_tokens(totals.token_usage.cache_read_tokens),
+                compact_tokens(totals.token_usage.thinking_tokens),
+            ),
+            t.dim_style(),
+        )),
+    ];
+    frame.render_widget(
+        Paragraph::new(summary).block(block(format!("Token usage by {group}"))),
+        chunks[0],
+    );
+
+    // Bars scale to the largest bucket; the key and figures take the rest.
+    let key_width = report
+        .buckets
+        .iter()
+        .map(|b| b.key.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(8, 32);
+    let bar_width = (chunks[1].width as usize)
+        .saturating_sub(key_width + 30)
+        .max(4);
+    let max_total = report
+        .buckets
+        .iter()
+        .map(|b| b.total_tokens)
+        .fold(0.0_f64, f64::max);
+
+    let mut items: Vec<ListItem> = Vec::new();
+    for bucket in &report.buckets {
+        let filled = if max_total > 0.0 {
+            ((bucket.total_tokens / max_total) * bar_width as f64).round() as usize
+        } else {
+            0
+        };
+        let key: String = bucket.key.chars().take(key_width).collect();
+        items.push(ListItem::new(Line::from(vec![
+            Span::raw(format!("{key:<key_width$} ")),
+            Span::styled("█".repeat(filled), t.success_fg_style()),
+            Span::styled("·".repeat(bar_width.saturating_sub(filled)), t.dim_style()),
+            Span::raw(format!(" {:>7}", compact_tokens(bucket.total_tokens))),
+            Span::styled(
+                format!(
+                    "  cache {:>5.1}%  {:>4} sess",
+                    bucket.cache_hit_ratio * 100.0,
+                    bucket.session_count
+                ),
+                t.dim_style(),
+            ),
+        ])));
+    }
+    if items.is_empty() {
+        items.push(ListItem::new(Line::from(Span::styled(
+            "No sessions",
+            t.placeholder_style(),
+        ))));
+    }
+
+    let selected = (!report.buckets.is_empty()).then_some(app.analytics_index);
+    let list = List::new(items)
+        .block(block("Buckets"))
+        .highlight_style(t.selected_row_style());
+    render_list(frame, list, chunks[1], selected);
+
+    let help = help_paragraph(
+        "Up/Down: select  Tab/g: group by day/week/project/model  x: export CSV/JSON  r: refresh  q/Esc: back",
+    );
+    frame.render_widget(help, chunks[2]);
+}
+
+fn draw_session_retention(frame: &mut Frame, app: &app::App, area: Rect) {
+    let t = theme();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(2),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    let policy = &app.retention_policy;
+    let mut rules: Vec<String> = Vec::new();
+    if let Some(days) = policy.max_age_days {
+        rules.push(format!("older than {days}d"));
+    }
+    if policy.empty {
+        rules.push("empty".to_string());
+    }
+    if let Some(tokens) = policy.min_total_tokens {
+        rules.push(format!("under {} tokens", tokens as u64));
+    }
+    if policy.missing_project_dir {
+        rules.push("project dir missing".to_string());
+    }
+    let rules_line = if rules.is_empty() {
+        Line::from(Span::styled(
+            "No rules set (press e to edit)",
+            t.placeholder_style(),
+        ))
+    } else {
+        Line::from(vec![
+            Span::raw("Rules: "),
+            Span::styled(rules.join(" | "), t.key_style()),
+        ])
+    };
+    frame.render_widget(
+        Paragraph::new(rules_line).block(block("Retention policy (Droid sessions)")),
+        chunks[0],
+    );
+
+    let mut items: Vec<ListItem> = Vec::new();
+    for c in &app.retention_candidates {
+        let reasons: Vec<String> = c.reasons.iter().map(|r| r.describe()).collect();
+        items.push(ListItem::new(Line::from(vec![
+            Span::styled(format!("{:<7} ", c.session.source.label()), t.dim_style()),
+            Span::raw(c.session.title.clone()),
+            Span::raw("  "),
+            Span::styled(format!("[{}]", c.session.project), t.dim_style()),
+            Span::raw("  "),
+            Span::styled(reasons.join(", "), t.warning_fg_style()),
+        ])));
+    }
+    if items.is_empty() {
+        items.push(ListItem::new(Line::from(Span::styled(
+            "No sessions match",
+            t.placeholder_style(),
+        ))));
+    }
+
+    let total_kib = app
+        .retention_candidates
+        .iter()
+        .map(|c| c.size_bytes)
+        .sum::<u64>()
+        .div_ceil(1024);
+    let selected = (!app.retention_candidates.is_empty()).then_some(app.retention_index);
+    let list = List::new(items)
+        .block(block(format!(
+            "Dry run: {} sessions, {total_kib} KiB",
+            app.retention_candidates.len()
+        )))
+        .highlight_style(t.selected_row_style());
+    render_list(frame, list, chunks[1], selected);
+
+    let help = help_paragraph(
+        "Up/Down: select  e: edit rules  A: archive to ~/.droidgear/archive  D: delete  r: refresh  q/Esc: back",
+    );
+    frame.render_widget(help, chunks[2]);
+}
+
+fn draw_session_detail(frame: &mut Frame, app: &app::App, area: Rect) {
+    let t = theme();
+    let Some(detail) = app.session_detail.as_ref() else {
+        let p = Paragraph::new(vec![Line::from(Span::styled(
+            "No session loaded",
+            t.warning_style(),
+        ))])
+        .block(block("Session"))
+        .wrap(Wrap { trim: true });
+        frame.render_widget(p, area);
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(2)].as_ref())
+        .split(area);
+
+    let rows = app.session_detail_rows();
+    let mut items: Vec<ListItem> = Vec::new();
+    for (i, (m, b)) in rows.iter().enumerate() {
+        let msg = &detail.messages[*m];
+        let content = &msg.content[*b];
+        let expanded = app.session_detail_expanded.contains(&i);
+        let marker = if expanded { "▾" } else { "▸" };
+
+        let mut header = vec![
+            Span::raw(format!("{marker} ")),
+            Span::styled(format!("[{}]", msg.role), t.key_style()),
+            Span::raw(" "),
+            Span::raw(session_block_summary(content)),
+        ];
+        if *b == 0 {
+            if let Some(usage) = msg.token_usage.as_ref() {
+                header.push(Span::styled(
+                    format!(
+                        "  in {} / out {}",
+                        usage.input_tokens as u64, usage.output_tokens as u64
+                    ),
+                    t.dim_style(),
+                ));
+            }
+        }
+
+        let mut lines = vec![Line::from(header)];
+        if expanded {
+            lines.extend(session_block_body(content));
+        }
+        items.push(ListItem::new(lines));
+    }
+    if items.is_empty() {
+        items.push(ListItem::new(Line::from(Span::styled(
+            "No messages",
+            t.placeholder_style(),
+        ))));
+    }
+
+    let selected = (!rows.is_empty()).then_some(app.session_detail_index);
+    let title = format!("Session: {} [{}]", detail.title, detail.model);
+    let list = List::new(items)
+        .block(block(title))
+        .highlight_style(t.selected_row_style());
+    render_list(frame, list, chunks[0], selected);
+
+    let help = help_paragraph(
+        "Up/Down: select  Enter/Space: expand/collapse  e: expand all  c: collapse all  p: pager  x: export  q/Esc: back",
+    );
     frame.render_widget(help, chunks[1]);
 }
 
@@ -2556,7 +3015,33 @@ fn draw_specs(frame: &mut Frame, app: &app::App, area: Rect) {
 
     let mut items: Vec<ListItem> = Vec::new();
     for s in app.specs.iter() {
-        items.push(ListItem::new(Line::from(Span::raw(s.name.clone()))));
+        let status = s.meta.status.map(|st| st.as_str()).unwrap_or("-");
+        let status_style = match s.meta.status {
+            Some(droidgear_core::specs::SpecStatus::Implemented) => t.success_fg_style(),
+            Some(droidgear_core::specs::SpecStatus::Approved) => t.warning_fg_style(),
+            _ => t.placeholder_style(),
+        };
+        let mut spans = vec![
+            Span::styled(format!("{status:<12}"), status_style),
+            Span::raw(s.display_title().to_string()),
+        ];
+        if !s.meta.tags.is_empty() {
+            let tags = s
+                .meta
+                .tags
+                .iter()
+                .map(|tag| format!("#{tag}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            spans.push(Span::styled(format!("  {tags}"), t.placeholder_style()));
+        }
+        if s.meta.title.is_some() {
+            spans.push(Span::styled(
+                format!("  ({})", s.name),
+                t.placeholder_style(),
+            ));
+        }
+        items.push(ListItem::new(Line::from(spans)));
     }
     if items.is_empty() {
         items.push(ListItem::new(Line::from(Span::styled(
@@ -2565,13 +3050,94 @@ fn draw_specs(frame: &mut Frame, app: &app::App, area: Rect) {
         ))));
     }
 
+    let query = &app.spec_query;
+    let mut filters = vec![format!(
+        "sort: {}{}",
+        query.sort.label(),
+        if query.reverse { " (reversed)" } else { "" }
+    )];
+    if let Some(status) = query.status {
+        filters.push(format!("status: {}", status.as_str()));
+    }
+    if let Some(project) = &query.project {
+        filters.push(format!("project: {project}"));
+    }
+    if let Some(tag) = &query.tag {
+        filters.push(format!("tag: {tag}"));
+    }
+    if let Some(text) = &query.text {
+        filters.push(format!("search: {text}"));
+    }
+    let title = format!("{} [{}]", crumb_title(app, "Specs"), filters.join(", "));
+
     let selected = (!app.specs.is_empty()).then_some(app.specs_index);
     let list = List::new(items)
-        .block(block(crumb_title(app, "Specs")))
+        .block(block(title))
         .highlight_style(t.selected_row_style());
     render_list(frame, list, chunks[0], selected);
 
-    let help = help_paragraph("Up/Down: select  Enter/e: edit  d: delete  r: refresh  q/Esc: back");
+    let help = help_paragraph(
+        "Up/Down: select  Enter/e: edit  n: new  a: set status  f: status  p: project  t: tag  /: search  o/O: sort/reverse  l: link sessions  g: go to session  h: history  d: delete  r: refresh  q/Esc: back",
+    );
+    frame.render_widget(help, chunks[1]);
+}
+
+/// Coarse age of a millisecond timestamp, e.g. `5m ago`.
+fn format_age(ms: f64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0);
+    let secs = ((now - ms) / 1000.0).max(0.0) as u64;
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+fn draw_spec_history(frame: &mut Frame, app: &app::App, area: Rect) {
+    let t = theme();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(2)].as_ref())
+        .split(area);
+
+    let mut items: Vec<ListItem> = Vec::new();
+    for rev in &app.spec_revisions {
+        let mut spans = vec![
+            Span::styled(format!("#{:<4} ", rev.id), t.key_style()),
+            Span::styled(
+                format!("{:>8}  ", format_age(rev.created_at)),
+                t.dim_style(),
+            ),
+            Span::raw(rev.reason.clone()),
+        ];
+        if rev.name != app.spec_history_name {
+            spans.push(Span::styled(
+                format!("  (as {})", rev.name),
+                t.placeholder_style(),
+            ));
+        }
+        items.push(ListItem::new(Line::from(spans)));
+    }
+    if items.is_empty() {
+        items.push(ListItem::new(Line::from(Span::styled(
+            "No revisions yet",
+            t.placeholder_style(),
+        ))));
+    }
+
+    let selected = (!app.spec_revisions.is_empty()).then_some(app.spec_revisions_index);
+    let list = List::new(items)
+        .block(block(format!("History of {}", app.spec_history_name)))
+        .highlight_style(t.selected_row_style());
+    render_list(frame, list, chunks[0], selected);
+
+    let help = help_paragraph(
+        "Up/Down: select  Enter/c: diff vs current  p: diff vs previous  R: restore  r: refresh  q/Esc: back",
+    );
     frame.render_widget(help, chunks[1]);
 }
 
@@ -3750,9 +4316,16 @@ fn draw_codex_auth(frame: &mut Frame, app: &app::App, area: Rect) {
         } else {
             ""
         };
+        let limited_tag = match profile.limited_until.as_deref() {
+            Some(until) if profile.is_limited() => format!(" [limited until {until}]"),
+            _ => String::new(),
+        };
         lines.push(Line::from(vec![
             Span::styled(
-                format!("{}{}{}", profile.label, official_tag, active_tag),
+                format!(
+                    "{}{}{}{}",
+                    profile.label, official_tag, active_tag, limited_tag
+                ),
                 style,
             ),
             Span::styled(
@@ -3783,7 +4356,7 @@ fn draw_codex_auth(frame: &mut Frame, app: &app::App, area: Rect) {
     frame.render_widget(list, chunks[0]);
 
     let help = help_paragraph(
-        "Up/Down: select  Enter: switch  s: save current  w: overwrite  r: rename  d: delete  q/Esc: back",
+        "Up/Down: select  Enter: switch  n: rotate  K/J: move  c: clear limit  s: save current  w: overwrite  r: rename  d: delete  q/Esc: back",
     );
     frame.render_widget(help, chunks[1]);
 }
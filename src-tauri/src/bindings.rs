@@ -2,9 +2,10 @@ use tauri_specta::{collect_commands, Builder};
 
 pub fn generate_bindings() -> Builder<tauri::Wry> {
     use crate::commands::{
-        channel, channel_export, claude, claude_settings, codex, codex_auth_profiles, config,
-        connectivity, droid_settings, env, factory_auth_profiles, hermes, mcp, notifications, omp,
-        openclaw, opencode, paths, pi, preferences, recovery, sessions, specs, updater, window,
+        channel, channel_export, claude, claude_agents, claude_settings, codex,
+        codex_auth_profiles, config, connectivity, droid_settings, env, factory_auth_profiles,
+        hermes, mcp, notifications, omp, openclaw, opencode, paths, pi, preferences, recovery,
+        sessions, specs, updater, window,
     };
 
     Builder::<tauri::Wry>::new().commands(collect_commands![
@@ -72,12 +73,34 @@ pub fn generate_bindings() -> Builder<tauri::Wry> {
         specs::rename_spec,
         specs::delete_spec,
         specs::update_spec,
+        specs::list_spec_summaries,
+        specs::set_spec_status,
+        specs::correlate_spec_sessions,
+        specs::link_specs_to_sessions,
+        specs::list_specs_for_project,
+        specs::list_spec_revisions,
+        specs::read_spec_revision,
+        specs::diff_spec_revisions,
+        specs::restore_spec_revision,
+        specs::list_spec_templates,
+        specs::create_spec_from_template,
         specs::start_specs_watcher,
         specs::stop_specs_watcher,
         mcp::load_mcp_servers,
         mcp::save_mcp_server,
         mcp::delete_mcp_server,
         mcp::toggle_mcp_server,
+        mcp::preview_mcp_sync,
+        mcp::sync_mcp_servers,
+        mcp::probe_mcp_server,
+        mcp::load_mcp_presets,
+        mcp::install_mcp_preset,
+        mcp::load_project_mcp_configs,
+        mcp::save_project_mcp_server,
+        mcp::delete_project_mcp_server,
+        mcp::load_effective_mcp_servers,
+        mcp::promote_mcp_server,
+        mcp::demote_mcp_server,
         claude::list_claude_profiles,
         claude::get_claude_profile,
         claude::save_claude_profile,
@@ -91,6 +114,12 @@ pub fn generate_bindings() -> Builder<tauri::Wry> {
         claude::read_claude_current_config,
         claude::get_claude_temporary_run_plan,
         claude::launch_claude,
+        claude_agents::list_claude_assets,
+        claude_agents::read_claude_asset,
+        claude_agents::create_claude_asset,
+        claude_agents::save_claude_asset,
+        claude_agents::delete_claude_asset,
+        claude_agents::validate_claude_asset,
         claude_settings::list_claude_settings_files,
         claude_settings::get_active_claude_settings_file,
         claude_settings::set_active_claude_settings_file,
@@ -100,6 +129,17 @@ pub fn generate_bindings() -> Builder<tauri::Wry> {
         claude_settings::save_claude_settings_file,
         claude_settings::duplicate_claude_settings_file,
         claude_settings::merge_claude_settings_to_global,
+        claude_settings::merge_claude_settings_to_global_with_options,
+        claude_settings::read_claude_settings_sections,
+        claude_settings::save_claude_settings_sections,
+        claude_settings::validate_claude_permissions,
+        claude_settings::simulate_claude_permission,
+        claude_settings::list_claude_settings_layers,
+        claude_settings::read_claude_project_settings,
+        claude_settings::save_claude_project_settings,
+        claude_settings::read_claude_project_settings_sections,
+        claude_settings::save_claude_project_settings_sections,
+        claude_settings::get_claude_effective_settings,
         claude_settings::load_claude_settings_from_live,
         claude_settings::get_claude_settings_launch_command,
         claude_settings::plan_claude_temporary_run_from_file,
@@ -115,10 +155,16 @@ pub fn generate_bindings() -> Builder<tauri::Wry> {
         codex::apply_codex_profile,
         codex::get_codex_config_status,
         codex::read_codex_current_config,
+        codex::list_codex_native_profiles,
+        codex::import_codex_native_profiles,
+        codex::export_codex_profiles_to_native,
         codex::get_codex_cli_capability,
         codex::get_codex_temporary_run_plan,
         codex::prepare_codex_in_app_run,
         codex::launch_codex,
+        codex::list_codex_isolated_runs,
+        codex::merge_codex_run_history,
+        codex::discard_codex_runtime_home,
         hermes::list_hermes_profiles,
         hermes::get_hermes_profile,
         hermes::save_hermes_profile,
@@ -179,7 +225,14 @@ pub fn generate_bindings() -> Builder<tauri::Wry> {
         openclaw::save_openclaw_subagents,
         sessions::list_session_projects,
         sessions::list_sessions,
+        sessions::list_all_sessions,
         sessions::get_session_detail,
+        sessions::export_session,
+        sessions::get_usage_report,
+        sessions::export_usage_report,
+        sessions::get_retention_policy,
+        sessions::save_retention_policy,
+        sessions::apply_retention,
         sessions::start_sessions_watcher,
         sessions::stop_sessions_watcher,
         sessions::delete_session,
@@ -225,6 +278,10 @@ pub fn generate_bindings() -> Builder<tauri::Wry> {
         codex_auth_profiles::rename_codex_auth_profile,
         codex_auth_profiles::detect_codex_auth_conflict,
         codex_auth_profiles::detect_codex_apply_auth_conflict,
+        codex_auth_profiles::check_codex_auth_freshness,
+        codex_auth_profiles::set_codex_auth_rotation_order,
+        codex_auth_profiles::set_codex_auth_profile_limited_until,
+        codex_auth_profiles::rotate_codex_auth_profile,
         window::reset_window_state,
     ])
 }
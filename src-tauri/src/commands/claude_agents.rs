@@ -0,0 +1,74 @@
+//! Claude Code subagent and slash command commands (Tauri wrappers).
+//!
+//! Core logic lives in `droidgear_core::claude_agents`. `project_dir` selects
+//! the project whose `.claude/agents` and `.claude/commands` are included.
+
+pub use droidgear_core::claude_agents::{
+    ClaudeAsset, ClaudeAssetIssue, ClaudeAssetKind, ClaudeAssetScope,
+};
+
+use droidgear_core::claude_agents;
+use std::path::Path;
+
+/// Lists user subagents or slash commands, plus the project's when given.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_claude_assets(
+    kind: ClaudeAssetKind,
+    project_dir: Option<String>,
+) -> Result<Vec<ClaudeAsset>, String> {
+    claude_agents::list_assets(kind, project_dir.as_deref().map(Path::new))
+}
+
+/// Reads a single subagent or slash command.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_claude_asset(
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    project_dir: Option<String>,
+    name: String,
+) -> Result<ClaudeAsset, String> {
+    claude_agents::read_asset(kind, scope, project_dir.as_deref().map(Path::new), &name)
+}
+
+/// Creates a subagent or slash command from a placeholder template.
+#[tauri::command]
+#[specta::specta]
+pub async fn create_claude_asset(
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    project_dir: Option<String>,
+    name: String,
+) -> Result<ClaudeAsset, String> {
+    claude_agents::create_asset(kind, scope, project_dir.as_deref().map(Path::new), &name)
+}
+
+/// Validates and writes a subagent or slash command.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_claude_asset(
+    project_dir: Option<String>,
+    asset: ClaudeAsset,
+) -> Result<(), String> {
+    claude_agents::save_asset(project_dir.as_deref().map(Path::new), &asset)
+}
+
+/// Deletes a subagent or slash command.
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_claude_asset(
+    kind: ClaudeAssetKind,
+    scope: ClaudeAssetScope,
+    project_dir: Option<String>,
+    name: String,
+) -> Result<(), String> {
+    claude_agents::delete_asset(kind, scope, project_dir.as_deref().map(Path::new), &name)
+}
+
+/// Checks front matter without saving.
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_claude_asset(asset: ClaudeAsset) -> Result<Vec<ClaudeAssetIssue>, String> {
+    Ok(claude_agents::validate_asset(&asset))
+}
@@ -5,9 +5,14 @@
 //! manager (Global + custom) and a dual-mode launch entry point (normal vs.
 //! `--dangerously-skip-permissions`).
 
-pub use droidgear_core::claude_settings_files::ClaudeSettingsFileInfo;
+pub use droidgear_core::claude_permissions::{ClaudePermissionDecision, ClaudePermissionIssue};
+pub use droidgear_core::claude_settings_files::{
+    ClaudeEffectiveSettings, ClaudeSettingsFileInfo, ClaudeSettingsLayerInfo, ClaudeSettingsScope,
+};
+pub use droidgear_core::claude_settings_sections::{ClaudeMergeOptions, ClaudeSettingsSections};
 
-use droidgear_core::{claude_runtime, claude_settings_files};
+use droidgear_core::{claude_permissions, claude_runtime, claude_settings_files};
+use std::path::Path;
 
 use crate::utils::preferences::load_preferences;
 use crate::utils::terminal_launch::{launch_in_terminal, LaunchSpec};
@@ -86,12 +91,128 @@ pub async fn duplicate_claude_settings_file(
 }
 
 /// Merges the contents of a settings file into the global settings.json.
+/// Returns warnings about hook events and types DroidGear does not know.
 #[tauri::command]
 #[specta::specta]
-pub async fn merge_claude_settings_to_global(name: String) -> Result<(), String> {
+pub async fn merge_claude_settings_to_global(name: String) -> Result<Vec<String>, String> {
     claude_settings_files::merge_settings_file_to_global(&name)
 }
 
+/// Merges a settings file into the global settings.json with a merge
+/// strategy per top-level key (e.g. appending hooks instead of replacing them).
+#[tauri::command]
+#[specta::specta]
+pub async fn merge_claude_settings_to_global_with_options(
+    name: String,
+    options: ClaudeMergeOptions,
+) -> Result<Vec<String>, String> {
+    claude_settings_files::merge_settings_file_to_global_with_options(&name, &options)
+}
+
+/// Reads the typed sections (hooks, statusLine, apiKeyHelper, outputStyle,
+/// enabledPlugins) of a settings file.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_claude_settings_sections(name: String) -> Result<ClaudeSettingsSections, String> {
+    claude_settings_files::read_settings_sections(&name)
+}
+
+/// Validates and writes the typed sections into a settings file.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_claude_settings_sections(
+    name: String,
+    sections: ClaudeSettingsSections,
+) -> Result<(), String> {
+    claude_settings_files::save_settings_sections(&name, &sections)
+}
+
+/// Flags malformed, duplicate and shadowed permission rules in a settings file.
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_claude_permissions(
+    name: String,
+) -> Result<Vec<ClaudePermissionIssue>, String> {
+    claude_permissions::validate_settings_file_permissions(&name)
+}
+
+/// Simulates a tool call such as `Bash("git push")` against the user,
+/// project and local settings (plus an optional settings file) and reports
+/// the deciding rule.
+#[tauri::command]
+#[specta::specta]
+pub async fn simulate_claude_permission(
+    call: String,
+    project_dir: Option<String>,
+    settings_name: Option<String>,
+) -> Result<ClaudePermissionDecision, String> {
+    claude_permissions::simulate_permission_for_project(
+        project_dir.as_deref().map(std::path::Path::new),
+        settings_name.as_deref(),
+        &call,
+    )
+}
+
+/// Lists the user, project and local settings layers of a project.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_claude_settings_layers(
+    project_dir: String,
+) -> Result<Vec<ClaudeSettingsLayerInfo>, String> {
+    claude_settings_files::list_settings_layers(Path::new(&project_dir))
+}
+
+/// Reads `<project>/.claude/settings.json` or `settings.local.json`.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_claude_project_settings(
+    project_dir: String,
+    scope: ClaudeSettingsScope,
+) -> Result<serde_json::Value, String> {
+    claude_settings_files::read_project_settings_layer(Path::new(&project_dir), scope)
+}
+
+/// Writes a project settings layer.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_claude_project_settings(
+    project_dir: String,
+    scope: ClaudeSettingsScope,
+    contents: serde_json::Value,
+) -> Result<(), String> {
+    claude_settings_files::save_project_settings_layer(Path::new(&project_dir), scope, contents)
+}
+
+/// Reads the typed sections of a project settings layer.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_claude_project_settings_sections(
+    project_dir: String,
+    scope: ClaudeSettingsScope,
+) -> Result<ClaudeSettingsSections, String> {
+    claude_settings_files::read_project_settings_sections(Path::new(&project_dir), scope)
+}
+
+/// Validates and writes the typed sections into a project settings layer.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_claude_project_settings_sections(
+    project_dir: String,
+    scope: ClaudeSettingsScope,
+    sections: ClaudeSettingsSections,
+) -> Result<(), String> {
+    claude_settings_files::save_project_settings_sections(Path::new(&project_dir), scope, &sections)
+}
+
+/// Merges the user, project and local settings with per-key provenance.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_claude_effective_settings(
+    project_dir: String,
+) -> Result<ClaudeEffectiveSettings, String> {
+    claude_settings_files::effective_settings(Path::new(&project_dir))
+}
+
 /// Loads the current global settings.json content into a settings file.
 #[tauri::command]
 #[specta::specta]
@@ -3,9 +3,11 @@
 //! Core logic lives in `droidgear-core`.
 
 pub use droidgear_core::codex::{CodexConfigStatus, CodexCurrentConfig, CodexProfile};
+pub use droidgear_core::codex_native_profiles::CodexNativeProfile;
 
 use droidgear_core::codex_runtime::{
-    self, CodexCliCapability, CodexInAppRunPlan, CodexTemporaryLaunchPlan, CodexTemporaryRunPlan,
+    self, CodexCliCapability, CodexHistoryMergeReport, CodexInAppRunPlan, CodexIsolatedRun,
+    CodexTemporaryLaunchPlan, CodexTemporaryRunPlan,
 };
 
 use crate::utils::login_shell::run_command_in_login_shell;
@@ -82,6 +84,31 @@ pub async fn read_codex_current_config() -> Result<CodexCurrentConfig, String> {
     droidgear_core::codex::read_codex_current_config()
 }
 
+/// List the `[profiles.*]` tables in `~/.codex/config.toml`
+#[tauri::command]
+#[specta::specta]
+pub async fn list_codex_native_profiles() -> Result<Vec<CodexNativeProfile>, String> {
+    droidgear_core::codex_native_profiles::list_codex_native_profiles()
+}
+
+/// Import `[profiles.*]` tables as profiles (all of them when `names` is empty)
+#[tauri::command]
+#[specta::specta]
+pub async fn import_codex_native_profiles(names: Vec<String>) -> Result<Vec<CodexProfile>, String> {
+    droidgear_core::codex_native_profiles::import_codex_native_profiles(&names)
+}
+
+/// Export profiles into `~/.codex/config.toml` as `[profiles.*]` tables.
+/// Existing tables of the same name are only replaced when `overwrite` is set.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_codex_profiles_to_native(
+    ids: Vec<String>,
+    overwrite: bool,
+) -> Result<Vec<CodexNativeProfile>, String> {
+    droidgear_core::codex_native_profiles::export_codex_profiles_to_native(&ids, overwrite)
+}
+
 /// Inspect the installed Codex CLI and report whether temporary-run launch-time
 /// overrides are supported.
 #[tauri::command]
@@ -112,26 +139,53 @@ pub async fn get_codex_cli_capability() -> Result<CodexCliCapability, String> {
 /// Build the zero-write temporary-run launch plan preview for a Codex profile.
 #[tauri::command]
 #[specta::specta]
-pub async fn get_codex_temporary_run_plan(id: String) -> Result<CodexTemporaryRunPlan, String> {
-    let profile = droidgear_core::codex::get_codex_profile(&id)?;
-    let plan = codex_runtime::build_temporary_run_plan(&profile)?;
+pub async fn get_codex_temporary_run_plan(
+    id: String,
+    app: tauri::AppHandle,
+) -> Result<CodexTemporaryRunPlan, String> {
+    let plan = build_codex_run_plan(&id, &app)?;
     Ok((&plan).into())
 }
 
+/// Builds a temporary-run plan using the saved `codex_run` preferences.
+///
+/// The app does not see Codex exit in either the external or the in-app
+/// terminal, so exit options are adjusted with
+/// [`codex_runtime::unattended_run_options`].
+fn build_codex_run_plan(
+    id: &str,
+    app: &tauri::AppHandle,
+) -> Result<CodexTemporaryLaunchPlan, String> {
+    let home_dir = dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
+    let saved = load_preferences(app)
+        .unwrap_or_default()
+        .codex_run
+        .unwrap_or_default();
+    let (options, warning) = codex_runtime::unattended_run_options(saved);
+    let profile = droidgear_core::codex::get_codex_profile(id)?;
+    let mut plan = codex_runtime::build_temporary_run_plan_with_options_for_home(
+        &home_dir, &profile, &options,
+    )?;
+    plan.warnings.extend(warning);
+    Ok(plan)
+}
+
 /// Prepare a one-shot in-app terminal run (runtime CODEX_HOME + env, including secrets).
 ///
 /// Does not open an external terminal. The frontend injects env into the PTY and
 /// auto-executes the command. Secret values must not be persisted client-side.
 #[tauri::command]
 #[specta::specta]
-pub async fn prepare_codex_in_app_run(id: String) -> Result<CodexInAppRunPlan, String> {
+pub async fn prepare_codex_in_app_run(
+    id: String,
+    app: tauri::AppHandle,
+) -> Result<CodexInAppRunPlan, String> {
     let home_dir = dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
     if let Err(error) = codex_runtime::cleanup_stale_runtime_homes_for_home(&home_dir) {
         log::warn!("Failed to clean up stale Codex runtime homes: {error}");
     }
 
-    let profile = droidgear_core::codex::get_codex_profile(&id)?;
-    let plan = codex_runtime::build_temporary_run_plan(&profile)?;
+    let plan = build_codex_run_plan(&id, &app)?;
     Ok((&plan).into())
 }
 
@@ -148,8 +202,7 @@ pub async fn launch_codex(
         log::warn!("Failed to clean up stale Codex runtime homes: {error}");
     }
 
-    let profile = droidgear_core::codex::get_codex_profile(&id)?;
-    let plan = codex_runtime::build_temporary_run_plan(&profile)?;
+    let plan = build_codex_run_plan(&id, &app)?;
     let prefs = load_preferences(&app).unwrap_or_default();
     let preferred = prefs.preferred_terminal.unwrap_or_default();
 
@@ -159,6 +212,27 @@ pub async fn launch_codex(
     launch_in_terminal(&spec, &preferred)
 }
 
+/// List isolated temporary runs whose history has not been merged or discarded
+#[tauri::command]
+#[specta::specta]
+pub async fn list_codex_isolated_runs() -> Result<Vec<CodexIsolatedRun>, String> {
+    codex_runtime::list_codex_isolated_runs()
+}
+
+/// Merge the sessions and history of a temporary run into the live CODEX_HOME
+#[tauri::command]
+#[specta::specta]
+pub async fn merge_codex_run_history(path: String) -> Result<CodexHistoryMergeReport, String> {
+    codex_runtime::merge_codex_run_history(std::path::Path::new(&path))
+}
+
+/// Delete a temporary run's runtime home and the history it kept
+#[tauri::command]
+#[specta::specta]
+pub async fn discard_codex_runtime_home(path: String) -> Result<(), String> {
+    codex_runtime::discard_codex_runtime_home(std::path::Path::new(&path))
+}
+
 fn build_codex_launch_spec(plan: &CodexTemporaryLaunchPlan) -> LaunchSpec {
     LaunchSpec {
         program: plan.program.clone(),
@@ -188,6 +262,7 @@ mod tests {
             unset_env: vec!["OPENAI_API_KEY".to_string()],
             warnings: vec!["warning".to_string()],
             runtime_home_path: PathBuf::from("/tmp/runtime-codex"),
+            options: Default::default(),
         });
 
         assert_eq!(spec.program, "codex");
@@ -2,7 +2,9 @@
 //!
 //! Core logic lives in `droidgear-core`.
 
-pub use droidgear_core::codex_auth_profiles::{CodexAuthConflictInfo, CodexAuthProfileState};
+pub use droidgear_core::codex_auth_profiles::{
+    CodexAuthConflictInfo, CodexAuthFreshness, CodexAuthProfileState, CodexAuthRotation,
+};
 
 /// List all Codex auth profiles
 #[tauri::command]
@@ -63,3 +65,36 @@ pub async fn detect_codex_apply_auth_conflict(
 ) -> Result<CodexAuthConflictInfo, String> {
     droidgear_core::codex_auth_profiles::detect_apply_auth_conflict(&codex_profile_id)
 }
+
+/// Compare the live auth.json with the active profile's saved snapshot
+#[tauri::command]
+#[specta::specta]
+pub async fn check_codex_auth_freshness() -> Result<CodexAuthFreshness, String> {
+    droidgear_core::codex_auth_profiles::check_auth_freshness()
+}
+
+/// Reorder saved auth profiles; the order is used for rotation
+#[tauri::command]
+#[specta::specta]
+pub async fn set_codex_auth_rotation_order(names: Vec<String>) -> Result<(), String> {
+    droidgear_core::codex_auth_profiles::set_rotation_order(&names)
+}
+
+/// Mark an auth profile as rate limited until a time, or clear the marker
+#[tauri::command]
+#[specta::specta]
+pub async fn set_codex_auth_profile_limited_until(
+    name: String,
+    until: Option<String>,
+) -> Result<(), String> {
+    droidgear_core::codex_auth_profiles::set_profile_limited_until(&name, until.as_deref())
+}
+
+/// Mark the active auth profile as limited and switch to the next healthy one
+#[tauri::command]
+#[specta::specta]
+pub async fn rotate_codex_auth_profile(
+    cooldown_minutes: Option<u32>,
+) -> Result<CodexAuthRotation, String> {
+    droidgear_core::codex_auth_profiles::rotate_profile(cooldown_minutes)
+}
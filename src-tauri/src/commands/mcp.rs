@@ -2,9 +2,16 @@
 //!
 //! Core logic lives in `droidgear-core`.
 
-pub use droidgear_core::mcp::McpServer;
+pub use droidgear_core::mcp::{
+    McpEffectiveServer, McpProjectConfig, McpProjectFile, McpServer, McpServerConfig,
+};
+pub use droidgear_core::mcp_presets::McpPreset;
+pub use droidgear_core::mcp_probe::McpProbeResult;
+pub use droidgear_core::mcp_sync::{McpSyncPreview, McpSyncTarget};
+use std::collections::HashMap;
+use std::path::Path;
 
-/// Loads all MCP servers from ~/.factory/mcp.json
+/// Loads all MCP servers from the DroidGear catalog
 #[tauri::command]
 #[specta::specta]
 pub async fn load_mcp_servers() -> Result<Vec<McpServer>, String> {
@@ -31,3 +38,109 @@ pub async fn delete_mcp_server(name: String) -> Result<(), String> {
 pub async fn toggle_mcp_server(name: String, disabled: bool) -> Result<(), String> {
     droidgear_core::mcp::toggle_mcp_server(&name, disabled)
 }
+
+/// Previews syncing the MCP catalog into the selected tools
+#[tauri::command]
+#[specta::specta]
+pub async fn preview_mcp_sync(targets: Vec<McpSyncTarget>) -> Result<Vec<McpSyncPreview>, String> {
+    droidgear_core::mcp_sync::preview_mcp_sync(&targets)
+}
+
+/// Syncs the MCP catalog into the selected tools
+#[tauri::command]
+#[specta::specta]
+pub async fn sync_mcp_servers(targets: Vec<McpSyncTarget>) -> Result<Vec<McpSyncPreview>, String> {
+    droidgear_core::mcp_sync::sync_mcp_servers(&targets)
+}
+
+/// Launches (or connects to) an MCP server and runs `initialize` + `tools/list`
+#[tauri::command]
+#[specta::specta]
+pub async fn probe_mcp_server(config: McpServerConfig) -> Result<McpProbeResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        droidgear_core::mcp_probe::probe_mcp_server(&config)
+    })
+    .await
+    .map_err(|e| format!("MCP probe task failed: {e}"))?
+}
+
+/// Lists bundled and user MCP presets
+#[tauri::command]
+#[specta::specta]
+pub async fn load_mcp_presets() -> Result<Vec<McpPreset>, String> {
+    droidgear_core::mcp_presets::load_mcp_presets()
+}
+
+/// Installs an MCP preset variant into the catalog with the given inputs
+#[tauri::command]
+#[specta::specta]
+pub async fn install_mcp_preset(
+    preset_id: String,
+    variant_id: String,
+    server_name: String,
+    values: HashMap<String, String>,
+) -> Result<McpServer, String> {
+    droidgear_core::mcp_presets::install_mcp_preset(&preset_id, &variant_id, &server_name, &values)
+}
+
+/// Lists a project's MCP files (`.factory/mcp.json`, `.mcp.json`)
+#[tauri::command]
+#[specta::specta]
+pub async fn load_project_mcp_configs(
+    project_dir: String,
+) -> Result<Vec<McpProjectConfig>, String> {
+    droidgear_core::mcp::load_project_mcp_configs(Path::new(&project_dir))
+}
+
+/// Saves an MCP server into a project file
+#[tauri::command]
+#[specta::specta]
+pub async fn save_project_mcp_server(
+    project_dir: String,
+    file: McpProjectFile,
+    server: McpServer,
+) -> Result<(), String> {
+    droidgear_core::mcp::save_project_mcp_server(Path::new(&project_dir), file, server)
+}
+
+/// Deletes an MCP server from a project file
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_project_mcp_server(
+    project_dir: String,
+    file: McpProjectFile,
+    name: String,
+) -> Result<(), String> {
+    droidgear_core::mcp::delete_project_mcp_server(Path::new(&project_dir), file, &name)
+}
+
+/// User and project MCP servers merged, with their origins
+#[tauri::command]
+#[specta::specta]
+pub async fn load_effective_mcp_servers(
+    project_dir: String,
+) -> Result<Vec<McpEffectiveServer>, String> {
+    droidgear_core::mcp::effective_mcp_servers(Path::new(&project_dir))
+}
+
+/// Moves a project MCP server into the user catalog
+#[tauri::command]
+#[specta::specta]
+pub async fn promote_mcp_server(
+    project_dir: String,
+    file: McpProjectFile,
+    name: String,
+) -> Result<(), String> {
+    droidgear_core::mcp::promote_mcp_server(Path::new(&project_dir), file, &name)
+}
+
+/// Moves a user MCP server into a project file
+#[tauri::command]
+#[specta::specta]
+pub async fn demote_mcp_server(
+    project_dir: String,
+    file: McpProjectFile,
+    name: String,
+) -> Result<(), String> {
+    droidgear_core::mcp::demote_mcp_server(Path::new(&project_dir), file, &name)
+}
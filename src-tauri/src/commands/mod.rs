@@ -6,6 +6,7 @@
 pub mod channel;
 pub mod channel_export;
 pub mod claude;
+pub mod claude_agents;
 pub mod claude_settings;
 pub mod codex;
 pub mod codex_auth_profiles;
@@ -7,6 +7,9 @@ use std::path::PathBuf;
 use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager};
 
+pub use droidgear_core::session_analytics::{UsageExportFormat, UsageGroupBy, UsageReport};
+pub use droidgear_core::session_export::SessionExportOptions;
+pub use droidgear_core::session_retention::{RetentionAction, RetentionPolicy, RetentionReport};
 pub use droidgear_core::sessions::{SessionDetail, SessionProject, SessionSummary};
 
 fn sessions_dir() -> Result<PathBuf, String> {
@@ -27,6 +30,13 @@ pub async fn list_sessions(project: Option<String>) -> Result<Vec<SessionSummary
     droidgear_core::sessions::list_sessions(project.as_deref())
 }
 
+/// Lists Droid, Claude Code and Codex sessions as one timeline.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_all_sessions() -> Result<Vec<SessionSummary>, String> {
+    droidgear_core::sessions::list_all_sessions()
+}
+
 /// Gets detailed session information including messages.
 #[tauri::command]
 #[specta::specta]
@@ -34,6 +44,69 @@ pub async fn get_session_detail(session_path: String) -> Result<SessionDetail, S
     droidgear_core::sessions::get_session_detail(&session_path)
 }
 
+/// Exports a session as Markdown, HTML or JSON to `output_path`, redacting
+/// sensitive values according to `options`.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_session(
+    session_path: String,
+    options: SessionExportOptions,
+    output_path: String,
+) -> Result<(), String> {
+    droidgear_core::session_export::export_session_to_file(
+        &session_path,
+        &options,
+        std::path::Path::new(&output_path),
+    )
+}
+
+/// Rolls token usage of all sessions up by day, week, project or model.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_usage_report(group_by: UsageGroupBy) -> Result<UsageReport, String> {
+    droidgear_core::session_analytics::usage_report(group_by)
+}
+
+/// Writes a token usage report as CSV or JSON to `output_path`.
+#[tauri::command]
+#[specta::specta]
+pub async fn export_usage_report(
+    group_by: UsageGroupBy,
+    format: UsageExportFormat,
+    output_path: String,
+) -> Result<(), String> {
+    droidgear_core::session_analytics::export_usage_report(
+        group_by,
+        format,
+        std::path::Path::new(&output_path),
+    )
+}
+
+/// Loads the saved session retention rules.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_retention_policy() -> Result<RetentionPolicy, String> {
+    droidgear_core::session_retention::load_retention_policy()
+}
+
+/// Saves the session retention rules.
+#[tauri::command]
+#[specta::specta]
+pub async fn save_retention_policy(policy: RetentionPolicy) -> Result<(), String> {
+    droidgear_core::session_retention::save_retention_policy(&policy)
+}
+
+/// Archives or deletes sessions matching `policy`; with `dry_run` only reports.
+#[tauri::command]
+#[specta::specta]
+pub async fn apply_retention(
+    policy: RetentionPolicy,
+    action: RetentionAction,
+    dry_run: bool,
+) -> Result<RetentionReport, String> {
+    droidgear_core::session_retention::apply_retention(&policy, action, dry_run)
+}
+
 /// Deletes a session by removing its .jsonl and .settings.json files.
 #[tauri::command]
 #[specta::specta]
@@ -7,7 +7,9 @@ use std::path::PathBuf;
 use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager};
 
-pub use droidgear_core::specs::SpecFile;
+pub use droidgear_core::spec_templates::{SpecTemplate, SpecTemplateVars};
+pub use droidgear_core::specs::{SpecFile, SpecQuery, SpecSessionLink, SpecStatus, SpecSummary};
+pub use droidgear_core::specs_history::SpecRevision;
 
 fn specs_dir() -> Result<PathBuf, String> {
     Ok(droidgear_core::paths::get_factory_home()?.join("specs"))
@@ -48,6 +50,91 @@ pub async fn update_spec(path: String, content: String) -> Result<SpecFile, Stri
     droidgear_core::specs::update_spec(&path, &content)
 }
 
+/// Lists spec front matter matching a query, without loading content.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_spec_summaries(query: SpecQuery) -> Result<Vec<SpecSummary>, String> {
+    droidgear_core::specs::list_spec_summaries(&query)
+}
+
+/// Sets a spec's front-matter status, leaving the body untouched.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_spec_status(path: String, status: SpecStatus) -> Result<SpecFile, String> {
+    droidgear_core::specs::set_spec_status(&path, status)
+}
+
+/// Finds the session that first wrote each spec.
+#[tauri::command]
+#[specta::specta]
+pub async fn correlate_spec_sessions() -> Result<Vec<SpecSessionLink>, String> {
+    droidgear_core::specs::correlate_spec_sessions()
+}
+
+/// Writes `session_id` and `project` into specs that lack them.
+#[tauri::command]
+#[specta::specta]
+pub async fn link_specs_to_sessions() -> Result<u32, String> {
+    droidgear_core::specs::link_specs_to_sessions().map(|n| n as u32)
+}
+
+/// Lists specs whose front matter names the given project.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_specs_for_project(project: String) -> Result<Vec<SpecSummary>, String> {
+    droidgear_core::specs::list_specs_for_project(&project)
+}
+
+/// Lists stored revisions of a spec, newest first.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_spec_revisions(name: String) -> Result<Vec<SpecRevision>, String> {
+    droidgear_core::specs_history::list_spec_revisions(&name)
+}
+
+/// Reads the content of one spec revision.
+#[tauri::command]
+#[specta::specta]
+pub async fn read_spec_revision(name: String, id: u32) -> Result<String, String> {
+    droidgear_core::specs_history::read_spec_revision(&name, id)
+}
+
+/// Unified diff between two revisions, or a revision and the current file.
+#[tauri::command]
+#[specta::specta]
+pub async fn diff_spec_revisions(
+    name: String,
+    from: u32,
+    to: Option<u32>,
+) -> Result<String, String> {
+    droidgear_core::specs_history::diff_spec_revisions(&name, from, to)
+}
+
+/// Restores a spec to an earlier revision.
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_spec_revision(name: String, id: u32) -> Result<SpecFile, String> {
+    droidgear_core::specs_history::restore_spec_revision(&name, id)
+}
+
+/// Lists the bundled and user spec templates.
+#[tauri::command]
+#[specta::specta]
+pub async fn list_spec_templates() -> Result<Vec<SpecTemplate>, String> {
+    droidgear_core::spec_templates::list_spec_templates()
+}
+
+/// Creates a dated spec from a template.
+#[tauri::command]
+#[specta::specta]
+pub async fn create_spec_from_template(
+    template: String,
+    name: String,
+    vars: SpecTemplateVars,
+) -> Result<SpecFile, String> {
+    droidgear_core::spec_templates::create_spec_from_template(&template, &name, &vars)
+}
+
 /// State for the specs file watcher
 pub struct SpecsWatcherState(pub Mutex<Option<RecommendedWatcher>>);
 
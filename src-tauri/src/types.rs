@@ -1,5 +1,6 @@
 //! Shared types and validation functions for the Tauri application.
 
+use droidgear_core::codex_runtime::CodexRunOptions;
 use droidgear_core::droid_runtime::DroidRunPreferences;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -51,6 +52,11 @@ pub struct AppPreferences {
     /// Droid temporary-run runtime policy.
     #[serde(default)]
     pub droid_run: Option<DroidRunPreferences>,
+    /// Codex temporary-run history handling. Runs launched from the app
+    /// cannot act on exit, so they ignore merge on exit and run ephemeral
+    /// ones as isolated; their history waits in the isolated runs list.
+    #[serde(default)]
+    pub codex_run: Option<CodexRunOptions>,
 }
 
 impl Default for AppPreferences {
@@ -63,6 +69,7 @@ impl Default for AppPreferences {
             disable_auto_update: None,    // None means auto-update enabled (default)
             preferred_terminal: None,     // None means platform default
             droid_run: None,
+            codex_run: None,
         }
     }
 }
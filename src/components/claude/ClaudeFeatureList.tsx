@@ -127,9 +127,10 @@ export function ClaudeFeatureList() {
   const handleMergeToGlobal = async () => {
     if (!activeFile) return
     try {
-      await mergeToGlobal(activeFile.name)
+      const warnings = await mergeToGlobal(activeFile.name)
       setMergeDialogOpen(false)
       toast.success(t('claude.settingsFile.mergeDone'))
+      warnings.forEach(warning => toast.warning(warning))
     } catch (err) {
       toast.error(String(err))
     }
@@ -606,7 +606,7 @@ export function SessionsPage() {
                                     {block.thinking}
                                   </div>
                                 </details>
-                              ) : block.text ? (
+                              ) : block.type === 'text' && block.text ? (
                                 message.role === 'user' ? (
                                   <div className="whitespace-pre-wrap text-sm">
                                     {block.text}
@@ -716,6 +716,127 @@ async updateSpec(path: string, content: string) : Promise<Result<SpecFile, strin
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Lists spec front matter matching a query, without loading content.
+ */
+async listSpecSummaries(query: SpecQuery) : Promise<Result<SpecSummary[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_spec_summaries", { query }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sets a spec's front-matter status, leaving the body untouched.
+ */
+async setSpecStatus(path: string, status: SpecStatus) : Promise<Result<SpecFile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_spec_status", { path, status }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Finds the session that first wrote each spec.
+ */
+async correlateSpecSessions() : Promise<Result<SpecSessionLink[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("correlate_spec_sessions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Writes `session_id` and `project` into specs that lack them.
+ */
+async linkSpecsToSessions() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("link_specs_to_sessions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists specs whose front matter names the given project.
+ */
+async listSpecsForProject(project: string) : Promise<Result<SpecSummary[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_specs_for_project", { project }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists stored revisions of a spec, newest first.
+ */
+async listSpecRevisions(name: string) : Promise<Result<SpecRevision[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_spec_revisions", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads the content of one spec revision.
+ */
+async readSpecRevision(name: string, id: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_spec_revision", { name, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Unified diff between two revisions, or a revision and the current file.
+ */
+async diffSpecRevisions(name: string, from: number, to: number | null) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("diff_spec_revisions", { name, from, to }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restores a spec to an earlier revision.
+ */
+async restoreSpecRevision(name: string, id: number) : Promise<Result<SpecFile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_spec_revision", { name, id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists the bundled and user spec templates.
+ */
+async listSpecTemplates() : Promise<Result<SpecTemplate[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_spec_templates") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Creates a dated spec from a template.
+ */
+async createSpecFromTemplate(template: string, name: string, vars: SpecTemplateVars) : Promise<Result<SpecFile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_spec_from_template", { template, name, vars }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Starts watching the specs directory for changes.
  */
@@ -739,7 +860,7 @@ async stopSpecsWatcher() : Promise<Result<null, string>> {
 }
 },
 /**
- * Loads all MCP servers from ~/.factory/mcp.json
+ * Loads all MCP servers from the DroidGear catalog
  */
 async loadMcpServers() : Promise<Result<McpServer[], string>> {
     try {
@@ -782,6 +903,127 @@ async toggleMcpServer(name: string, disabled: boolean) : Promise<Result<null, st
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Previews syncing the MCP catalog into the selected tools
+ */
+async previewMcpSync(targets: McpSyncTarget[]) : Promise<Result<McpSyncPreview[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_mcp_sync", { targets }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Syncs the MCP catalog into the selected tools
+ */
+async syncMcpServers(targets: McpSyncTarget[]) : Promise<Result<McpSyncPreview[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_mcp_servers", { targets }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Launches (or connects to) an MCP server and runs `initialize` + `tools/list`
+ */
+async probeMcpServer(config: McpServerConfig) : Promise<Result<McpProbeResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("probe_mcp_server", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists bundled and user MCP presets
+ */
+async loadMcpPresets() : Promise<Result<McpPreset[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_mcp_presets") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Installs an MCP preset variant into the catalog with the given inputs
+ */
+async installMcpPreset(presetId: string, variantId: string, serverName: string, values: Partial<{ [key in string]: string }>) : Promise<Result<McpServer, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_mcp_preset", { presetId, variantId, serverName, values }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists a project's MCP files (`.factory/mcp.json`, `.mcp.json`)
+ */
+async loadProjectMcpConfigs(projectDir: string) : Promise<Result<McpProjectConfig[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_project_mcp_configs", { projectDir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Saves an MCP server into a project file
+ */
+async saveProjectMcpServer(projectDir: string, file: McpProjectFile, server: McpServer) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_project_mcp_server", { projectDir, file, server }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Deletes an MCP server from a project file
+ */
+async deleteProjectMcpServer(projectDir: string, file: McpProjectFile, name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_project_mcp_server", { projectDir, file, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * User and project MCP servers merged, with their origins
+ */
+async loadEffectiveMcpServers(projectDir: string) : Promise<Result<McpEffectiveServer[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("load_effective_mcp_servers", { projectDir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Moves a project MCP server into the user catalog
+ */
+async promoteMcpServer(projectDir: string, file: McpProjectFile, name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("promote_mcp_server", { projectDir, file, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Moves a user MCP server into a project file
+ */
+async demoteMcpServer(projectDir: string, file: McpProjectFile, name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("demote_mcp_server", { projectDir, file, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * List all Claude Code profiles
  */
@@ -925,6 +1167,72 @@ async launchClaude(id: string, cwd: string | null) : Promise<Result<null, string
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Lists user subagents or slash commands, plus the project's when given.
+ */
+async listClaudeAssets(kind: ClaudeAssetKind, projectDir: string | null) : Promise<Result<ClaudeAsset[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_claude_assets", { kind, projectDir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads a single subagent or slash command.
+ */
+async readClaudeAsset(kind: ClaudeAssetKind, scope: ClaudeAssetScope, projectDir: string | null, name: string) : Promise<Result<ClaudeAsset, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_claude_asset", { kind, scope, projectDir, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Creates a subagent or slash command from a placeholder template.
+ */
+async createClaudeAsset(kind: ClaudeAssetKind, scope: ClaudeAssetScope, projectDir: string | null, name: string) : Promise<Result<ClaudeAsset, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_claude_asset", { kind, scope, projectDir, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Validates and writes a subagent or slash command.
+ */
+async saveClaudeAsset(projectDir: string | null, asset: ClaudeAsset) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_claude_asset", { projectDir, asset }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Deletes a subagent or slash command.
+ */
+async deleteClaudeAsset(kind: ClaudeAssetKind, scope: ClaudeAssetScope, projectDir: string | null, name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_claude_asset", { kind, scope, projectDir, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Checks front matter without saving.
+ */
+async validateClaudeAsset(asset: ClaudeAsset) : Promise<Result<ClaudeAssetIssue[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_claude_asset", { asset }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Lists every Claude settings file (global + custom).
  */
@@ -1015,8 +1323,9 @@ async duplicateClaudeSettingsFile(name: string, newName: string) : Promise<Resul
 },
 /**
  * Merges the contents of a settings file into the global settings.json.
+ * Returns warnings about hook events and types DroidGear does not know.
  */
-async mergeClaudeSettingsToGlobal(name: string) : Promise<Result<null, string>> {
+async mergeClaudeSettingsToGlobal(name: string) : Promise<Result<string[], string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("merge_claude_settings_to_global", { name }) };
 } catch (e) {
@@ -1024,6 +1333,131 @@ async mergeClaudeSettingsToGlobal(name: string) : Promise<Result<null, string>>
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Merges a settings file into the global settings.json with a merge
+ * strategy per top-level key (e.g. appending hooks instead of replacing them).
+ */
+async mergeClaudeSettingsToGlobalWithOptions(name: string, options: ClaudeMergeOptions) : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("merge_claude_settings_to_global_with_options", { name, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads the typed sections (hooks, statusLine, apiKeyHelper, outputStyle,
+ * enabledPlugins) of a settings file.
+ */
+async readClaudeSettingsSections(name: string) : Promise<Result<ClaudeSettingsSections, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_claude_settings_sections", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Validates and writes the typed sections into a settings file.
+ */
+async saveClaudeSettingsSections(name: string, sections: ClaudeSettingsSections) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_claude_settings_sections", { name, sections }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Flags malformed, duplicate and shadowed permission rules in a settings file.
+ */
+async validateClaudePermissions(name: string) : Promise<Result<ClaudePermissionIssue[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_claude_permissions", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Simulates a tool call such as `Bash("git push")` against the user,
+ * project and local settings (plus an optional settings file) and reports
+ * the deciding rule.
+ */
+async simulateClaudePermission(call: string, projectDir: string | null, settingsName: string | null) : Promise<Result<ClaudePermissionDecision, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("simulate_claude_permission", { call, projectDir, settingsName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists the user, project and local settings layers of a project.
+ */
+async listClaudeSettingsLayers(projectDir: string) : Promise<Result<ClaudeSettingsLayerInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_claude_settings_layers", { projectDir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads `<project>/.claude/settings.json` or `settings.local.json`.
+ */
+async readClaudeProjectSettings(projectDir: string, scope: ClaudeSettingsScope) : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_claude_project_settings", { projectDir, scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Writes a project settings layer.
+ */
+async saveClaudeProjectSettings(projectDir: string, scope: ClaudeSettingsScope, contents: JsonValue) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_claude_project_settings", { projectDir, scope, contents }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reads the typed sections of a project settings layer.
+ */
+async readClaudeProjectSettingsSections(projectDir: string, scope: ClaudeSettingsScope) : Promise<Result<ClaudeSettingsSections, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_claude_project_settings_sections", { projectDir, scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Validates and writes the typed sections into a project settings layer.
+ */
+async saveClaudeProjectSettingsSections(projectDir: string, scope: ClaudeSettingsScope, sections: ClaudeSettingsSections) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_claude_project_settings_sections", { projectDir, scope, sections }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Merges the user, project and local settings with per-key provenance.
+ */
+async getClaudeEffectiveSettings(projectDir: string) : Promise<Result<ClaudeEffectiveSettings, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_claude_effective_settings", { projectDir }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Loads the current global settings.json content into a settings file.
  */
@@ -1183,11 +1617,45 @@ async getCodexConfigStatus() : Promise<Result<CodexConfigStatus, string>> {
 }
 },
 /**
- * Read current Codex configuration from config files
+ * Read current Codex configuration from config files
+ */
+async readCodexCurrentConfig() : Promise<Result<CodexCurrentConfig, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_codex_current_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * List the `[profiles.*]` tables in `~/.codex/config.toml`
+ */
+async listCodexNativeProfiles() : Promise<Result<CodexNativeProfile[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_codex_native_profiles") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Import `[profiles.*]` tables as profiles (all of them when `names` is empty)
+ */
+async importCodexNativeProfiles(names: string[]) : Promise<Result<CodexProfile[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_codex_native_profiles", { names }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Export profiles into `~/.codex/config.toml` as `[profiles.*]` tables.
+ * Existing tables of the same name are only replaced when `overwrite` is set.
  */
-async readCodexCurrentConfig() : Promise<Result<CodexCurrentConfig, string>> {
+async exportCodexProfilesToNative(ids: string[], overwrite: boolean) : Promise<Result<CodexNativeProfile[], string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("read_codex_current_config") };
+    return { status: "ok", data: await TAURI_INVOKE("export_codex_profiles_to_native", { ids, overwrite }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -1241,6 +1709,39 @@ async launchCodex(id: string, cwd: string | null) : Promise<Result<null, string>
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * List isolated temporary runs whose history has not been merged or discarded
+ */
+async listCodexIsolatedRuns() : Promise<Result<CodexIsolatedRun[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_codex_isolated_runs") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Merge the sessions and history of a temporary run into the live CODEX_HOME
+ */
+async mergeCodexRunHistory(path: string) : Promise<Result<CodexHistoryMergeReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("merge_codex_run_history", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a temporary run's runtime home and the history it kept
+ */
+async discardCodexRuntimeHome(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("discard_codex_runtime_home", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * List all Hermes profiles
  */
@@ -1901,6 +2402,17 @@ async listSessions(project: string | null) : Promise<Result<SessionSummary[], st
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Lists Droid, Claude Code and Codex sessions as one timeline.
+ */
+async listAllSessions() : Promise<Result<SessionSummary[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_all_sessions") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Gets detailed session information including messages.
  */
@@ -1912,6 +2424,73 @@ async getSessionDetail(sessionPath: string) : Promise<Result<SessionDetail, stri
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Exports a session as Markdown, HTML or JSON to `output_path`, redacting
+ * sensitive values according to `options`.
+ */
+async exportSession(sessionPath: string, options: SessionExportOptions, outputPath: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_session", { sessionPath, options, outputPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Rolls token usage of all sessions up by day, week, project or model.
+ */
+async getUsageReport(groupBy: UsageGroupBy) : Promise<Result<UsageReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_report", { groupBy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Writes a token usage report as CSV or JSON to `output_path`.
+ */
+async exportUsageReport(groupBy: UsageGroupBy, format: UsageExportFormat, outputPath: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_usage_report", { groupBy, format, outputPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Loads the saved session retention rules.
+ */
+async getRetentionPolicy() : Promise<Result<RetentionPolicy, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_retention_policy") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Saves the session retention rules.
+ */
+async saveRetentionPolicy(policy: RetentionPolicy) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_retention_policy", { policy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Archives or deletes sessions matching `policy`; with `dry_run` only reports.
+ */
+async applyRetention(policy: RetentionPolicy, action: RetentionAction, dryRun: boolean) : Promise<Result<RetentionReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("apply_retention", { policy, action, dryRun }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Starts watching the sessions directory for changes.
  */
@@ -2365,6 +2944,50 @@ async detectCodexApplyAuthConflict(codexProfileId: string) : Promise<Result<Code
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Compare the live auth.json with the active profile's saved snapshot
+ */
+async checkCodexAuthFreshness() : Promise<Result<CodexAuthFreshness, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_codex_auth_freshness") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reorder saved auth profiles; the order is used for rotation
+ */
+async setCodexAuthRotationOrder(names: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_codex_auth_rotation_order", { names }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Mark an auth profile as rate limited until a time, or clear the marker
+ */
+async setCodexAuthProfileLimitedUntil(name: string, until: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_codex_auth_profile_limited_until", { name, until }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Mark the active auth profile as limited and switch to the next healthy one
+ */
+async rotateCodexAuthProfile(cooldownMinutes: number | null) : Promise<Result<CodexAuthRotation, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rotate_codex_auth_profile", { cooldownMinutes }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Tauri command: snap the main window to the default size, clear fullscreen,
  * re-center, and quarantine the saved state file so the next launch starts
@@ -2427,7 +3050,13 @@ preferred_terminal?: string | null;
 /**
  * Droid temporary-run runtime policy.
  */
-droid_run?: DroidRunPreferences | null }
+droid_run?: DroidRunPreferences | null; 
+/**
+ * Codex temporary-run history handling. Runs launched from the app
+ * cannot act on exit, so they ignore merge on exit and run ephemeral
+ * ones as isolated; their history waits in the isolated runs list.
+ */
+codex_run?: CodexRunOptions | null }
 export type AuthProfile = { name: string; label: string; createdAt: string }
 export type AuthProfileState = { active: string | null; profiles: AuthProfile[] }
 /**
@@ -2530,6 +3159,62 @@ groupName: string | null }
  * Channel types supported
  */
 export type ChannelType = "new-api" | "sub-2-api" | "cli-proxy-api" | "ollama" | "general" | "deep-seek"
+/**
+ * A subagent or slash command file.
+ */
+export type ClaudeAsset = { kind: ClaudeAssetKind; scope: ClaudeAssetScope; 
+/**
+ * File name without `.md`: the agent name or the `/command` name, with
+ * `:` between the subdirectories of a namespaced command.
+ */
+name: string; 
+/**
+ * Full path to the file; filled in when read.
+ */
+path?: string; description?: string | null; 
+/**
+ * `tools` of an agent or `allowed-tools` of a command. Empty means the
+ * key is omitted (agents then inherit every tool).
+ */
+tools?: string[]; model?: string | null; 
+/**
+ * `argument-hint` of a slash command.
+ */
+argumentHint?: string | null; 
+/**
+ * Markdown after the front matter (the system prompt or command prompt).
+ */
+body?: string; 
+/**
+ * Validation issues; filled in when read, ignored on save.
+ */
+issues?: ClaudeAssetIssue[] }
+/**
+ * A front-matter problem found while reading or before saving.
+ */
+export type ClaudeAssetIssue = { 
+/**
+ * Front-matter key the issue is about.
+ */
+field: string; severity: ClaudePermissionSeverity; message: string }
+export type ClaudeAssetKind = 
+/**
+ * A subagent (`agents/*.md`).
+ */
+"agent" | 
+/**
+ * A slash command (`commands/*.md`).
+ */
+"command"
+export type ClaudeAssetScope = 
+/**
+ * `~/.claude/`.
+ */
+"user" | 
+/**
+ * `<project>/.claude/`.
+ */
+"project"
 /**
  * Claude Code profile stored in DroidGear.
  */
@@ -2542,6 +3227,75 @@ export type ClaudeConfigStatus = { settingsExists: boolean; settingsPath: string
  * Current Claude Code configuration read back from live settings.
  */
 export type ClaudeCurrentConfig = { baseUrl?: string | null; bearerToken?: string | null; model?: string | null; smallModelUsesMainModel?: boolean; smallModel?: string | null; reasoningEffort?: ClaudeReasoningEffort | null; thinkingMode?: ClaudeThinkingMode }
+/**
+ * User, project and local settings merged the way Claude Code does.
+ */
+export type ClaudeEffectiveSettings = { layers: ClaudeSettingsLayerInfo[]; settings: JsonValue; sources: ClaudeSettingsKeySource[] }
+/**
+ * A single hook: a shell command, or a prompt evaluated by the model.
+ */
+export type ClaudeHook = { 
+/**
+ * `command` or `prompt`; other types are kept as is.
+ */
+type: string; command?: string | null; prompt?: string | null; 
+/**
+ * Timeout in seconds.
+ */
+timeout?: number | null }
+/**
+ * Hooks that run when `matcher` matches (all of them when it is empty).
+ */
+export type ClaudeHookMatcher = { matcher?: string | null; hooks: ClaudeHook[] }
+export type ClaudeMergeOptions = { 
+/**
+ * Strategy per top-level key. Unlisted keys use
+ * [`default_merge_strategy`].
+ */
+strategies?: Partial<{ [key in string]: ClaudeMergeStrategy }> }
+/**
+ * How a top-level key of a settings file is merged into the global file.
+ */
+export type ClaudeMergeStrategy = 
+/**
+ * The source value replaces the live one.
+ */
+"replace" | 
+/**
+ * Objects are shallow-merged, source keys winning.
+ */
+"merge" | 
+/**
+ * Arrays are concatenated without duplicates, recursively inside objects
+ * (e.g. hooks are added next to the live hooks for the same event).
+ */
+"append" | 
+/**
+ * The live value is kept; the source value is only used when the key is
+ * missing from the global file.
+ */
+"keep"
+export type ClaudePermissionDecision = { outcome: ClaudePermissionOutcome; 
+/**
+ * The rule that decided, if any.
+ */
+rule: string | null; 
+/**
+ * Scope and path of the layer holding `rule`.
+ */
+scope: string | null; path: string | null; 
+/**
+ * `defaultMode` of the highest layer that sets one.
+ */
+defaultMode: string | null }
+export type ClaudePermissionIssue = { list: ClaudePermissionList; rule: string; severity: ClaudePermissionSeverity; message: string }
+export type ClaudePermissionList = "allow" | "ask" | "deny"
+export type ClaudePermissionOutcome = "allow" | "ask" | "deny" | 
+/**
+ * No rule matched; Claude Code falls back to `defaultMode`.
+ */
+"default"
+export type ClaudePermissionSeverity = "error" | "warning"
 export type ClaudeReasoningEffort = "low" | "medium" | "high" | "max"
 /**
  * Information about a single Claude settings file.
@@ -2567,38 +3321,187 @@ isActive: boolean;
  * Whether the file exists on disk.
  */
 exists: boolean }
+/**
+ * Where a key of the effective settings comes from.
+ */
+export type ClaudeSettingsKeySource = { 
+/**
+ * Dotted path of the key, e.g. `env.ANTHROPIC_MODEL`.
+ */
+key: string; 
+/**
+ * Effective value of the key.
+ */
+value: JsonValue; 
+/**
+ * Layers that set the key, lowest precedence first. The last one wins
+ * for scalars; arrays are concatenated.
+ */
+scopes: ClaudeSettingsScope[] }
+/**
+ * A settings layer discovered for a project.
+ */
+export type ClaudeSettingsLayerInfo = { scope: ClaudeSettingsScope; path: string; exists: boolean }
+/**
+ * A Claude Code settings layer. Layers are merged in declaration order,
+ * later ones taking precedence.
+ */
+export type ClaudeSettingsScope = 
+/**
+ * `~/.claude/settings.json`.
+ */
+"user" | 
+/**
+ * `<project>/.claude/settings.json`, shared through the repo.
+ */
+"project" | 
+/**
+ * `<project>/.claude/settings.local.json`, not checked in.
+ */
+"local"
+/**
+ * The typed sections of a Claude settings document. `None` means the key
+ * is absent from the document.
+ */
+export type ClaudeSettingsSections = { 
+/**
+ * Hook matchers keyed by event name.
+ */
+hooks?: Partial<{ [key in string]: ClaudeHookMatcher[] }> | null; statusLine?: ClaudeStatusLine | null; 
+/**
+ * Script whose output is used as the API key.
+ */
+apiKeyHelper?: string | null; outputStyle?: string | null; 
+/**
+ * `plugin@marketplace` -> enabled.
+ */
+enabledPlugins?: Partial<{ [key in string]: boolean }> | null }
+export type ClaudeStatusLine = { 
+/**
+ * Always `command`.
+ */
+type: string; command: string; padding?: number | null }
 export type ClaudeTemporaryRunDebugPreview = { profileId: string; profileName: string; program: string; args: string[]; childProgram: string; childArgs: string[]; liveConfigDir: string; inheritedEnvFileSource?: string | null; env: ([string, string])[]; unsetEnv: string[]; secretEnvKeys: string[]; warnings: string[]; settingsOverlayJson: string }
 export type ClaudeTemporaryRunPlan = { program: string; args: string[]; env: ([string, string])[]; unsetEnv: string[]; secretEnvKeys: string[]; warnings: string[] }
 export type ClaudeThinkingMode = "inherit" | "on" | "off"
 export type CodexAuthConflictInfo = { hasConflict: boolean; currentIsOfficial: boolean; targetIsOfficial: boolean }
+/**
+ * How the live `auth.json` relates to the active profile's saved snapshot.
+ */
+export type CodexAuthFreshness = { active: string | null; 
+/**
+ * Whether the live login is the same ChatGPT account as the snapshot.
+ */
+sameAccount: boolean; savedLastRefresh: string | null; liveLastRefresh: string | null; 
+/**
+ * Codex refreshed the live tokens after the snapshot was saved, so
+ * restoring the snapshot as-is would discard them.
+ */
+refreshedSinceSave: boolean }
 export type CodexAuthProfile = { name: string; label: string; createdAt: string; 
 /**
- * Whether this profile contains official auth (has auth_mode field)
+ * Whether this profile contains official auth (has auth_mode field)
+ */
+isOfficial: boolean; 
+/**
+ * The CodexProfile ID that was active when this auth profile was saved.
+ * When restoring, this profile will also be applied (config.toml only).
+ */
+codexProfileId?: string | null; 
+/**
+ * Live config.toml `model` snapshotted when this auth was saved.
+ */
+model?: string | null; 
+/**
+ * Live config.toml `model_reasoning_effort` snapshotted when this auth was saved.
+ */
+modelReasoningEffort?: string | null; 
+/**
+ * RFC 3339 time until which the account is considered rate limited.
+ * Rotation skips the profile until then.
+ */
+limitedUntil?: string | null }
+export type CodexAuthProfileState = { active: string | null; profiles: CodexAuthProfile[]; isCurrentOfficial: boolean }
+export type CodexAuthRotation = { 
+/**
+ * Profile that was active (and is now marked as limited), if any.
+ */
+from: string | null; to: string; 
+/**
+ * Official profiles passed over because they are still limited.
+ */
+skipped: string[]; limitedUntil: string | null }
+export type CodexCliCapability = { version: string; supportsConfigOverride: boolean }
+/**
+ * Codex Live 配置状态
+ */
+export type CodexConfigStatus = { authExists: boolean; configExists: boolean; authPath: string; configPath: string }
+/**
+ * 当前 Codex Live 配置（从 `~/.codex/*` 读取）
+ */
+export type CodexCurrentConfig = { providers?: Partial<{ [key in string]: CodexProviderConfig }>; modelProvider: string; model: string; modelReasoningEffort?: string | null; apiKey?: string | null; execution?: CodexExecutionSettings }
+/**
+ * Sandbox, approval and execution settings of a profile.
+ * 
+ * Applying a profile first removes the keys any saved profile sets, so
+ * switching away from a profile never leaves its settings behind. A profile
+ * with no settings leaves the other keys of config.toml alone; once any is
+ * set, applying the profile also removes the ones it leaves unset.
+ */
+export type CodexExecutionSettings = { 
+/**
+ * One of [`CODEX_SANDBOX_MODES`]
+ */
+sandboxMode?: string | null; 
+/**
+ * One of [`CODEX_APPROVAL_POLICIES`]
+ */
+approvalPolicy?: string | null; 
+/**
+ * `[sandbox_workspace_write]`, used with `sandbox_mode = "workspace-write"`
+ */
+sandboxWorkspaceWrite?: CodexSandboxWorkspaceWrite | null; shellEnvironmentPolicy?: CodexShellEnvironmentPolicy | null; 
+/**
+ * Command (program and arguments) run on agent notifications
+ */
+notify?: string[] | null; 
+/**
+ * One of [`CODEX_MODEL_VERBOSITIES`]
+ */
+modelVerbosity?: string | null; 
+/**
+ * One of [`CODEX_REASONING_SUMMARIES`]
+ */
+modelReasoningSummary?: string | null; hideAgentReasoning?: boolean | null }
+/**
+ * What merging a run's history into the live CODEX_HOME copied. Codex's
+ * SQLite session indexes are never merged.
+ */
+export type CodexHistoryMergeReport = { 
+/**
+ * Session rollout files copied
  */
-isOfficial: boolean; 
+sessions: number; 
 /**
- * The CodexProfile ID that was active when this auth profile was saved.
- * When restoring, this profile will also be applied (config.toml only).
+ * `history.jsonl` entries appended
  */
-codexProfileId?: string | null; 
+historyEntries: number }
 /**
- * Live config.toml `model` snapshotted when this auth was saved.
+ * Where a temporary run keeps the sessions and history it records
  */
-model?: string | null; 
+export type CodexHistoryMode = 
 /**
- * Live config.toml `model_reasoning_effort` snapshotted when this auth was saved.
+ * Shared with the live CODEX_HOME, like a normal Codex run
  */
-modelReasoningEffort?: string | null }
-export type CodexAuthProfileState = { active: string | null; profiles: CodexAuthProfile[]; isCurrentOfficial: boolean }
-export type CodexCliCapability = { version: string; supportsConfigOverride: boolean }
+"shared" | 
 /**
- * Codex Live 配置状态
+ * Kept in the runtime home until merged back or discarded
  */
-export type CodexConfigStatus = { authExists: boolean; configExists: boolean; authPath: string; configPath: string }
+"isolated" | 
 /**
- * 当前 Codex Live 配置（从 `~/.codex/*` 读取）
+ * Discarded when the run exits
  */
-export type CodexCurrentConfig = { providers?: Partial<{ [key in string]: CodexProviderConfig }>; modelProvider: string; model: string; modelReasoningEffort?: string | null; apiKey?: string | null }
+"ephemeral"
 /**
  * One-shot plan for launching Codex inside the app PTY terminal.
  * 
@@ -2611,6 +3514,14 @@ export type CodexInAppRunPlan = { program: string; args: string[];
  * Public env (e.g. CODEX_HOME) plus secret API keys for PTY injection.
  */
 env: ([string, string])[]; unsetEnv: string[]; warnings: string[] }
+/**
+ * An isolated run whose history is still in its runtime home
+ */
+export type CodexIsolatedRun = { path: string; profileId: string; profileName: string; createdAt: string; sessionFiles: number }
+/**
+ * A `[profiles.<name>]` table in config.toml
+ */
+export type CodexNativeProfile = { name: string; modelProvider?: string | null; model?: string | null; modelReasoningEffort?: string | null }
 /**
  * Codex Profile（用于在 DroidGear 内部保存并切换）
  */
@@ -2618,7 +3529,23 @@ export type CodexProfile = { id: string; name: string; description?: string | nu
 /**
  * Saved Codex auth profile name to restore on apply (openai mode only).
  */
-authProfileName?: string | null }
+authProfileName?: string | null; 
+/**
+ * Sandbox, approval and execution settings written to config.toml.
+ */
+execution?: CodexExecutionSettings; 
+/**
+ * MCP servers written to `[mcp_servers.<name>]` while the profile is active.
+ */
+mcpServers?: McpServer[]; 
+/**
+ * `[features]` flags set while the profile is active.
+ */
+features?: Partial<{ [key in string]: boolean }>; 
+/**
+ * Top-level `web_search` mode, one of [`CODEX_WEB_SEARCH_MODES`].
+ */
+webSearch?: string | null }
 /**
  * Codex Provider 配置（对应 config.toml 中的 [model_providers.<id>]）
  */
@@ -2627,6 +3554,27 @@ export type CodexProviderConfig = { name?: string | null; baseUrl?: string | nul
  * Provider API key. Written to config.toml as `experimental_bearer_token`.
  */
 apiKey?: string | null }
+/**
+ * Per-run options of a temporary run
+ */
+export type CodexRunOptions = { historyMode?: CodexHistoryMode; 
+/**
+ * Merge the run's sessions into the live CODEX_HOME when it exits
+ * (isolated and ephemeral runs only).
+ */
+mergeHistoryOnExit?: boolean }
+/**
+ * `[sandbox_workspace_write]` in config.toml
+ */
+export type CodexSandboxWorkspaceWrite = { writableRoots?: string[]; networkAccess?: boolean | null; excludeTmpdirEnvVar?: boolean | null; excludeSlashTmp?: boolean | null }
+/**
+ * `[shell_environment_policy]` in config.toml
+ */
+export type CodexShellEnvironmentPolicy = { 
+/**
+ * One of [`CODEX_SHELL_ENV_INHERIT`]
+ */
+inherit?: string | null; ignoreDefaultExcludes?: boolean | null; exclude?: string[]; includeOnly?: string[]; set?: Partial<{ [key in string]: string }>; experimentalUseProfile?: boolean | null }
 export type CodexTemporaryRunPlan = { program: string; args: string[]; env: ([string, string])[]; unsetEnv: string[]; secretEnvKeys: string[]; warnings: string[] }
 /**
  * User-defined configuration paths (only stores explicitly set paths)
@@ -2645,7 +3593,23 @@ export type ConnectivitySummary = { totalModels: number; availableModels: number
 /**
  * Message content block
  */
-export type ContentBlock = { type: string; text?: string | null; thinking?: string | null }
+export type ContentBlock = { type: "text"; text: string } | { type: "thinking"; thinking: string } | 
+/**
+ * Tool invocation requested by the assistant
+ */
+{ type: "tool_use"; id: string; name: string; input: JsonValue } | 
+/**
+ * Output returned to the assistant for a previous tool call
+ */
+{ type: "tool_result"; toolUseId: string; output: string; isError: boolean } | 
+/**
+ * Image attachment (the payload itself is not kept)
+ */
+{ type: "image"; mediaType: string; url?: string | null } | 
+/**
+ * Tool call that creates or edits a file
+ */
+{ type: "file_edit"; id: string; toolName: string; path: string; oldText?: string | null; newText?: string | null }
 /**
  * Custom model configuration
  */
@@ -2796,6 +3760,134 @@ export type HermesProfile = { id: string; name: string; description?: string | n
  */
 reasoningEffort?: string | null }
 export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * A server in the merged user + project view
+ */
+export type McpEffectiveServer = { name: string; config: McpServerConfig; 
+/**
+ * The definition that wins
+ */
+origin: McpServerOrigin; 
+/**
+ * Definitions of the same name it overrides, lowest precedence first
+ */
+shadowed: McpServerOrigin[] }
+/**
+ * Header value resolved when syncing or probing instead of stored inline
+ */
+export type McpHeaderSource = { kind: McpHeaderSourceKind; 
+/**
+ * Variable name, file path or shell command
+ */
+value: string; 
+/**
+ * Prepended to the resolved value, e.g. `Bearer `
+ */
+prefix?: string | null }
+/**
+ * Where a dynamic header value comes from
+ */
+export type McpHeaderSourceKind = 
+/**
+ * An environment variable
+ */
+"env" | 
+/**
+ * A file holding the value (`~` expands to home)
+ */
+"file" | 
+/**
+ * A shell command printing the value, e.g. a token refresh
+ */
+"command"
+/**
+ * An installable MCP server template
+ */
+export type McpPreset = { id: string; name: string; description?: string; inputs?: McpPresetInput[]; variants: McpPresetVariant[]; source?: McpPresetSource }
+/**
+ * A value the user supplies when installing a preset
+ */
+export type McpPresetInput = { 
+/**
+ * Referenced from variant configs as `${id}`
+ */
+id: string; label: string; kind?: McpPresetInputKind; required?: boolean; placeholder?: string | null; 
+/**
+ * Used when the value is left blank
+ */
+default?: string | null; 
+/**
+ * Regex the value must match
+ */
+pattern?: string | null; 
+/**
+ * Where to obtain the value, e.g. an API key dashboard
+ */
+helpUrl?: string | null }
+/**
+ * What a preset input holds, which decides how it is validated
+ */
+export type McpPresetInputKind = "text" | 
+/**
+ * Masked when prompted
+ */
+"secret" | 
+/**
+ * Must exist; `~` expands to home
+ */
+"path" | 
+/**
+ * Must be an http(s) URL
+ */
+"url"
+/**
+ * Where a preset was loaded from
+ */
+export type McpPresetSource = "bundled" | "user"
+/**
+ * One way of running a preset (e.g. local stdio vs hosted HTTP)
+ */
+export type McpPresetVariant = { id: string; description?: string | null; config: McpServerConfig }
+/**
+ * Outcome of probing one MCP server
+ */
+export type McpProbeResult = { success: boolean; serverName?: string | null; serverVersion?: string | null; protocolVersion?: string | null; toolCount: number; toolNames: string[]; 
+/**
+ * Time from launch (or first request) to the `initialize` response
+ */
+latencyMs: number; error?: string | null; 
+/**
+ * Tail of the server's stderr (stdio only), kept on failure
+ */
+stderr?: string | null }
+/**
+ * A project-level MCP file and the servers it defines
+ */
+export type McpProjectConfig = { file: McpProjectFile; path: string; exists: boolean; servers: McpServer[] }
+/**
+ * Project-level MCP file
+ */
+export type McpProjectFile = 
+/**
+ * `.factory/mcp.json`, read by Droid
+ */
+"factory" | 
+/**
+ * `.mcp.json`, read by Claude Code
+ */
+"claude"
+/**
+ * Where an MCP server is configured
+ */
+export type McpScope = 
+/**
+ * The DroidGear catalog, synced to every tool
+ */
+"user" | 
+/**
+ * A project-level file inside a repository
+ */
+"project"
 /**
  * MCP server entry with name
  */
@@ -2813,7 +3905,7 @@ config: McpServerConfig }
  */
 export type McpServerConfig = { 
 /**
- * Server type (stdio or http)
+ * Server type (stdio, http or sse)
  */
 type: McpServerType; 
 /**
@@ -2833,17 +3925,98 @@ args?: string[] | null;
  */
 env?: Partial<{ [key in string]: string }> | null; 
 /**
- * HTTP URL (http only)
+ * Server URL (http/sse only)
  */
 url?: string | null; 
 /**
- * HTTP headers (http only)
+ * Static HTTP headers (http/sse only)
+ */
+headers?: Partial<{ [key in string]: string }> | null; 
+/**
+ * Headers resolved from env vars, files or commands (http/sse only).
+ * DroidGear-only; targets receive the resolved values.
+ */
+headerSources?: Partial<{ [key in string]: McpHeaderSource }> | null; 
+/**
+ * Seconds to wait for the server to start and answer `initialize`
  */
-headers?: Partial<{ [key in string]: string }> | null }
+startupTimeoutSec?: number | null; 
+/**
+ * Seconds a single tool call may take
+ */
+toolTimeoutSec?: number | null }
+/**
+ * Where an effective server comes from
+ */
+export type McpServerOrigin = { scope: McpScope; 
+/**
+ * Set for project-scoped servers
+ */
+file?: McpProjectFile | null; path: string }
 /**
  * MCP server type
  */
-export type McpServerType = "stdio" | "http"
+export type McpServerType = 
+/**
+ * Local process over stdin/stdout
+ */
+"stdio" | 
+/**
+ * Streamable HTTP
+ */
+"http" | 
+/**
+ * Legacy HTTP+SSE transport (GET an event stream, POST to its endpoint)
+ */
+"sse"
+/**
+ * What a sync changes (or would change) in one target
+ */
+export type McpSyncPreview = { target: McpSyncTarget; path: string; 
+/**
+ * File contents before the sync (`None` if the file does not exist)
+ */
+before: string | null; 
+/**
+ * File contents after the sync (equal to `before` when nothing changes)
+ */
+after: string | null; added: string[]; updated: string[]; removed: string[]; 
+/**
+ * Catalog servers skipped because an unmanaged entry uses the name
+ */
+conflicts: string[]; 
+/**
+ * Entries in the target that DroidGear does not manage
+ */
+unmanaged: string[] }
+/**
+ * Tool config an MCP catalog can be synced into
+ */
+export type McpSyncTarget = 
+/**
+ * `~/.factory/mcp.json` (`mcpServers`)
+ */
+"factory" | 
+/**
+ * `~/.claude.json` (`mcpServers`)
+ */
+"claude" | 
+/**
+ * `~/.codex/config.toml` (`[mcp_servers]`)
+ */
+"codex" | 
+/**
+ * `~/.config/opencode/opencode.json` (`mcp`)
+ */
+"opencode" | 
+/**
+ * `~/.pi/agent/mcp.json` (`mcpServers`, read by pi-mcp-adapter)
+ */
+"pi" | 
+/**
+ * `~/.openclaw/openclaw.json` (`mcp.servers`)
+ */
+"openclaw"
 /**
  * Mission model settings for Mission mode workers
  */
@@ -3054,6 +4227,72 @@ export type RecoveryError =
  * JSON serialization/deserialization error
  */
 { type: "ParseError"; message: string }
+/**
+ * What to redact before rendering. Missing fields take the [`Default`],
+ * which enables every built-in rule.
+ */
+export type RedactionOptions = { 
+/**
+ * Replace API keys and bearer tokens
+ */
+apiKeys?: boolean; 
+/**
+ * Replace the home directory with `~`
+ */
+homePaths?: boolean; 
+/**
+ * Replace email addresses
+ */
+emails?: boolean; 
+/**
+ * Extra regular expressions whose matches are replaced
+ */
+customPatterns?: string[] }
+/**
+ * What to do with matching sessions
+ */
+export type RetentionAction = "archive" | "delete"
+/**
+ * A session selected by the policy
+ */
+export type RetentionCandidate = { session: SessionSummary; reasons: RetentionReason[]; 
+/**
+ * Files that will be archived or removed
+ */
+files: string[]; sizeBytes: number }
+/**
+ * Which sessions a cleanup selects. A session matches if any enabled rule
+ * matches; with no rule enabled nothing is selected.
+ */
+export type RetentionPolicy = { 
+/**
+ * Sessions not modified for more than this many days
+ */
+maxAgeDays?: number | null; 
+/**
+ * Sessions without any user or assistant message
+ */
+empty?: boolean; 
+/**
+ * Sessions whose total tokens are below this threshold
+ */
+minTotalTokens?: number | null; 
+/**
+ * Sessions whose working directory no longer exists
+ */
+missingProjectDir?: boolean }
+/**
+ * Why a session was selected
+ */
+export type RetentionReason = { rule: "olderThan"; ageDays: number } | { rule: "empty" } | { rule: "belowTokenThreshold"; totalTokens: number } | { rule: "missingProjectDir"; cwd: string }
+/**
+ * Result of a cleanup run (or of a dry run when `dry_run` is set)
+ */
+export type RetentionReport = { action: RetentionAction; dryRun: boolean; candidates: RetentionCandidate[]; totalBytes: number; 
+/**
+ * Archive written by this run, if any
+ */
+archivePath: string | null }
 /**
  * Session default settings for mixed models configuration
  */
@@ -3061,11 +4300,23 @@ export type SessionDefaultSettings = { model?: string | null; reasoningEffort?:
 /**
  * Session detail with messages
  */
-export type SessionDetail = { id: string; title: string; project: string; model: string; cwd: string; modifiedAt: number; tokenUsage: TokenUsage; messages: SessionMessage[] }
+export type SessionDetail = { id: string; title: string; project: string; model: string; cwd: string; modifiedAt: number; tokenUsage: TokenUsage; messages: SessionMessage[]; source?: SessionSource }
+/**
+ * Export output format
+ */
+export type SessionExportFormat = "markdown" | "html" | "json"
+/**
+ * Export request
+ */
+export type SessionExportOptions = { format: SessionExportFormat; redaction?: RedactionOptions }
 /**
  * Session message
  */
-export type SessionMessage = { id: string; role: string; content: ContentBlock[]; timestamp: string }
+export type SessionMessage = { id: string; role: string; content: ContentBlock[]; timestamp: string; 
+/**
+ * Token usage reported for this message, if any
+ */
+tokenUsage?: TokenUsage | null }
 /**
  * Session project (directory containing sessions)
  */
@@ -3086,6 +4337,10 @@ sessionCount: number;
  * Last modified timestamp in milliseconds
  */
 modifiedAt: number }
+/**
+ * Tool that produced a session transcript
+ */
+export type SessionSource = "droid" | "claudeCode" | "codex"
 /**
  * Session summary for list view
  */
@@ -3117,7 +4372,11 @@ tokenUsage: TokenUsage;
 /**
  * Full path to the session files (without extension)
  */
-path: string }
+path: string; 
+/**
+ * Tool that wrote the session
+ */
+source?: SessionSource }
 /**
  * Information about a single settings file
  */
@@ -3161,7 +4420,104 @@ content: string;
 /**
  * Last modified timestamp in milliseconds
  */
-modifiedAt: number }
+modifiedAt: number; 
+/**
+ * Parsed front matter
+ */
+meta?: SpecMeta }
+/**
+ * Metadata from a spec's YAML front matter
+ */
+export type SpecMeta = { title?: string | null; status?: SpecStatus | null; tags?: string[]; 
+/**
+ * Droid session that produced the spec
+ */
+sessionId?: string | null; 
+/**
+ * Working directory the spec belongs to
+ */
+project?: string | null }
+/**
+ * Filters and ordering for [`list_spec_summaries_for_home`]
+ */
+export type SpecQuery = { status?: SpecStatus | null; 
+/**
+ * Case-insensitive tag match
+ */
+tag?: string | null; project?: string | null; 
+/**
+ * Case-insensitive substring of the name or title
+ */
+text?: string | null; sort?: SpecSortKey; 
+/**
+ * Reverses the natural order (newest first for `modified`, A-Z otherwise)
+ */
+reverse?: boolean }
+/**
+ * One stored version of a spec
+ */
+export type SpecRevision = { 
+/**
+ * Sequence number, starting at 1
+ */
+id: number; 
+/**
+ * Spec file name when the revision was taken
+ */
+name: string; 
+/**
+ * Unix time in milliseconds
+ */
+createdAt: number; 
+/**
+ * Why the revision was taken, e.g. `edit` or `restore 3`
+ */
+reason: string }
+/**
+ * The Droid session a spec was written from
+ */
+export type SpecSessionLink = { 
+/**
+ * Spec file name
+ */
+specName: string; sessionId: string; 
+/**
+ * Session path without extension, as used by `sessions`
+ */
+sessionPath: string; 
+/**
+ * Working directory of the session
+ */
+project: string }
+/**
+ * Field to order specs by
+ */
+export type SpecSortKey = "modified" | "name" | "title" | "status"
+/**
+ * Spec lifecycle status
+ */
+export type SpecStatus = "draft" | "approved" | "implemented"
+/**
+ * A spec without its content, for listing
+ */
+export type SpecSummary = { name: string; path: string; modifiedAt: number; meta: SpecMeta }
+/**
+ * A spec template
+ */
+export type SpecTemplate = { 
+/**
+ * File stem, e.g. `default`
+ */
+name: string; 
+/**
+ * False for the template bundled with DroidGear
+ */
+user: boolean; content: string }
+/**
+ * Values substituted into a template. Blank values fall back to defaults:
+ * today's date, the `USER` environment variable and the spec name.
+ */
+export type SpecTemplateVars = { date?: string | null; project?: string | null; author?: string | null; title?: string | null }
 /**
  * Telegram channel configuration
  */
@@ -3191,6 +4547,30 @@ export type TokenUsage = { inputTokens: number; outputTokens: number; cacheCreat
  */
 export type TrustedFolder = { path: string; trustedAt: string }
 export type UpdateChannel = "managed" | "portable"
+/**
+ * Usage rolled up for one bucket
+ */
+export type UsageBucket = { 
+/**
+ * Day (`YYYY-MM-DD`), ISO week (`YYYY-Www`), project or model
+ */
+key: string; sessionCount: number; tokenUsage: TokenUsage; totalTokens: number; 
+/**
+ * Cache read tokens over all prompt tokens, `0.0..=1.0`
+ */
+cacheHitRatio: number }
+/**
+ * Report export format
+ */
+export type UsageExportFormat = "csv" | "json"
+/**
+ * Dimension to roll usage up by
+ */
+export type UsageGroupBy = "day" | "week" | "project" | "model"
+/**
+ * Usage report for one grouping
+ */
+export type UsageReport = { groupBy: UsageGroupBy; buckets: UsageBucket[]; totals: UsageBucket }
 /**
  * WSL distribution info
  */
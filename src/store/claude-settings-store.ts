@@ -23,7 +23,7 @@ interface ClaudeSettingsState {
   createFile: (name: string, copyFromActive: boolean) => Promise<void>
   deleteFile: (name: string) => Promise<void>
   duplicateFile: (name: string, newName: string) => Promise<void>
-  mergeToGlobal: (name: string) => Promise<void>
+  mergeToGlobal: (name: string) => Promise<string[]>
   loadFromLive: (name: string) => Promise<void>
   preview: (name: string) => Promise<ClaudeTemporaryRunDebugPreview>
   patchJson: (mutator: (draft: ClaudeSettingsDoc) => void) => void
@@ -191,6 +191,7 @@ export const useClaudeSettingsStore = create<ClaudeSettingsState>()(
           )
           throw new Error(result.error)
         }
+        return result.data
       },
 
       loadFromLive: async name => {
@@ -263,6 +263,7 @@ export const useCodexStore = create<CodexState>()(
           model: live.model,
           modelReasoningEffort: live.modelReasoningEffort ?? null,
           apiKey: live.apiKey ?? null,
+          execution: live.execution ?? {},
           updatedAt: new Date().toISOString(),
         }
         set(